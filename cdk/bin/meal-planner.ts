@@ -39,4 +39,6 @@ new ApplicationLayerStack(app, 'DevApplicationLayerStack', {
   parentHostedZoneId: hostedZone.hostedZoneId,
   domain: delegate.normalizedDomain,
   recipeTable: devPersistence.recipeTable,
+  recipeRevisionTable: devPersistence.recipeRevisionTable,
+  shoppingListTable: devPersistence.shoppingListTable,
 });
@@ -1,7 +1,10 @@
-import { Stack, StackProps } from 'aws-cdk-lib';
+import { Duration, Stack, StackProps } from 'aws-cdk-lib';
 import { RetentionDays } from 'aws-cdk-lib/aws-logs';
 import { TableV2 } from 'aws-cdk-lib/aws-dynamodb';
 import { Role } from 'aws-cdk-lib/aws-iam';
+import { StartingPosition } from 'aws-cdk-lib/aws-lambda';
+import { DynamoEventSource } from 'aws-cdk-lib/aws-lambda-event-sources';
+import { StringParameter } from 'aws-cdk-lib/aws-ssm';
 import {
   CrossAccountZoneDelegationRecord,
   PublicHostedZone,
@@ -26,6 +29,77 @@ export interface ApplicationLayerStackProps extends StackProps {
     * Table where the partition key refers to the top-level element of recipes.
     */
   readonly recipeTable: TableV2;
+
+  /**
+    * Table where the partition key refers to a recipe and the sort key to its revision number.
+    */
+  readonly recipeRevisionTable: TableV2;
+
+  /**
+    * Table where the partition key refers to the token of a recipe share link.
+    */
+  readonly recipeShareTable: TableV2;
+
+  /**
+    * Table where the partition key refers to a shopping list.
+    */
+  readonly shoppingListTable: TableV2;
+
+  /**
+    * Table where the partition key refers to the subject of a household member.
+    */
+  readonly householdMemberTable: TableV2;
+
+  /**
+    * Table where the partition key refers to the token of a household invitation.
+    */
+  readonly householdInvitationTable: TableV2;
+
+  /**
+    * Table where the partition key refers to the id of an API key.
+    */
+  readonly apiKeyTable: TableV2;
+
+  /**
+    * Table where the partition key refers to an idempotency key of a caller.
+    */
+  readonly idempotencyTable: TableV2;
+
+  /**
+    * Table where the partition key refers to a household and the sort key to one of its webhooks.
+    */
+  readonly webhookTable: TableV2;
+
+  /**
+    * Table where the partition key refers to a webhook and the sort key to an attempt to deliver to it.
+    */
+  readonly webhookDeliveryTable: TableV2;
+
+  /**
+    * Table where the partition key refers to a household and the sort key to one of its meal plans.
+    */
+  readonly mealPlanTable: TableV2;
+
+  /**
+    * Table where the partition key refers to the token of a member's calendar feed.
+    */
+  readonly calendarFeedTable: TableV2;
+
+  /**
+    * Table where the partition key refers to the household that owns the kitchen.
+    */
+  readonly kitchenTable: TableV2;
+
+  /**
+    * Table where the partition key refers to the household that cooked a recipe.
+    */
+  readonly cookLogTable: TableV2;
+
+  /**
+    * URL of the JSON Web Key Set used to verify bearer tokens. Read from the
+    * `/meal-planner/jwks-url` SSM parameter when not given.
+    */
+  readonly jwksUrl?: string;
 }
 
 /**
@@ -41,16 +115,99 @@ export default class ApplicationLayerStack extends Stack {
   constructor(scope: Construct, id: string, props: ApplicationLayerStackProps) {
     super(scope, id, props);
 
+    const jwksUrl = props.jwksUrl ??
+      StringParameter.valueForStringParameter(this, '/meal-planner/jwks-url');
+
     const handler = new RustFunction(this, 'RecipeFunction', {
       functionName: 'RecipeFunction',
       manifestPath: join(__dirname, '..', '..', 'lambda/Cargo.toml'),
+      binaryName: 'recipes',
       environment: {
+        JWKS_SOURCE: jwksUrl,
         RECIPE_TABLE_NAME: props.recipeTable.tableName,
+        RECIPE_REVISION_TABLE_NAME: props.recipeRevisionTable.tableName,
+        RECIPE_SHARE_TABLE_NAME: props.recipeShareTable.tableName,
+        RECIPE_TRASH_RETENTION_DAYS: '30',
+        SHOPPING_LIST_TABLE_NAME: props.shoppingListTable.tableName,
+        HOUSEHOLD_MEMBER_TABLE_NAME: props.householdMemberTable.tableName,
+        HOUSEHOLD_INVITATION_TABLE_NAME: props.householdInvitationTable.tableName,
+        API_KEY_TABLE_NAME: props.apiKeyTable.tableName,
+        IDEMPOTENCY_TABLE_NAME: props.idempotencyTable.tableName,
+        WEBHOOK_TABLE_NAME: props.webhookTable.tableName,
+        WEBHOOK_DELIVERY_TABLE_NAME: props.webhookDeliveryTable.tableName,
+        MEAL_PLAN_TABLE_NAME: props.mealPlanTable.tableName,
+        CALENDAR_FEED_TABLE_NAME: props.calendarFeedTable.tableName,
+        KITCHEN_TABLE_NAME: props.kitchenTable.tableName,
+        COOK_LOG_TABLE_NAME: props.cookLogTable.tableName,
+        PUBLIC_BASE_URL: `https://api.${props.domain}/recipes`,
+        RATE_LIMIT_BURST: '20',
+        RATE_LIMIT_PER_SECOND: '5',
+        MAX_REQUEST_BODY_BYTES: '1048576',
       },
       logRetention: RetentionDays.ONE_WEEK,
     });
 
     props.recipeTable.grantReadWriteData(handler);
+    props.recipeRevisionTable.grantReadWriteData(handler);
+    props.recipeShareTable.grantReadWriteData(handler);
+    props.shoppingListTable.grantReadWriteData(handler);
+    props.householdMemberTable.grantReadWriteData(handler);
+    props.householdInvitationTable.grantReadWriteData(handler);
+    props.apiKeyTable.grantReadWriteData(handler);
+    props.idempotencyTable.grantReadWriteData(handler);
+    props.webhookTable.grantReadWriteData(handler);
+    props.webhookDeliveryTable.grantReadData(handler);
+    props.mealPlanTable.grantReadWriteData(handler);
+    props.calendarFeedTable.grantReadWriteData(handler);
+    props.kitchenTable.grantReadWriteData(handler);
+    props.cookLogTable.grantReadWriteData(handler);
+
+    const changeHandler = new RustFunction(this, 'RecipeChangeFunction', {
+      functionName: 'RecipeChangeFunction',
+      manifestPath: join(__dirname, '..', '..', 'lambda/Cargo.toml'),
+      binaryName: 'recipe-changes',
+      environment: {
+        WEBHOOK_TABLE_NAME: props.webhookTable.tableName,
+        WEBHOOK_DELIVERY_TABLE_NAME: props.webhookDeliveryTable.tableName,
+      },
+      // Leaves room for retrying deliveries to slow webhooks.
+      timeout: Duration.minutes(5),
+      logRetention: RetentionDays.ONE_WEEK,
+    });
+
+    props.webhookTable.grantReadData(changeHandler);
+    props.webhookDeliveryTable.grantWriteData(changeHandler);
+
+    changeHandler.addEventSource(new DynamoEventSource(props.recipeTable, {
+      startingPosition: StartingPosition.TRIM_HORIZON,
+      // Every record may take up to the delivery deadline of 20 seconds.
+      batchSize: 10,
+      retryAttempts: 10,
+      reportBatchItemFailures: true,
+    }));
+
+    const planChangeHandler = new RustFunction(this, 'PlanChangeFunction', {
+      functionName: 'PlanChangeFunction',
+      manifestPath: join(__dirname, '..', '..', 'lambda/Cargo.toml'),
+      binaryName: 'plan-changes',
+      environment: {
+        WEBHOOK_TABLE_NAME: props.webhookTable.tableName,
+        WEBHOOK_DELIVERY_TABLE_NAME: props.webhookDeliveryTable.tableName,
+      },
+      timeout: Duration.minutes(5),
+      logRetention: RetentionDays.ONE_WEEK,
+    });
+
+    props.webhookTable.grantReadData(planChangeHandler);
+    props.webhookDeliveryTable.grantWriteData(planChangeHandler);
+
+    planChangeHandler.addEventSource(new DynamoEventSource(props.mealPlanTable, {
+      startingPosition: StartingPosition.TRIM_HORIZON,
+      // Every record may take up to the delivery deadline of 20 seconds.
+      batchSize: 10,
+      retryAttempts: 10,
+      reportBatchItemFailures: true,
+    }));
 
     const hostedZone = new PublicHostedZone(this, 'HostedZone', {
       zoneName: props.domain,
@@ -39,6 +39,19 @@ export default class MealPlannerStage extends Stage {
     new ApplicationLayerStack(this, 'ApplicationLayer', {
       delegationRole: delegate.delegationRole,
       recipeTable: persistanceLayer.recipeTable,
+      recipeRevisionTable: persistanceLayer.recipeRevisionTable,
+      recipeShareTable: persistanceLayer.recipeShareTable,
+      shoppingListTable: persistanceLayer.shoppingListTable,
+      householdMemberTable: persistanceLayer.householdMemberTable,
+      householdInvitationTable: persistanceLayer.householdInvitationTable,
+      apiKeyTable: persistanceLayer.apiKeyTable,
+      idempotencyTable: persistanceLayer.idempotencyTable,
+      webhookTable: persistanceLayer.webhookTable,
+      webhookDeliveryTable: persistanceLayer.webhookDeliveryTable,
+      mealPlanTable: persistanceLayer.mealPlanTable,
+      calendarFeedTable: persistanceLayer.calendarFeedTable,
+      kitchenTable: persistanceLayer.kitchenTable,
+      cookLogTable: persistanceLayer.cookLogTable,
       domain: delegate.normalizedDomain,
       parentHostedZoneId: hostedZone.hostedZoneId,
     });
@@ -1,5 +1,5 @@
 import { Stack, StackProps } from 'aws-cdk-lib';
-import { AttributeType, TableV2 } from 'aws-cdk-lib/aws-dynamodb';
+import { AttributeType, StreamViewType, TableV2 } from 'aws-cdk-lib/aws-dynamodb';
 import { Construct } from 'constructs';
 
 /**
@@ -7,6 +7,19 @@ import { Construct } from 'constructs';
   */
 export default class PersistenceLayerStack extends Stack {
   public readonly recipeTable: TableV2;
+  public readonly recipeRevisionTable: TableV2;
+  public readonly recipeShareTable: TableV2;
+  public readonly shoppingListTable: TableV2;
+  public readonly householdMemberTable: TableV2;
+  public readonly householdInvitationTable: TableV2;
+  public readonly apiKeyTable: TableV2;
+  public readonly idempotencyTable: TableV2;
+  public readonly webhookTable: TableV2;
+  public readonly webhookDeliveryTable: TableV2;
+  public readonly mealPlanTable: TableV2;
+  public readonly calendarFeedTable: TableV2;
+  public readonly kitchenTable: TableV2;
+  public readonly cookLogTable: TableV2;
 
   /**
    * Constructs a stack meant for the image repository and other fixed resources.
@@ -18,8 +31,118 @@ export default class PersistenceLayerStack extends Stack {
     super(scope, id, props);
 
     this.recipeTable = new TableV2(this, 'RecipeTable', {
+      partitionKey: { name: 'household_id', type: AttributeType.STRING },
+      sortKey: { name: 'id', type: AttributeType.STRING },
+      tableName: 'recipes-by-household',
+      timeToLiveAttribute: 'expires_at',
+      dynamoStream: StreamViewType.NEW_AND_OLD_IMAGES,
+    });
+
+    this.recipeRevisionTable = new TableV2(this, 'RecipeRevisionTable', {
+      partitionKey: { name: 'recipe_key', type: AttributeType.STRING },
+      sortKey: { name: 'revision', type: AttributeType.NUMBER },
+      tableName: 'recipe-revisions',
+    });
+
+    this.recipeShareTable = new TableV2(this, 'RecipeShareTable', {
+      partitionKey: { name: 'token', type: AttributeType.STRING },
+      tableName: 'recipe-shares',
+      timeToLiveAttribute: 'expires_at',
+      globalSecondaryIndexes: [
+        {
+          indexName: 'recipe_id-index',
+          partitionKey: { name: 'recipe_id', type: AttributeType.STRING },
+        },
+      ],
+    });
+
+    this.shoppingListTable = new TableV2(this, 'ShoppingListTable', {
+      partitionKey: { name: 'household_id', type: AttributeType.STRING },
+      sortKey: { name: 'id', type: AttributeType.STRING },
+      tableName: 'shopping-lists-by-household',
+    });
+
+    this.householdMemberTable = new TableV2(this, 'HouseholdMemberTable', {
+      partitionKey: { name: 'subject', type: AttributeType.STRING },
+      tableName: 'household-members',
+      globalSecondaryIndexes: [
+        {
+          indexName: 'household_id-index',
+          partitionKey: { name: 'household_id', type: AttributeType.STRING },
+        },
+      ],
+    });
+
+    this.householdInvitationTable = new TableV2(this, 'HouseholdInvitationTable', {
+      partitionKey: { name: 'token', type: AttributeType.STRING },
+      tableName: 'household-invitations',
+      timeToLiveAttribute: 'expires_at',
+    });
+
+    this.apiKeyTable = new TableV2(this, 'ApiKeyTable', {
       partitionKey: { name: 'id', type: AttributeType.STRING },
-      tableName: 'recipes',
+      tableName: 'api-keys',
+      globalSecondaryIndexes: [
+        {
+          indexName: 'household_id-index',
+          partitionKey: { name: 'household_id', type: AttributeType.STRING },
+        },
+      ],
+    });
+
+    this.idempotencyTable = new TableV2(this, 'IdempotencyTable', {
+      partitionKey: { name: 'key', type: AttributeType.STRING },
+      tableName: 'idempotency-keys',
+      timeToLiveAttribute: 'expires_at',
+    });
+
+    this.webhookTable = new TableV2(this, 'WebhookTable', {
+      partitionKey: { name: 'household_id', type: AttributeType.STRING },
+      sortKey: { name: 'id', type: AttributeType.STRING },
+      tableName: 'webhooks',
+    });
+
+    this.webhookDeliveryTable = new TableV2(this, 'WebhookDeliveryTable', {
+      partitionKey: { name: 'webhook_id', type: AttributeType.STRING },
+      sortKey: { name: 'attempt_id', type: AttributeType.STRING },
+      tableName: 'webhook-deliveries',
+      timeToLiveAttribute: 'expires_at',
+    });
+
+    this.mealPlanTable = new TableV2(this, 'MealPlanTable', {
+      partitionKey: { name: 'household_id', type: AttributeType.STRING },
+      sortKey: { name: 'id', type: AttributeType.STRING },
+      tableName: 'meal-plans',
+      dynamoStream: StreamViewType.NEW_AND_OLD_IMAGES,
+    });
+
+    this.calendarFeedTable = new TableV2(this, 'CalendarFeedTable', {
+      partitionKey: { name: 'token', type: AttributeType.STRING },
+      tableName: 'calendar-feeds',
+      globalSecondaryIndexes: [
+        {
+          indexName: 'subject-index',
+          partitionKey: { name: 'subject', type: AttributeType.STRING },
+        },
+      ],
+    });
+
+    this.kitchenTable = new TableV2(this, 'KitchenTable', {
+      partitionKey: { name: 'household_id', type: AttributeType.STRING },
+      tableName: 'kitchens',
+    });
+
+    this.cookLogTable = new TableV2(this, 'CookLogTable', {
+      partitionKey: { name: 'household_id', type: AttributeType.STRING },
+      sortKey: { name: 'id', type: AttributeType.STRING },
+      tableName: 'cook-log',
+      globalSecondaryIndexes: [
+        {
+          indexName: 'recipe-index',
+          partitionKey: { name: 'recipe_id', type: AttributeType.STRING },
+          sortKey: { name: 'cooked_on', type: AttributeType.STRING },
+        },
+      ],
     });
   }
 }
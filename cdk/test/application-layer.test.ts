@@ -29,6 +29,20 @@ describe('Application Layer Stack', () => {
       domain: delegate.normalizedDomain,
       parentHostedZoneId: hostedZone.hostedZoneId,
       recipeTable: persistenceStack.recipeTable,
+      recipeRevisionTable: persistenceStack.recipeRevisionTable,
+      recipeShareTable: persistenceStack.recipeShareTable,
+      shoppingListTable: persistenceStack.shoppingListTable,
+      householdMemberTable: persistenceStack.householdMemberTable,
+      householdInvitationTable: persistenceStack.householdInvitationTable,
+      apiKeyTable: persistenceStack.apiKeyTable,
+      idempotencyTable: persistenceStack.idempotencyTable,
+      webhookTable: persistenceStack.webhookTable,
+      webhookDeliveryTable: persistenceStack.webhookDeliveryTable,
+      mealPlanTable: persistenceStack.mealPlanTable,
+      calendarFeedTable: persistenceStack.calendarFeedTable,
+      kitchenTable: persistenceStack.kitchenTable,
+      cookLogTable: persistenceStack.cookLogTable,
+      jwksUrl: 'https://auth.example.com/.well-known/jwks.json',
     });
 
     // THEN
@@ -65,7 +79,7 @@ describe('Application Layer Stack', () => {
 
     template.hasResourceProperties('AWS::IAM::Policy', {
       'PolicyDocument': {
-        'Statement': [
+        'Statement': Match.arrayWith([
           {
             'Action': Match.arrayWith([
               'dynamodb:GetItem',
@@ -80,7 +94,7 @@ describe('Application Layer Stack', () => {
                 Match.stringLikeRegexp('TestPersistenceStack'),
             },
           },
-        ],
+        ]),
         'Version': '2012-10-17',
       },
       'PolicyName': Match.stringLikeRegexp('RecipeFunctionServiceRoleDefaultPolicy'),
@@ -100,10 +114,68 @@ describe('Application Layer Stack', () => {
       },
       'Environment': {
         'Variables': {
+          'JWKS_SOURCE': 'https://auth.example.com/.well-known/jwks.json',
           'RECIPE_TABLE_NAME': {
             'Fn::ImportValue':
               Match.stringLikeRegexp('TestPersistenceStack'),
           },
+          'RECIPE_REVISION_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'RECIPE_SHARE_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'RECIPE_TRASH_RETENTION_DAYS': '30',
+          'SHOPPING_LIST_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'HOUSEHOLD_MEMBER_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'HOUSEHOLD_INVITATION_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'API_KEY_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'IDEMPOTENCY_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'WEBHOOK_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'WEBHOOK_DELIVERY_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'MEAL_PLAN_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'CALENDAR_FEED_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'KITCHEN_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'COOK_LOG_TABLE_NAME': {
+            'Fn::ImportValue':
+              Match.stringLikeRegexp('TestPersistenceStack'),
+          },
+          'PUBLIC_BASE_URL': Match.anyValue(),
+          'RATE_LIMIT_BURST': '20',
+          'RATE_LIMIT_PER_SECOND': '5',
+          'MAX_REQUEST_BODY_BYTES': '1048576',
         },
       },
       'FunctionName': 'RecipeFunction',
@@ -223,5 +295,18 @@ describe('Application Layer Stack', () => {
       },
       'Stage': '$default',
     });
+
+    template.hasResourceProperties('AWS::Lambda::Function', {
+      'FunctionName': 'RecipeChangeFunction',
+      'Timeout': 300,
+    });
+
+    template.hasResourceProperties('AWS::Lambda::EventSourceMapping', {
+      'FunctionName': {
+        'Ref': Match.stringLikeRegexp('RecipeChangeFunction'),
+      },
+      'StartingPosition': 'TRIM_HORIZON',
+      'FunctionResponseTypes': ['ReportBatchItemFailures'],
+    });
   });
 });
@@ -10,9 +10,266 @@ test('Recipe table created', () => {
   const template = Template.fromStack(stack);
 
   template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
-    TableName: 'recipes',
-    AttributeDefinitions: [
-      { AttributeName: 'id', AttributeType: 'S' },
+    TableName: 'recipes-by-household',
+    KeySchema: [
+      { AttributeName: 'household_id', KeyType: 'HASH' },
+      { AttributeName: 'id', KeyType: 'RANGE' },
+    ],
+  });
+});
+
+test('Recipe table purges expired trash', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'recipes-by-household',
+    TimeToLiveSpecification: { AttributeName: 'expires_at', Enabled: true },
+  });
+});
+
+test('Recipe table streams changes', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'recipes-by-household',
+    StreamSpecification: { StreamViewType: 'NEW_AND_OLD_IMAGES' },
+  });
+});
+
+test('Recipe revision table created', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'recipe-revisions',
+    KeySchema: [
+      { AttributeName: 'recipe_id', KeyType: 'HASH' },
+      { AttributeName: 'revision', KeyType: 'RANGE' },
+    ],
+  });
+});
+
+test('Recipe share table created', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'recipe-shares',
+    KeySchema: [
+      { AttributeName: 'token', KeyType: 'HASH' },
+    ],
+    GlobalSecondaryIndexes: [
+      {
+        IndexName: 'recipe_id-index',
+        KeySchema: [
+          { AttributeName: 'recipe_id', KeyType: 'HASH' },
+        ],
+        Projection: { ProjectionType: 'ALL' },
+      },
+    ],
+    TimeToLiveSpecification: { AttributeName: 'expires_at', Enabled: true },
+  });
+});
+
+test('Shopping list table created', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'shopping-lists-by-household',
+    KeySchema: [
+      { AttributeName: 'household_id', KeyType: 'HASH' },
+      { AttributeName: 'id', KeyType: 'RANGE' },
+    ],
+  });
+});
+
+test('Household member table created', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'household-members',
+    KeySchema: [
+      { AttributeName: 'subject', KeyType: 'HASH' },
+    ],
+    GlobalSecondaryIndexes: [
+      {
+        IndexName: 'household_id-index',
+        KeySchema: [
+          { AttributeName: 'household_id', KeyType: 'HASH' },
+        ],
+        Projection: { ProjectionType: 'ALL' },
+      },
+    ],
+  });
+});
+
+test('Household invitation table expires invitations', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'household-invitations',
+    TimeToLiveSpecification: { AttributeName: 'expires_at', Enabled: true },
+  });
+});
+
+test('API key table created', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'api-keys',
+    KeySchema: [
+      { AttributeName: 'id', KeyType: 'HASH' },
+    ],
+    GlobalSecondaryIndexes: [
+      {
+        IndexName: 'household_id-index',
+        KeySchema: [
+          { AttributeName: 'household_id', KeyType: 'HASH' },
+        ],
+        Projection: { ProjectionType: 'ALL' },
+      },
+    ],
+  });
+});
+
+test('Idempotency table expires keys', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'idempotency-keys',
+    KeySchema: [
+      { AttributeName: 'key', KeyType: 'HASH' },
+    ],
+    TimeToLiveSpecification: { AttributeName: 'expires_at', Enabled: true },
+  });
+});
+
+test('Webhook tables created', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'webhooks',
+    KeySchema: [
+      { AttributeName: 'household_id', KeyType: 'HASH' },
+      { AttributeName: 'id', KeyType: 'RANGE' },
+    ],
+  });
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'webhook-deliveries',
+    KeySchema: [
+      { AttributeName: 'webhook_id', KeyType: 'HASH' },
+      { AttributeName: 'attempt_id', KeyType: 'RANGE' },
+    ],
+    TimeToLiveSpecification: { AttributeName: 'expires_at', Enabled: true },
+  });
+});
+
+test('Meal plan tables created', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'meal-plans',
+    KeySchema: [
+      { AttributeName: 'household_id', KeyType: 'HASH' },
+      { AttributeName: 'id', KeyType: 'RANGE' },
+    ],
+  });
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'calendar-feeds',
+    KeySchema: [
+      { AttributeName: 'token', KeyType: 'HASH' },
+    ],
+    GlobalSecondaryIndexes: [
+      {
+        IndexName: 'subject-index',
+        KeySchema: [
+          { AttributeName: 'subject', KeyType: 'HASH' },
+        ],
+        Projection: { ProjectionType: 'ALL' },
+      },
+    ],
+  });
+});
+
+test('Kitchen table created', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'kitchens',
+    KeySchema: [
+      { AttributeName: 'household_id', KeyType: 'HASH' },
+    ],
+  });
+});
+
+test('Cook log table created', () => {
+  const app = new cdk.App();
+  // WHEN
+  const stack = new PersistenceStack(app, 'TestPersistenceStack');
+  // THEN
+  const template = Template.fromStack(stack);
+
+  template.hasResourceProperties('AWS::DynamoDB::GlobalTable', {
+    TableName: 'cook-log',
+    KeySchema: [
+      { AttributeName: 'household_id', KeyType: 'HASH' },
+      { AttributeName: 'id', KeyType: 'RANGE' },
+    ],
+    GlobalSecondaryIndexes: [
+      {
+        IndexName: 'recipe-index',
+        KeySchema: [
+          { AttributeName: 'recipe_id', KeyType: 'HASH' },
+          { AttributeName: 'cooked_on', KeyType: 'RANGE' },
+        ],
+        Projection: { ProjectionType: 'ALL' },
+      },
     ],
   });
 });
@@ -8,6 +8,11 @@ import { defineConfig } from '@playwright/test';
 const baseUrl = process.env.PLAYWRIGHT_BASE_URL ??
   'https://api.sandbox.mealplanner.chittyinsights.dev';
 
+/**
+ * Bearer token sent with every request; the API rejects calls without one.
+ */
+const apiToken = process.env.PLAYWRIGHT_API_TOKEN ?? '';
+
 /**
  * See https://playwright.dev/docs/test-configuration.
  */
@@ -0,0 +1,90 @@
+import { test, expect } from '@playwright/test';
+import { createData } from './recipeConstants';
+
+let keyId: string;
+let key: string;
+
+test.describe('API Keys', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test('Create Read Key', async ({ request }) => {
+    const response = await request.post('./recipes/api-keys', {
+      data: { name: 'Playwright', scope: 'read' },
+    });
+
+    expect(response.status()).toEqual(201);
+    const created = await response.json();
+    expect(created).toEqual({
+      id: expect.any(String),
+      name: 'Playwright',
+      scope: 'read',
+      created_by: expect.any(String),
+      created_at: expect.any(Number),
+      last_used_at: null,
+      key: expect.stringMatching(/^mpk_/),
+    });
+    keyId = created.id;
+    key = created.key;
+  });
+
+  test('Create Unnamed Key', async ({ request }) => {
+    const response = await request.post('./recipes/api-keys', {
+      data: { name: ' ', scope: 'read' },
+    });
+
+    expect(response.status()).toEqual(422);
+  });
+
+  test('List Keys', async ({ request }) => {
+    const response = await request.get('./recipes/api-keys');
+
+    expect(response.ok()).toBeTruthy();
+    const keys = await response.json();
+    expect(keys.map((listed: { id: string }) => listed.id)).toContain(keyId);
+    expect(keys[0].key).toBeUndefined();
+    expect(keys[0].hash).toBeUndefined();
+  });
+
+  test.describe('With Key', () => {
+    test.use({ extraHTTPHeaders: { 'Accept': 'application/json' } });
+
+    test('Read Recipes', async ({ request }) => {
+      const response = await request.get('./recipes', { headers: { 'X-Api-Key': key } });
+
+      expect(response.ok()).toBeTruthy();
+    });
+
+    test('Write Recipe', async ({ request }) => {
+      const response = await request.post('./recipes', {
+        headers: { 'X-Api-Key': key },
+        data: createData,
+      });
+
+      expect(response.status()).toEqual(403);
+    });
+
+    test('Create Another Key', async ({ request }) => {
+      const response = await request.post('./recipes/api-keys', {
+        headers: { 'X-Api-Key': key },
+        data: { name: 'Sneaky', scope: 'read' },
+      });
+
+      expect(response.status()).toEqual(403);
+    });
+
+    test('Wrong Key', async ({ request }) => {
+      const response = await request.get('./recipes', { headers: { 'X-Api-Key': `${key}0` } });
+
+      expect(response.status()).toEqual(401);
+    });
+  });
+
+  test('Revoke Key', async ({ request }) => {
+    const response = await request.delete(`./recipes/api-keys/${keyId}`);
+
+    expect(response.status()).toEqual(204);
+
+    const revoked = await request.get('./recipes', { headers: { 'X-Api-Key': key } });
+    expect(revoked.status()).toEqual(401);
+  });
+});
@@ -0,0 +1,26 @@
+import { test, expect } from '@playwright/test';
+
+test.describe('Unauthenticated', () => {
+  test.use({ extraHTTPHeaders: { 'Accept': 'application/json' } });
+
+  test('Ping Without Token', async ({ request }) => {
+    const response = await request.get('./recipes/ping');
+
+    expect(response.ok()).toBeTruthy();
+  });
+
+  test('List Recipes Without Token', async ({ request }) => {
+    const response = await request.get('./recipes');
+
+    expect(response.status()).toEqual(401);
+    expect(response.headers()['www-authenticate']).toEqual('Bearer');
+  });
+
+  test('List Recipes With Invalid Token', async ({ request }) => {
+    const response = await request.get('./recipes', {
+      headers: { 'Authorization': 'Bearer not-a-token' },
+    });
+
+    expect(response.status()).toEqual(401);
+  });
+});
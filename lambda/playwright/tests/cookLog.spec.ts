@@ -0,0 +1,92 @@
+import { test, expect } from '@playwright/test';
+
+let favouriteUuid: string;
+let untriedUuid: string;
+let entryUuid: string;
+
+test.describe('Cook Log', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test.beforeAll('Create Recipes', async ({ request }) => {
+    const favourite = await request.post('./recipes', { data: { name: 'Lasagne' } });
+    favouriteUuid = (await favourite.json()).id;
+    const untried = await request.post('./recipes', { data: { name: 'Ramen' } });
+    untriedUuid = (await untried.json()).id;
+  });
+
+  test('Record Cooked Recipe', async ({ request }) => {
+    const response = await request.post(`./recipes/${favouriteUuid}/cook-log`, {
+      data: {
+        cooked_on: '2025-01-10',
+        rating: 5,
+        notes: 'Everyone had seconds.',
+        modifications: ['Added spinach'],
+      },
+    });
+
+    expect(response.status()).toEqual(201);
+    const entry = await response.json();
+    expect(entry).toMatchObject({ recipe_id: favouriteUuid, cooked_on: '2025-01-10', rating: 5 });
+    entryUuid = entry.id;
+  });
+
+  test('Reject Rating Out Of Range', async ({ request }) => {
+    const response = await request.post(`./recipes/${favouriteUuid}/cook-log`, {
+      data: { rating: 6 },
+    });
+
+    expect(response.status()).toEqual(422);
+  });
+
+  test('Read Stats On Recipe', async ({ request }) => {
+    await request.post(`./recipes/${favouriteUuid}/cook-log`, {
+      data: { cooked_on: '2025-01-03', rating: 4 },
+    });
+
+    const response = await request.get(`./recipes/${favouriteUuid}`);
+
+    expect(response.ok()).toBeTruthy();
+    expect((await response.json()).stats).toEqual({
+      times_cooked: 2,
+      average_rating: 4.5,
+      last_cooked: '2025-01-10',
+    });
+  });
+
+  test('List Cook Log Newest First', async ({ request }) => {
+    const response = await request.get(`./recipes/${favouriteUuid}/cook-log`);
+
+    expect(response.ok()).toBeTruthy();
+    const days = (await response.json()).map((entry: { cooked_on: string }) => entry.cooked_on);
+    expect(days).toEqual(['2025-01-10', '2025-01-03']);
+  });
+
+  test('List Cook Log Of Unknown Recipe', async ({ request }) => {
+    const response = await request.get(
+      './recipes/00000000-0000-0000-0000-000000000000/cook-log',
+    );
+
+    expect(response.status()).toEqual(404);
+  });
+
+  test('Sort By Rating', async ({ request }) => {
+    const response = await request.get(
+      `./recipes?ids=${untriedUuid},${favouriteUuid}&sort=rating`,
+    );
+
+    expect(response.ok()).toBeTruthy();
+    const ids = (await response.json()).map((recipe: { id: string }) => recipe.id);
+    expect(ids).toEqual([favouriteUuid, untriedUuid]);
+  });
+
+  test('Delete Entry', async ({ request }) => {
+    const response = await request.delete(`./recipes/${favouriteUuid}/cook-log/${entryUuid}`);
+
+    expect(response.status()).toEqual(204);
+  });
+
+  test.afterAll('Clean Up', async ({ request }) => {
+    await request.delete(`./recipes/${favouriteUuid}`);
+    await request.delete(`./recipes/${untriedUuid}`);
+  });
+});
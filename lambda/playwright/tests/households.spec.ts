@@ -0,0 +1,53 @@
+import { test, expect } from '@playwright/test';
+
+let token: string;
+
+test.describe('Households', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test('Read Current Household', async ({ request }) => {
+    const response = await request.get('./recipes/households/current');
+
+    expect(response.ok()).toBeTruthy();
+    const household = await response.json();
+    expect(household.role).toEqual('owner');
+    expect(household.members).toContainEqual(expect.objectContaining({ role: 'owner' }));
+  });
+
+  test('Invite Viewer', async ({ request }) => {
+    const response = await request.post('./recipes/households/invitations', {
+      data: { role: 'viewer' },
+    });
+
+    expect(response.status()).toEqual(201);
+    const invitation = await response.json();
+    expect(invitation).toEqual({
+      token: expect.any(String),
+      household_id: expect.any(String),
+      role: 'viewer',
+      created_by: expect.any(String),
+      expires_at: expect.any(Number),
+    });
+    token = invitation.token;
+  });
+
+  test('Invite Owner', async ({ request }) => {
+    const response = await request.post('./recipes/households/invitations', {
+      data: { role: 'owner' },
+    });
+
+    expect(response.status()).toEqual(422);
+  });
+
+  test('Join Own Household', async ({ request }) => {
+    const response = await request.post('./recipes/households/join', { data: { token } });
+
+    expect(response.status()).toEqual(409);
+  });
+
+  test('Reuse Invitation', async ({ request }) => {
+    const response = await request.post('./recipes/households/join', { data: { token } });
+
+    expect(response.status()).toEqual(404);
+  });
+});
@@ -0,0 +1,48 @@
+import { test, expect } from '@playwright/test';
+import { randomUUID } from 'crypto';
+import { createData } from './recipeConstants';
+
+let recipeUuid: string;
+
+test.describe('Idempotency Keys', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  const idempotencyKey = randomUUID();
+
+  test('Retry Replays Response', async ({ request }) => {
+    const headers = { 'Idempotency-Key': idempotencyKey };
+    const first = await request.post('./recipes', { headers, data: createData });
+
+    expect(first.status()).toBe(201);
+    const created = await first.json();
+    recipeUuid = created.id;
+
+    const retry = await request.post('./recipes', { headers, data: createData });
+
+    expect(retry.status()).toBe(201);
+    expect(retry.headers()['idempotent-replayed']).toEqual('true');
+    expect(await retry.json()).toEqual(created);
+  });
+
+  test('Reused Key With Different Body', async ({ request }) => {
+    const response = await request.post('./recipes', {
+      headers: { 'Idempotency-Key': idempotencyKey },
+      data: { ...createData, name: 'Another Recipe' },
+    });
+
+    expect(response.status()).toBe(422);
+  });
+
+  test('Blank Key', async ({ request }) => {
+    const response = await request.post('./recipes', {
+      headers: { 'Idempotency-Key': ' ' },
+      data: createData,
+    });
+
+    expect(response.status()).toBe(400);
+  });
+
+  test.afterAll('Delete Recipe', async ({ request }) => {
+    await request.delete(`./recipes/${recipeUuid}?permanent=true`);
+  });
+});
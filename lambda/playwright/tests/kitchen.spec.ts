@@ -0,0 +1,79 @@
+import { test, expect } from '@playwright/test';
+
+let sousVideUuid: string;
+let planUuid: string;
+
+test.describe('Kitchen', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test.beforeAll('Create Recipe', async ({ request }) => {
+    const response = await request.post('./recipes', {
+      data: {
+        name: 'Sous Vide Steak',
+        steps: [{
+          instruction: 'Cook sous vide.',
+          minutes: 90,
+          passive: true,
+          equipment: [{ appliance: 'sous_vide', celsius: 57 }],
+        }],
+      },
+    });
+    sousVideUuid = (await response.json()).id;
+  });
+
+  test('Declare Kitchen', async ({ request }) => {
+    const response = await request.put('./recipes/kitchen', {
+      data: { appliances: { oven: 1, burner: 4, sous_vide: 0 } },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    expect((await response.json()).appliances).toEqual({ oven: 1, burner: 4 });
+  });
+
+  test('List Cookable Recipes', async ({ request }) => {
+    const response = await request.get('./recipes?cookable=true');
+
+    expect(response.ok()).toBeTruthy();
+    const ids = (await response.json()).map((recipe: { id: string }) => recipe.id);
+    expect(ids).not.toContain(sousVideUuid);
+  });
+
+  test('Flag Missing Cookware In Timeline', async ({ request }) => {
+    const response = await request.post('./recipes/timeline', {
+      data: { recipe_ids: [sousVideUuid], serve_at: '2025-11-27T19:00' },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    expect((await response.json()).conflicts).toEqual([{
+      appliance: 'sous_vide',
+      needed: 1,
+      available: 0,
+      starts_at: '2025-11-27T17:30',
+      ends_at: '2025-11-27T19:00',
+      steps: [{ recipe_id: sousVideUuid, step: 0 }],
+    }]);
+  });
+
+  test('Flag Conflicts In Meal Plan', async ({ request }) => {
+    const plan = await request.post('./recipes/plans', {
+      data: {
+        name: 'Steak Night',
+        slots: [{ date: '2025-11-28', meal: 'dinner', recipe_id: sousVideUuid }],
+      },
+    });
+    planUuid = (await plan.json()).id;
+
+    const response = await request.get(`./recipes/plans/${planUuid}/conflicts`);
+
+    expect(response.ok()).toBeTruthy();
+    const conflicts = await response.json();
+    expect(conflicts).toHaveLength(1);
+    expect(conflicts[0].serve_at).toEqual('2025-11-28T18:30');
+  });
+
+  test.afterAll('Clean Up', async ({ request }) => {
+    await request.delete(`./recipes/plans/${planUuid}`);
+    await request.delete(`./recipes/${sousVideUuid}`);
+    await request.put('./recipes/kitchen', { data: { appliances: {} } });
+  });
+});
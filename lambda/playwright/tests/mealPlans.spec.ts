@@ -0,0 +1,106 @@
+import { test, expect } from '@playwright/test';
+import { createData } from './recipeConstants';
+
+let recipeUuid: string;
+let planUuid: string;
+let slotUuid: string;
+let token: string;
+
+test.describe('Meal Plans', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test.beforeAll('Create Recipe', async ({ request }) => {
+    const response = await request.post('./recipes', {
+      data: { ...createData, total_minutes: 45 },
+    });
+    recipeUuid = (await response.json()).id;
+  });
+
+  test('Create Plan', async ({ request }) => {
+    const response = await request.post('./recipes/plans', {
+      data: {
+        name: 'This Week',
+        slots: [{ date: '2025-10-12', meal: 'dinner', recipe_id: recipeUuid }],
+      },
+    });
+
+    expect(response.status()).toEqual(201);
+    const plan = await response.json();
+    expect(plan.slots).toEqual([
+      { id: expect.any(String), date: '2025-10-12', meal: 'dinner', recipe_id: recipeUuid },
+    ]);
+    planUuid = plan.id;
+    slotUuid = plan.slots[0].id;
+  });
+
+  test('Replace Plan Keeps Slot Ids', async ({ request }) => {
+    const response = await request.put(`./recipes/plans/${planUuid}`, {
+      data: {
+        name: 'Sunday',
+        slots: [
+          { id: slotUuid, date: '2025-10-12', meal: 'dinner', recipe_id: recipeUuid },
+          { date: '2025-10-13', meal: 'lunch', recipe_id: recipeUuid },
+        ],
+      },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    const slots = (await response.json()).slots;
+    expect(slots[0].id).toEqual(slotUuid);
+    expect(slots[1].id).not.toEqual(slotUuid);
+  });
+
+  test('Create Plan With Invalid Date', async ({ request }) => {
+    const response = await request.post('./recipes/plans', {
+      data: {
+        name: 'This Week',
+        slots: [{ date: '2025-02-30', meal: 'dinner', recipe_id: recipeUuid }],
+      },
+    });
+
+    expect(response.status()).toEqual(422);
+    expect(await response.json()).toEqual({
+      errors: [{ pointer: '/slots/0/date', message: 'must be a date as YYYY-MM-DD' }],
+    });
+  });
+
+  test('Calendar Feed Is Stable', async ({ request }) => {
+    const first = await request.post('./recipes/plans/calendar-feed');
+    expect([200, 201]).toContain(first.status());
+    token = (await first.json()).token;
+    expect(token).toMatch(/^cal_/);
+
+    const second = await request.post('./recipes/plans/calendar-feed');
+    expect(second.status()).toEqual(200);
+    expect((await second.json()).token).toEqual(token);
+  });
+
+  test('Read Plan As Calendar', async ({ request }) => {
+    const response = await request.get(
+        `./recipes/plans/${planUuid}/calendar.ics?token=${token}`,
+    );
+
+    expect(response.ok()).toBeTruthy();
+    expect(response.headers()['content-type']).toContain('text/calendar');
+    const calendar = await response.text();
+    expect(calendar).toContain(`UID:${slotUuid}@meal-planner`);
+    expect(calendar).toContain('DTSTART:20251012T183000');
+    expect(calendar).toContain(`SUMMARY:Dinner: ${createData.name}`);
+    expect(calendar).toContain('TRIGGER:-PT45M');
+  });
+
+  test('Revoke Calendar Feed', async ({ request }) => {
+    const response = await request.delete('./recipes/plans/calendar-feed');
+    expect(response.status()).toEqual(204);
+
+    const calendar = await request.get(
+        `./recipes/plans/${planUuid}/calendar.ics?token=${token}`,
+    );
+    expect(calendar.status()).toEqual(404);
+  });
+
+  test('Delete Plan', async ({ request }) => {
+    const response = await request.delete(`./recipes/plans/${planUuid}`);
+    expect(response.status()).toEqual(204);
+  });
+});
@@ -0,0 +1,26 @@
+import { test, expect } from '@playwright/test';
+
+test.describe('OpenAPI', () => {
+  test.use({ extraHTTPHeaders: { 'Accept': 'application/json' } });
+
+  test('Read Specification', async ({ request }) => {
+    const response = await request.get('./recipes/openapi.json');
+
+    expect(response.ok()).toBeTruthy();
+    const spec = await response.json();
+    expect(spec.openapi).toMatch(/^3\./);
+    expect(Object.keys(spec.paths)).toContain('/{id}');
+    expect(Object.keys(spec.components.schemas)).toEqual(
+      expect.arrayContaining(['Recipe', 'PostRecipe', 'PutRecipe', 'PatchRecipe']),
+    );
+  });
+
+  test('Read Docs', async ({ request }) => {
+    const response = await request.get('./recipes/docs/', {
+      headers: { 'Accept': 'text/html' },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    expect(response.headers()['content-type']).toContain('text/html');
+  });
+});
@@ -0,0 +1,113 @@
+import { test, expect } from '@playwright/test';
+import { NIL_UUID, createData } from './recipeConstants';
+
+let createdUuid: string;
+
+test.describe('Batch', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test('Create And Update', async ({ request }) => {
+    const response = await request.post('./recipes/batch', {
+      data: {
+        operations: [
+          { op: 'create', recipe: createData },
+          { op: 'create', recipe: { ...createData, name: 'Second Batch Recipe' } },
+        ],
+      },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    const { results } = await response.json();
+    expect(results.map((result: { status: number }) => result.status)).toEqual([201, 201]);
+    createdUuid = results[0].id;
+
+    const update = await request.post('./recipes/batch', {
+      data: {
+        operations: [
+          { op: 'update', recipe: { id: createdUuid, ...createData, name: 'Batch Updated' } },
+          { op: 'delete', id: results[1].id },
+        ],
+      },
+    });
+
+    expect(update.ok()).toBeTruthy();
+    expect(await update.json()).toEqual({
+      results: [
+        { id: createdUuid, status: 200 },
+        { id: results[1].id, status: 204 },
+      ],
+    });
+
+    const read = await request.get(`./recipes/${createdUuid}`);
+    expect((await read.json()).name).toEqual('Batch Updated');
+
+    const trash = await request.get('./recipes/trash');
+    const trashed = (await trash.json()).map((recipe: { id: string }) => recipe.id);
+    expect(trashed).toContain(results[1].id);
+  });
+
+  test('Missing Recipes Are Not Found', async ({ request }) => {
+    const response = await request.post('./recipes/batch', {
+      data: {
+        operations: [
+          { op: 'update', recipe: { id: NIL_UUID, ...createData } },
+          { op: 'update', recipe: { id: createdUuid, ...createData, name: 'Batch Updated' } },
+        ],
+      },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    expect(await response.json()).toEqual({
+      results: [
+        { id: NIL_UUID, status: 404 },
+        { id: createdUuid, status: 200 },
+      ],
+    });
+  });
+
+  test('Atomic Batch Fails As A Whole', async ({ request }) => {
+    const response = await request.post('./recipes/batch?atomic=true', {
+      data: {
+        operations: [
+          { op: 'update', recipe: { id: createdUuid, ...createData, name: 'Never Stored' } },
+          { op: 'delete', id: NIL_UUID },
+        ],
+      },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    expect(await response.json()).toEqual({
+      results: [
+        { id: createdUuid, status: 424 },
+        { id: NIL_UUID, status: 404 },
+      ],
+    });
+
+    const read = await request.get(`./recipes/${createdUuid}`);
+    expect((await read.json()).name).toEqual('Batch Updated');
+  });
+
+  test('Invalid Operations', async ({ request }) => {
+    const response = await request.post('./recipes/batch', {
+      data: {
+        operations: [
+          { op: 'create', recipe: { name: '' } },
+          { op: 'delete', id: createdUuid },
+          { op: 'delete', id: createdUuid },
+        ],
+      },
+    });
+
+    expect(response.status()).toEqual(422);
+    expect(await response.json()).toEqual({
+      errors: [
+        { pointer: '/operations/0/recipe/name', message: 'must not be blank' },
+        { pointer: '/operations/2/id', message: 'must not repeat the recipe of an earlier operation' },
+      ],
+    });
+  });
+
+  test.afterAll('Delete Recipe', async ({ request }) => {
+    await request.delete(`./recipes/${createdUuid}?permanent=true`);
+  });
+});
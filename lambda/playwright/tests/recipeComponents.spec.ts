@@ -0,0 +1,79 @@
+import { test, expect } from '@playwright/test';
+import { createData } from './recipeConstants';
+
+let doughUuid: string;
+let pizzaUuid: string;
+let listUuid: string;
+
+test.describe('Components', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test.beforeAll('Create Recipes', async ({ request }) => {
+    const dough = await request.post('./recipes', { data: createData });
+    doughUuid = (await dough.json()).id;
+
+    const pizza = await request.post('./recipes', {
+      data: {
+        name: 'Playwright Pizza',
+        ingredients: [
+          { name: 'Pizza Dough', quantity: 2, unit: 'batch', recipe_id: doughUuid },
+          { name: 'Mozzarella', quantity: 250, unit: 'g' },
+        ],
+      },
+    });
+    pizzaUuid = (await pizza.json()).id;
+  });
+
+  test('Read Expanded Recipe', async ({ request }) => {
+    const response = await request.get(`./recipes/${pizzaUuid}?expand=true`);
+
+    expect(response.ok()).toBeTruthy();
+    const responseBody = await response.json();
+    expect(responseBody.components).toHaveLength(1);
+    expect(responseBody.components[0]).toMatchObject({
+      ingredient: 0,
+      batches: 2,
+      recipe: { id: doughUuid },
+    });
+    expect(responseBody.components[0].recipe.ingredients[0])
+        .toEqual({ name: 'Flour', quantity: 1000, unit: 'g' });
+  });
+
+  test('Read Unexpanded Recipe', async ({ request }) => {
+    const response = await request.get(`./recipes/${pizzaUuid}`);
+
+    expect(response.ok()).toBeTruthy();
+    expect(await response.json()).not.toHaveProperty('components');
+  });
+
+  test('Shopping List From Recipes', async ({ request }) => {
+    const response = await request.post('./recipes/shopping-lists/from-recipes', {
+      data: { name: 'Pizza Night', recipe_ids: [pizzaUuid] },
+    });
+
+    expect(response.status()).toBe(201);
+    const responseBody = await response.json();
+    const items = responseBody.aisles.flatMap(
+        (group: { items: { name: string, quantity: string }[] }) => group.items,
+    );
+    expect(items).toContainEqual(expect.objectContaining({ name: 'Flour', quantity: '1000 g' }));
+    expect(items).toContainEqual(expect.objectContaining({ name: 'Mozzarella', quantity: '250 g' }));
+    listUuid = responseBody.id;
+  });
+
+  test('Read Cyclic Recipe', async ({ request }) => {
+    await request.patch(`./recipes/${doughUuid}`, {
+      data: { ingredients: [{ name: 'Pizza', quantity: 1, recipe_id: pizzaUuid }] },
+    });
+
+    const response = await request.get(`./recipes/${pizzaUuid}?expand=true`);
+
+    expect(response.status()).toEqual(422);
+  });
+
+  test.afterAll('Delete Recipes', async ({ request }) => {
+    await request.delete(`./recipes/shopping-lists/${listUuid}`);
+    await request.delete(`./recipes/${pizzaUuid}`);
+    await request.delete(`./recipes/${doughUuid}`);
+  });
+});
@@ -2,8 +2,21 @@ export const NIL_UUID = '00000000-0000-0000-0000-000000000000';
 
 export const createData = {
   name: 'Playwright Recipe',
+  ingredients: [
+    { name: 'Flour', quantity: 500, unit: 'g' },
+    { name: 'Water', quantity: 350, unit: 'ml' },
+  ],
+  steps: [
+    { instruction: 'Mix the flour and water.' },
+    { instruction: 'Knead for ten minutes.' },
+  ],
 };
 
 export const updateData = {
   name: 'Updated Playwright Recipe',
 };
+
+export const emptyLists = {
+  ingredients: [],
+  steps: [],
+};
@@ -22,6 +22,29 @@ test('Create Recipe w/ Null Name', async ({ request }) => {
   expect(response.status()).toEqual(422);
 });
 
+test('Create Recipe w/ Blank Name And Bad Quantity', async ({ request }) => {
+  const response = await request.post(`./recipes`, {
+    data: {
+      name: '  ',
+      ingredients: [{ name: 'Flour', quantity: -1 }],
+    },
+  });
+
+  expect(response.status()).toEqual(422);
+  expect(await response.json()).toEqual({
+    errors: [
+      { pointer: '/name', message: 'must not be blank' },
+      { pointer: '/ingredients/0/quantity', message: 'must be greater than zero' },
+    ],
+  });
+});
+
+test('Update Recipe w/ Empty Name', async ({ request }) => {
+  const response = await request.patch(`./recipes/${NIL_UUID}`, { data: { name: '' } });
+
+  expect(response.status()).toEqual(422);
+});
+
 test('Read Non-existent Recipe', async ({ request }) => {
   const response = await request.get(`./recipes/${NIL_UUID}`);
 
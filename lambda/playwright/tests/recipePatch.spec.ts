@@ -0,0 +1,79 @@
+import { test, expect } from '@playwright/test';
+import { createData } from './recipeConstants';
+
+let recipeUuid: string;
+
+test.describe('Patch Formats', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test.beforeAll('Create Recipe', async ({ request }) => {
+    const response = await request.post('./recipes', { data: createData });
+    recipeUuid = (await response.json()).id;
+  });
+
+  test('Merge Patch', async ({ request }) => {
+    const response = await request.patch(`./recipes/${recipeUuid}`, {
+      headers: { 'Content-Type': 'application/merge-patch+json' },
+      data: { name: 'Merged Recipe', steps: [{ instruction: 'Bake.' }] },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    const recipe = await response.json();
+    expect(recipe.name).toEqual('Merged Recipe');
+    expect(recipe.ingredients).toEqual(createData.ingredients);
+    expect(recipe.steps).toEqual([{ instruction: 'Bake.' }]);
+  });
+
+  test('JSON Patch', async ({ request }) => {
+    const response = await request.patch(`./recipes/${recipeUuid}`, {
+      headers: { 'Content-Type': 'application/json-patch+json' },
+      data: [
+        { op: 'test', path: '/ingredients/0/name', value: 'Flour' },
+        { op: 'replace', path: '/ingredients/0/quantity', value: 450 },
+        { op: 'add', path: '/ingredients/-', value: { name: 'Salt', quantity: 10, unit: 'g' } },
+        { op: 'add', path: '/steps/0', value: { instruction: 'Mix.' } },
+      ],
+    });
+
+    expect(response.ok()).toBeTruthy();
+    const recipe = await response.json();
+    expect(recipe.ingredients.map((ingredient: { name: string }) => ingredient.name))
+      .toEqual(['Flour', 'Water', 'Salt']);
+    expect(recipe.ingredients[0].quantity).toEqual(450);
+    expect(recipe.steps).toEqual([{ instruction: 'Mix.' }, { instruction: 'Bake.' }]);
+  });
+
+  test('Failed Test Operation', async ({ request }) => {
+    const response = await request.patch(`./recipes/${recipeUuid}`, {
+      headers: { 'Content-Type': 'application/json-patch+json' },
+      data: [
+        { op: 'test', path: '/name', value: 'Someone Else\'s Recipe' },
+        { op: 'replace', path: '/name', value: 'Overwritten' },
+      ],
+    });
+
+    expect(response.status()).toEqual(409);
+  });
+
+  test('Patch Breaks Rules', async ({ request }) => {
+    const response = await request.patch(`./recipes/${recipeUuid}`, {
+      headers: { 'Content-Type': 'application/json-patch+json' },
+      data: [
+        { op: 'replace', path: '/id', value: '00000000-0000-0000-0000-000000000000' },
+        { op: 'replace', path: '/steps/0/instruction', value: ' ' },
+      ],
+    });
+
+    expect(response.status()).toEqual(422);
+    expect(await response.json()).toEqual({
+      errors: [
+        { pointer: '/id', message: 'must not be changed' },
+        { pointer: '/steps/0/instruction', message: 'must not be blank' },
+      ],
+    });
+  });
+
+  test.afterAll('Delete Recipe', async ({ request }) => {
+    await request.delete(`./recipes/${recipeUuid}?permanent=true`);
+  });
+});
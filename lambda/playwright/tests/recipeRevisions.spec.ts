@@ -0,0 +1,63 @@
+import { test, expect } from '@playwright/test';
+import { createData, updateData } from './recipeConstants';
+
+let recipeUuid: string;
+
+test.describe('Revision History', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test.beforeAll('Create and Update Recipe', async ({ request }) => {
+    const created = await request.post('./recipes', { data: createData });
+    recipeUuid = (await created.json()).id;
+
+    await request.patch(`./recipes/${recipeUuid}`, { data: updateData });
+  });
+
+  test('List Revisions', async ({ request }) => {
+    const response = await request.get(`./recipes/${recipeUuid}/revisions`);
+
+    expect(response.ok()).toBeTruthy();
+    const revisions = await response.json();
+    expect(revisions.map((revision: { revision: number }) => revision.revision)).toEqual([1, 2]);
+    expect(revisions[1].changes).toEqual([
+      { field: 'name', old: createData.name, new: updateData.name },
+    ]);
+  });
+
+  test('Read Original Revision', async ({ request }) => {
+    const response = await request.get(`./recipes/${recipeUuid}/revisions/1`);
+
+    expect(response.ok()).toBeTruthy();
+    expect((await response.json()).recipe).toEqual({
+      id: recipeUuid,
+      household_id: expect.any(String),
+      ...createData,
+    });
+  });
+
+  test('Restore Original Revision', async ({ request }) => {
+    const response = await request.post(`./recipes/${recipeUuid}/revisions/1/restore`);
+
+    expect(response.ok()).toBeTruthy();
+    expect(await response.json()).toEqual({
+      id: recipeUuid,
+      household_id: expect.any(String),
+      ...createData,
+    });
+
+    const revisions = await request.get(`./recipes/${recipeUuid}/revisions`);
+    expect(await revisions.json()).toHaveLength(3);
+  });
+
+  test('Read Non-existent Revision', async ({ request }) => {
+    const response = await request.get(`./recipes/${recipeUuid}/revisions/99`);
+
+    expect(response.status()).toEqual(404);
+  });
+
+  test.afterAll('Delete Recipe', async ({ request }) => {
+    const response = await request.delete(`./recipes/${recipeUuid}`);
+
+    expect(response.status()).toEqual(204);
+  });
+});
@@ -0,0 +1,70 @@
+import { test, expect } from '@playwright/test';
+import { createData } from './recipeConstants';
+
+let recipeUuid: string;
+let token: string;
+
+test.describe('Share Links', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test.beforeAll('Create Recipe', async ({ request }) => {
+    const response = await request.post('./recipes', { data: createData });
+    recipeUuid = (await response.json()).id;
+  });
+
+  test('Share Recipe', async ({ request }) => {
+    const response = await request.post(`./recipes/${recipeUuid}/shares`, {
+      data: { expires_in_days: 1 },
+    });
+
+    expect(response.status()).toEqual(201);
+    const link = await response.json();
+    expect(link.recipe_id).toEqual(recipeUuid);
+    expect(link.expires_at).toEqual(expect.any(Number));
+    token = link.token;
+  });
+
+  test('List Shares', async ({ request }) => {
+    const response = await request.get(`./recipes/${recipeUuid}/shares`);
+
+    expect(response.ok()).toBeTruthy();
+    const tokens = (await response.json()).map((link: { token: string }) => link.token);
+    expect(tokens).toContain(token);
+  });
+
+  test.describe('Without Account', () => {
+    test.use({ extraHTTPHeaders: { 'Accept': 'application/json' } });
+
+    test('Read Shared Recipe', async ({ request }) => {
+      const response = await request.get(`./recipes/shared/${token}`);
+
+      expect(response.ok()).toBeTruthy();
+      expect(await response.json()).toEqual(createData);
+    });
+
+    test('Read Shared Recipe As Page', async ({ request }) => {
+      const response = await request.get(`./recipes/shared/${token}`, {
+        headers: { 'Accept': 'text/html' },
+      });
+
+      expect(response.ok()).toBeTruthy();
+      expect(response.headers()['content-type']).toContain('text/html');
+      expect(await response.text()).toContain(`<h1>${createData.name}</h1>`);
+    });
+  });
+
+  test('Revoke Share', async ({ request }) => {
+    const response = await request.delete(`./recipes/${recipeUuid}/shares/${token}`);
+
+    expect(response.status()).toEqual(204);
+
+    const shared = await request.get(`./recipes/shared/${token}`);
+    expect(shared.status()).toEqual(404);
+  });
+
+  test.afterAll('Delete Recipe', async ({ request }) => {
+    const response = await request.delete(`./recipes/${recipeUuid}?permanent=true`);
+
+    expect(response.status()).toEqual(204);
+  });
+});
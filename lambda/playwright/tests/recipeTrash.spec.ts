@@ -0,0 +1,65 @@
+import { test, expect } from '@playwright/test';
+import { createData } from './recipeConstants';
+
+let recipeUuid: string;
+
+test.describe('Trash', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test.beforeAll('Create Recipe', async ({ request }) => {
+    const response = await request.post('./recipes', { data: createData });
+    recipeUuid = (await response.json()).id;
+  });
+
+  test('Delete Recipe', async ({ request }) => {
+    const response = await request.delete(`./recipes/${recipeUuid}`);
+
+    expect(response.status()).toEqual(204);
+  });
+
+  test('Deleted Recipe Is Hidden', async ({ request }) => {
+    const read = await request.get(`./recipes/${recipeUuid}`);
+    expect(read.status()).toEqual(404);
+
+    const list = await request.get('./recipes');
+    expect(list.ok()).toBeTruthy();
+    const ids = (await list.json()).map((recipe: { id: string }) => recipe.id);
+    expect(ids).not.toContain(recipeUuid);
+  });
+
+  test('List Trash', async ({ request }) => {
+    const response = await request.get('./recipes/trash');
+
+    expect(response.ok()).toBeTruthy();
+    expect(await response.json()).toContainEqual({
+      id: recipeUuid,
+      household_id: expect.any(String),
+      ...createData,
+      deleted_at: expect.any(Number),
+      expires_at: expect.any(Number),
+    });
+  });
+
+  test('Restore Recipe', async ({ request }) => {
+    const response = await request.post(`./recipes/trash/${recipeUuid}/restore`);
+
+    expect(response.ok()).toBeTruthy();
+    expect(await response.json()).toEqual({
+      id: recipeUuid,
+      household_id: expect.any(String),
+      ...createData,
+    });
+  });
+
+  test('Restore Recipe Not In Trash', async ({ request }) => {
+    const response = await request.post(`./recipes/trash/${recipeUuid}/restore`);
+
+    expect(response.status()).toEqual(404);
+  });
+
+  test.afterAll('Delete Recipe Permanently', async ({ request }) => {
+    const response = await request.delete(`./recipes/${recipeUuid}?permanent=true`);
+
+    expect(response.status()).toEqual(204);
+  });
+});
@@ -0,0 +1,76 @@
+import { test, expect } from '@playwright/test';
+import { createData } from './recipeConstants';
+
+let parentUuid: string;
+let variantUuid: string;
+
+test.describe('Variants', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test.beforeAll('Create Parent Recipe', async ({ request }) => {
+    const response = await request.post('./recipes', { data: createData });
+    parentUuid = (await response.json()).id;
+  });
+
+  test('Fork Recipe', async ({ request }) => {
+    const response = await request.post(`./recipes/${parentUuid}/fork`, {
+      data: { name: 'Spicy Playwright Recipe' },
+    });
+
+    expect(response.status()).toBe(201);
+    const responseBody = await response.json();
+    expect(responseBody).toEqual({
+      ...createData,
+      id: expect.any(String),
+      household_id: expect.any(String),
+      name: 'Spicy Playwright Recipe',
+      parent_id: parentUuid,
+    });
+    variantUuid = responseBody.id;
+  });
+
+  test('List Variants', async ({ request }) => {
+    const response = await request.get(`./recipes/${parentUuid}/variants`);
+
+    expect(response.ok()).toBeTruthy();
+    const variants = await response.json();
+    expect(variants.map((variant: { id: string }) => variant.id)).toEqual([variantUuid]);
+  });
+
+  test('Diff Variant', async ({ request }) => {
+    await request.patch(`./recipes/${variantUuid}`, {
+      data: {
+        ingredients: [
+          ...createData.ingredients,
+          { name: 'Chili Flakes', quantity: 2, unit: 'tsp' },
+        ],
+      },
+    });
+
+    const response = await request.get(`./recipes/${variantUuid}/diff`);
+
+    expect(response.ok()).toBeTruthy();
+    expect(await response.json()).toEqual({
+      parent_id: parentUuid,
+      variant_id: variantUuid,
+      ingredients: [
+        {
+          change: 'added',
+          ingredient: { name: 'Chili Flakes', quantity: 2, unit: 'tsp' },
+        },
+      ],
+      steps: [],
+    });
+  });
+
+  test('Diff Non-variant Recipe', async ({ request }) => {
+    const response = await request.get(`./recipes/${parentUuid}/diff`);
+
+    expect(response.status()).toEqual(404);
+  });
+
+  test.afterAll('Delete Recipes', async ({ request }) => {
+    await request.delete(`./recipes/${variantUuid}`);
+    await request.delete(`./recipes/${parentUuid}`);
+  });
+});
@@ -1,5 +1,5 @@
 import { test, expect } from '@playwright/test';
-import { createData, updateData } from './recipeConstants';
+import { NIL_UUID, createData, updateData } from './recipeConstants';
 
 
 let recipeUuid: string;
@@ -23,6 +23,7 @@ test.describe('Happy Path', () => {
     expect(responseBody).toEqual({
       // eslint-disable-next-line max-len
       id: expect.stringMatching(/^[0-9a-fA-F]{8}\b-[0-9a-fA-F]{4}\b-[0-9a-fA-F]{4}\b-[0-9a-fA-F]{4}\b-[0-9a-fA-F]{12}$/),
+      household_id: expect.any(String),
       ...createData,
     });
     recipeUuid = responseBody.id;
@@ -36,6 +37,7 @@ test.describe('Happy Path', () => {
     const responseBody = await response.json();
     expect(responseBody).toEqual({
       id: recipeUuid,
+      household_id: expect.any(String),
       ...createData,
     });
     recipeUuid = responseBody.id;
@@ -47,6 +49,7 @@ test.describe('Happy Path', () => {
     expect(response.ok()).toBeTruthy();
     expect(await response.json()).toEqual({
       id: recipeUuid,
+      household_id: expect.any(String),
       ...createData,
     });
   });
@@ -58,16 +61,36 @@ test.describe('Happy Path', () => {
     const json = await response.json();
     expect(json).toContainEqual({
       id: recipeUuid,
+      household_id: expect.any(String),
       ...createData,
     });
   });
 
+  test('List Recipes By Id', async ({ request }) => {
+    const response = await request.get(`./recipes?ids=${NIL_UUID},${recipeUuid}`);
+
+    expect(response.ok()).toBeTruthy();
+    expect(await response.json()).toEqual([{
+      id: recipeUuid,
+      household_id: expect.any(String),
+      ...createData,
+    }]);
+  });
+
+  test('List Recipes By Invalid Id', async ({ request }) => {
+    const response = await request.get('./recipes?ids=pizza');
+
+    expect(response.status()).toEqual(400);
+  });
+
   test('Update Recipe', async ({ request }) => {
     const response = await request.patch(`./recipes/${recipeUuid}`, { data: updateData });
 
     expect(response.ok()).toBeTruthy();
     expect(await response.json()).toEqual({
       id: recipeUuid,
+      household_id: expect.any(String),
+      ...createData,
       ...updateData,
     });
   });
@@ -78,6 +101,8 @@ test.describe('Happy Path', () => {
     expect(response.ok()).toBeTruthy();
     expect(await response.json()).toEqual({
       id: recipeUuid,
+      household_id: expect.any(String),
+      ...createData,
       ...updateData,
     });
   });
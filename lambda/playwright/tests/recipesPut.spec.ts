@@ -1,5 +1,5 @@
 import { test, expect } from '@playwright/test';
-import { createData, updateData } from './recipeConstants';
+import { createData, emptyLists, updateData } from './recipeConstants';
 
 let recipeUuid: string = '01010101-0101-0101-0101-010101010101';
 
@@ -21,6 +21,7 @@ test.describe('Happy Path', () => {
     const responseBody = await response.json();
     expect(responseBody).toEqual({
       id: recipeUuid,
+      household_id: expect.any(String),
       ...createData,
     });
   });
@@ -31,6 +32,7 @@ test.describe('Happy Path', () => {
     expect(response.ok()).toBeTruthy();
     expect(await response.json()).toEqual({
       id: recipeUuid,
+      household_id: expect.any(String),
       ...createData,
     });
   });
@@ -42,6 +44,7 @@ test.describe('Happy Path', () => {
     const json = await response.json();
     expect(json).toContainEqual({
       id: recipeUuid,
+      household_id: expect.any(String),
       ...createData,
     });
   });
@@ -59,7 +62,9 @@ test.describe('Happy Path', () => {
     const responseBody = await response.json();
     expect(responseBody).toEqual({
       id: recipeUuid,
+      household_id: expect.any(String),
       name: 'PUT Update',
+      ...emptyLists,
     });
     recipeUuid = responseBody.id;
   });
@@ -70,6 +75,8 @@ test.describe('Happy Path', () => {
     expect(response.ok()).toBeTruthy();
     expect(await response.json()).toEqual({
       id: recipeUuid,
+      household_id: expect.any(String),
+      ...emptyLists,
       ...updateData,
     });
   });
@@ -80,6 +87,8 @@ test.describe('Happy Path', () => {
     expect(response.ok()).toBeTruthy();
     expect(await response.json()).toEqual({
       id: recipeUuid,
+      household_id: expect.any(String),
+      ...emptyLists,
       ...updateData,
     });
   });
@@ -0,0 +1,84 @@
+import { test, expect } from '@playwright/test';
+
+const createData = {
+  name: 'Playwright Shopping List',
+  items: [
+    { name: 'Milk', aisle: 'dairy' },
+    { name: 'Apples', quantity: '6', aisle: 'produce' },
+  ],
+};
+
+let listUuid: string;
+let itemUuid: string;
+
+test.describe('Happy Path', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test('Post Shopping List', async ({ request }) => {
+    const response = await request.post('./recipes/shopping-lists', { data: createData });
+
+    expect(response.status()).toBe(201);
+
+    const responseBody = await response.json();
+    expect(responseBody.name).toEqual(createData.name);
+    expect(responseBody.aisles.map((group: { aisle: string }) => group.aisle))
+        .toEqual(['produce', 'dairy']);
+    listUuid = responseBody.id;
+    itemUuid = responseBody.aisles[0].items[0].id;
+  });
+
+  test('Check Item', async ({ request }) => {
+    const response = await request.patch(
+        `./recipes/shopping-lists/${listUuid}/items/${itemUuid}`,
+        { data: { checked: true } },
+    );
+
+    expect(response.ok()).toBeTruthy();
+    expect(await response.json()).toEqual({
+      id: itemUuid,
+      name: 'Apples',
+      quantity: '6',
+      aisle: 'produce',
+      checked: true,
+    });
+  });
+
+  test('Read Checked Item', async ({ request }) => {
+    const response = await request.get(`./recipes/shopping-lists/${listUuid}`);
+
+    expect(response.ok()).toBeTruthy();
+    const responseBody = await response.json();
+    expect(responseBody.aisles[0].items[0].checked).toBe(true);
+    expect(responseBody.aisles[1].items[0].checked).toBe(false);
+  });
+
+  test('Add Item', async ({ request }) => {
+    const response = await request.post(
+        `./recipes/shopping-lists/${listUuid}/items`,
+        { data: { name: 'Sourdough', aisle: 'bakery' } },
+    );
+
+    expect(response.status()).toBe(201);
+    expect(await response.json()).toMatchObject({
+      name: 'Sourdough',
+      aisle: 'bakery',
+      checked: false,
+    });
+  });
+
+  test.afterAll('Delete Shopping List', async ({ request }) => {
+    const response = await request.delete(`./recipes/shopping-lists/${listUuid}`);
+
+    expect(response.status()).toEqual(204);
+  });
+});
+
+test('Check Item on Non-existent List', async ({ request }) => {
+  const nil = '00000000-0000-0000-0000-000000000000';
+  const response = await request.patch(
+      `./recipes/shopping-lists/${nil}/items/${nil}`,
+      { data: { checked: true } },
+  );
+
+  expect(response.status()).toEqual(404);
+});
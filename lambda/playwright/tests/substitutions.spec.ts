@@ -0,0 +1,57 @@
+import { test, expect } from '@playwright/test';
+
+let recipeUuid: string;
+
+test.describe('Substitutions', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test.beforeAll('Create Recipe', async ({ request }) => {
+    const response = await request.post('./recipes', {
+      data: {
+        name: 'Buttermilk Pancakes',
+        ingredients: [
+          { name: 'Flour', quantity: 250, unit: 'g' },
+          { name: 'Buttermilk', quantity: 2, unit: 'cups' },
+          { name: 'Eggs', quantity: 2 },
+        ],
+      },
+    });
+    recipeUuid = (await response.json()).id;
+  });
+
+  test('Swap Missing Ingredients', async ({ request }) => {
+    const response = await request.get(
+      `./recipes/${recipeUuid}/substitutions?pantry=flour,milk,lemon%20juice,eggs`,
+    );
+
+    expect(response.ok()).toBeTruthy();
+    const preview = await response.json();
+    expect(preview.suggestions).toHaveLength(1);
+    expect(preview.suggestions[0]).toMatchObject({ index: 1, missing: true, conflicts: [] });
+    expect(preview.suggestions[0].options[0].ingredients).toEqual([
+      { name: 'milk', quantity: 2, unit: 'cups' },
+      { name: 'lemon juice', quantity: 2, unit: 'tbsp' },
+    ]);
+    expect(preview.recipe.ingredients).toHaveLength(4);
+  });
+
+  test('Swap For A Diet', async ({ request }) => {
+    const response = await request.get(`./recipes/${recipeUuid}/substitutions?diet=vegan`);
+
+    expect(response.ok()).toBeTruthy();
+    const names = (await response.json()).recipe.ingredients.map(
+      (ingredient: { name: string }) => ingredient.name,
+    );
+    expect(names).toEqual(['Flour', 'oat milk', 'lemon juice', 'ground flaxseed', 'water']);
+  });
+
+  test('Reject Unknown Diets', async ({ request }) => {
+    const response = await request.get(`./recipes/${recipeUuid}/substitutions?diet=keto`);
+
+    expect(response.status()).toEqual(400);
+  });
+
+  test.afterAll('Clean Up', async ({ request }) => {
+    await request.delete(`./recipes/${recipeUuid}`);
+  });
+});
@@ -0,0 +1,84 @@
+import { test, expect } from '@playwright/test';
+import { NIL_UUID } from './recipeConstants';
+
+let turkeyUuid: string;
+let potatoesUuid: string;
+
+test.describe('Cook Timeline', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test.beforeAll('Create Recipes', async ({ request }) => {
+    const turkey = await request.post('./recipes', {
+      data: {
+        name: 'Roast Turkey',
+        steps: [
+          { instruction: 'Stuff the turkey.', minutes: 20 },
+          { instruction: 'Roast.', minutes: 180, passive: true },
+          { instruction: 'Rest.', minutes: 30, passive: true },
+        ],
+      },
+    });
+    turkeyUuid = (await turkey.json()).id;
+
+    const potatoes = await request.post('./recipes', {
+      data: {
+        name: 'Mashed Potatoes',
+        steps: [
+          { instruction: 'Peel.', minutes: 15 },
+          { instruction: 'Boil.', minutes: 20, passive: true },
+          { instruction: 'Mash.', minutes: 10 },
+        ],
+      },
+    });
+    potatoesUuid = (await potatoes.json()).id;
+  });
+
+  test('Schedule Dinner', async ({ request }) => {
+    const response = await request.post('./recipes/timeline', {
+      data: {
+        recipe_ids: [turkeyUuid, potatoesUuid],
+        serve_at: '2025-11-27T17:00',
+      },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    const timeline = await response.json();
+    expect(timeline.starts_at).toEqual('2025-11-27T13:10');
+    expect(timeline.tasks[0]).toEqual({
+      recipe_id: turkeyUuid,
+      recipe_name: 'Roast Turkey',
+      step: 0,
+      instruction: 'Stuff the turkey.',
+      active: true,
+      starts_at: '2025-11-27T13:10',
+      ends_at: '2025-11-27T13:30',
+    });
+  });
+
+  test('Schedule Unknown Recipe', async ({ request }) => {
+    const response = await request.post('./recipes/timeline', {
+      data: { recipe_ids: [NIL_UUID], serve_at: '2025-11-27T17:00' },
+    });
+
+    expect(response.status()).toEqual(404);
+  });
+
+  test('Step Waiting For A Later Step', async ({ request }) => {
+    const response = await request.post('./recipes', {
+      data: {
+        name: 'Backwards',
+        steps: [{ instruction: 'Serve.', after: [1] }, { instruction: 'Cook.' }],
+      },
+    });
+
+    expect(response.status()).toEqual(422);
+    expect(await response.json()).toEqual({
+      errors: [{ pointer: '/steps/0/after', message: 'must refer to earlier steps' }],
+    });
+  });
+
+  test.afterAll('Delete Recipes', async ({ request }) => {
+    await request.delete(`./recipes/${turkeyUuid}`);
+    await request.delete(`./recipes/${potatoesUuid}`);
+  });
+});
@@ -0,0 +1,66 @@
+import { test, expect } from '@playwright/test';
+
+let webhookId: string;
+
+test.describe('Webhooks', () => {
+  test.describe.configure({ mode: 'serial' });
+
+  test('Register Webhook', async ({ request }) => {
+    const response = await request.post('./recipes/webhooks', {
+      data: {
+        url: 'https://dashboard.example.com/hooks/meal-planner',
+        events: ['recipe.created', 'plan.updated'],
+      },
+    });
+
+    expect(response.status()).toEqual(201);
+    const created = await response.json();
+    expect(created).toEqual({
+      id: expect.any(String),
+      url: 'https://dashboard.example.com/hooks/meal-planner',
+      events: ['recipe.created', 'plan.updated'],
+      created_by: expect.any(String),
+      created_at: expect.any(Number),
+      secret: expect.stringMatching(/^whsec_/),
+    });
+    webhookId = created.id;
+  });
+
+  test('Register Insecure Webhook', async ({ request }) => {
+    const response = await request.post('./recipes/webhooks', {
+      data: { url: 'http://dashboard.example.com/hooks', events: [] },
+    });
+
+    expect(response.status()).toEqual(422);
+    expect(await response.json()).toEqual({
+      errors: [
+        { pointer: '/url', message: 'must use https' },
+        { pointer: '/events', message: 'must not be empty' },
+      ],
+    });
+  });
+
+  test('List Webhooks', async ({ request }) => {
+    const response = await request.get('./recipes/webhooks');
+
+    expect(response.ok()).toBeTruthy();
+    const webhooks = await response.json();
+    expect(webhooks.map((listed: { id: string }) => listed.id)).toContain(webhookId);
+    expect(webhooks[0].secret).toBeUndefined();
+  });
+
+  test('List Deliveries', async ({ request }) => {
+    const response = await request.get(`./recipes/webhooks/${webhookId}/deliveries`);
+
+    expect(response.ok()).toBeTruthy();
+    expect(Array.isArray(await response.json())).toBeTruthy();
+  });
+
+  test('Delete Webhook', async ({ request }) => {
+    const response = await request.delete(`./recipes/webhooks/${webhookId}`);
+    expect(response.status()).toEqual(204);
+
+    const again = await request.delete(`./recipes/webhooks/${webhookId}`);
+    expect(again.status()).toEqual(404);
+  });
+});
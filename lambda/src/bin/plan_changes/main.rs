@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use aws_config::BehaviorVersion;
+use lambda_runtime::{run, service_fn, Error, LambdaEvent};
+use meal_planner::change_feed::{StreamEvent, StreamResponse};
+use meal_planner::webhook::delivery::{ReqwestSender, WebhookPublisher};
+use meal_planner::webhook::repository::DynamoDbWebhook;
+
+/// Consumes the stream of the meal plan table and announces changed plans to
+/// webhooks. Recorded events can be replayed locally by running
+/// `cargo lambda watch` and invoking the function with
+/// `src/lib/change_feed/fixtures/plan_stream.json`.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_target(false)
+        .without_time()
+        .init();
+
+    let publisher = Arc::new(webhook_publisher().await);
+
+    run(service_fn(move |event: LambdaEvent<StreamEvent>| {
+        let publisher = Arc::clone(&publisher);
+        async move {
+            Ok::<StreamResponse, Error>(publisher.publish_plan_changes(&event.payload).await)
+        }
+    }))
+    .await
+}
+
+async fn webhook_publisher() -> WebhookPublisher<DynamoDbWebhook, ReqwestSender> {
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let table_name = std::env::var("WEBHOOK_TABLE_NAME")
+        .ok()
+        .unwrap_or("webhooks".to_string());
+    let delivery_table_name = std::env::var("WEBHOOK_DELIVERY_TABLE_NAME")
+        .ok()
+        .unwrap_or("webhook-deliveries".to_string());
+    let repo = DynamoDbWebhook::new(&sdk_config, &table_name, &delivery_table_name);
+
+    WebhookPublisher::new(repo, ReqwestSender::new())
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use aws_config::BehaviorVersion;
+use lambda_runtime::{run, service_fn, Error, LambdaEvent};
+use meal_planner::change_feed::audit::AuditLog;
+use meal_planner::change_feed::{ChangeFeed, StreamEvent, StreamResponse};
+use meal_planner::webhook::delivery::{ReqwestSender, WebhookPublisher};
+use meal_planner::webhook::repository::DynamoDbWebhook;
+
+/// Consumes the stream of the recipe table. Recorded events can be replayed
+/// locally by running `cargo lambda watch` and invoking the function with the
+/// events in `src/lib/change_feed/fixtures`.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_target(false)
+        .without_time()
+        .init();
+
+    let feed = Arc::new(ChangeFeed::new(vec![
+        Arc::new(AuditLog),
+        Arc::new(webhook_publisher().await),
+    ]));
+
+    run(service_fn(move |event: LambdaEvent<StreamEvent>| {
+        let feed = Arc::clone(&feed);
+        async move { Ok::<StreamResponse, Error>(feed.process(&event.payload).await) }
+    }))
+    .await
+}
+
+async fn webhook_publisher() -> WebhookPublisher<DynamoDbWebhook, ReqwestSender> {
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let table_name = std::env::var("WEBHOOK_TABLE_NAME")
+        .ok()
+        .unwrap_or("webhooks".to_string());
+    let delivery_table_name = std::env::var("WEBHOOK_DELIVERY_TABLE_NAME")
+        .ok()
+        .unwrap_or("webhook-deliveries".to_string());
+    let repo = DynamoDbWebhook::new(&sdk_config, &table_name, &delivery_table_name);
+
+    WebhookPublisher::new(repo, ReqwestSender::new())
+}
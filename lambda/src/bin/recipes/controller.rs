@@ -1,27 +1,480 @@
-use aws_config::BehaviorVersion;
+use std::time::Duration;
+
+use aws_config::{BehaviorVersion, SdkConfig};
+use axum::extract::FromRef;
 use axum::routing::{delete, get, patch, post, put};
 use axum::Router;
-use meal_planner::recipe::repository::DynamoDbRecipe;
-use meal_planner::services::{recipes, ApplicationContext};
+use meal_planner::api_key::repository::DynamoDbApiKey;
+use meal_planner::cook_log::repository::DynamoDbCookLog;
+use meal_planner::household::repository::DynamoDbHousehold;
+use meal_planner::idempotency::repository::DynamoDbIdempotency;
+use meal_planner::kitchen::repository::DynamoDbKitchen;
+use meal_planner::plan::calendar::RecipeLinks;
+use meal_planner::plan::repository::DynamoDbMealPlan;
+use meal_planner::recipe::repository::{DynamoDbRecipe, DEFAULT_TRASH_RETENTION};
+use meal_planner::services::{
+    api_keys,
+    cook_log,
+    households,
+    kitchens,
+    plans,
+    recipes,
+    shopping_lists,
+    timeline,
+    webhooks,
+    ApplicationContext,
+};
+use meal_planner::shopping_list::repository::DynamoDbShoppingList;
+use meal_planner::webhook::repository::DynamoDbWebhook;
 use tracing::{info, instrument};
 
 #[instrument(name = "recipes")]
 pub async fn recipes() -> Router {
     info!("Initializing routes for recipes");
     let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
-    let table_name = std::env::var("RECIPE_TABLE_NAME")
-        .ok()
-        .unwrap_or("recipes".to_string());
-
-    let repo = DynamoDbRecipe::new(&sdk_config, &table_name);
-    let recipe_context = ApplicationContext { repo };
+    let recipe_state = RecipeState {
+        recipes: ApplicationContext {
+            repo: recipe_repository(&sdk_config),
+        },
+        kitchens: ApplicationContext {
+            repo: kitchen_repository(&sdk_config),
+        },
+        cook_log: ApplicationContext {
+            repo: cook_log_repository(&sdk_config),
+        },
+    };
 
     Router::new()
-        .route("/", get(recipes::list::<DynamoDbRecipe>))
+        .route(
+            "/",
+            get(recipes::list::<DynamoDbRecipe, DynamoDbKitchen, DynamoDbCookLog>),
+        )
         .route("/", post(recipes::create::<DynamoDbRecipe>))
         .route("/", put(recipes::write::<DynamoDbRecipe>))
-        .route("/:id", get(recipes::read_one::<DynamoDbRecipe>))
+        .route(
+            "/:id",
+            get(recipes::read_one::<DynamoDbRecipe, DynamoDbCookLog>),
+        )
         .route("/:id", patch(recipes::update::<DynamoDbRecipe>))
         .route("/:id", delete(recipes::delete_one::<DynamoDbRecipe>))
+        .route("/batch", post(recipes::batch::<DynamoDbRecipe>))
+        .route("/trash", get(recipes::list_trash::<DynamoDbRecipe>))
+        .route(
+            "/timeline",
+            post(timeline::schedule::<DynamoDbRecipe, DynamoDbKitchen>),
+        )
+        .route(
+            "/trash/:id/restore",
+            post(recipes::restore_from_trash::<DynamoDbRecipe>),
+        )
+        .route("/:id/fork", post(recipes::fork::<DynamoDbRecipe>))
+        .route(
+            "/:id/variants",
+            get(recipes::list_variants::<DynamoDbRecipe>),
+        )
+        .route("/:id/diff", get(recipes::diff_variant::<DynamoDbRecipe>))
+        .route(
+            "/:id/substitutions",
+            get(recipes::substitutions::<DynamoDbRecipe>),
+        )
+        .route(
+            "/:id/cook-log",
+            get(cook_log::list::<DynamoDbRecipe, DynamoDbCookLog>),
+        )
+        .route(
+            "/:id/cook-log",
+            post(cook_log::record::<DynamoDbRecipe, DynamoDbCookLog>),
+        )
+        .route(
+            "/:id/cook-log/:entry_id",
+            delete(cook_log::delete_one::<DynamoDbCookLog>),
+        )
+        .route(
+            "/:id/revisions",
+            get(recipes::list_revisions::<DynamoDbRecipe>),
+        )
+        .route(
+            "/:id/revisions/:revision",
+            get(recipes::read_revision::<DynamoDbRecipe>),
+        )
+        .route(
+            "/:id/revisions/:revision/restore",
+            post(recipes::restore_revision::<DynamoDbRecipe>),
+        )
+        .route("/:id/shares", get(recipes::list_shares::<DynamoDbRecipe>))
+        .route("/:id/shares", post(recipes::share::<DynamoDbRecipe>))
+        .route(
+            "/:id/shares/:token",
+            delete(recipes::revoke_share::<DynamoDbRecipe>),
+        )
+        .with_state(recipe_state)
+}
+
+/// Routes for reading recipes through share links, served without
+/// authentication.
+#[instrument(name = "shared")]
+pub async fn shared() -> Router {
+    info!("Initializing routes for shared recipes");
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let recipe_context = ApplicationContext {
+        repo: recipe_repository(&sdk_config),
+    };
+
+    Router::new()
+        .route("/:token", get(recipes::read_shared::<DynamoDbRecipe>))
         .with_state(recipe_context)
 }
+
+#[instrument(name = "shopping_lists")]
+pub async fn shopping_lists() -> Router {
+    info!("Initializing routes for shopping lists");
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let table_name = std::env::var("SHOPPING_LIST_TABLE_NAME")
+        .ok()
+        .unwrap_or("shopping-lists-by-household".to_string());
+
+    let repo = DynamoDbShoppingList::new(&sdk_config, &table_name);
+    let shopping_list_state = ShoppingListState {
+        shopping_lists: ApplicationContext { repo },
+        recipes: ApplicationContext {
+            repo: recipe_repository(&sdk_config),
+        },
+    };
+
+    Router::new()
+        .route("/", get(shopping_lists::list::<DynamoDbShoppingList>))
+        .route("/", post(shopping_lists::create::<DynamoDbShoppingList>))
+        .route(
+            "/from-recipes",
+            post(shopping_lists::create_from_recipes::<DynamoDbShoppingList, DynamoDbRecipe>),
+        )
+        .route(
+            "/:id",
+            get(shopping_lists::read_one::<DynamoDbShoppingList>),
+        )
+        .route(
+            "/:id",
+            delete(shopping_lists::delete_one::<DynamoDbShoppingList>),
+        )
+        .route(
+            "/:id/items",
+            post(shopping_lists::add_item::<DynamoDbShoppingList>),
+        )
+        .route(
+            "/:id/items/:item_id",
+            patch(shopping_lists::check_item::<DynamoDbShoppingList>),
+        )
+        .with_state(shopping_list_state)
+}
+
+#[instrument(name = "households")]
+pub async fn households() -> Router {
+    info!("Initializing routes for households");
+
+    Router::new()
+        .route(
+            "/current",
+            get(households::read_current::<DynamoDbHousehold>),
+        )
+        .route(
+            "/invitations",
+            post(households::invite::<DynamoDbHousehold>),
+        )
+        .route("/join", post(households::join::<DynamoDbHousehold>))
+        .route(
+            "/members/:subject",
+            put(households::update_member::<DynamoDbHousehold>),
+        )
+        .route(
+            "/members/:subject",
+            delete(households::remove_member::<DynamoDbHousehold>),
+        )
+        .with_state(membership().await)
+}
+
+/// The household repository used to resolve the membership of every caller.
+pub async fn membership() -> ApplicationContext<DynamoDbHousehold> {
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+
+    ApplicationContext {
+        repo: household_repository(&sdk_config),
+    }
+}
+
+fn household_repository(sdk_config: &SdkConfig) -> DynamoDbHousehold {
+    let member_table_name = std::env::var("HOUSEHOLD_MEMBER_TABLE_NAME")
+        .ok()
+        .unwrap_or("household-members".to_string());
+    let invitation_table_name = std::env::var("HOUSEHOLD_INVITATION_TABLE_NAME")
+        .ok()
+        .unwrap_or("household-invitations".to_string());
+
+    DynamoDbHousehold::new(sdk_config, &member_table_name, &invitation_table_name)
+}
+
+#[instrument(name = "kitchen")]
+pub async fn kitchen() -> Router {
+    info!("Initializing routes for the kitchen");
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let kitchen_context = ApplicationContext {
+        repo: kitchen_repository(&sdk_config),
+    };
+
+    Router::new()
+        .route("/", get(kitchens::read::<DynamoDbKitchen>))
+        .route("/", put(kitchens::replace::<DynamoDbKitchen>))
+        .with_state(kitchen_context)
+}
+
+fn cook_log_repository(sdk_config: &SdkConfig) -> DynamoDbCookLog {
+    let table_name = std::env::var("COOK_LOG_TABLE_NAME")
+        .ok()
+        .unwrap_or("cook-log".to_string());
+
+    DynamoDbCookLog::new(sdk_config, &table_name)
+}
+
+fn kitchen_repository(sdk_config: &SdkConfig) -> DynamoDbKitchen {
+    let table_name = std::env::var("KITCHEN_TABLE_NAME")
+        .ok()
+        .unwrap_or("kitchens".to_string());
+
+    DynamoDbKitchen::new(sdk_config, &table_name)
+}
+
+#[instrument(name = "api_keys")]
+pub async fn api_keys() -> Router {
+    info!("Initializing routes for API keys");
+
+    Router::new()
+        .route("/", get(api_keys::list::<DynamoDbApiKey>))
+        .route("/", post(api_keys::create::<DynamoDbApiKey>))
+        .route("/:id", delete(api_keys::revoke::<DynamoDbApiKey>))
+        .with_state(api_key_store().await)
+}
+
+#[instrument(name = "webhooks")]
+pub async fn webhooks() -> Router {
+    info!("Initializing routes for webhooks");
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let webhook_context = ApplicationContext {
+        repo: webhook_repository(&sdk_config),
+    };
+
+    Router::new()
+        .route("/", get(webhooks::list::<DynamoDbWebhook>))
+        .route("/", post(webhooks::create::<DynamoDbWebhook>))
+        .route("/:id", delete(webhooks::delete_one::<DynamoDbWebhook>))
+        .route(
+            "/:id/deliveries",
+            get(webhooks::list_deliveries::<DynamoDbWebhook>),
+        )
+        .with_state(webhook_context)
+}
+
+fn webhook_repository(sdk_config: &SdkConfig) -> DynamoDbWebhook {
+    let table_name = std::env::var("WEBHOOK_TABLE_NAME")
+        .ok()
+        .unwrap_or("webhooks".to_string());
+    let delivery_table_name = std::env::var("WEBHOOK_DELIVERY_TABLE_NAME")
+        .ok()
+        .unwrap_or("webhook-deliveries".to_string());
+
+    DynamoDbWebhook::new(sdk_config, &table_name, &delivery_table_name)
+}
+
+#[instrument(name = "plans")]
+pub async fn plans() -> Router {
+    info!("Initializing routes for meal plans");
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let plan_state = plan_state(&sdk_config);
+
+    Router::new()
+        .route("/", get(plans::list::<DynamoDbMealPlan>))
+        .route("/", post(plans::create::<DynamoDbMealPlan>))
+        .route(
+            "/calendar-feed",
+            post(plans::create_feed::<DynamoDbMealPlan>),
+        )
+        .route(
+            "/calendar-feed",
+            delete(plans::revoke_feed::<DynamoDbMealPlan>),
+        )
+        .route("/:id", get(plans::read_one::<DynamoDbMealPlan>))
+        .route("/:id", put(plans::replace::<DynamoDbMealPlan>))
+        .route("/:id", delete(plans::delete_one::<DynamoDbMealPlan>))
+        .route(
+            "/:id/conflicts",
+            get(plans::conflicts::<DynamoDbMealPlan, DynamoDbRecipe, DynamoDbKitchen>),
+        )
+        .with_state(plan_state)
+}
+
+/// Calendar apps cannot sign in, so plans are read as calendars with the token
+/// of a calendar feed instead.
+#[instrument(name = "calendars")]
+pub async fn calendars() -> Router {
+    info!("Initializing routes for meal plan calendars");
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let plan_state = plan_state(&sdk_config);
+
+    Router::new()
+        .route(
+            "/:id/calendar.ics",
+            get(plans::calendar::<DynamoDbMealPlan, DynamoDbRecipe, DynamoDbHousehold>),
+        )
+        .with_state(plan_state)
+}
+
+fn plan_state(sdk_config: &SdkConfig) -> PlanState {
+    let base_url = std::env::var("PUBLIC_BASE_URL")
+        .ok()
+        .unwrap_or("http://localhost:8080".to_string());
+
+    PlanState {
+        plans: ApplicationContext {
+            repo: plan_repository(sdk_config),
+        },
+        recipes: ApplicationContext {
+            repo: recipe_repository(sdk_config),
+        },
+        kitchens: ApplicationContext {
+            repo: kitchen_repository(sdk_config),
+        },
+        households: ApplicationContext {
+            repo: household_repository(sdk_config),
+        },
+        links: RecipeLinks::new(&base_url),
+    }
+}
+
+fn plan_repository(sdk_config: &SdkConfig) -> DynamoDbMealPlan {
+    let table_name = std::env::var("MEAL_PLAN_TABLE_NAME")
+        .ok()
+        .unwrap_or("meal-plans".to_string());
+    let feed_table_name = std::env::var("CALENDAR_FEED_TABLE_NAME")
+        .ok()
+        .unwrap_or("calendar-feeds".to_string());
+
+    DynamoDbMealPlan::new(sdk_config, &table_name, &feed_table_name)
+}
+
+/// The API key repository used to authenticate scripts and integrations.
+pub async fn api_key_store() -> ApplicationContext<DynamoDbApiKey> {
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let table_name = std::env::var("API_KEY_TABLE_NAME")
+        .ok()
+        .unwrap_or("api-keys".to_string());
+
+    ApplicationContext {
+        repo: DynamoDbApiKey::new(&sdk_config, &table_name),
+    }
+}
+
+/// The store of idempotency keys that make retried POST requests safe.
+pub async fn idempotency_store() -> ApplicationContext<DynamoDbIdempotency> {
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let table_name = std::env::var("IDEMPOTENCY_TABLE_NAME")
+        .ok()
+        .unwrap_or("idempotency-keys".to_string());
+
+    ApplicationContext {
+        repo: DynamoDbIdempotency::new(&sdk_config, &table_name),
+    }
+}
+
+/// Recipes are listed and scheduled against the cookware of the household,
+/// and read with their cook log. Each handler extracts only the context it
+/// needs.
+#[derive(Clone)]
+struct RecipeState {
+    recipes: ApplicationContext<DynamoDbRecipe>,
+    kitchens: ApplicationContext<DynamoDbKitchen>,
+    cook_log: ApplicationContext<DynamoDbCookLog>,
+}
+
+impl FromRef<RecipeState> for ApplicationContext<DynamoDbRecipe> {
+    fn from_ref(state: &RecipeState) -> Self { state.recipes.clone() }
+}
+
+impl FromRef<RecipeState> for ApplicationContext<DynamoDbKitchen> {
+    fn from_ref(state: &RecipeState) -> Self { state.kitchens.clone() }
+}
+
+impl FromRef<RecipeState> for ApplicationContext<DynamoDbCookLog> {
+    fn from_ref(state: &RecipeState) -> Self { state.cook_log.clone() }
+}
+
+/// Shopping lists are built from recipes, so their routes can reach both
+/// repositories. Each handler extracts only the context it needs.
+#[derive(Clone)]
+struct ShoppingListState {
+    shopping_lists: ApplicationContext<DynamoDbShoppingList>,
+    recipes: ApplicationContext<DynamoDbRecipe>,
+}
+
+impl FromRef<ShoppingListState> for ApplicationContext<DynamoDbShoppingList> {
+    fn from_ref(state: &ShoppingListState) -> Self { state.shopping_lists.clone() }
+}
+
+impl FromRef<ShoppingListState> for ApplicationContext<DynamoDbRecipe> {
+    fn from_ref(state: &ShoppingListState) -> Self { state.recipes.clone() }
+}
+
+/// Calendars show the recipes of a plan and link to them, and meals served
+/// together are checked against the cookware of the household.
+#[derive(Clone)]
+struct PlanState {
+    plans: ApplicationContext<DynamoDbMealPlan>,
+    recipes: ApplicationContext<DynamoDbRecipe>,
+    kitchens: ApplicationContext<DynamoDbKitchen>,
+    households: ApplicationContext<DynamoDbHousehold>,
+    links: RecipeLinks,
+}
+
+impl FromRef<PlanState> for ApplicationContext<DynamoDbMealPlan> {
+    fn from_ref(state: &PlanState) -> Self { state.plans.clone() }
+}
+
+impl FromRef<PlanState> for ApplicationContext<DynamoDbRecipe> {
+    fn from_ref(state: &PlanState) -> Self { state.recipes.clone() }
+}
+
+impl FromRef<PlanState> for ApplicationContext<DynamoDbKitchen> {
+    fn from_ref(state: &PlanState) -> Self { state.kitchens.clone() }
+}
+
+impl FromRef<PlanState> for ApplicationContext<DynamoDbHousehold> {
+    fn from_ref(state: &PlanState) -> Self { state.households.clone() }
+}
+
+impl FromRef<PlanState> for RecipeLinks {
+    fn from_ref(state: &PlanState) -> Self { state.links.clone() }
+}
+
+fn recipe_repository(sdk_config: &SdkConfig) -> DynamoDbRecipe {
+    let table_name = std::env::var("RECIPE_TABLE_NAME")
+        .ok()
+        .unwrap_or("recipes-by-household".to_string());
+    let revision_table_name = std::env::var("RECIPE_REVISION_TABLE_NAME")
+        .ok()
+        .unwrap_or("recipe-revisions".to_string());
+    let share_table_name = std::env::var("RECIPE_SHARE_TABLE_NAME")
+        .ok()
+        .unwrap_or("recipe-shares".to_string());
+
+    let trash_retention = std::env::var("RECIPE_TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|days| days.parse::<u64>().ok())
+        .map_or(DEFAULT_TRASH_RETENTION, |days| {
+            Duration::from_secs(days * 24 * 60 * 60)
+        });
+
+    DynamoDbRecipe::new(
+        sdk_config,
+        &table_name,
+        &revision_table_name,
+        &share_table_name,
+    )
+    .with_trash_retention(trash_retention)
+}
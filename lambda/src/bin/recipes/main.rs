@@ -1,7 +1,20 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::DefaultBodyLimit;
 use axum::response::Json;
 use axum::routing::get;
-use axum::Router;
+use axum::{middleware, Router};
 use lambda_http::{run, Error};
+use meal_planner::api_key::repository::DynamoDbApiKey;
+use meal_planner::auth::jwt::JwtVerifier;
+use meal_planner::auth::{self};
+use meal_planner::household::repository::DynamoDbHousehold;
+use meal_planner::idempotency::repository::DynamoDbIdempotency;
+use meal_planner::live::EventLog;
+use meal_planner::openapi;
+use meal_planner::rate_limit::{self, RateLimiter};
+use meal_planner::services::{api_keys, events, households, idempotency};
 use serde_json::{json, Value};
 use tower_http::trace::{
     DefaultMakeSpan,
@@ -12,6 +25,7 @@ use tower_http::trace::{
 };
 use tower_http::LatencyUnit;
 use tracing::Level;
+use utoipa_swagger_ui::{Config, SwaggerUi};
 
 mod controller;
 
@@ -25,10 +39,68 @@ async fn main() -> Result<(), Error> {
         .without_time()
         .init();
 
+    let verifier = jwt_verifier().await?;
     let recipes_controller = controller::recipes().await;
+    let shared_controller = controller::shared().await;
+    let shopping_lists_controller = controller::shopping_lists().await;
+    let households_controller = controller::households().await;
+    let api_keys_controller = controller::api_keys().await;
+    let webhooks_controller = controller::webhooks().await;
+    let plans_controller = controller::plans().await;
+    let calendars_controller = controller::calendars().await;
+    let kitchen_controller = controller::kitchen().await;
+    let listen_address = std::env::var("LISTEN_ADDRESS").ok();
+    let mut authenticated = Router::new()
+        .nest("/api-keys", api_keys_controller)
+        .nest("/webhooks", webhooks_controller)
+        .nest("/households", households_controller)
+        .nest("/shopping-lists", shopping_lists_controller)
+        .nest("/plans", plans_controller)
+        .nest("/kitchen", kitchen_controller)
+        .nest("/", recipes_controller);
+    // Live events only reach clients connected to the same process, which
+    // Lambda cannot promise, so they are left to the standalone server.
+    if listen_address.is_some() {
+        let log = Arc::new(EventLog::default());
+        authenticated = authenticated
+            .merge(
+                Router::new()
+                    .route("/events", get(events::stream))
+                    .with_state(log.clone()),
+            )
+            .layer(middleware::from_fn_with_state(log, events::publish_changes));
+    }
+    let authenticated = authenticated
+        .layer(middleware::from_fn_with_state(
+            controller::idempotency_store().await,
+            idempotency::idempotent::<DynamoDbIdempotency>,
+        ))
+        .layer(middleware::from_fn_with_state(
+            controller::membership().await,
+            households::resolve_membership::<DynamoDbHousehold>,
+        ))
+        .layer(middleware::from_fn_with_state(
+            Arc::new(verifier),
+            auth::authenticate,
+        ))
+        .layer(middleware::from_fn_with_state(
+            controller::api_key_store().await,
+            api_keys::authenticate_api_key::<DynamoDbApiKey>,
+        ));
     let app = Router::new()
         .route("/ping", get(ping))
-        .nest("/", recipes_controller)
+        .route("/openapi.json", get(openapi::spec))
+        // The UI is served below the API's base path, so it has to find the
+        // document relative to its own location.
+        .merge(SwaggerUi::new("/docs").config(Config::new(["../openapi.json"])))
+        .nest("/shared", shared_controller)
+        .nest("/plans", calendars_controller)
+        .merge(authenticated)
+        .layer(DefaultBodyLimit::max(max_request_body_bytes()))
+        .layer(middleware::from_fn_with_state(
+            Arc::new(rate_limiter()),
+            rate_limit::limit,
+        ))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::new().include_headers(true))
@@ -45,5 +117,49 @@ async fn main() -> Result<(), Error> {
                 ),
         );
 
-    run(app).await
+    match listen_address {
+        Some(address) => {
+            let listener = tokio::net::TcpListener::bind(&address).await?;
+            tracing::info!("Listening on {address}");
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await?;
+            Ok(())
+        },
+        None => run(app).await,
+    }
+}
+
+async fn jwt_verifier() -> Result<JwtVerifier, Error> {
+    let source = std::env::var("JWKS_SOURCE").map_err(|_| "JWKS_SOURCE must be set")?;
+    let mut verifier = JwtVerifier::load(&source).await?;
+    if let Ok(issuer) = std::env::var("JWT_ISSUER") {
+        verifier = verifier.with_issuer(&issuer);
+    }
+    if let Ok(audience) = std::env::var("JWT_AUDIENCE") {
+        verifier = verifier.with_audience(&audience);
+    }
+
+    Ok(verifier)
+}
+
+/// Defaults to 20 requests at once and 5 requests a second per client.
+fn rate_limiter() -> RateLimiter {
+    let burst = env_number("RATE_LIMIT_BURST").unwrap_or(20);
+    let per_second = env_number("RATE_LIMIT_PER_SECOND").unwrap_or(5);
+
+    RateLimiter::new(burst, per_second)
+}
+
+/// Larger bodies are rejected with payload too large. Defaults to 1 MiB.
+fn max_request_body_bytes() -> usize {
+    env_number("MAX_REQUEST_BODY_BYTES").map_or(1024 * 1024, |bytes: u32| bytes as usize)
+}
+
+fn env_number(name: &str) -> Option<u32> {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
 }
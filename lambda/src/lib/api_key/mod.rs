@@ -0,0 +1,179 @@
+use std::future::Future;
+use std::time::Duration;
+
+use axum::async_trait;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::household::Role;
+
+pub mod repository;
+pub mod request_models;
+pub mod response_models;
+
+/// Every key starts with this prefix, so that leaked keys are easy to spot.
+pub const KEY_PREFIX: &str = "mpk_";
+
+/// How stale the last used timestamp of a key may get before it is written
+/// again, so that busy scripts do not cause a write on every request.
+pub const LAST_USED_RESOLUTION: Duration = Duration::from_secs(60);
+
+/// What a key is allowed to do within the household it was created for.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// Can read recipes and shopping lists.
+    #[default]
+    Read,
+    /// Can also create, change and delete them.
+    ReadWrite,
+}
+
+impl Scope {
+    /// The household role a caller with a key of this scope acts with.
+    #[must_use]
+    pub fn role(self) -> Role {
+        match self {
+            Scope::Read => Role::Viewer,
+            Scope::ReadWrite => Role::Editor,
+        }
+    }
+}
+
+/// A long lived credential for scripts and integrations that cannot sign in
+/// interactively.
+///
+/// Only a hash of the secret part of the key is stored, the key itself is
+/// shown once when it is created.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ApiKey {
+    pub(crate) id: Uuid,
+    pub(crate) household_id: String,
+    pub(crate) name: String,
+    pub(crate) scope: Scope,
+    pub(crate) created_by: String,
+    pub(crate) created_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) last_used_at: Option<u64>,
+    /// The hex encoded SHA-256 digest of the secret.
+    pub(crate) hash: String,
+}
+
+impl ApiKey {
+    /// Creates a key with a fresh secret, returning it along with the full key
+    /// to hand to the caller.
+    #[must_use]
+    pub fn generate(
+        household_id: &str,
+        created_by: &str,
+        name: &str,
+        scope: Scope,
+        now: u64,
+    ) -> (Self, String) {
+        let id = Uuid::new_v4();
+        // Version 4 uuids are drawn from a cryptographically secure generator,
+        // two of them make for a secret of 244 random bits.
+        let secret = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let key = Self {
+            id,
+            household_id: household_id.to_owned(),
+            name: name.to_owned(),
+            scope,
+            created_by: created_by.to_owned(),
+            created_at: now,
+            last_used_at: None,
+            hash: hash_secret(&secret),
+        };
+
+        (key, format!("{KEY_PREFIX}{}_{secret}", id.simple()))
+    }
+
+    /// Checks the secret part of a key against the stored hash in constant
+    /// time.
+    #[must_use]
+    pub fn verify(&self, secret: &str) -> bool {
+        hash_secret(secret)
+            .as_bytes()
+            .ct_eq(self.hash.as_bytes())
+            .into()
+    }
+
+    /// Whether the last used timestamp is stale enough to be written again.
+    #[must_use]
+    pub fn needs_touch(&self, now: u64) -> bool {
+        self.last_used_at
+            .is_none_or(|last_used_at| last_used_at + LAST_USED_RESOLUTION.as_secs() <= now)
+    }
+}
+
+/// Splits a key into the id it is stored under and its secret.
+#[must_use]
+pub fn parse(key: &str) -> Option<(Uuid, &str)> {
+    let (id, secret) = key.trim().strip_prefix(KEY_PREFIX)?.split_once('_')?;
+    let id = Uuid::try_parse(id).ok()?;
+
+    (!secret.is_empty()).then_some((id, secret))
+}
+
+fn hash_secret(secret: &str) -> String { hex::encode(Sha256::digest(secret.as_bytes())) }
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    fn find_key(&self, id: Uuid) -> impl Future<Output = Result<ApiKey, StatusCode>> + Send;
+    fn get_keys(
+        &self,
+        household_id: &str,
+    ) -> impl Future<Output = Result<Vec<ApiKey>, StatusCode>> + Send;
+    fn save_key(&self, key: &ApiKey) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    /// Records that a key was used at the given time.
+    fn touch_key(&self, id: Uuid, now: u64) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    fn delete_key(&self, id: Uuid) -> impl Future<Output = Result<(), StatusCode>> + Send;
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use super::{parse, ApiKey, Scope};
+
+    #[test]
+    fn generated_key_verifies() {
+        let (key, full_key) = ApiKey::generate("household", "grandma", "Kitchen", Scope::Read, 0);
+
+        let (id, secret) = parse(&full_key).unwrap();
+
+        assert_eq!(id, key.id);
+        assert!(key.verify(secret));
+        assert!(!key.verify("guess"));
+        assert!(!key.hash.contains(secret));
+    }
+
+    #[test]
+    fn parse_malformed() {
+        let id = Uuid::nil().simple();
+
+        assert_eq!(
+            parse(&format!("mpk_{id}_secret")),
+            Some((Uuid::nil(), "secret"))
+        );
+        assert_eq!(parse(&format!("{id}_secret")), None);
+        assert_eq!(parse(&format!("mpk_{id}_")), None);
+        assert_eq!(parse("mpk_not-an-id_secret"), None);
+    }
+
+    #[test]
+    fn needs_touch() {
+        let key = ApiKey {
+            last_used_at: Some(100),
+            ..ApiKey::default()
+        };
+
+        assert!(ApiKey::default().needs_touch(0));
+        assert!(!key.needs_touch(159));
+        assert!(key.needs_touch(160));
+    }
+}
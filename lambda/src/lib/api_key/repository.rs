@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aws_config::SdkConfig;
+use aws_sdk_dynamodb::operation::delete_item::DeleteItemError;
+use aws_sdk_dynamodb::operation::update_item::UpdateItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use axum::http::StatusCode;
+use serde_dynamo::aws_sdk_dynamodb_1::{from_item, to_item};
+use uuid::Uuid;
+
+use super::{ApiKey, ApiKeyRepository};
+use crate::aws_client::{
+    query_all,
+    DynamoDbClient,
+    DynamoDbClientImpl,
+    QueryExpression,
+    UpdateExpression,
+};
+
+/// The global secondary index of the key table, partitioned by household.
+pub const KEY_HOUSEHOLD_INDEX: &str = "household_id-index";
+
+#[derive(Clone)]
+pub struct DynamoDbApiKey {
+    client: Arc<dyn DynamoDbClient>,
+    table_name: String,
+}
+
+impl DynamoDbApiKey {
+    #[must_use]
+    pub fn new(sdk_config: &SdkConfig, table_name: &str) -> Self {
+        Self {
+            client: Arc::new(DynamoDbClientImpl::new(sdk_config)),
+            table_name: table_name.to_owned(),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn mock(client: Arc<dyn DynamoDbClient>) -> Self {
+        Self {
+            client,
+            table_name: "api-keys".to_owned(),
+        }
+    }
+}
+
+impl ApiKeyRepository for DynamoDbApiKey {
+    async fn find_key(&self, id: Uuid) -> Result<ApiKey, StatusCode> {
+        let output = self
+            .client
+            .get_item(&self.table_name, DynamoDbApiKey::get_key(id))
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let item = output.item().ok_or(StatusCode::NOT_FOUND)?.to_owned();
+
+        from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    async fn get_keys(&self, household_id: &str) -> Result<Vec<ApiKey>, StatusCode> {
+        let query = QueryExpression {
+            key_condition: "household_id = :household_id".to_owned(),
+            index_name: Some(KEY_HOUSEHOLD_INDEX.to_owned()),
+            values: HashMap::from([(
+                ":household_id".to_owned(),
+                AttributeValue::S(household_id.to_owned()),
+            )]),
+            scan_forward: true,
+            ..QueryExpression::default()
+        };
+        let items = query_all(self.client.as_ref(), &self.table_name, query)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(items.into_iter().flat_map(from_item).collect())
+    }
+
+    async fn save_key(&self, key: &ApiKey) -> Result<(), StatusCode> {
+        let item = to_item(key).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.client
+            .put_new_item(&self.table_name, item, "id")
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(())
+    }
+
+    async fn touch_key(&self, id: Uuid, now: u64) -> Result<(), StatusCode> {
+        let update = UpdateExpression {
+            expression: "SET last_used_at = :now".to_owned(),
+            // Keeps a key that was revoked in the meantime from reappearing.
+            condition: Some("attribute_exists(id)".to_owned()),
+            values: HashMap::from([(":now".to_owned(), AttributeValue::N(now.to_string()))]),
+            ..UpdateExpression::default()
+        };
+        self.client
+            .update_item(&self.table_name, DynamoDbApiKey::get_key(id), update)
+            .await
+            .map_err(|err| match err {
+                UpdateItemError::ConditionalCheckFailedException(_)
+                | UpdateItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+
+        Ok(())
+    }
+
+    async fn delete_key(&self, id: Uuid) -> Result<(), StatusCode> {
+        self.client
+            .delete_item(&self.table_name, DynamoDbApiKey::get_key(id), "id")
+            .await
+            .map_err(|err| match err {
+                DeleteItemError::ConditionalCheckFailedException(_)
+                | DeleteItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+
+        Ok(())
+    }
+}
+
+impl DynamoDbApiKey {
+    fn get_key(id: Uuid) -> HashMap<String, AttributeValue> {
+        HashMap::from([(
+            "id".to_owned(),
+            AttributeValue::S(id.as_hyphenated().to_string()),
+        )])
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::result_large_err)]
+mod test {
+    use aws_sdk_dynamodb::operation::get_item::GetItemOutput;
+    use aws_sdk_dynamodb::operation::update_item::UpdateItemOutput;
+    use aws_sdk_dynamodb::types::error::ConditionalCheckFailedException;
+    use mockall::predicate::{eq, function};
+
+    use super::*;
+    use crate::api_key::Scope;
+    use crate::aws_client::MockDynamoDbClient as DynamoDbClient;
+
+    #[tokio::test]
+    async fn test_find_key() {
+        let key = ApiKey {
+            id: Uuid::nil(),
+            household_id: "household".to_owned(),
+            name: "Kitchen".to_owned(),
+            scope: Scope::ReadWrite,
+            hash: "abc".to_owned(),
+            ..ApiKey::default()
+        };
+        let item = to_item(&key).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .with(eq("api-keys"), eq(DynamoDbApiKey::get_key(Uuid::nil())))
+            .return_once(|_, _| Ok(GetItemOutput::builder().set_item(Some(item)).build()));
+
+        let repo = DynamoDbApiKey::mock(Arc::new(mock));
+
+        let result = repo.find_key(Uuid::nil()).await;
+        assert_eq!(result, Ok(key));
+    }
+
+    #[tokio::test]
+    async fn test_touch_key() {
+        let mut mock = DynamoDbClient::default();
+        mock.expect_update_item()
+            .with(
+                eq("api-keys"),
+                eq(DynamoDbApiKey::get_key(Uuid::nil())),
+                function(|update: &UpdateExpression| {
+                    update.values.get(":now") == Some(&AttributeValue::N("42".to_owned()))
+                }),
+            )
+            .return_once(|_, _, _| Ok(UpdateItemOutput::builder().build()));
+
+        let repo = DynamoDbApiKey::mock(Arc::new(mock));
+
+        let result = repo.touch_key(Uuid::nil(), 42).await;
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_touch_revoked_key() {
+        let mut mock = DynamoDbClient::default();
+        mock.expect_update_item().return_once(|_, _, _| {
+            Err(UpdateItemError::ConditionalCheckFailedException(
+                ConditionalCheckFailedException::builder().build(),
+            ))
+        });
+
+        let repo = DynamoDbApiKey::mock(Arc::new(mock));
+
+        let result = repo.touch_key(Uuid::nil(), 42).await;
+        assert_eq!(result, Err(StatusCode::NOT_FOUND));
+    }
+}
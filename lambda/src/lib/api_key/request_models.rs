@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+use super::Scope;
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct PostApiKey {
+    /// Tells the keys of a household apart, e.g. the script that uses it.
+    pub(crate) name: String,
+    pub(crate) scope: Scope,
+}
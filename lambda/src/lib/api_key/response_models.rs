@@ -0,0 +1,37 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{ApiKey, Scope};
+
+/// An API key as listed to the members of its household, without its hash.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ApiKeyResponse {
+    pub(crate) id: Uuid,
+    pub(crate) name: String,
+    pub(crate) scope: Scope,
+    pub(crate) created_by: String,
+    pub(crate) created_at: u64,
+    pub(crate) last_used_at: Option<u64>,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            id: key.id,
+            name: key.name,
+            scope: key.scope,
+            created_by: key.created_by,
+            created_at: key.created_at,
+            last_used_at: key.last_used_at,
+        }
+    }
+}
+
+/// A newly created API key along with the key itself, which cannot be read
+/// again later.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub(crate) details: ApiKeyResponse,
+    pub(crate) key: String,
+}
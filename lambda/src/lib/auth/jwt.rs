@@ -0,0 +1,266 @@
+use std::fmt;
+
+use axum::http::StatusCode;
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+
+use super::Principal;
+
+/// Verifies bearer tokens against a fixed set of public keys.
+///
+/// Only asymmetric signatures are accepted, RS256 with RSA keys and ES256 with
+/// P-256 keys. Keys of any other kind in the key set are ignored.
+pub struct JwtVerifier {
+    keys: Vec<VerificationKey>,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+struct VerificationKey {
+    id: Option<String>,
+    algorithm: Algorithm,
+    key: DecodingKey,
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+}
+
+#[derive(Debug)]
+pub enum JwksError {
+    Io(std::io::Error),
+    Http(reqwest::Error),
+    Json(serde_json::Error),
+    NoUsableKeys,
+}
+
+impl fmt::Display for JwksError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JwksError::Io(err) => write!(f, "could not read the key set: {err}"),
+            JwksError::Http(err) => write!(f, "could not fetch the key set: {err}"),
+            JwksError::Json(err) => write!(f, "could not parse the key set: {err}"),
+            JwksError::NoUsableKeys => write!(f, "the key set has no RS256 or ES256 keys"),
+        }
+    }
+}
+
+impl std::error::Error for JwksError {}
+
+impl JwtVerifier {
+    /// Builds a verifier from the usable keys of a key set.
+    ///
+    /// # Errors
+    ///
+    /// Fails if none of the keys can verify RS256 or ES256 signatures.
+    pub fn from_jwks(jwks: &JwkSet) -> Result<Self, JwksError> {
+        let keys: Vec<VerificationKey> = jwks
+            .keys
+            .iter()
+            .filter_map(|jwk| {
+                Some(VerificationKey {
+                    id: jwk.common.key_id.clone(),
+                    algorithm: signing_algorithm(jwk)?,
+                    key: DecodingKey::from_jwk(jwk).ok()?,
+                })
+            })
+            .collect();
+
+        if keys.is_empty() {
+            return Err(JwksError::NoUsableKeys);
+        }
+
+        Ok(Self {
+            keys,
+            issuer: None,
+            audience: None,
+        })
+    }
+
+    /// Loads the key set from an `http(s)` URL or, otherwise, a file path.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the key set cannot be read or has no usable keys.
+    pub async fn load(source: &str) -> Result<Self, JwksError> {
+        let jwks: JwkSet = if source.starts_with("https://") || source.starts_with("http://") {
+            reqwest::get(source)
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .map_err(JwksError::Http)?
+                .json()
+                .await
+                .map_err(JwksError::Http)?
+        } else {
+            let contents = std::fs::read(source).map_err(JwksError::Io)?;
+            serde_json::from_slice(&contents).map_err(JwksError::Json)?
+        };
+
+        Self::from_jwks(&jwks)
+    }
+
+    #[must_use]
+    pub fn with_issuer(mut self, issuer: &str) -> Self {
+        self.issuer = Some(issuer.to_owned());
+        self
+    }
+
+    #[must_use]
+    pub fn with_audience(mut self, audience: &str) -> Self {
+        self.audience = Some(audience.to_owned());
+        self
+    }
+
+    /// Verifies the signature and expiry of a token and returns its subject.
+    ///
+    /// # Errors
+    ///
+    /// Responds with unauthorized if the token cannot be verified by any key.
+    pub fn verify(&self, token: &str) -> Result<Principal, StatusCode> {
+        let header = jsonwebtoken::decode_header(token).map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_required_spec_claims(&["exp", "sub"]);
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        self.keys
+            .iter()
+            .filter(|key| key.algorithm == header.alg)
+            .filter(|key| header.kid.is_none() || key.id == header.kid)
+            .find_map(|key| jsonwebtoken::decode::<Claims>(token, &key.key, &validation).ok())
+            .map(|data| Principal::new(data.claims.sub))
+            .ok_or(StatusCode::UNAUTHORIZED)
+    }
+}
+
+fn signing_algorithm(jwk: &Jwk) -> Option<Algorithm> {
+    let inferred = match &jwk.algorithm {
+        AlgorithmParameters::RSA(_) => Algorithm::RS256,
+        AlgorithmParameters::EllipticCurve(params) if params.curve == EllipticCurve::P256 => {
+            Algorithm::ES256
+        },
+        _ => return None,
+    };
+
+    match jwk.common.key_algorithm {
+        None => Some(inferred),
+        Some(KeyAlgorithm::RS256) if inferred == Algorithm::RS256 => Some(inferred),
+        Some(KeyAlgorithm::ES256) if inferred == Algorithm::ES256 => Some(inferred),
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use jsonwebtoken::jwk::JwkSet;
+    use jsonwebtoken::{Algorithm, EncodingKey, Header};
+    use serde_json::json;
+
+    use super::JwtVerifier;
+    use crate::clock;
+
+    const JWKS: &str = include_str!("fixtures/jwks.json");
+    const RSA_PRIVATE_KEY: &[u8] = include_bytes!("fixtures/rsa_private.pem");
+    const EC_PRIVATE_KEY: &[u8] = include_bytes!("fixtures/ec_private.pem");
+
+    pub(crate) fn verifier() -> JwtVerifier {
+        let jwks: JwkSet = serde_json::from_str(JWKS).unwrap();
+        JwtVerifier::from_jwks(&jwks).unwrap()
+    }
+
+    fn sign(algorithm: Algorithm, kid: &str, claims: &serde_json::Value) -> String {
+        let key = match algorithm {
+            Algorithm::ES256 => EncodingKey::from_ec_pem(EC_PRIVATE_KEY).unwrap(),
+            _ => EncodingKey::from_rsa_pem(RSA_PRIVATE_KEY).unwrap(),
+        };
+        let mut header = Header::new(algorithm);
+        header.kid = Some(kid.to_owned());
+
+        jsonwebtoken::encode(&header, claims, &key).unwrap()
+    }
+
+    /// Signs a valid RS256 token for the given subject.
+    pub(crate) fn token(subject: &str) -> String {
+        sign(
+            Algorithm::RS256,
+            "test-rsa",
+            &json!({ "sub": subject, "exp": clock::now() + 60 }),
+        )
+    }
+
+    #[test]
+    fn verify_rs256() {
+        let principal = verifier().verify(&token("grandma")).unwrap();
+
+        assert_eq!(principal.subject(), "grandma");
+    }
+
+    #[test]
+    fn verify_es256() {
+        let token = sign(
+            Algorithm::ES256,
+            "test-ec",
+            &json!({ "sub": "grandpa", "exp": clock::now() + 60 }),
+        );
+
+        let principal = verifier().verify(&token).unwrap();
+
+        assert_eq!(principal.subject(), "grandpa");
+    }
+
+    #[test]
+    fn verify_expired() {
+        let token = sign(
+            Algorithm::RS256,
+            "test-rsa",
+            &json!({ "sub": "grandma", "exp": clock::now() - 3600 }),
+        );
+
+        assert!(verifier().verify(&token).is_err());
+    }
+
+    #[test]
+    fn verify_wrong_key() {
+        let token = sign(
+            Algorithm::RS256,
+            "test-ec",
+            &json!({ "sub": "grandma", "exp": clock::now() + 60 }),
+        );
+
+        assert!(verifier().verify(&token).is_err());
+    }
+
+    #[test]
+    fn verify_symmetric_algorithm() {
+        let token = jsonwebtoken::encode(
+            &Header::new(Algorithm::HS256),
+            &json!({ "sub": "grandma", "exp": clock::now() + 60 }),
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+
+        assert!(verifier().verify(&token).is_err());
+    }
+
+    #[test]
+    fn verify_issuer() {
+        let token = sign(
+            Algorithm::RS256,
+            "test-rsa",
+            &json!({ "sub": "grandma", "exp": clock::now() + 60, "iss": "someone-else" }),
+        );
+
+        assert!(verifier()
+            .with_issuer("meal-planner")
+            .verify(&token)
+            .is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use uuid::Uuid;
+
+use self::jwt::JwtVerifier;
+use crate::api_key::ApiKey;
+use crate::household::{Member, Role};
+
+pub mod jwt;
+
+/// The authenticated caller of a request, the household they act for and
+/// their role in it.
+///
+/// Every caller starts out as the owner of a household of their own,
+/// identified by their subject, until they join another household.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Principal {
+    subject: String,
+    household_id: String,
+    role: Role,
+    api_key: Option<Uuid>,
+    key_creator: Option<String>,
+}
+
+impl Principal {
+    #[must_use]
+    pub fn new(subject: impl Into<String>) -> Self {
+        let subject = subject.into();
+        Self {
+            household_id: subject.clone(),
+            subject,
+            role: Role::Owner,
+            api_key: None,
+            key_creator: None,
+        }
+    }
+
+    /// Makes a script act for the household its key was created for, with the
+    /// role its scope allows.
+    #[must_use]
+    pub fn from_api_key(key: &ApiKey) -> Self {
+        Self {
+            subject: format!("api-key:{}", key.id),
+            household_id: key.household_id.clone(),
+            role: key.scope.role(),
+            api_key: Some(key.id),
+            key_creator: Some(key.created_by.clone()),
+        }
+    }
+
+    /// Makes the caller act for the household they are a member of.
+    #[must_use]
+    pub fn with_membership(self, member: &Member) -> Self {
+        Self {
+            household_id: member.household_id.clone(),
+            role: member.role,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn subject(&self) -> &str { &self.subject }
+
+    #[must_use]
+    pub fn household_id(&self) -> &str { &self.household_id }
+
+    #[must_use]
+    pub fn role(&self) -> Role { self.role }
+
+    /// The id of the API key the caller authenticated with, if any.
+    #[must_use]
+    pub fn api_key(&self) -> Option<Uuid> { self.api_key }
+
+    /// The subject that created the API key the caller authenticated with, if
+    /// any.
+    #[must_use]
+    pub fn key_creator(&self) -> Option<&str> { self.key_creator.as_deref() }
+
+    /// Lowers the caller's role to the given one if it is higher.
+    #[must_use]
+    pub fn with_role_at_most(self, role: Role) -> Self {
+        Self {
+            role: self.role.min(role),
+            ..self
+        }
+    }
+
+    /// Checks that the caller has at least the given role in their household.
+    ///
+    /// # Errors
+    ///
+    /// Responds with forbidden if the caller's role is lower.
+    pub fn require(&self, role: Role) -> Result<(), StatusCode> {
+        if self.role >= role {
+            Ok(())
+        } else {
+            Err(StatusCode::FORBIDDEN)
+        }
+    }
+}
+
+/// Handlers can take the [`Principal`] as an argument once [`authenticate`]
+/// has run for the route.
+#[async_trait]
+impl<S> FromRequestParts<S> for Principal
+where
+    S: Send + Sync,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Principal>()
+            .cloned()
+            .ok_or(StatusCode::UNAUTHORIZED)
+    }
+}
+
+/// Middleware that rejects requests without a valid bearer token and stores
+/// the caller as a [`Principal`] request extension. Requests that already
+/// carry a principal, e.g. from an API key, are passed through.
+pub async fn authenticate(
+    State(verifier): State<Arc<JwtVerifier>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if request.extensions().get::<Principal>().is_some() {
+        return next.run(request).await;
+    }
+
+    let principal = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)
+        .and_then(|token| verifier.verify(token.trim()));
+
+    match principal {
+        Ok(principal) => {
+            request.extensions_mut().insert(principal);
+            next.run(request).await
+        },
+        Err(status) => (status, [(WWW_AUTHENTICATE, "Bearer")]).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use axum::body::Body;
+    use axum::http::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use axum::{middleware, Router};
+    use tower::ServiceExt;
+
+    use super::{authenticate, Principal};
+    use crate::auth::jwt::test::{token, verifier};
+    use crate::household::{Member, Role};
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/whoami",
+                get(|principal: Principal| async move { principal.subject().to_owned() }),
+            )
+            .layer(middleware::from_fn_with_state(
+                Arc::new(verifier()),
+                authenticate,
+            ))
+    }
+
+    #[tokio::test]
+    async fn authenticate_valid_token() {
+        let request = Request::get("/whoami")
+            .header(AUTHORIZATION, format!("Bearer {}", token("grandma")))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app().oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"grandma");
+    }
+
+    #[tokio::test]
+    async fn authenticate_missing_token() {
+        let request = Request::get("/whoami").body(Body::empty()).unwrap();
+
+        let response = app().oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[WWW_AUTHENTICATE], "Bearer");
+    }
+
+    #[tokio::test]
+    async fn authenticate_malformed_token() {
+        let request = Request::get("/whoami")
+            .header(AUTHORIZATION, "Bearer not-a-token")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app().oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn require_role() {
+        let member = Member {
+            household_id: "household".to_owned(),
+            role: Role::Editor,
+            ..Member::default()
+        };
+        let principal = Principal::new("grandpa").with_membership(&member);
+
+        assert_eq!(principal.household_id(), "household");
+        assert_eq!(principal.require(Role::Viewer), Ok(()));
+        assert_eq!(principal.require(Role::Editor), Ok(()));
+        assert_eq!(principal.require(Role::Owner), Err(StatusCode::FORBIDDEN));
+    }
+}
@@ -1,16 +1,59 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use aws_config::SdkConfig;
 use aws_sdk_dynamodb::error::SdkError;
+use aws_sdk_dynamodb::operation::batch_get_item::{BatchGetItemError, BatchGetItemOutput};
 use aws_sdk_dynamodb::operation::delete_item::{DeleteItemError, DeleteItemOutput};
 use aws_sdk_dynamodb::operation::get_item::{GetItemError, GetItemOutput};
 use aws_sdk_dynamodb::operation::put_item::{PutItemError, PutItemOutput};
+use aws_sdk_dynamodb::operation::query::{QueryError, QueryOutput};
 use aws_sdk_dynamodb::operation::scan::{ScanError, ScanOutput};
-use aws_sdk_dynamodb::types::{AttributeValue, ReturnValue};
+use aws_sdk_dynamodb::operation::transact_write_items::{
+    TransactWriteItemsError,
+    TransactWriteItemsOutput,
+};
+use aws_sdk_dynamodb::operation::update_item::{UpdateItemError, UpdateItemOutput};
+use aws_sdk_dynamodb::types::{AttributeValue, KeysAndAttributes, ReturnValue, TransactWriteItem};
 use aws_sdk_dynamodb::Client;
 use axum::async_trait;
 
+/// The most keys DynamoDB accepts in a single `BatchGetItem` request.
+pub const BATCH_GET_LIMIT: usize = 100;
+
+/// How often a batch is sent before the keys DynamoDB left unprocessed are
+/// given up on.
+const BATCH_ATTEMPTS: u32 = 5;
+
+/// How long to wait before resending unprocessed keys the first time. The wait
+/// doubles with every attempt.
+const BATCH_BACKOFF: Duration = Duration::from_millis(50);
+
+/// An update expression along with the names and values it refers to.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UpdateExpression {
+    pub expression: String,
+    pub condition: Option<String>,
+    pub names: HashMap<String, String>,
+    pub values: HashMap<String, AttributeValue>,
+}
+
+/// A key condition for querying a table, along with the names and values it
+/// refers to.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryExpression {
+    pub key_condition: String,
+    /// Queries a global secondary index of the table instead of the table.
+    pub index_name: Option<String>,
+    pub names: HashMap<String, String>,
+    pub values: HashMap<String, AttributeValue>,
+    pub scan_forward: bool,
+    pub limit: Option<i32>,
+    pub exclusive_start_key: Option<HashMap<String, AttributeValue>>,
+}
+
 #[cfg_attr(test, mockall::automock)]
+#[cfg_attr(test, allow(clippy::result_large_err))]
 #[async_trait]
 pub trait DynamoDbClient: Send + Sync {
     async fn get_item(
@@ -25,13 +68,143 @@ pub trait DynamoDbClient: Send + Sync {
         item: HashMap<String, AttributeValue>,
     ) -> Result<PutItemOutput, PutItemError>;
 
+    /// Puts an item only if no item with the same key exists yet, failing with
+    /// [`PutItemError::ConditionalCheckFailedException`] otherwise.
+    async fn put_new_item(
+        &self,
+        table_name: &str,
+        item: HashMap<String, AttributeValue>,
+        key_attribute: &str,
+    ) -> Result<PutItemOutput, PutItemError>;
+
+    /// Deletes an item only if it exists, failing with
+    /// [`DeleteItemError::ConditionalCheckFailedException`] otherwise.
     async fn delete_item(
         &self,
         table_name: &str,
         key: HashMap<String, AttributeValue>,
+        key_attribute: &str,
     ) -> Result<DeleteItemOutput, DeleteItemError>;
 
+    async fn update_item(
+        &self,
+        table_name: &str,
+        key: HashMap<String, AttributeValue>,
+        update: UpdateExpression,
+    ) -> Result<UpdateItemOutput, UpdateItemError>;
+
+    async fn query(
+        &self,
+        table_name: &str,
+        query: QueryExpression,
+    ) -> Result<QueryOutput, QueryError>;
+
     async fn scan(&self, table_name: &str) -> Result<ScanOutput, ScanError>;
+
+    /// Reads up to [`BATCH_GET_LIMIT`] items of a table by their keys. DynamoDB
+    /// may leave some of them unprocessed, see [`batch_get_all`].
+    async fn batch_get_item(
+        &self,
+        table_name: &str,
+        keys: Vec<HashMap<String, AttributeValue>>,
+    ) -> Result<BatchGetItemOutput, BatchGetItemError>;
+
+    /// Writes items all at once or not at all.
+    async fn transact_write_items(
+        &self,
+        items: Vec<TransactWriteItem>,
+    ) -> Result<TransactWriteItemsOutput, TransactWriteItemsError>;
+}
+
+/// Runs a query to completion, following the last evaluated key from page to
+/// page.
+///
+/// # Errors
+///
+/// Returns the error of the first page that fails.
+pub async fn query_all(
+    client: &dyn DynamoDbClient,
+    table_name: &str,
+    query: QueryExpression,
+) -> Result<Vec<HashMap<String, AttributeValue>>, QueryError> {
+    let mut items = Vec::new();
+    let mut exclusive_start_key = None;
+    loop {
+        let page = QueryExpression {
+            exclusive_start_key,
+            ..query.clone()
+        };
+        let output = client.query(table_name, page).await?;
+        items.extend_from_slice(output.items());
+
+        exclusive_start_key = output.last_evaluated_key().cloned();
+        if exclusive_start_key.is_none() {
+            return Ok(items);
+        }
+    }
+}
+
+/// Reads any number of items of a table by their keys, [`BATCH_GET_LIMIT`] at a
+/// time. Keys DynamoDB leaves unprocessed, and whole batches it throttles, are
+/// sent again with exponential backoff. Keys of items that do not exist are
+/// skipped, and the items come back in no particular order.
+///
+/// # Errors
+///
+/// Returns the first error that retrying cannot fix, or an unhandled error if
+/// some keys are still unprocessed after the last attempt.
+pub async fn batch_get_all(
+    client: &dyn DynamoDbClient,
+    table_name: &str,
+    keys: Vec<HashMap<String, AttributeValue>>,
+) -> Result<Vec<HashMap<String, AttributeValue>>, BatchGetItemError> {
+    let mut items = Vec::with_capacity(keys.len());
+    for chunk in keys.chunks(BATCH_GET_LIMIT) {
+        let mut pending = chunk.to_vec();
+        for attempt in 0..BATCH_ATTEMPTS {
+            backoff(attempt).await;
+            match client.batch_get_item(table_name, pending.clone()).await {
+                Ok(mut output) => {
+                    items.extend(
+                        output
+                            .responses
+                            .as_mut()
+                            .and_then(|responses| responses.remove(table_name))
+                            .unwrap_or_default(),
+                    );
+                    pending = output
+                        .unprocessed_keys
+                        .and_then(|mut keys| keys.remove(table_name))
+                        .map(|keys| keys.keys)
+                        .unwrap_or_default();
+                },
+                Err(
+                    BatchGetItemError::ProvisionedThroughputExceededException(_)
+                    | BatchGetItemError::RequestLimitExceeded(_)
+                    | BatchGetItemError::InternalServerError(_),
+                ) => {},
+                Err(err) => return Err(err),
+            }
+            if pending.is_empty() {
+                break;
+            }
+        }
+        if !pending.is_empty() {
+            return Err(BatchGetItemError::unhandled(format!(
+                "{} keys of {table_name} are still unprocessed",
+                pending.len()
+            )));
+        }
+    }
+
+    Ok(items)
+}
+
+/// Waits before an attempt to resend unprocessed keys.
+async fn backoff(attempt: u32) {
+    if attempt > 0 {
+        tokio::time::sleep(BATCH_BACKOFF * 2_u32.pow(attempt - 1)).await;
+    }
 }
 
 #[derive(Clone)]
@@ -73,16 +246,77 @@ impl DynamoDbClient for DynamoDbClientImpl {
             .map_err(SdkError::into_service_error)
     }
 
+    async fn put_new_item(
+        &self,
+        table_name: &str,
+        item: HashMap<String, AttributeValue>,
+        key_attribute: &str,
+    ) -> Result<PutItemOutput, PutItemError> {
+        self.0
+            .put_item()
+            .table_name(table_name)
+            .set_item(Some(item))
+            .condition_expression("attribute_not_exists(#key)")
+            .expression_attribute_names("#key", key_attribute)
+            .send()
+            .await
+            .map_err(SdkError::into_service_error)
+    }
+
     async fn delete_item(
         &self,
         table_name: &str,
         key: HashMap<String, AttributeValue>,
+        key_attribute: &str,
     ) -> Result<DeleteItemOutput, DeleteItemError> {
         self.0
             .delete_item()
             .table_name(table_name)
             .set_key(Some(key))
-            .condition_expression("attribute_exists(id)")
+            .condition_expression("attribute_exists(#key)")
+            .expression_attribute_names("#key", key_attribute)
+            .send()
+            .await
+            .map_err(SdkError::into_service_error)
+    }
+
+    async fn update_item(
+        &self,
+        table_name: &str,
+        key: HashMap<String, AttributeValue>,
+        update: UpdateExpression,
+    ) -> Result<UpdateItemOutput, UpdateItemError> {
+        self.0
+            .update_item()
+            .table_name(table_name)
+            .set_key(Some(key))
+            .update_expression(update.expression)
+            .set_condition_expression(update.condition)
+            .set_expression_attribute_names(Some(update.names).filter(|names| !names.is_empty()))
+            .set_expression_attribute_values(
+                Some(update.values).filter(|values| !values.is_empty()),
+            )
+            .return_values(ReturnValue::AllNew)
+            .send()
+            .await
+            .map_err(SdkError::into_service_error)
+    }
+
+    async fn query(
+        &self,
+        table_name: &str,
+        query: QueryExpression,
+    ) -> Result<QueryOutput, QueryError> {
+        self.0
+            .query()
+            .table_name(table_name)
+            .set_index_name(query.index_name)
+            .key_condition_expression(query.key_condition)
+            .set_expression_attribute_names(Some(query.names).filter(|names| !names.is_empty()))
+            .set_expression_attribute_values(Some(query.values).filter(|values| !values.is_empty()))
+            .scan_index_forward(query.scan_forward)
+            .set_limit(query.limit)
+            .set_exclusive_start_key(query.exclusive_start_key)
             .send()
             .await
             .map_err(SdkError::into_service_error)
@@ -96,4 +330,33 @@ impl DynamoDbClient for DynamoDbClientImpl {
             .await
             .map_err(SdkError::into_service_error)
     }
+
+    async fn batch_get_item(
+        &self,
+        table_name: &str,
+        keys: Vec<HashMap<String, AttributeValue>>,
+    ) -> Result<BatchGetItemOutput, BatchGetItemError> {
+        let keys = KeysAndAttributes::builder()
+            .set_keys(Some(keys))
+            .build()
+            .map_err(BatchGetItemError::unhandled)?;
+        self.0
+            .batch_get_item()
+            .request_items(table_name, keys)
+            .send()
+            .await
+            .map_err(SdkError::into_service_error)
+    }
+
+    async fn transact_write_items(
+        &self,
+        items: Vec<TransactWriteItem>,
+    ) -> Result<TransactWriteItemsOutput, TransactWriteItemsError> {
+        self.0
+            .transact_write_items()
+            .set_transact_items(Some(items))
+            .send()
+            .await
+            .map_err(SdkError::into_service_error)
+    }
 }
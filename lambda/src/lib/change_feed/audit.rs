@@ -0,0 +1,31 @@
+use axum::async_trait;
+use axum::http::StatusCode;
+use tracing::info;
+
+use super::{ChangeHandler, RecipeChange};
+
+/// Writes a line for every change to the log of the function, so that it can
+/// be traced who changed which recipe of a household and when.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AuditLog;
+
+#[async_trait]
+impl ChangeHandler for AuditLog {
+    fn name(&self) -> &'static str { "audit" }
+
+    async fn handle(&self, change: &RecipeChange) -> Result<(), StatusCode> {
+        info!(
+            target: "audit",
+            household_id = change.household_id(),
+            recipe_id = %change.id(),
+            change = ?change.kind(),
+            name = change.recipe().name,
+            expired = change.expired,
+            changed_at = change.changed_at,
+            sequence_number = change.sequence_number,
+            "Recipe changed"
+        );
+
+        Ok(())
+    }
+}
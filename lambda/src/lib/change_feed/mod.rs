@@ -0,0 +1,365 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_dynamo::Item;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::recipe::Recipe;
+
+pub mod audit;
+
+/// The principal DynamoDB removes expired items as.
+pub const TIME_TO_LIVE_PRINCIPAL: &str = "dynamodb.amazonaws.com";
+
+/// A batch of records read from the stream of the recipe table.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct StreamEvent {
+    #[serde(rename = "Records", default)]
+    pub records: Vec<StreamRecord>,
+}
+
+/// A change to a single item of the table.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamRecord {
+    #[serde(rename = "eventID", default)]
+    pub event_id: String,
+    pub event_name: OperationType,
+    pub dynamodb: StreamData,
+    /// Set when the item was removed by DynamoDB itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_identity: Option<UserIdentity>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OperationType {
+    #[default]
+    Insert,
+    Modify,
+    Remove,
+}
+
+/// The images of the item before and after the change, in the attribute value
+/// format of DynamoDB.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct StreamData {
+    /// When the change was made, in seconds since the epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approximate_creation_date_time: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_image: Option<Item>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_image: Option<Item>,
+    pub sequence_number: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserIdentity {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub principal_id: String,
+}
+
+/// The response that tells Lambda which records to retry, see
+/// <https://docs.aws.amazon.com/lambda/latest/dg/services-ddb-batchfailurereporting.html>.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamResponse {
+    pub batch_item_failures: Vec<BatchItemFailure>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItemFailure {
+    pub item_identifier: String,
+}
+
+/// What happened to a recipe, told apart by the images of the change.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    /// The recipe was moved to the trash.
+    Trashed,
+    /// The recipe was restored from the trash.
+    Restored,
+    /// The recipe was removed for good, either directly or when it expired in
+    /// the trash.
+    Deleted,
+}
+
+/// A change to a recipe, decoded from a stream record.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecipeChange {
+    pub(crate) kind: ChangeKind,
+    pub(crate) old: Option<Recipe>,
+    pub(crate) new: Option<Recipe>,
+    /// Whether DynamoDB removed the recipe because its time to live passed.
+    pub(crate) expired: bool,
+    /// When the change was made, in seconds since the epoch.
+    pub(crate) changed_at: Option<u64>,
+    pub(crate) sequence_number: String,
+}
+
+impl RecipeChange {
+    /// Decodes the images of a record into recipes.
+    ///
+    /// # Errors
+    ///
+    /// Responds with unprocessable entity if the record lacks the image its
+    /// operation needs or an image is not a recipe.
+    pub fn from_record(record: &StreamRecord) -> Result<Self, StatusCode> {
+        let decode = |image: &Option<Item>| {
+            image
+                .clone()
+                .map(serde_dynamo::from_item::<_, Recipe>)
+                .transpose()
+                .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)
+        };
+        let old = decode(&record.dynamodb.old_image)?;
+        let new = decode(&record.dynamodb.new_image)?;
+
+        let kind = match (record.event_name, &old, &new) {
+            (OperationType::Insert, _, Some(_)) => ChangeKind::Created,
+            (OperationType::Modify, Some(old), Some(new)) => {
+                match (old.is_deleted(), new.is_deleted()) {
+                    (false, true) => ChangeKind::Trashed,
+                    (true, false) => ChangeKind::Restored,
+                    _ => ChangeKind::Updated,
+                }
+            },
+            (OperationType::Remove, Some(_), _) => ChangeKind::Deleted,
+            _ => return Err(StatusCode::UNPROCESSABLE_ENTITY),
+        };
+
+        Ok(Self {
+            kind,
+            old,
+            new,
+            expired: record
+                .user_identity
+                .as_ref()
+                .is_some_and(|identity| identity.principal_id == TIME_TO_LIVE_PRINCIPAL),
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            changed_at: record
+                .dynamodb
+                .approximate_creation_date_time
+                .map(|seconds| seconds as u64),
+            sequence_number: record.dynamodb.sequence_number.clone(),
+        })
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> ChangeKind { self.kind }
+
+    /// The recipe as it is after the change, or as it was before it was
+    /// deleted.
+    #[must_use]
+    pub fn recipe(&self) -> &Recipe {
+        self.new
+            .as_ref()
+            .or(self.old.as_ref())
+            .expect("every change has an image")
+    }
+
+    #[must_use]
+    pub fn household_id(&self) -> &str { &self.recipe().household_id }
+
+    #[must_use]
+    pub fn id(&self) -> Uuid { self.recipe().id }
+}
+
+/// Something that reacts to changes to recipes, such as keeping an index up to
+/// date or notifying other systems.
+///
+/// Records are delivered at least once, so handlers have to cope with seeing a
+/// change again.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ChangeHandler: Send + Sync {
+    /// Names the handler in logs.
+    fn name(&self) -> &'static str;
+    async fn handle(&self, change: &RecipeChange) -> Result<(), StatusCode>;
+}
+
+/// Hands every change read from the stream to each of its handlers.
+#[derive(Clone, Default)]
+pub struct ChangeFeed {
+    handlers: Vec<Arc<dyn ChangeHandler>>,
+}
+
+impl ChangeFeed {
+    #[must_use]
+    pub fn new(handlers: Vec<Arc<dyn ChangeHandler>>) -> Self { Self { handlers } }
+
+    /// Processes the records of a batch in order. Records that cannot be
+    /// decoded are logged and skipped, since retrying them would hold up the
+    /// stream for good. When a handler fails, the remaining records are left
+    /// for Lambda to retry, starting with the failed one.
+    pub async fn process(&self, event: &StreamEvent) -> StreamResponse {
+        for record in &event.records {
+            let change = match RecipeChange::from_record(record) {
+                Ok(change) => change,
+                Err(_) => {
+                    warn!(
+                        event_id = record.event_id,
+                        sequence_number = record.dynamodb.sequence_number,
+                        "Skipping stream record that is not a recipe"
+                    );
+                    continue;
+                },
+            };
+
+            if let Err(status) = self.dispatch(&change).await {
+                error!(
+                    sequence_number = change.sequence_number,
+                    %status,
+                    "Failed to handle recipe change"
+                );
+                return StreamResponse {
+                    batch_item_failures: vec![BatchItemFailure {
+                        item_identifier: change.sequence_number,
+                    }],
+                };
+            }
+        }
+
+        StreamResponse::default()
+    }
+
+    async fn dispatch(&self, change: &RecipeChange) -> Result<(), StatusCode> {
+        for handler in &self.handlers {
+            handler.handle(change).await.inspect_err(|status| {
+                error!(handler = handler.name(), %status, "Handler failed");
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::sync::Arc;
+
+    use axum::http::StatusCode;
+    use mockall::predicate::function;
+    use uuid::Uuid;
+
+    use super::{
+        BatchItemFailure,
+        ChangeFeed,
+        ChangeKind,
+        MockChangeHandler,
+        RecipeChange,
+        StreamEvent,
+        StreamResponse,
+    };
+
+    /// Stream records as Lambda delivers them: a recipe is created, renamed,
+    /// trashed, restored and finally expires from the trash.
+    pub(crate) const RECIPE_STREAM: &str = include_str!("fixtures/recipe_stream.json");
+
+    pub(crate) fn event() -> StreamEvent { serde_json::from_str(RECIPE_STREAM).unwrap() }
+
+    fn changes() -> Vec<RecipeChange> {
+        event()
+            .records
+            .iter()
+            .map(|record| RecipeChange::from_record(record).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn decodes_recorded_event() {
+        let changes = changes();
+
+        assert_eq!(
+            changes.iter().map(RecipeChange::kind).collect::<Vec<_>>(),
+            vec![
+                ChangeKind::Created,
+                ChangeKind::Updated,
+                ChangeKind::Trashed,
+                ChangeKind::Restored,
+                ChangeKind::Deleted,
+            ]
+        );
+        let created = &changes[0];
+        assert_eq!(created.household_id(), "grandma");
+        assert_eq!(
+            created.id(),
+            Uuid::parse_str("0b5a6f83-0c5c-4d16-b5a4-7c8a0e1e2c1f").unwrap()
+        );
+        assert_eq!(created.recipe().ingredients[0].quantity, Some(500.0));
+        assert_eq!(created.changed_at, Some(1_760_000_000));
+        assert_eq!(changes[1].old.as_ref().unwrap().name, "Bread");
+        assert_eq!(changes[1].recipe().name, "Sourdough");
+        assert!(!changes[2].expired);
+        assert!(changes[4].expired);
+    }
+
+    #[tokio::test]
+    async fn process_hands_changes_to_every_handler() {
+        let mut audit = MockChangeHandler::new();
+        audit.expect_handle().times(5).returning(|_| Ok(()));
+        let mut index = MockChangeHandler::new();
+        index.expect_handle().times(5).returning(|_| Ok(()));
+        let feed = ChangeFeed::new(vec![Arc::new(audit), Arc::new(index)]);
+
+        let response = feed.process(&event()).await;
+
+        assert_eq!(response, StreamResponse::default());
+    }
+
+    #[tokio::test]
+    async fn process_stops_at_failed_record() {
+        let mut handler = MockChangeHandler::new();
+        handler
+            .expect_handle()
+            .with(function(|change: &RecipeChange| {
+                change.kind() != ChangeKind::Trashed
+            }))
+            .times(2)
+            .returning(|_| Ok(()));
+        handler
+            .expect_handle()
+            .with(function(|change: &RecipeChange| {
+                change.kind() == ChangeKind::Trashed
+            }))
+            .times(1)
+            .returning(|_| Err(StatusCode::SERVICE_UNAVAILABLE));
+        handler.expect_name().return_const("mock");
+        let feed = ChangeFeed::new(vec![Arc::new(handler)]);
+
+        let response = feed.process(&event()).await;
+
+        assert_eq!(
+            response.batch_item_failures,
+            vec![BatchItemFailure {
+                item_identifier: "300".to_owned(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn process_skips_records_of_other_items() {
+        let mut event = event();
+        event.records.truncate(1);
+        event.records[0].dynamodb.new_image =
+            Some(serde_json::from_str(r#"{ "household_id": { "S": "grandma" } }"#).unwrap());
+        let mut handler = MockChangeHandler::new();
+        handler.expect_handle().never();
+        let feed = ChangeFeed::new(vec![Arc::new(handler)]);
+
+        let response = feed.process(&event).await;
+
+        assert_eq!(response, StreamResponse::default());
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::future::Future;
+
+use axum::async_trait;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::date::parse_day;
+use crate::recipe::Recipe;
+
+pub mod repository;
+pub mod request_models;
+
+/// The rating assumed for recipes that have not been cooked yet, so that new
+/// dishes get a fair chance next to rated ones.
+pub const NEUTRAL_RATING: f64 = 3.0;
+
+/// After how many days without being cooked a recipe counts as fully rested.
+pub const RESTED_AFTER_DAYS: u64 = 28;
+
+/// A recipe cooked by a member of the household on a given day.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct CookEntry {
+    /// The household that owns the entry, the partition key of the table.
+    #[serde(default)]
+    pub(crate) household_id: String,
+    pub(crate) id: Uuid,
+    pub(crate) recipe_id: Uuid,
+    /// The day the recipe was cooked, as `YYYY-MM-DD`.
+    pub(crate) cooked_on: String,
+    /// How well the dish turned out, from 1 to 5.
+    pub(crate) rating: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) notes: Option<String>,
+    /// What was done differently from the recipe.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) modifications: Vec<String>,
+    /// The member who recorded the entry.
+    pub(crate) subject: String,
+}
+
+/// How often and how well a recipe has been cooked.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct CookStats {
+    pub(crate) times_cooked: u32,
+    /// The mean of the ratings, to one decimal.
+    pub(crate) average_rating: f64,
+    /// The last day the recipe was cooked, as `YYYY-MM-DD`.
+    pub(crate) last_cooked: String,
+}
+
+/// The orders recipes can be listed in.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipeSort {
+    /// The best rated first, then those never cooked.
+    Rating,
+    /// Those never cooked first, then the longest since they were cooked.
+    LeastRecent,
+    /// Well-liked dishes that have not been cooked for a while first.
+    Suggested,
+}
+
+/// Storage for the cook log of households.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait CookLogRepository: Send + Sync {
+    /// Lists every entry of the household.
+    fn get_entries(
+        &self,
+        household_id: &str,
+    ) -> impl Future<Output = Result<Vec<CookEntry>, StatusCode>> + Send;
+    /// Lists the entries of a recipe, the most recently cooked first.
+    fn get_recipe_entries(
+        &self,
+        household_id: &str,
+        recipe_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<CookEntry>, StatusCode>> + Send;
+    fn save_entry(&self, entry: &CookEntry) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    fn delete_entry(
+        &self,
+        household_id: &str,
+        id: Uuid,
+    ) -> impl Future<Output = Result<(), StatusCode>> + Send;
+}
+
+/// Aggregates the entries into the stats of each recipe cooked at least once.
+#[must_use]
+pub fn stats(entries: &[CookEntry]) -> HashMap<Uuid, CookStats> {
+    let mut totals: HashMap<Uuid, (u32, u32, &str)> = HashMap::new();
+    for entry in entries {
+        let (times, ratings, last) = totals.entry(entry.recipe_id).or_insert((0, 0, ""));
+        *times += 1;
+        *ratings += u32::from(entry.rating);
+        // Days as `YYYY-MM-DD` sort like the dates they stand for.
+        if entry.cooked_on.as_str() > *last {
+            *last = &entry.cooked_on;
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|(recipe_id, (times, ratings, last))| {
+            let average = f64::from(ratings) / f64::from(times);
+            (
+                recipe_id,
+                CookStats {
+                    times_cooked: times,
+                    average_rating: (average * 10.0).round() / 10.0,
+                    last_cooked: last.to_owned(),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Scores how good a pick a recipe is today, for suggesting what to cook. The
+/// average rating counts fully and up to two points are added for the time
+/// since it was last cooked, so a favourite cooked last night ranks below a
+/// dish rated one less that has not been cooked for four weeks.
+#[must_use]
+pub fn suggestion_score(stats: Option<&CookStats>, today: u64) -> f64 {
+    let rating = stats.map_or(NEUTRAL_RATING, |stats| stats.average_rating);
+    let rested = days_since(stats, today).min(RESTED_AFTER_DAYS);
+
+    // The rest is at most a few weeks, far below where u64 loses precision
+    // in f64.
+    #[allow(clippy::cast_precision_loss)]
+    let rest = 2.0 * rested as f64 / RESTED_AFTER_DAYS as f64;
+
+    rating + rest
+}
+
+/// Sorts recipes by their stats. Recipes that tie keep their order.
+pub fn sort(recipes: &mut [Recipe], by: RecipeSort, today: u64) {
+    match by {
+        RecipeSort::Rating => recipes.sort_by(|a, b| {
+            let rating = |recipe: &Recipe| {
+                recipe
+                    .stats
+                    .as_ref()
+                    .map_or(f64::NEG_INFINITY, |stats| stats.average_rating)
+            };
+            rating(b).total_cmp(&rating(a))
+        }),
+        RecipeSort::LeastRecent => recipes.sort_by(|a, b| {
+            days_since(b.stats.as_ref(), today).cmp(&days_since(a.stats.as_ref(), today))
+        }),
+        RecipeSort::Suggested => recipes.sort_by(|a, b| {
+            suggestion_score(b.stats.as_ref(), today)
+                .total_cmp(&suggestion_score(a.stats.as_ref(), today))
+        }),
+    }
+}
+
+/// The current day, as days since the epoch in UTC.
+#[must_use]
+pub fn today(now: u64) -> u64 { now / 86_400 }
+
+/// Days since the recipe was last cooked. Recipes never cooked count as
+/// cooked at the start of the epoch.
+fn days_since(stats: Option<&CookStats>, today: u64) -> u64 {
+    let last = stats
+        .and_then(|stats| parse_day(&stats.last_cooked))
+        .unwrap_or(0);
+
+    today.saturating_sub(last)
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use super::{sort, stats, suggestion_score, CookEntry, CookStats, RecipeSort};
+    use crate::date::parse_day;
+    use crate::recipe::Recipe;
+
+    fn entry(recipe_id: u128, cooked_on: &str, rating: u8) -> CookEntry {
+        CookEntry {
+            recipe_id: Uuid::from_u128(recipe_id),
+            cooked_on: cooked_on.to_owned(),
+            rating,
+            ..CookEntry::default()
+        }
+    }
+
+    fn recipe(id: u128, stats: Option<(f64, &str)>) -> Recipe {
+        Recipe {
+            id: Uuid::from_u128(id),
+            stats: stats.map(|(average_rating, last_cooked)| CookStats {
+                times_cooked: 1,
+                average_rating,
+                last_cooked: last_cooked.to_owned(),
+            }),
+            ..Recipe::default()
+        }
+    }
+
+    #[test]
+    fn aggregate_entries_per_recipe() {
+        let entries = [
+            entry(1, "2025-03-01", 4),
+            entry(1, "2025-05-14", 5),
+            entry(1, "2025-04-02", 4),
+            entry(2, "2025-01-09", 2),
+        ];
+
+        let stats = stats(&entries);
+
+        assert_eq!(
+            stats[&Uuid::from_u128(1)],
+            CookStats {
+                times_cooked: 3,
+                average_rating: 4.3,
+                last_cooked: "2025-05-14".to_owned(),
+            }
+        );
+        assert_eq!(stats[&Uuid::from_u128(2)].times_cooked, 1);
+        assert_eq!(stats.len(), 2);
+    }
+
+    #[test]
+    fn favor_liked_dishes_not_cooked_lately() {
+        let today = parse_day("2025-06-01").unwrap();
+        let mut recipes = vec![
+            recipe(1, Some((5.0, "2025-05-31"))),
+            recipe(2, Some((4.0, "2025-04-01"))),
+            recipe(3, None),
+            recipe(4, Some((2.0, "2025-01-01"))),
+        ];
+
+        sort(&mut recipes, RecipeSort::Suggested, today);
+
+        let ids: Vec<u128> = recipes.iter().map(|recipe| recipe.id.as_u128()).collect();
+        assert_eq!(ids, vec![2, 1, 3, 4]);
+        assert!(suggestion_score(None, today) > suggestion_score(recipes[3].stats.as_ref(), today));
+    }
+
+    #[test]
+    fn sort_by_rating_and_recency() {
+        let today = parse_day("2025-06-01").unwrap();
+        let mut recipes = vec![
+            recipe(1, Some((3.5, "2025-05-31"))),
+            recipe(2, None),
+            recipe(3, Some((4.5, "2025-02-01"))),
+        ];
+
+        sort(&mut recipes, RecipeSort::Rating, today);
+        let ids: Vec<u128> = recipes.iter().map(|recipe| recipe.id.as_u128()).collect();
+        assert_eq!(ids, vec![3, 1, 2]);
+
+        sort(&mut recipes, RecipeSort::LeastRecent, today);
+        let ids: Vec<u128> = recipes.iter().map(|recipe| recipe.id.as_u128()).collect();
+        assert_eq!(ids, vec![2, 3, 1]);
+    }
+}
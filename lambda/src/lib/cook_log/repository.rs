@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aws_config::SdkConfig;
+use aws_sdk_dynamodb::operation::delete_item::DeleteItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use axum::http::StatusCode;
+use serde_dynamo::aws_sdk_dynamodb_1::{from_item, to_item};
+use uuid::Uuid;
+
+use super::{CookEntry, CookLogRepository};
+use crate::aws_client::{query_all, DynamoDbClient, DynamoDbClientImpl, QueryExpression};
+
+/// The global secondary index of the cook log table, partitioned by the recipe
+/// and sorted by the day it was cooked.
+pub const RECIPE_INDEX: &str = "recipe-index";
+
+#[derive(Clone)]
+pub struct DynamoDbCookLog {
+    client: Arc<dyn DynamoDbClient>,
+    table_name: String,
+}
+
+impl DynamoDbCookLog {
+    #[must_use]
+    pub fn new(sdk_config: &SdkConfig, table_name: &str) -> Self {
+        Self {
+            client: Arc::new(DynamoDbClientImpl::new(sdk_config)),
+            table_name: table_name.to_owned(),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn mock(client: Arc<dyn DynamoDbClient>) -> Self {
+        Self {
+            client,
+            table_name: "cook-log".to_owned(),
+        }
+    }
+}
+
+impl CookLogRepository for DynamoDbCookLog {
+    async fn get_entries(&self, household_id: &str) -> Result<Vec<CookEntry>, StatusCode> {
+        let query = QueryExpression {
+            key_condition: "household_id = :household_id".to_owned(),
+            values: HashMap::from([(
+                ":household_id".to_owned(),
+                AttributeValue::S(household_id.to_owned()),
+            )]),
+            scan_forward: true,
+            ..QueryExpression::default()
+        };
+        let items = query_all(self.client.as_ref(), &self.table_name, query)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(items.into_iter().flat_map(from_item).collect())
+    }
+
+    async fn get_recipe_entries(
+        &self,
+        household_id: &str,
+        recipe_id: Uuid,
+    ) -> Result<Vec<CookEntry>, StatusCode> {
+        let query = QueryExpression {
+            key_condition: "recipe_id = :recipe_id".to_owned(),
+            index_name: Some(RECIPE_INDEX.to_owned()),
+            values: HashMap::from([(
+                ":recipe_id".to_owned(),
+                AttributeValue::S(recipe_id.as_hyphenated().to_string()),
+            )]),
+            scan_forward: false,
+            ..QueryExpression::default()
+        };
+        let items = query_all(self.client.as_ref(), &self.table_name, query)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(items
+            .into_iter()
+            .flat_map(from_item::<CookEntry>)
+            .filter(|entry| entry.household_id == household_id)
+            .collect())
+    }
+
+    async fn save_entry(&self, entry: &CookEntry) -> Result<(), StatusCode> {
+        let item = to_item(entry).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.client
+            .put_item(&self.table_name, item)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(())
+    }
+
+    async fn delete_entry(&self, household_id: &str, id: Uuid) -> Result<(), StatusCode> {
+        self.client
+            .delete_item(
+                &self.table_name,
+                DynamoDbCookLog::get_key(household_id, id),
+                "id",
+            )
+            .await
+            .map_err(|err| match err {
+                DeleteItemError::ConditionalCheckFailedException(_)
+                | DeleteItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+
+        Ok(())
+    }
+}
+
+impl DynamoDbCookLog {
+    fn get_key(household_id: &str, id: Uuid) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            (
+                "household_id".to_owned(),
+                AttributeValue::S(household_id.to_owned()),
+            ),
+            (
+                "id".to_owned(),
+                AttributeValue::S(id.as_hyphenated().to_string()),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::result_large_err)]
+mod test {
+    use aws_sdk_dynamodb::operation::query::QueryOutput;
+    use mockall::predicate::{eq, function};
+
+    use super::*;
+    use crate::aws_client::MockDynamoDbClient as DynamoDbClient;
+
+    #[tokio::test]
+    async fn test_get_recipe_entries_of_the_household() {
+        let entry = |household_id: &str| CookEntry {
+            household_id: household_id.to_owned(),
+            cooked_on: "2025-05-14".to_owned(),
+            rating: 5,
+            ..CookEntry::default()
+        };
+        let items = vec![
+            to_item(entry("grandma")).unwrap(),
+            to_item(entry("stranger")).unwrap(),
+        ];
+        let mut mock = DynamoDbClient::default();
+        mock.expect_query()
+            .with(
+                eq("cook-log"),
+                function(|query: &QueryExpression| {
+                    query.index_name.as_deref() == Some(RECIPE_INDEX) && !query.scan_forward
+                }),
+            )
+            .return_once(|_, _| Ok(QueryOutput::builder().set_items(Some(items)).build()));
+
+        let repo = DynamoDbCookLog::mock(Arc::new(mock));
+
+        let result = repo.get_recipe_entries("grandma", Uuid::nil()).await;
+        assert_eq!(result, Ok(vec![entry("grandma")]));
+    }
+}
@@ -0,0 +1,129 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::clock;
+use crate::cook_log::today;
+use crate::date::parse_day;
+use crate::validation::{max_items, max_length, not_blank, Validate, Violations};
+
+pub const MAX_NOTES_LENGTH: usize = 2000;
+pub const MAX_MODIFICATIONS: usize = 20;
+pub const MAX_MODIFICATION_LENGTH: usize = 200;
+
+/// A recipe the caller has cooked.
+#[derive(Debug, Deserialize, PartialEq, ToSchema)]
+pub struct PostCookEntry {
+    /// The day the recipe was cooked, as `YYYY-MM-DD`. Defaults to today.
+    #[serde(default)]
+    pub(crate) cooked_on: Option<String>,
+    pub(crate) rating: u8,
+    #[serde(default)]
+    pub(crate) notes: Option<String>,
+    #[serde(default)]
+    pub(crate) modifications: Vec<String>,
+}
+
+impl Default for PostCookEntry {
+    fn default() -> Self {
+        Self {
+            cooked_on: None,
+            rating: 5,
+            notes: None,
+            modifications: Vec::new(),
+        }
+    }
+}
+
+impl Validate for PostCookEntry {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        if let Some(cooked_on) = &self.cooked_on {
+            violations.check(
+                &format!("{pointer}/cooked_on"),
+                cooked_day(cooked_on, today(clock::now())),
+            );
+        }
+        violations.check(
+            &format!("{pointer}/rating"),
+            if (1..=5).contains(&self.rating) {
+                Ok(())
+            } else {
+                Err("must be from 1 to 5".to_owned())
+            },
+        );
+        if let Some(notes) = &self.notes {
+            violations.check(
+                &format!("{pointer}/notes"),
+                max_length(notes, MAX_NOTES_LENGTH),
+            );
+        }
+        let modifications_pointer = format!("{pointer}/modifications");
+        violations.check(
+            &modifications_pointer,
+            max_items(&self.modifications, MAX_MODIFICATIONS),
+        );
+        for (index, modification) in self.modifications.iter().enumerate() {
+            let modification_pointer = format!("{modifications_pointer}/{index}");
+            violations.check(&modification_pointer, not_blank(modification));
+            violations.check(
+                &modification_pointer,
+                max_length(modification, MAX_MODIFICATION_LENGTH),
+            );
+        }
+    }
+}
+
+/// Days are in the household's local time, which can be a day ahead of UTC,
+/// so only days after tomorrow in UTC count as the future.
+fn cooked_day(value: &str, today: u64) -> Result<(), String> {
+    let day = parse_day(value).ok_or_else(|| "must be a date as YYYY-MM-DD".to_owned())?;
+    if day > today + 1 {
+        return Err("must not be in the future".to_owned());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{cooked_day, PostCookEntry};
+    use crate::date::parse_day;
+    use crate::validation::Validate;
+
+    #[test]
+    fn validate_day_rating_and_modifications() {
+        let entry = PostCookEntry {
+            cooked_on: Some("2025-13-01".to_owned()),
+            rating: 0,
+            notes: Some("Lovely".to_owned()),
+            modifications: vec!["Less salt".to_owned(), " ".to_owned()],
+        };
+
+        let violations = entry.validate().unwrap_err();
+
+        let pointers: Vec<&str> = violations
+            .errors()
+            .iter()
+            .map(|violation| violation.pointer.as_str())
+            .collect();
+        assert_eq!(pointers, vec!["/cooked_on", "/rating", "/modifications/1"]);
+        assert!(PostCookEntry::default().validate().is_ok());
+    }
+
+    #[test]
+    fn reject_future_days() {
+        let today = parse_day("2025-05-14").unwrap();
+
+        assert_eq!(cooked_day("2025-05-14", today), Ok(()));
+        assert_eq!(cooked_day("2025-05-15", today), Ok(()));
+        assert_eq!(
+            cooked_day("2025-05-16", today),
+            Err("must not be in the future".to_owned())
+        );
+        assert!(PostCookEntry {
+            cooked_on: Some("2999-01-01".to_owned()),
+            ..PostCookEntry::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
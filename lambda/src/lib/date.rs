@@ -0,0 +1,146 @@
+/// Parses a day as `YYYY-MM-DD` into its year, month and day.
+#[must_use]
+pub fn parse_date(value: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = value.splitn(3, '-');
+    let year = parse_digits(parts.next()?, 4)?;
+    let month = parse_digits(parts.next()?, 2)?;
+    let day = parse_digits(parts.next()?, 2)?;
+    let valid = (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month);
+
+    valid.then_some((year, month, day))
+}
+
+/// Parses a time of day as `HH:MM` into its hours and minutes.
+#[must_use]
+pub fn parse_time(value: &str) -> Option<(u32, u32)> {
+    let (hours, minutes) = value.split_once(':')?;
+    let hours = parse_digits(hours, 2)?;
+    let minutes = parse_digits(minutes, 2)?;
+
+    (hours < 24 && minutes < 60).then_some((hours, minutes))
+}
+
+/// Parses a local date and time as `YYYY-MM-DDTHH:MM` into minutes since the
+/// epoch. Times before the epoch are rejected.
+#[must_use]
+pub fn parse_date_time(value: &str) -> Option<u64> {
+    let (date, time) = value.split_once('T')?;
+    let (year, month, day) = parse_date(date)?;
+    let (hours, minutes) = parse_time(time)?;
+    let days = days_from_civil(year, month, day)?;
+
+    Some(days * 1440 + u64::from(hours) * 60 + u64::from(minutes))
+}
+
+/// Formats minutes since the epoch as a local date and time, as
+/// `YYYY-MM-DDTHH:MM`.
+#[must_use]
+pub fn format_date_time(minutes: u64) -> String {
+    let (year, month, day) = civil_from_days(minutes / 1440);
+    let time = minutes % 1440;
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}",
+        time / 60,
+        time % 60
+    )
+}
+
+/// Parses a day as `YYYY-MM-DD` into days since the epoch. Days before the
+/// epoch are rejected.
+#[must_use]
+pub fn parse_day(value: &str) -> Option<u64> {
+    let (year, month, day) = parse_date(value)?;
+
+    days_from_civil(year, month, day)
+}
+
+/// Formats days since the epoch as `YYYY-MM-DD`.
+#[must_use]
+pub fn format_day(days: u64) -> String {
+    let (year, month, day) = civil_from_days(days);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn parse_digits(value: &str, length: usize) -> Option<u32> {
+    if value.len() == length && value.bytes().all(|byte| byte.is_ascii_digit()) {
+        value.parse().ok()
+    } else {
+        None
+    }
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        },
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Converts days since the epoch to a date in the Gregorian calendar, after
+/// Howard Hinnant's `civil_from_days`.
+pub(crate) fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Converts a date in the Gregorian calendar to days since the epoch, after
+/// Howard Hinnant's `days_from_civil`.
+fn days_from_civil(year: u32, month: u32, day: u32) -> Option<u64> {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let shifted_month = i64::from((month + 9) % 12);
+    let day_of_year = (153 * shifted_month + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    u64::try_from(era * 146_097 + day_of_era - 719_468).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_date_time, format_day, parse_date, parse_date_time, parse_day, parse_time};
+
+    #[test]
+    fn parse_dates_and_times() {
+        assert_eq!(parse_date("2024-02-29"), Some((2024, 2, 29)));
+        assert_eq!(parse_date("2023-02-29"), None);
+        assert_eq!(parse_date("2024-13-01"), None);
+        assert_eq!(parse_date("24-01-01"), None);
+        assert_eq!(parse_time("18:30"), Some((18, 30)));
+        assert_eq!(parse_time("24:00"), None);
+        assert_eq!(parse_time("6:30"), None);
+    }
+
+    #[test]
+    fn parse_and_format_date_times() {
+        assert_eq!(parse_date_time("1970-01-01T00:00"), Some(0));
+        assert_eq!(parse_date_time("1970-01-02T01:30"), Some(1440 + 90));
+        assert_eq!(parse_date_time("1969-12-31T23:59"), None);
+        assert_eq!(parse_date_time("2025-11-27 17:00"), None);
+        let leap_day = parse_date_time("2024-02-29T17:00").unwrap();
+        assert_eq!(format_date_time(leap_day), "2024-02-29T17:00");
+        assert_eq!(format_date_time(leap_day - 18 * 60), "2024-02-28T23:00");
+        assert_eq!(parse_day("2024-02-29"), Some(leap_day / 1440));
+        assert_eq!(format_day(leap_day / 1440 + 1), "2024-03-01");
+    }
+}
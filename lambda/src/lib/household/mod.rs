@@ -0,0 +1,81 @@
+use std::future::Future;
+use std::time::Duration;
+
+use axum::async_trait;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub mod repository;
+pub mod request_models;
+pub mod response_models;
+
+/// How long an invitation can be used to join a household.
+pub const INVITATION_LIFETIME: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// What a member is allowed to do within their household. Roles are ordered,
+/// every role can do everything the roles before it can.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Can read recipes and shopping lists.
+    Viewer,
+    /// Can also create, change and delete them.
+    Editor,
+    /// Can also invite people and manage the members of the household.
+    #[default]
+    Owner,
+}
+
+/// A caller that belongs to a household other than their own.
+///
+/// A caller without a membership is the owner of a household of their own,
+/// identified by their subject.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Member {
+    /// The subject of the caller, the partition key of the table.
+    pub(crate) subject: String,
+    pub(crate) household_id: String,
+    pub(crate) role: Role,
+    pub(crate) joined_at: u64,
+}
+
+/// A single use token that lets whoever holds it join a household with the
+/// given role until it expires.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Invitation {
+    pub(crate) token: Uuid,
+    pub(crate) household_id: String,
+    pub(crate) role: Role,
+    pub(crate) created_by: String,
+    pub(crate) expires_at: u64,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait HouseholdRepository: Send + Sync {
+    fn find_member(&self, subject: &str)
+        -> impl Future<Output = Result<Member, StatusCode>> + Send;
+    fn get_members(
+        &self,
+        household_id: &str,
+    ) -> impl Future<Output = Result<Vec<Member>, StatusCode>> + Send;
+    fn save_member(&self, member: &Member) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    fn delete_member(&self, subject: &str) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    fn save_invitation(
+        &self,
+        invitation: &Invitation,
+    ) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    /// Finds an invitation without using it up. Expired invitations are
+    /// reported as not found.
+    fn find_invitation(
+        &self,
+        token: Uuid,
+    ) -> impl Future<Output = Result<Invitation, StatusCode>> + Send;
+    /// Removes an invitation so that it cannot be used again. Expired
+    /// invitations are reported as not found.
+    fn take_invitation(
+        &self,
+        token: Uuid,
+    ) -> impl Future<Output = Result<Invitation, StatusCode>> + Send;
+}
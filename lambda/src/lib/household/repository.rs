@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aws_config::SdkConfig;
+use aws_sdk_dynamodb::operation::delete_item::DeleteItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use axum::http::StatusCode;
+use serde_dynamo::aws_sdk_dynamodb_1::{from_item, to_item};
+use uuid::Uuid;
+
+use super::{HouseholdRepository, Invitation, Member};
+use crate::aws_client::{query_all, DynamoDbClient, DynamoDbClientImpl, QueryExpression};
+use crate::clock;
+
+/// The global secondary index of the member table, partitioned by household.
+pub const MEMBER_HOUSEHOLD_INDEX: &str = "household_id-index";
+
+#[derive(Clone)]
+pub struct DynamoDbHousehold {
+    client: Arc<dyn DynamoDbClient>,
+    member_table_name: String,
+    invitation_table_name: String,
+}
+
+impl DynamoDbHousehold {
+    #[must_use]
+    pub fn new(
+        sdk_config: &SdkConfig,
+        member_table_name: &str,
+        invitation_table_name: &str,
+    ) -> Self {
+        Self {
+            client: Arc::new(DynamoDbClientImpl::new(sdk_config)),
+            member_table_name: member_table_name.to_owned(),
+            invitation_table_name: invitation_table_name.to_owned(),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn mock(client: Arc<dyn DynamoDbClient>) -> Self {
+        Self {
+            client,
+            member_table_name: "household-members".to_owned(),
+            invitation_table_name: "household-invitations".to_owned(),
+        }
+    }
+}
+
+impl HouseholdRepository for DynamoDbHousehold {
+    async fn find_member(&self, subject: &str) -> Result<Member, StatusCode> {
+        let output = self
+            .client
+            .get_item(
+                &self.member_table_name,
+                DynamoDbHousehold::get_member_key(subject),
+            )
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let item = output.item().ok_or(StatusCode::NOT_FOUND)?.to_owned();
+
+        from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    async fn get_members(&self, household_id: &str) -> Result<Vec<Member>, StatusCode> {
+        let query = QueryExpression {
+            key_condition: "household_id = :household_id".to_owned(),
+            index_name: Some(MEMBER_HOUSEHOLD_INDEX.to_owned()),
+            values: HashMap::from([(
+                ":household_id".to_owned(),
+                AttributeValue::S(household_id.to_owned()),
+            )]),
+            scan_forward: true,
+            ..QueryExpression::default()
+        };
+        let items = query_all(self.client.as_ref(), &self.member_table_name, query)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(items.into_iter().flat_map(from_item).collect())
+    }
+
+    async fn save_member(&self, member: &Member) -> Result<(), StatusCode> {
+        let item = to_item(member).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.client
+            .put_item(&self.member_table_name, item)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(())
+    }
+
+    async fn delete_member(&self, subject: &str) -> Result<(), StatusCode> {
+        self.client
+            .delete_item(
+                &self.member_table_name,
+                DynamoDbHousehold::get_member_key(subject),
+                "subject",
+            )
+            .await
+            .map_err(DynamoDbHousehold::map_delete_error)?;
+
+        Ok(())
+    }
+
+    async fn save_invitation(&self, invitation: &Invitation) -> Result<(), StatusCode> {
+        let item = to_item(invitation).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.client
+            .put_new_item(&self.invitation_table_name, item, "token")
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(())
+    }
+
+    async fn find_invitation(&self, token: Uuid) -> Result<Invitation, StatusCode> {
+        let output = self
+            .client
+            .get_item(
+                &self.invitation_table_name,
+                DynamoDbHousehold::get_invitation_key(token),
+            )
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let item = output.item().ok_or(StatusCode::NOT_FOUND)?.to_owned();
+        let invitation: Invitation =
+            from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        // Expired invitations linger until the table's TTL removes them.
+        if invitation.expires_at <= clock::now() {
+            return Err(StatusCode::NOT_FOUND);
+        }
+
+        Ok(invitation)
+    }
+
+    async fn take_invitation(&self, token: Uuid) -> Result<Invitation, StatusCode> {
+        let invitation = self.find_invitation(token).await?;
+
+        // Only the first of several concurrent callers gets to delete it.
+        self.client
+            .delete_item(
+                &self.invitation_table_name,
+                DynamoDbHousehold::get_invitation_key(token),
+                "token",
+            )
+            .await
+            .map_err(DynamoDbHousehold::map_delete_error)?;
+
+        Ok(invitation)
+    }
+}
+
+impl DynamoDbHousehold {
+    fn get_member_key(subject: &str) -> HashMap<String, AttributeValue> {
+        HashMap::from([("subject".to_owned(), AttributeValue::S(subject.to_owned()))])
+    }
+
+    fn get_invitation_key(token: Uuid) -> HashMap<String, AttributeValue> {
+        HashMap::from([(
+            "token".to_owned(),
+            AttributeValue::S(token.as_hyphenated().to_string()),
+        )])
+    }
+
+    fn map_delete_error(err: DeleteItemError) -> StatusCode {
+        match err {
+            DeleteItemError::ConditionalCheckFailedException(_)
+            | DeleteItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::result_large_err)]
+mod test {
+    use aws_sdk_dynamodb::operation::delete_item::DeleteItemOutput;
+    use aws_sdk_dynamodb::operation::get_item::GetItemOutput;
+    use aws_sdk_dynamodb::operation::query::QueryOutput;
+    use aws_sdk_dynamodb::types::error::ConditionalCheckFailedException;
+    use mockall::predicate::{eq, function};
+
+    use super::*;
+    use crate::aws_client::MockDynamoDbClient as DynamoDbClient;
+    use crate::household::Role;
+
+    fn invitation(expires_at: u64) -> Invitation {
+        Invitation {
+            token: Uuid::nil(),
+            household_id: "household".to_owned(),
+            role: Role::Editor,
+            created_by: "grandma".to_owned(),
+            expires_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_member_not_found() {
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .with(
+                eq("household-members"),
+                eq(DynamoDbHousehold::get_member_key("grandma")),
+            )
+            .return_once(|_, _| Ok(GetItemOutput::builder().build()));
+
+        let repo = DynamoDbHousehold::mock(Arc::new(mock));
+
+        let result = repo.find_member("grandma").await;
+        assert_eq!(result, Err(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn test_get_members_queries_index() {
+        let member = Member {
+            subject: "grandpa".to_owned(),
+            household_id: "household".to_owned(),
+            role: Role::Viewer,
+            joined_at: 0,
+        };
+        let item = to_item(&member).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_query()
+            .with(
+                eq("household-members"),
+                function(|query: &QueryExpression| {
+                    query.index_name.as_deref() == Some(MEMBER_HOUSEHOLD_INDEX)
+                }),
+            )
+            .return_once(|_, _| Ok(QueryOutput::builder().items(item).build()));
+
+        let repo = DynamoDbHousehold::mock(Arc::new(mock));
+
+        let result = repo.get_members("household").await;
+        assert_eq!(result, Ok(vec![member]));
+    }
+
+    #[tokio::test]
+    async fn test_take_invitation() {
+        let item = to_item(invitation(u64::MAX)).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .return_once(|_, _| Ok(GetItemOutput::builder().set_item(Some(item)).build()));
+        mock.expect_delete_item()
+            .with(
+                eq("household-invitations"),
+                eq(DynamoDbHousehold::get_invitation_key(Uuid::nil())),
+                eq("token"),
+            )
+            .return_once(|_, _, _| Ok(DeleteItemOutput::builder().build()));
+
+        let repo = DynamoDbHousehold::mock(Arc::new(mock));
+
+        let result = repo.take_invitation(Uuid::nil()).await;
+        assert_eq!(result, Ok(invitation(u64::MAX)));
+    }
+
+    #[tokio::test]
+    async fn test_take_invitation_expired() {
+        let item = to_item(invitation(1)).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .return_once(|_, _| Ok(GetItemOutput::builder().set_item(Some(item)).build()));
+        mock.expect_delete_item().never();
+
+        let repo = DynamoDbHousehold::mock(Arc::new(mock));
+
+        let result = repo.take_invitation(Uuid::nil()).await;
+        assert_eq!(result, Err(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn test_take_invitation_already_taken() {
+        let item = to_item(invitation(u64::MAX)).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .return_once(|_, _| Ok(GetItemOutput::builder().set_item(Some(item)).build()));
+        mock.expect_delete_item().return_once(|_, _, _| {
+            Err(DeleteItemError::ConditionalCheckFailedException(
+                ConditionalCheckFailedException::builder().build(),
+            ))
+        });
+
+        let repo = DynamoDbHousehold::mock(Arc::new(mock));
+
+        let result = repo.take_invitation(Uuid::nil()).await;
+        assert_eq!(result, Err(StatusCode::NOT_FOUND));
+    }
+}
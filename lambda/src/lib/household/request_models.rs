@@ -0,0 +1,19 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::Role;
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct PostInvitation {
+    pub(crate) role: Role,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct JoinHousehold {
+    pub(crate) token: Uuid,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct PutMember {
+    pub(crate) role: Role,
+}
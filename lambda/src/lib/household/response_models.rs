@@ -0,0 +1,11 @@
+use serde::Serialize;
+
+use super::{Member, Role};
+
+/// The household of the caller, along with the caller's own role in it.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct HouseholdResponse {
+    pub(crate) id: String,
+    pub(crate) role: Role,
+    pub(crate) members: Vec<Member>,
+}
@@ -0,0 +1,142 @@
+use std::future::Future;
+use std::time::Duration;
+
+use axum::async_trait;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub mod repository;
+
+/// The header clients send to make a request safe to retry.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Set on responses that were replayed from an earlier request.
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// The longest idempotency key that is accepted.
+pub const MAX_KEY_LENGTH: usize = 255;
+
+/// How long a response is kept for replaying.
+pub const DEFAULT_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long a request may hold on to a key before it counts as abandoned, well
+/// above the timeout of the function.
+pub const PENDING_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// The response to the first request made with an idempotency key.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct StoredResponse {
+    pub(crate) status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) content_type: Option<String>,
+    pub(crate) body: String,
+}
+
+/// A claim on an idempotency key, holding the response once the request that
+/// claimed it has finished.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct IdempotencyRecord {
+    /// The key the client sent, scoped to the household and caller.
+    pub(crate) key: String,
+    /// The hex encoded SHA-256 digest of the method, path and body.
+    pub(crate) fingerprint: String,
+    pub(crate) created_at: u64,
+    /// The time after which DynamoDB removes the record.
+    pub(crate) expires_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) response: Option<StoredResponse>,
+}
+
+impl IdempotencyRecord {
+    /// Claims a key for a request that has not been answered yet.
+    #[must_use]
+    pub fn pending(key: &str, fingerprint: &str, now: u64) -> Self {
+        Self {
+            key: key.to_owned(),
+            fingerprint: fingerprint.to_owned(),
+            created_at: now,
+            expires_at: now + DEFAULT_WINDOW.as_secs(),
+            response: None,
+        }
+    }
+
+    /// Whether the key can be claimed again, because the window has passed or
+    /// the request that claimed it never finished.
+    #[must_use]
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at <= now
+            || (self.response.is_none() && self.created_at + PENDING_TIMEOUT.as_secs() <= now)
+    }
+}
+
+/// Scopes a key sent by a client to its household and caller, so that callers
+/// cannot replay the responses of others.
+#[must_use]
+pub fn scoped_key(household_id: &str, subject: &str, key: &str) -> String {
+    format!("{household_id}#{subject}#{key}")
+}
+
+/// Digests what makes two requests the same, so that a key reused for a
+/// different request can be told apart from a retry.
+#[must_use]
+pub fn fingerprint(method: &str, path: &str, body: &[u8]) -> String {
+    let mut digest = Sha256::new();
+    digest.update(method.as_bytes());
+    digest.update(b" ");
+    digest.update(path.as_bytes());
+    digest.update(b"\n");
+    digest.update(body);
+
+    hex::encode(digest.finalize())
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait IdempotencyRepository: Send + Sync {
+    fn find_record(
+        &self,
+        key: &str,
+    ) -> impl Future<Output = Result<IdempotencyRecord, StatusCode>> + Send;
+    /// Stores a record only if there is none for its key yet, responding with
+    /// conflict otherwise.
+    fn claim(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    /// Stores the record along with the response to the request.
+    fn complete(
+        &self,
+        record: &IdempotencyRecord,
+    ) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    fn release(&self, key: &str) -> impl Future<Output = Result<(), StatusCode>> + Send;
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fingerprint, IdempotencyRecord, StoredResponse};
+
+    #[test]
+    fn fingerprint_covers_request() {
+        let original = fingerprint("POST", "/recipes", b"{}");
+
+        assert_eq!(original, fingerprint("POST", "/recipes", b"{}"));
+        assert_ne!(original, fingerprint("POST", "/recipes", b"{ }"));
+        assert_ne!(original, fingerprint("POST", "/recipes/batch", b"{}"));
+        assert_ne!(original, fingerprint("PUT", "/recipes", b"{}"));
+    }
+
+    #[test]
+    fn is_expired() {
+        let pending = IdempotencyRecord::pending("key", "abc", 100);
+        let completed = IdempotencyRecord {
+            response: Some(StoredResponse::default()),
+            ..pending.clone()
+        };
+
+        assert!(!pending.is_expired(399));
+        assert!(pending.is_expired(400));
+        assert!(!completed.is_expired(400));
+        assert!(completed.is_expired(100 + 24 * 60 * 60));
+    }
+}
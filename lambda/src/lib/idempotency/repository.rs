@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aws_config::SdkConfig;
+use aws_sdk_dynamodb::operation::delete_item::DeleteItemError;
+use aws_sdk_dynamodb::operation::put_item::PutItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use axum::http::StatusCode;
+use serde_dynamo::aws_sdk_dynamodb_1::{from_item, to_item};
+
+use super::{IdempotencyRecord, IdempotencyRepository};
+use crate::aws_client::{DynamoDbClient, DynamoDbClientImpl};
+
+#[derive(Clone)]
+pub struct DynamoDbIdempotency {
+    client: Arc<dyn DynamoDbClient>,
+    table_name: String,
+}
+
+impl DynamoDbIdempotency {
+    #[must_use]
+    pub fn new(sdk_config: &SdkConfig, table_name: &str) -> Self {
+        Self {
+            client: Arc::new(DynamoDbClientImpl::new(sdk_config)),
+            table_name: table_name.to_owned(),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn mock(client: Arc<dyn DynamoDbClient>) -> Self {
+        Self {
+            client,
+            table_name: "idempotency-keys".to_owned(),
+        }
+    }
+}
+
+impl IdempotencyRepository for DynamoDbIdempotency {
+    async fn find_record(&self, key: &str) -> Result<IdempotencyRecord, StatusCode> {
+        let output = self
+            .client
+            .get_item(&self.table_name, DynamoDbIdempotency::get_key(key))
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let item = output.item().ok_or(StatusCode::NOT_FOUND)?.to_owned();
+
+        from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    async fn claim(&self, record: &IdempotencyRecord) -> Result<(), StatusCode> {
+        let item = to_item(record).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.client
+            .put_new_item(&self.table_name, item, "key")
+            .await
+            .map_err(|err| match err {
+                PutItemError::ConditionalCheckFailedException(_) => StatusCode::CONFLICT,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+
+        Ok(())
+    }
+
+    async fn complete(&self, record: &IdempotencyRecord) -> Result<(), StatusCode> {
+        let item = to_item(record).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.client
+            .put_item(&self.table_name, item)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<(), StatusCode> {
+        self.client
+            .delete_item(&self.table_name, DynamoDbIdempotency::get_key(key), "key")
+            .await
+            .map_err(|err| match err {
+                DeleteItemError::ConditionalCheckFailedException(_)
+                | DeleteItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+
+        Ok(())
+    }
+}
+
+impl DynamoDbIdempotency {
+    fn get_key(key: &str) -> HashMap<String, AttributeValue> {
+        HashMap::from([("key".to_owned(), AttributeValue::S(key.to_owned()))])
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::result_large_err)]
+mod test {
+    use aws_sdk_dynamodb::operation::get_item::GetItemOutput;
+    use aws_sdk_dynamodb::types::error::ConditionalCheckFailedException;
+    use mockall::predicate::{always, eq};
+
+    use super::*;
+    use crate::aws_client::MockDynamoDbClient as DynamoDbClient;
+    use crate::idempotency::StoredResponse;
+
+    #[tokio::test]
+    async fn test_find_record() {
+        let record = IdempotencyRecord {
+            response: Some(StoredResponse {
+                status: 201,
+                content_type: Some("application/json".to_owned()),
+                body: "{}".to_owned(),
+            }),
+            ..IdempotencyRecord::pending("grandma#grandma#retry", "abc", 100)
+        };
+        let item = to_item(&record).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .with(
+                eq("idempotency-keys"),
+                eq(DynamoDbIdempotency::get_key("grandma#grandma#retry")),
+            )
+            .return_once(|_, _| Ok(GetItemOutput::builder().set_item(Some(item)).build()));
+
+        let repo = DynamoDbIdempotency::mock(Arc::new(mock));
+
+        let result = repo.find_record("grandma#grandma#retry").await;
+        assert_eq!(result, Ok(record));
+    }
+
+    #[tokio::test]
+    async fn test_claim_taken_key() {
+        let mut mock = DynamoDbClient::default();
+        mock.expect_put_new_item()
+            .with(eq("idempotency-keys"), always(), eq("key"))
+            .return_once(|_, _, _| {
+                Err(PutItemError::ConditionalCheckFailedException(
+                    ConditionalCheckFailedException::builder().build(),
+                ))
+            });
+
+        let repo = DynamoDbIdempotency::mock(Arc::new(mock));
+
+        let result = repo
+            .claim(&IdempotencyRecord::pending("retry", "abc", 100))
+            .await;
+        assert_eq!(result, Err(StatusCode::CONFLICT));
+    }
+}
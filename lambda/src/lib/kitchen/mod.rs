@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+
+use axum::async_trait;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::recipe::Recipe;
+
+pub mod repository;
+pub mod request_models;
+
+/// The kinds of cookware recipes can ask for.
+#[derive(
+    Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Appliance {
+    Oven,
+    Burner,
+    Grill,
+    Microwave,
+    StandMixer,
+    FoodProcessor,
+    Blender,
+    SlowCooker,
+    PressureCooker,
+    SousVide,
+    DeepFryer,
+}
+
+impl Appliance {
+    /// How many of the appliance a kitchen is assumed to have while its
+    /// household has not said what it owns.
+    #[must_use]
+    pub fn assumed_count(self) -> u32 {
+        match self {
+            Appliance::Burner => 4,
+            _ => 1,
+        }
+    }
+}
+
+/// Cookware a step of a recipe needs while it runs.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct Cookware {
+    pub(crate) appliance: Appliance,
+    /// The temperature the appliance is set to, in degrees Celsius. Two steps
+    /// cannot share an oven at different temperatures.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) celsius: Option<u16>,
+    /// How many are needed, like two burners. Defaults to one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) count: Option<u32>,
+}
+
+impl Cookware {
+    #[must_use]
+    pub fn count(&self) -> u32 { self.count.unwrap_or(1) }
+}
+
+/// The appliances a household owns.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Kitchen {
+    /// The household that owns the kitchen, the partition key of the table.
+    #[serde(default)]
+    pub(crate) household_id: String,
+    /// How many of each appliance the household owns. Appliances that are
+    /// left out are not owned.
+    #[serde(default)]
+    pub(crate) appliances: BTreeMap<Appliance, u32>,
+}
+
+impl Kitchen {
+    /// How many of the appliance the household owns.
+    #[must_use]
+    pub fn count(&self, appliance: Appliance) -> u32 {
+        self.appliances.get(&appliance).copied().unwrap_or(0)
+    }
+
+    /// Whether the household owns enough cookware for every step of the
+    /// recipe.
+    #[must_use]
+    pub fn can_cook(&self, recipe: &Recipe) -> bool {
+        recipe
+            .steps
+            .iter()
+            .flat_map(|step| &step.equipment)
+            .all(|cookware| self.count(cookware.appliance) >= cookware.count())
+    }
+}
+
+/// Storage for the kitchens of households.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait KitchenRepository: Send + Sync {
+    /// Finds the kitchen of a household. Households that have not said what
+    /// they own are reported as not found.
+    fn find_kitchen(
+        &self,
+        household_id: &str,
+    ) -> impl Future<Output = Result<Kitchen, StatusCode>> + Send;
+    fn save_kitchen(
+        &self,
+        kitchen: &Kitchen,
+    ) -> impl Future<Output = Result<(), StatusCode>> + Send;
+}
+
+/// Finds the kitchen of a household, if it has said what it owns.
+///
+/// # Errors
+///
+/// Responds with the status code of any failure other than not finding the
+/// kitchen.
+pub async fn find_declared<K: KitchenRepository>(
+    repo: &K,
+    household_id: &str,
+) -> Result<Option<Kitchen>, StatusCode> {
+    match repo.find_kitchen(household_id).await {
+        Ok(kitchen) => Ok(Some(kitchen)),
+        Err(StatusCode::NOT_FOUND) => Ok(None),
+        Err(status) => Err(status),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::{Appliance, Cookware, Kitchen};
+    use crate::recipe::{Recipe, Step};
+
+    #[test]
+    fn can_cook_with_enough_cookware() {
+        let kitchen = Kitchen {
+            appliances: BTreeMap::from([(Appliance::Oven, 1), (Appliance::Burner, 2)]),
+            ..Kitchen::default()
+        };
+        let recipe = |appliance, count| Recipe {
+            steps: vec![Step {
+                instruction: "Cook".to_owned(),
+                equipment: vec![Cookware {
+                    appliance,
+                    celsius: None,
+                    count,
+                }],
+                ..Step::default()
+            }],
+            ..Recipe::default()
+        };
+
+        assert!(kitchen.can_cook(&Recipe::default()));
+        assert!(kitchen.can_cook(&recipe(Appliance::Oven, None)));
+        assert!(kitchen.can_cook(&recipe(Appliance::Burner, Some(2))));
+        assert!(!kitchen.can_cook(&recipe(Appliance::Burner, Some(3))));
+        assert!(!kitchen.can_cook(&recipe(Appliance::SousVide, None)));
+    }
+
+    #[test]
+    fn serialize_appliances_by_name() {
+        let kitchen = Kitchen {
+            household_id: "grandma".to_owned(),
+            appliances: BTreeMap::from([(Appliance::SousVide, 1)]),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&kitchen).unwrap(),
+            r#"{"household_id":"grandma","appliances":{"sous_vide":1}}"#
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aws_config::SdkConfig;
+use aws_sdk_dynamodb::operation::get_item::GetItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use axum::http::StatusCode;
+use serde_dynamo::aws_sdk_dynamodb_1::{from_item, to_item};
+
+use super::{Kitchen, KitchenRepository};
+use crate::aws_client::{DynamoDbClient, DynamoDbClientImpl};
+
+#[derive(Clone)]
+pub struct DynamoDbKitchen {
+    client: Arc<dyn DynamoDbClient>,
+    table_name: String,
+}
+
+impl DynamoDbKitchen {
+    #[must_use]
+    pub fn new(sdk_config: &SdkConfig, table_name: &str) -> Self {
+        Self {
+            client: Arc::new(DynamoDbClientImpl::new(sdk_config)),
+            table_name: table_name.to_owned(),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn mock(client: Arc<dyn DynamoDbClient>) -> Self {
+        Self {
+            client,
+            table_name: "kitchens".to_owned(),
+        }
+    }
+}
+
+impl KitchenRepository for DynamoDbKitchen {
+    async fn find_kitchen(&self, household_id: &str) -> Result<Kitchen, StatusCode> {
+        let output = self
+            .client
+            .get_item(&self.table_name, DynamoDbKitchen::get_key(household_id))
+            .await
+            .map_err(|err| match err {
+                GetItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+        let item = output.item().ok_or(StatusCode::NOT_FOUND)?.to_owned();
+
+        from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    async fn save_kitchen(&self, kitchen: &Kitchen) -> Result<(), StatusCode> {
+        let item = to_item(kitchen).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.client
+            .put_item(&self.table_name, item)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(())
+    }
+}
+
+impl DynamoDbKitchen {
+    fn get_key(household_id: &str) -> HashMap<String, AttributeValue> {
+        HashMap::from([(
+            "household_id".to_owned(),
+            AttributeValue::S(household_id.to_owned()),
+        )])
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::result_large_err)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use aws_sdk_dynamodb::operation::get_item::GetItemOutput;
+    use mockall::predicate::eq;
+
+    use super::*;
+    use crate::aws_client::MockDynamoDbClient as DynamoDbClient;
+    use crate::kitchen::Appliance;
+
+    #[tokio::test]
+    async fn test_find_kitchen() {
+        let kitchen = Kitchen {
+            household_id: "grandma".to_owned(),
+            appliances: BTreeMap::from([(Appliance::Oven, 2), (Appliance::StandMixer, 1)]),
+        };
+        let item = to_item(&kitchen).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .with(eq("kitchens"), eq(DynamoDbKitchen::get_key("grandma")))
+            .return_once(|_, _| Ok(GetItemOutput::builder().set_item(Some(item)).build()));
+
+        let repo = DynamoDbKitchen::mock(Arc::new(mock));
+
+        let result = repo.find_kitchen("grandma").await;
+        assert_eq!(result, Ok(kitchen));
+    }
+
+    #[tokio::test]
+    async fn test_find_undeclared_kitchen() {
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .return_once(|_, _| Ok(GetItemOutput::builder().build()));
+
+        let repo = DynamoDbKitchen::mock(Arc::new(mock));
+
+        let result = repo.find_kitchen("grandma").await;
+        assert_eq!(result, Err(StatusCode::NOT_FOUND));
+    }
+}
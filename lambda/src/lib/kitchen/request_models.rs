@@ -0,0 +1,60 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use super::Appliance;
+use crate::validation::{Validate, Violations};
+
+/// The most of any one appliance a kitchen can have.
+pub const MAX_COUNT: u32 = 20;
+
+/// What a household owns, replacing whatever it said before.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct PutKitchen {
+    #[serde(default)]
+    pub(crate) appliances: BTreeMap<Appliance, u32>,
+}
+
+impl Validate for PutKitchen {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        for (appliance, count) in &self.appliances {
+            let name = serde_json::to_value(appliance).unwrap_or_default();
+            violations.check(
+                &format!("{pointer}/appliances/{}", name.as_str().unwrap_or_default()),
+                if *count > MAX_COUNT {
+                    Err(format!("must be at most {MAX_COUNT}"))
+                } else {
+                    Ok(())
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PutKitchen;
+    use crate::validation::Validate;
+
+    #[test]
+    fn validate_counts() {
+        let kitchen: PutKitchen =
+            serde_json::from_str(r#"{ "appliances": { "oven": 2, "burner": 40 } }"#).unwrap();
+
+        let violations = kitchen.validate().unwrap_err();
+
+        let pointers: Vec<&str> = violations
+            .errors()
+            .iter()
+            .map(|violation| violation.pointer.as_str())
+            .collect();
+        assert_eq!(pointers, vec!["/appliances/burner"]);
+    }
+
+    #[test]
+    fn reject_unknown_appliances() {
+        let kitchen = serde_json::from_str::<PutKitchen>(r#"{ "appliances": { "robot": 1 } }"#);
+
+        assert!(kitchen.is_err());
+    }
+}
@@ -4,15 +4,54 @@ use axum::async_trait;
 use axum::http::StatusCode;
 use uuid::Uuid;
 
+pub mod api_key;
+pub mod auth;
 pub mod aws_client;
+pub mod change_feed;
+pub mod clock;
+pub mod cook_log;
+pub mod date;
+pub mod household;
+pub mod idempotency;
+pub mod kitchen;
+pub mod live;
+pub mod openapi;
+pub mod plan;
+pub mod rate_limit;
 pub mod recipe;
 pub mod services;
+pub mod shopping_list;
+pub mod timeline;
+pub mod validation;
+pub mod webhook;
 
+/// Storage for items owned by a household.
+///
+/// Every operation is scoped to a single household, items of other households
+/// are reported as not found. Saved items carry their own household.
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait Repository<T: Send + Sync>: Send + Sync {
-    fn get_all(&self) -> impl Future<Output = Result<Vec<T>, StatusCode>> + Send;
-    fn find_by_id(&self, id: Uuid) -> impl Future<Output = Result<T, StatusCode>> + Send;
+    fn get_all(
+        &self,
+        household_id: &str,
+    ) -> impl Future<Output = Result<Vec<T>, StatusCode>> + Send;
+    fn find_by_id(
+        &self,
+        household_id: &str,
+        id: Uuid,
+    ) -> impl Future<Output = Result<T, StatusCode>> + Send;
+    /// Finds the items with the given ids in as few requests as possible, in
+    /// the order of the ids. Ids of items that do not exist are skipped.
+    fn find_by_ids(
+        &self,
+        household_id: &str,
+        ids: &[Uuid],
+    ) -> impl Future<Output = Result<Vec<T>, StatusCode>> + Send;
     fn save(&self, item: &T) -> impl Future<Output = Result<Option<T>, StatusCode>> + Send;
-    fn delete_by_id(&self, id: Uuid) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    fn delete_by_id(
+        &self,
+        household_id: &str,
+        id: Uuid,
+    ) -> impl Future<Output = Result<(), StatusCode>> + Send;
 }
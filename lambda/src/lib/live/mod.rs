@@ -0,0 +1,221 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// How many events are kept for clients that reconnect.
+pub const DEFAULT_CAPACITY: usize = 1000;
+
+/// What kind of resource changed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topic {
+    Recipe,
+    Plan,
+    ShoppingList,
+}
+
+/// How the resource changed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// A notification that something in a household changed. It carries no more
+/// than what changed, clients read the resource again to see how.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LiveEvent {
+    /// Counts up from one for every event published by this process.
+    #[serde(skip)]
+    pub(crate) sequence: u64,
+    #[serde(skip)]
+    pub(crate) household_id: String,
+    pub(crate) topic: Topic,
+    pub(crate) kind: ChangeKind,
+    /// The resource that changed, unless the change touched several.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) id: Option<Uuid>,
+}
+
+impl LiveEvent {
+    /// The name of the event in the stream, e.g. `shopping_list.updated`.
+    #[must_use]
+    pub fn name(&self) -> String {
+        let topic = match self.topic {
+            Topic::Recipe => "recipe",
+            Topic::Plan => "plan",
+            Topic::ShoppingList => "shopping_list",
+        };
+        let kind = match self.kind {
+            ChangeKind::Created => "created",
+            ChangeKind::Updated => "updated",
+            ChangeKind::Deleted => "deleted",
+        };
+
+        format!("{topic}.{kind}")
+    }
+}
+
+/// Where a new subscriber starts: the events it missed since the id it last
+/// saw, and the events that follow.
+#[derive(Debug)]
+pub struct Subscription {
+    pub(crate) replay: Vec<LiveEvent>,
+    /// Set if some of the missed events are no longer kept, so that the
+    /// subscriber has to read everything again.
+    pub(crate) gap: bool,
+    pub(crate) receiver: broadcast::Receiver<LiveEvent>,
+}
+
+/// The latest events of a single process, kept in memory so that clients can
+/// pick up where they left off after a dropped connection.
+#[derive(Debug)]
+pub struct EventLog {
+    capacity: usize,
+    events: Mutex<LogState>,
+    sender: broadcast::Sender<LiveEvent>,
+}
+
+#[derive(Debug, Default)]
+struct LogState {
+    last_sequence: u64,
+    events: VecDeque<LiveEvent>,
+}
+
+impl EventLog {
+    /// Keeps at most `capacity` events.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (sender, _) = broadcast::channel(capacity);
+
+        Self {
+            capacity,
+            events: Mutex::new(LogState::default()),
+            sender,
+        }
+    }
+
+    /// Appends an event and hands it to every subscriber.
+    pub fn publish(
+        &self,
+        household_id: &str,
+        topic: Topic,
+        kind: ChangeKind,
+        id: Option<Uuid>,
+    ) -> LiveEvent {
+        let mut state = self.lock();
+        state.last_sequence += 1;
+        let event = LiveEvent {
+            sequence: state.last_sequence,
+            household_id: household_id.to_owned(),
+            topic,
+            kind,
+            id,
+        };
+        if state.events.len() == self.capacity {
+            state.events.pop_front();
+        }
+        state.events.push_back(event.clone());
+        // Sent while holding the lock, so that subscribers see every event
+        // either in their replay or from the channel, never both or neither.
+        let _ = self.sender.send(event.clone());
+
+        event
+    }
+
+    /// Subscribes to the events that follow the one with the given sequence
+    /// number, or to new events only.
+    pub fn subscribe(&self, last_sequence: Option<u64>) -> Subscription {
+        let state = self.lock();
+        let receiver = self.sender.subscribe();
+        let Some(last_sequence) = last_sequence else {
+            return Subscription {
+                replay: Vec::new(),
+                gap: false,
+                receiver,
+            };
+        };
+
+        let oldest = state
+            .events
+            .front()
+            .map_or(state.last_sequence + 1, |event| event.sequence);
+        Subscription {
+            replay: state
+                .events
+                .iter()
+                .filter(|event| event.sequence > last_sequence)
+                .cloned()
+                .collect(),
+            // Ids the process never handed out mean it was restarted.
+            gap: last_sequence + 1 < oldest || last_sequence > state.last_sequence,
+            receiver,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LogState> {
+        self.events
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self { Self::new(DEFAULT_CAPACITY) }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use super::{ChangeKind, EventLog, Topic};
+
+    fn sequences(log: &EventLog, last_sequence: Option<u64>) -> (Vec<u64>, bool) {
+        let subscription = log.subscribe(last_sequence);
+        (
+            subscription
+                .replay
+                .iter()
+                .map(|event| event.sequence)
+                .collect(),
+            subscription.gap,
+        )
+    }
+
+    #[test]
+    fn replays_missed_events() {
+        let log = EventLog::new(3);
+        for _ in 0..5 {
+            log.publish("grandma", Topic::Recipe, ChangeKind::Updated, None);
+        }
+
+        assert_eq!(sequences(&log, None), (vec![], false));
+        assert_eq!(sequences(&log, Some(3)), (vec![4, 5], false));
+        assert_eq!(sequences(&log, Some(2)), (vec![3, 4, 5], false));
+        assert_eq!(sequences(&log, Some(1)), (vec![3, 4, 5], true));
+        assert_eq!(sequences(&log, Some(5)), (vec![], false));
+        assert_eq!(sequences(&log, Some(9)), (vec![], true));
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_new_events() {
+        let log = EventLog::default();
+        let mut subscription = log.subscribe(None);
+
+        let published = log.publish(
+            "grandma",
+            Topic::ShoppingList,
+            ChangeKind::Updated,
+            Some(Uuid::nil()),
+        );
+
+        assert_eq!(subscription.receiver.recv().await.unwrap(), published);
+        assert_eq!(published.name(), "shopping_list.updated");
+    }
+}
@@ -0,0 +1,156 @@
+use axum::Json;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::cook_log::request_models::PostCookEntry;
+use crate::cook_log::{CookEntry, CookStats};
+use crate::kitchen::{Appliance, Cookware};
+use crate::recipe::batch::{BatchOperation, BatchResponse, BatchResult, PostBatch};
+use crate::recipe::component::{Component, ExpandedRecipe};
+use crate::recipe::request_models::{ForkRecipe, PatchRecipe, PostRecipe, PostShare, PutRecipe};
+use crate::recipe::revision::{FieldChange, RevisionResponse};
+use crate::recipe::share::{ShareLink, SharedRecipe};
+use crate::recipe::substitution::{Diet, SubstitutionPreview, Suggestion, SwapOption};
+use crate::recipe::variant::{IngredientChange, RecipeDiff, StepChange};
+use crate::recipe::{Ingredient, Recipe, Step};
+use crate::services::api_keys::API_KEY_HEADER;
+use crate::services::{cook_log, recipes, timeline};
+use crate::timeline::request_models::PostTimeline;
+use crate::timeline::{Conflict, StepReference, Task, Timeline};
+use crate::validation::{Violation, Violations};
+
+/// The OpenAPI document of the recipe routes, generated from their handlers
+/// and the models they take and return.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Meal Planner Recipes API"),
+    servers((url = "/recipes")),
+    paths(
+        recipes::list,
+        recipes::write,
+        recipes::create,
+        recipes::read_one,
+        recipes::update,
+        recipes::delete_one,
+        recipes::batch,
+        recipes::list_trash,
+        recipes::restore_from_trash,
+        recipes::fork,
+        recipes::list_variants,
+        recipes::diff_variant,
+        recipes::substitutions,
+        recipes::list_revisions,
+        recipes::read_revision,
+        recipes::restore_revision,
+        recipes::share,
+        recipes::list_shares,
+        recipes::revoke_share,
+        recipes::read_shared,
+        timeline::schedule,
+        cook_log::list,
+        cook_log::record,
+        cook_log::delete_one,
+    ),
+    components(schemas(
+        Recipe,
+        Ingredient,
+        Step,
+        Cookware,
+        Appliance,
+        CookStats,
+        CookEntry,
+        PostCookEntry,
+        PostRecipe,
+        PutRecipe,
+        PatchRecipe,
+        ForkRecipe,
+        PostBatch,
+        BatchOperation,
+        BatchResponse,
+        BatchResult,
+        ExpandedRecipe,
+        Component,
+        RecipeDiff,
+        SubstitutionPreview,
+        Suggestion,
+        SwapOption,
+        Diet,
+        IngredientChange,
+        StepChange,
+        RevisionResponse,
+        FieldChange,
+        PostShare,
+        ShareLink,
+        SharedRecipe,
+        PostTimeline,
+        Timeline,
+        Task,
+        Conflict,
+        StepReference,
+        Violations,
+        Violation,
+    )),
+    modifiers(&SecuritySchemes),
+    security(("bearer" = []), ("api_key" = [])),
+    tags(
+        (name = "recipes", description = "Recipes of the caller's household"),
+        (name = "shares", description = "Links that let anyone read a recipe"),
+    ),
+)]
+pub struct ApiDoc;
+
+/// Documents the two ways of authenticating, a bearer token for people and an
+/// API key for scripts.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+    }
+}
+
+/// Serves the OpenAPI document.
+pub async fn spec() -> Json<utoipa::openapi::OpenApi> { Json(ApiDoc::openapi()) }
+
+#[cfg(test)]
+mod test {
+    use utoipa::OpenApi;
+
+    use super::ApiDoc;
+
+    #[test]
+    fn documents_recipe_routes() {
+        let doc = ApiDoc::openapi();
+
+        let read_one = &doc.paths.paths["/{id}"];
+        assert_eq!(read_one.operations.len(), 3);
+        assert!(doc.paths.paths.contains_key("/timeline"));
+        assert_eq!(doc.paths.paths["/{id}/cook-log"].operations.len(), 2);
+        assert!(doc.paths.paths.contains_key("/{id}/cook-log/{entry_id}"));
+
+        let schemas = doc.components.unwrap().schemas;
+        for name in ["Recipe", "PostRecipe", "PutRecipe", "PatchRecipe"] {
+            assert!(schemas.contains_key(name), "{name} is missing");
+        }
+    }
+
+    #[test]
+    fn serializes_to_json() {
+        let json = ApiDoc::openapi().to_json().unwrap();
+
+        assert!(json.contains("\"openapi\":\"3."));
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use super::MealPlan;
+use crate::date::{civil_from_days, parse_date, parse_time};
+use crate::recipe::Recipe;
+
+/// The content type of iCalendar documents.
+pub const CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+/// How long a meal lasts in the calendar.
+const MEAL_MINUTES: u32 = 30;
+
+/// Lines longer than this many bytes are folded.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// Builds links to recipes from the address the API is reachable at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecipeLinks {
+    base_url: String,
+}
+
+impl RecipeLinks {
+    #[must_use]
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    #[must_use]
+    pub fn recipe(&self, id: Uuid) -> String { format!("{}/{id}", self.base_url) }
+}
+
+/// Renders a meal plan as an iCalendar document with an event per meal. Meals
+/// are in floating local time, so calendar apps show them at the planned time
+/// wherever they are. Recipes with a total time get a reminder to start
+/// preparing them that long before the meal.
+#[must_use]
+pub fn render(
+    plan: &MealPlan,
+    recipes: &HashMap<Uuid, Recipe>,
+    links: &RecipeLinks,
+    now: u64,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//Meal Planner//Meal Plans//EN".to_owned(),
+        "CALSCALE:GREGORIAN".to_owned(),
+        format!("X-WR-CALNAME:{}", escape_text(&plan.name)),
+    ];
+
+    for slot in &plan.slots {
+        let (Some(date), Some(time)) = (parse_date(&slot.date), parse_time(slot.serve_time()))
+        else {
+            continue;
+        };
+        let recipe = recipes.get(&slot.recipe_id);
+        let summary = match recipe {
+            Some(recipe) => format!("{}: {}", slot.meal.label(), recipe.name),
+            None => slot.meal.label().to_owned(),
+        };
+
+        lines.push("BEGIN:VEVENT".to_owned());
+        lines.push(format!("UID:{}@meal-planner", slot.id));
+        lines.push(format!("DTSTAMP:{}", format_utc(now)));
+        lines.push(format!(
+            "DTSTART:{:04}{:02}{:02}T{:02}{:02}00",
+            date.0, date.1, date.2, time.0, time.1
+        ));
+        lines.push(format!("DURATION:PT{MEAL_MINUTES}M"));
+        lines.push(format!("SUMMARY:{}", escape_text(&summary)));
+        if let Some(recipe) = recipe {
+            let link = links.recipe(recipe.id);
+            lines.push(format!("URL:{link}"));
+            lines.push(format!("DESCRIPTION:{}", escape_text(&link)));
+            if let Some(total_minutes) = recipe.total_minutes {
+                lines.push("BEGIN:VALARM".to_owned());
+                lines.push("ACTION:DISPLAY".to_owned());
+                lines.push(format!(
+                    "DESCRIPTION:{}",
+                    escape_text(&format!("Start preparing {}", recipe.name))
+                ));
+                lines.push(format!("TRIGGER:-PT{total_minutes}M"));
+                lines.push("END:VALARM".to_owned());
+            }
+        }
+        lines.push("END:VEVENT".to_owned());
+    }
+    lines.push("END:VCALENDAR".to_owned());
+
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// Formats seconds since the epoch as an iCalendar UTC date-time.
+fn format_utc(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let time = seconds % 86_400;
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+/// Splits a line into lines of at most 75 bytes, continued with a space,
+/// without splitting characters.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for character in line.chars() {
+        if length + character.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(character);
+        length += character.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use uuid::Uuid;
+
+    use super::{fold, format_utc, render, RecipeLinks};
+    use crate::plan::{Meal, MealPlan, MealSlot};
+    use crate::recipe::Recipe;
+
+    #[test]
+    fn format_timestamps() {
+        assert_eq!(format_utc(0), "19700101T000000Z");
+        assert_eq!(format_utc(1_760_000_000), "20251009T085320Z");
+    }
+
+    #[test]
+    fn fold_long_lines() {
+        let line = format!("SUMMARY:{}", "é".repeat(40));
+
+        let folded = fold(&line);
+
+        assert!(folded.split("\r\n").all(|part| part.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn render_plan() {
+        let recipe = Recipe {
+            id: Uuid::from_u128(1),
+            name: "Roast, with potatoes".to_owned(),
+            total_minutes: Some(90),
+            ..Recipe::default()
+        };
+        let plan = MealPlan {
+            id: Uuid::nil(),
+            name: "Sunday".to_owned(),
+            slots: vec![
+                MealSlot {
+                    id: Uuid::from_u128(10),
+                    date: "2025-10-12".to_owned(),
+                    meal: Meal::Dinner,
+                    time: Some("19:00".to_owned()),
+                    recipe_id: recipe.id,
+                },
+                MealSlot {
+                    id: Uuid::from_u128(11),
+                    date: "2025-10-12".to_owned(),
+                    meal: Meal::Breakfast,
+                    time: None,
+                    recipe_id: Uuid::from_u128(2),
+                },
+            ],
+            ..MealPlan::default()
+        };
+        let recipes = HashMap::from([(recipe.id, recipe)]);
+
+        let calendar = render(
+            &plan,
+            &recipes,
+            &RecipeLinks::new("https://api.example.com/recipes/"),
+            0,
+        );
+
+        let lines: Vec<&str> = calendar.split("\r\n").collect();
+        assert_eq!(
+            lines.iter().filter(|line| **line == "BEGIN:VEVENT").count(),
+            2
+        );
+        assert!(lines.contains(&"UID:00000000-0000-0000-0000-00000000000a@meal-planner"));
+        assert!(lines.contains(&"DTSTART:20251012T190000"));
+        assert!(lines.contains(&"SUMMARY:Dinner: Roast\\, with potatoes"));
+        assert!(lines
+            .contains(&"URL:https://api.example.com/recipes/00000000-0000-0000-0000-000000000001"));
+        assert!(lines.contains(&"TRIGGER:-PT90M"));
+        assert!(lines.contains(&"DTSTART:20251012T080000"));
+        assert!(lines.contains(&"SUMMARY:Breakfast"));
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.starts_with("TRIGGER"))
+                .count(),
+            1
+        );
+    }
+}
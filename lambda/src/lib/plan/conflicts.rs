@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::MealPlan;
+use crate::date::parse_date_time;
+use crate::kitchen::Kitchen;
+use crate::recipe::Recipe;
+use crate::timeline::{self, Conflict};
+
+/// The cookware conflicts of the meals served at the same time.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MealConflicts {
+    /// When the meals are served, as `YYYY-MM-DDTHH:MM` in local time.
+    pub(crate) serve_at: String,
+    pub(crate) conflicts: Vec<Conflict>,
+}
+
+/// Schedules the recipes of the meals served at the same time together, and
+/// collects the moments they need more cookware than the kitchen has. Recipes
+/// that are missing from the map are left out.
+#[must_use]
+pub fn find(
+    plan: &MealPlan,
+    recipes: &HashMap<Uuid, Recipe>,
+    kitchen: Option<&Kitchen>,
+) -> Vec<MealConflicts> {
+    let mut meals: Vec<(String, Vec<Recipe>)> = Vec::new();
+    for slot in &plan.slots {
+        let serve_at = format!("{}T{}", slot.date, slot.serve_time());
+        let Some(recipe) = recipes.get(&slot.recipe_id) else {
+            continue;
+        };
+        match meals.iter_mut().find(|(time, _)| *time == serve_at) {
+            Some((_, recipes)) => recipes.push(recipe.clone()),
+            None => meals.push((serve_at, vec![recipe.clone()])),
+        }
+    }
+
+    meals
+        .into_iter()
+        .filter_map(|(serve_at, recipes)| {
+            let minutes = parse_date_time(&serve_at)?;
+            let conflicts = timeline::schedule(&recipes, minutes, kitchen).conflicts;
+            (!conflicts.is_empty()).then_some(MealConflicts {
+                serve_at,
+                conflicts,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use uuid::Uuid;
+
+    use super::find;
+    use crate::kitchen::{Appliance, Cookware};
+    use crate::plan::{Meal, MealPlan, MealSlot};
+    use crate::recipe::{Recipe, Step};
+
+    fn roast(id: u128, celsius: u16) -> Recipe {
+        Recipe {
+            id: Uuid::from_u128(id),
+            steps: vec![Step {
+                instruction: "Roast".to_owned(),
+                minutes: Some(60),
+                passive: true,
+                equipment: vec![Cookware {
+                    appliance: Appliance::Oven,
+                    celsius: Some(celsius),
+                    count: None,
+                }],
+                ..Step::default()
+            }],
+            ..Recipe::default()
+        }
+    }
+
+    fn slot(date: &str, recipe_id: u128) -> MealSlot {
+        MealSlot {
+            id: Uuid::new_v4(),
+            date: date.to_owned(),
+            meal: Meal::Dinner,
+            time: None,
+            recipe_id: Uuid::from_u128(recipe_id),
+        }
+    }
+
+    #[test]
+    fn meals_served_together_share_the_oven() {
+        let recipes = HashMap::from([
+            (Uuid::from_u128(1), roast(1, 180)),
+            (Uuid::from_u128(2), roast(2, 220)),
+        ]);
+        let plan = MealPlan {
+            slots: vec![
+                slot("2025-12-24", 1),
+                slot("2025-12-24", 2),
+                slot("2025-12-25", 1),
+                slot("2025-12-26", 3),
+            ],
+            ..MealPlan::default()
+        };
+
+        let conflicts = find(&plan, &recipes, None);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].serve_at, "2025-12-24T18:30");
+        assert_eq!(conflicts[0].conflicts[0].appliance, Appliance::Oven);
+        assert_eq!(conflicts[0].conflicts[0].steps.len(), 2);
+    }
+}
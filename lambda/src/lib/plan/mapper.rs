@@ -0,0 +1,24 @@
+use uuid::Uuid;
+
+use super::request_models::PostMealPlan;
+use super::{MealPlan, MealSlot};
+
+#[must_use]
+pub fn map_post_meal_plan(id: Uuid, household_id: &str, value: &PostMealPlan) -> MealPlan {
+    MealPlan {
+        household_id: household_id.to_owned(),
+        id,
+        name: value.name.clone(),
+        slots: value
+            .slots
+            .iter()
+            .map(|slot| MealSlot {
+                id: slot.id.unwrap_or_else(Uuid::new_v4),
+                date: slot.date.clone(),
+                meal: slot.meal,
+                time: slot.time.clone(),
+                recipe_id: slot.recipe_id,
+            })
+            .collect(),
+    }
+}
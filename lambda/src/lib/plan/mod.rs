@@ -0,0 +1,198 @@
+use std::future::Future;
+
+use axum::async_trait;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub mod calendar;
+pub mod conflicts;
+pub mod mapper;
+pub mod repository;
+pub mod request_models;
+
+/// Prefixes calendar feed tokens, so that they can be told apart from other
+/// secrets.
+pub const FEED_TOKEN_PREFIX: &str = "cal_";
+
+/// The meals of a planned day in the household's local time.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MealPlan {
+    /// The household that owns the plan, the partition key of the table.
+    #[serde(default)]
+    pub(crate) household_id: String,
+    pub(crate) id: Uuid,
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) slots: Vec<MealSlot>,
+}
+
+impl Default for MealPlan {
+    fn default() -> Self {
+        Self {
+            household_id: String::new(),
+            id: Uuid::nil(),
+            name: "This Week".to_owned(),
+            slots: Vec::new(),
+        }
+    }
+}
+
+/// A recipe planned for a meal on a given day.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MealSlot {
+    /// Stays the same when the plan is replaced with the slot's id, so that
+    /// calendars keep telling the meal apart from the others.
+    pub(crate) id: Uuid,
+    /// The day of the meal, as `YYYY-MM-DD`.
+    pub(crate) date: String,
+    pub(crate) meal: Meal,
+    /// When the meal is served, as `HH:MM`. Defaults to the usual time of the
+    /// meal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) time: Option<String>,
+    pub(crate) recipe_id: Uuid,
+}
+
+impl Default for MealSlot {
+    fn default() -> Self {
+        Self {
+            id: Uuid::nil(),
+            date: "2024-01-01".to_owned(),
+            meal: Meal::Dinner,
+            time: None,
+            recipe_id: Uuid::nil(),
+        }
+    }
+}
+
+impl MealSlot {
+    /// When the meal is served, as `HH:MM`.
+    #[must_use]
+    pub fn serve_time(&self) -> &str {
+        self.time
+            .as_deref()
+            .unwrap_or_else(|| self.meal.default_time())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Meal {
+    Breakfast,
+    Lunch,
+    Dinner,
+    Snack,
+}
+
+impl Meal {
+    #[must_use]
+    pub fn default_time(self) -> &'static str {
+        match self {
+            Meal::Breakfast => "08:00",
+            Meal::Lunch => "12:30",
+            Meal::Dinner => "18:30",
+            Meal::Snack => "15:30",
+        }
+    }
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Meal::Breakfast => "Breakfast",
+            Meal::Lunch => "Lunch",
+            Meal::Dinner => "Dinner",
+            Meal::Snack => "Snack",
+        }
+    }
+}
+
+/// A secret that lets calendar apps read the plans of a household without an
+/// account. Every member has their own, which stays the same until they
+/// revoke it.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CalendarFeed {
+    pub(crate) token: String,
+    pub(crate) household_id: String,
+    pub(crate) subject: String,
+    pub(crate) created_at: u64,
+}
+
+impl CalendarFeed {
+    /// Mints a feed with a new token. Version 4 uuids are drawn from a
+    /// cryptographically secure generator, so the token cannot be guessed.
+    #[must_use]
+    pub fn generate(household_id: &str, subject: &str, now: u64) -> Self {
+        Self {
+            token: format!("{FEED_TOKEN_PREFIX}{}", Uuid::new_v4().simple()),
+            household_id: household_id.to_owned(),
+            subject: subject.to_owned(),
+            created_at: now,
+        }
+    }
+}
+
+/// Storage for the calendar feeds of household members.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait CalendarFeedRepository: Send + Sync {
+    fn find_feed(
+        &self,
+        token: &str,
+    ) -> impl Future<Output = Result<CalendarFeed, StatusCode>> + Send;
+    /// Finds the feed of a member of a household.
+    fn find_member_feed(
+        &self,
+        household_id: &str,
+        subject: &str,
+    ) -> impl Future<Output = Result<CalendarFeed, StatusCode>> + Send;
+    fn create_feed(
+        &self,
+        feed: &CalendarFeed,
+    ) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    fn delete_feed(&self, token: &str) -> impl Future<Output = Result<(), StatusCode>> + Send;
+}
+
+#[cfg(test)]
+mockall::mock! {
+    pub MealPlanRepository {}
+
+    impl crate::Repository<MealPlan> for MealPlanRepository {
+        fn get_all(
+            &self,
+            household_id: &str,
+        ) -> impl Future<Output = Result<Vec<MealPlan>, StatusCode>> + Send;
+        fn find_by_id(
+            &self,
+            household_id: &str,
+            id: Uuid,
+        ) -> impl Future<Output = Result<MealPlan, StatusCode>> + Send;
+        fn find_by_ids(
+            &self,
+            household_id: &str,
+            ids: &[Uuid],
+        ) -> impl Future<Output = Result<Vec<MealPlan>, StatusCode>> + Send;
+        fn save(
+            &self,
+            item: &MealPlan,
+        ) -> impl Future<Output = Result<Option<MealPlan>, StatusCode>> + Send;
+        fn delete_by_id(
+            &self,
+            household_id: &str,
+            id: Uuid,
+        ) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    }
+
+    impl CalendarFeedRepository for MealPlanRepository {
+        fn find_feed(&self, token: &str)
+            -> impl Future<Output = Result<CalendarFeed, StatusCode>> + Send;
+        fn find_member_feed(
+            &self,
+            household_id: &str,
+            subject: &str,
+        ) -> impl Future<Output = Result<CalendarFeed, StatusCode>> + Send;
+        fn create_feed(&self, feed: &CalendarFeed)
+            -> impl Future<Output = Result<(), StatusCode>> + Send;
+        fn delete_feed(&self, token: &str) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    }
+}
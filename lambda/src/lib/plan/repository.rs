@@ -0,0 +1,292 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use aws_config::SdkConfig;
+use aws_sdk_dynamodb::operation::delete_item::DeleteItemError;
+use aws_sdk_dynamodb::operation::get_item::GetItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use axum::http::StatusCode;
+use serde_dynamo::aws_sdk_dynamodb_1::{from_item, from_items, to_item};
+use uuid::Uuid;
+
+use super::{CalendarFeed, CalendarFeedRepository, MealPlan};
+use crate::aws_client::{
+    batch_get_all,
+    query_all,
+    DynamoDbClient,
+    DynamoDbClientImpl,
+    QueryExpression,
+};
+use crate::Repository;
+
+/// The global secondary index of the calendar feed table, partitioned by the
+/// subject of the member.
+pub const FEED_SUBJECT_INDEX: &str = "subject-index";
+
+#[derive(Clone)]
+pub struct DynamoDbMealPlan {
+    client: Arc<dyn DynamoDbClient>,
+    table_name: String,
+    feed_table_name: String,
+}
+
+impl DynamoDbMealPlan {
+    #[must_use]
+    pub fn new(sdk_config: &SdkConfig, table_name: &str, feed_table_name: &str) -> Self {
+        Self {
+            client: Arc::new(DynamoDbClientImpl::new(sdk_config)),
+            table_name: table_name.to_owned(),
+            feed_table_name: feed_table_name.to_owned(),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn mock(client: Arc<dyn DynamoDbClient>) -> Self {
+        Self {
+            client,
+            table_name: "meal-plans".to_owned(),
+            feed_table_name: "calendar-feeds".to_owned(),
+        }
+    }
+}
+
+impl Repository<MealPlan> for DynamoDbMealPlan {
+    async fn get_all(&self, household_id: &str) -> Result<Vec<MealPlan>, StatusCode> {
+        let query = QueryExpression {
+            key_condition: "household_id = :household_id".to_owned(),
+            values: HashMap::from([(
+                ":household_id".to_owned(),
+                AttributeValue::S(household_id.to_owned()),
+            )]),
+            scan_forward: true,
+            ..QueryExpression::default()
+        };
+        let items = query_all(self.client.as_ref(), &self.table_name, query)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(items.into_iter().flat_map(from_item).collect())
+    }
+
+    async fn find_by_id(&self, household_id: &str, id: Uuid) -> Result<MealPlan, StatusCode> {
+        let output = self
+            .client
+            .get_item(
+                &self.table_name,
+                DynamoDbMealPlan::get_key(household_id, id),
+            )
+            .await
+            .map_err(|err| match err {
+                GetItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+        let item = output.item().ok_or(StatusCode::NOT_FOUND)?.to_owned();
+
+        from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    async fn find_by_ids(
+        &self,
+        household_id: &str,
+        ids: &[Uuid],
+    ) -> Result<Vec<MealPlan>, StatusCode> {
+        // DynamoDB rejects batches that ask for the same key twice.
+        let mut seen = HashSet::new();
+        let ids: Vec<Uuid> = ids.iter().copied().filter(|id| seen.insert(*id)).collect();
+        let keys = ids
+            .iter()
+            .map(|id| DynamoDbMealPlan::get_key(household_id, *id))
+            .collect();
+        let items = batch_get_all(self.client.as_ref(), &self.table_name, keys)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        let mut plans: Vec<MealPlan> =
+            from_items(items).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        plans.sort_by_key(|plan| ids.iter().position(|id| *id == plan.id));
+
+        Ok(plans)
+    }
+
+    async fn save(&self, plan: &MealPlan) -> Result<Option<MealPlan>, StatusCode> {
+        let item = to_item(plan).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let output = self
+            .client
+            .put_item(&self.table_name, item)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        match output.attributes {
+            Some(item) => from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR),
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_by_id(&self, household_id: &str, id: Uuid) -> Result<(), StatusCode> {
+        self.client
+            .delete_item(
+                &self.table_name,
+                DynamoDbMealPlan::get_key(household_id, id),
+                "id",
+            )
+            .await
+            .map_err(|err| match err {
+                DeleteItemError::ConditionalCheckFailedException(_)
+                | DeleteItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+
+        Ok(())
+    }
+}
+
+impl CalendarFeedRepository for DynamoDbMealPlan {
+    async fn find_feed(&self, token: &str) -> Result<CalendarFeed, StatusCode> {
+        let output = self
+            .client
+            .get_item(&self.feed_table_name, DynamoDbMealPlan::get_feed_key(token))
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let item = output.item().ok_or(StatusCode::NOT_FOUND)?.to_owned();
+
+        from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    async fn find_member_feed(
+        &self,
+        household_id: &str,
+        subject: &str,
+    ) -> Result<CalendarFeed, StatusCode> {
+        let query = QueryExpression {
+            key_condition: "subject = :subject".to_owned(),
+            index_name: Some(FEED_SUBJECT_INDEX.to_owned()),
+            values: HashMap::from([(":subject".to_owned(), AttributeValue::S(subject.to_owned()))]),
+            scan_forward: true,
+            ..QueryExpression::default()
+        };
+        let items = query_all(self.client.as_ref(), &self.feed_table_name, query)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        // Members who moved households keep the feeds of their old household
+        // until they revoke them.
+        items
+            .into_iter()
+            .flat_map(from_item::<CalendarFeed>)
+            .find(|feed| feed.household_id == household_id)
+            .ok_or(StatusCode::NOT_FOUND)
+    }
+
+    async fn create_feed(&self, feed: &CalendarFeed) -> Result<(), StatusCode> {
+        let item = to_item(feed).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.client
+            .put_new_item(&self.feed_table_name, item, "token")
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(())
+    }
+
+    async fn delete_feed(&self, token: &str) -> Result<(), StatusCode> {
+        self.client
+            .delete_item(
+                &self.feed_table_name,
+                DynamoDbMealPlan::get_feed_key(token),
+                "token",
+            )
+            .await
+            .map_err(|err| match err {
+                DeleteItemError::ConditionalCheckFailedException(_)
+                | DeleteItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+
+        Ok(())
+    }
+}
+
+impl DynamoDbMealPlan {
+    fn get_key(household_id: &str, id: Uuid) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            (
+                "household_id".to_owned(),
+                AttributeValue::S(household_id.to_owned()),
+            ),
+            (
+                "id".to_owned(),
+                AttributeValue::S(id.as_hyphenated().to_string()),
+            ),
+        ])
+    }
+
+    fn get_feed_key(token: &str) -> HashMap<String, AttributeValue> {
+        HashMap::from([("token".to_owned(), AttributeValue::S(token.to_owned()))])
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::result_large_err)]
+mod test {
+    use aws_sdk_dynamodb::operation::get_item::GetItemOutput;
+    use aws_sdk_dynamodb::operation::query::QueryOutput;
+    use mockall::predicate::{eq, function};
+
+    use super::*;
+    use crate::aws_client::MockDynamoDbClient as DynamoDbClient;
+    use crate::plan::{Meal, MealSlot};
+
+    #[tokio::test]
+    async fn test_find_by_id() {
+        let plan = MealPlan {
+            household_id: "grandma".to_owned(),
+            id: Uuid::nil(),
+            slots: vec![MealSlot {
+                meal: Meal::Lunch,
+                time: Some("13:00".to_owned()),
+                ..MealSlot::default()
+            }],
+            ..MealPlan::default()
+        };
+        let item = to_item(&plan).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .with(
+                eq("meal-plans"),
+                eq(DynamoDbMealPlan::get_key("grandma", Uuid::nil())),
+            )
+            .return_once(|_, _| Ok(GetItemOutput::builder().set_item(Some(item)).build()));
+
+        let repo = DynamoDbMealPlan::mock(Arc::new(mock));
+
+        let result = repo.find_by_id("grandma", Uuid::nil()).await;
+        assert_eq!(result, Ok(plan));
+    }
+
+    #[tokio::test]
+    async fn test_find_member_feed_of_household() {
+        let feed = |household_id: &str| CalendarFeed {
+            token: format!("cal_{household_id}"),
+            household_id: household_id.to_owned(),
+            subject: "grandpa".to_owned(),
+            created_at: 100,
+        };
+        let items = vec![
+            to_item(feed("grandma")).unwrap(),
+            to_item(feed("aunt")).unwrap(),
+        ];
+        let mut mock = DynamoDbClient::default();
+        mock.expect_query()
+            .with(
+                eq("calendar-feeds"),
+                function(|query: &QueryExpression| {
+                    query.index_name.as_deref() == Some(FEED_SUBJECT_INDEX)
+                }),
+            )
+            .return_once(|_, _| Ok(QueryOutput::builder().set_items(Some(items)).build()));
+
+        let repo = DynamoDbMealPlan::mock(Arc::new(mock));
+
+        let result = repo.find_member_feed("aunt", "grandpa").await;
+        assert_eq!(result, Ok(feed("aunt")));
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::Meal;
+use crate::date::{parse_date, parse_time};
+use crate::validation::{max_length, not_blank, Validate, Violations};
+
+pub const MAX_NAME_LENGTH: usize = 200;
+/// Enough for every meal of a month.
+pub const MAX_SLOTS: usize = 200;
+
+/// A new meal plan, or the new contents of an existing one.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct PostMealPlan {
+    pub(super) name: String,
+    #[serde(default)]
+    pub(super) slots: Vec<PostMealSlot>,
+}
+
+impl Default for PostMealPlan {
+    fn default() -> Self {
+        Self {
+            name: "This Week".to_owned(),
+            slots: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct PostMealSlot {
+    /// The id of a slot of the plan being replaced, so that calendars see the
+    /// same meal. New slots get a new id.
+    #[serde(default)]
+    pub(super) id: Option<Uuid>,
+    pub(super) date: String,
+    pub(super) meal: Meal,
+    #[serde(default)]
+    pub(super) time: Option<String>,
+    pub(super) recipe_id: Uuid,
+}
+
+impl Validate for PostMealPlan {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        let name_pointer = format!("{pointer}/name");
+        violations.check(&name_pointer, not_blank(&self.name));
+        violations.check(&name_pointer, max_length(&self.name, MAX_NAME_LENGTH));
+        let slots_pointer = format!("{pointer}/slots");
+        violations.check_each(&slots_pointer, &self.slots, MAX_SLOTS);
+        let mut ids = HashSet::new();
+        for (index, slot) in self.slots.iter().enumerate() {
+            if slot.id.is_some_and(|id| !ids.insert(id)) {
+                violations.check(
+                    &format!("{slots_pointer}/{index}/id"),
+                    Err("must not repeat the id of an earlier slot".to_owned()),
+                );
+            }
+        }
+    }
+}
+
+impl Validate for PostMealSlot {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        violations.check(
+            &format!("{pointer}/date"),
+            parse_date(&self.date)
+                .map(|_| ())
+                .ok_or_else(|| "must be a date as YYYY-MM-DD".to_owned()),
+        );
+        if let Some(time) = &self.time {
+            violations.check(
+                &format!("{pointer}/time"),
+                parse_time(time)
+                    .map(|_| ())
+                    .ok_or_else(|| "must be a time as HH:MM".to_owned()),
+            );
+        }
+    }
+}
+
+/// Query parameters accepted when reading a plan as a calendar.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct ReadCalendar {
+    /// The calendar feed token of a member of the household.
+    pub(crate) token: String,
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use super::{PostMealPlan, PostMealSlot};
+    use crate::plan::Meal;
+    use crate::validation::Validate;
+
+    #[test]
+    fn deserialize_post_meal_plan_request() {
+        let actual: PostMealPlan = serde_json::from_str(
+            r#"{
+                "name": "Sunday",
+                "slots": [
+                    {
+                        "date": "2025-10-12",
+                        "meal": "dinner",
+                        "recipe_id": "00000000-0000-0000-0000-000000000000"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            actual,
+            PostMealPlan {
+                name: "Sunday".to_owned(),
+                slots: vec![PostMealSlot {
+                    id: None,
+                    date: "2025-10-12".to_owned(),
+                    meal: Meal::Dinner,
+                    time: None,
+                    recipe_id: Uuid::nil(),
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn validate_dates_and_times() {
+        let plan = PostMealPlan {
+            name: " ".to_owned(),
+            slots: vec![
+                PostMealSlot {
+                    id: Some(Uuid::nil()),
+                    date: "2025-02-30".to_owned(),
+                    meal: Meal::Lunch,
+                    time: Some("noon".to_owned()),
+                    recipe_id: Uuid::nil(),
+                },
+                PostMealSlot {
+                    id: Some(Uuid::nil()),
+                    date: "2025-03-01".to_owned(),
+                    meal: Meal::Lunch,
+                    time: None,
+                    recipe_id: Uuid::nil(),
+                },
+            ],
+        };
+
+        let violations = plan.validate().unwrap_err();
+
+        let pointers: Vec<&str> = violations
+            .errors()
+            .iter()
+            .map(|violation| violation.pointer.as_str())
+            .collect();
+        assert_eq!(
+            pointers,
+            vec!["/name", "/slots/0/date", "/slots/0/time", "/slots/1/id"]
+        );
+    }
+}
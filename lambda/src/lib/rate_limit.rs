@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::header::RETRY_AFTER;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use lambda_http::request::RequestContext;
+use lambda_http::RequestExt;
+
+/// Once this many clients are tracked, clients whose buckets have filled up
+/// again are forgotten.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Limits how many requests each client can make with a token bucket per
+/// client. Every request takes a token, tokens are refilled at a steady rate
+/// up to the size of the bucket.
+///
+/// Buckets live in the memory of a single instance of the function, so the
+/// limit applies per instance rather than across all of them.
+#[derive(Debug)]
+pub struct RateLimiter {
+    burst: f64,
+    per_second: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    /// Allows `burst` requests at once and `per_second` requests a second on
+    /// average.
+    #[must_use]
+    pub fn new(burst: u32, per_second: u32) -> Self {
+        Self {
+            burst: f64::from(burst.max(1)),
+            per_second: f64::from(per_second.max(1)),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token from the bucket of a client.
+    ///
+    /// # Errors
+    ///
+    /// Returns how long the client has to wait for the next token if its bucket
+    /// is empty.
+    pub fn check(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if buckets.len() >= PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| self.refill(*bucket, now).tokens < self.burst);
+        }
+
+        let bucket = buckets.entry(client.to_owned()).or_insert(Bucket {
+            tokens: self.burst,
+            updated_at: now,
+        });
+        *bucket = self.refill(*bucket, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.per_second,
+            ))
+        }
+    }
+
+    fn refill(&self, bucket: Bucket, now: Instant) -> Bucket {
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        Bucket {
+            tokens: (bucket.tokens + elapsed.as_secs_f64() * self.per_second).min(self.burst),
+            updated_at: now,
+        }
+    }
+}
+
+/// Middleware that rejects clients that exceed their rate limit with too many
+/// requests and a `Retry-After` header. Clients are told apart by their source
+/// IP.
+///
+/// The limit runs before any credentials are checked, so it must not trust
+/// them: keying by an unverified API key would give every made-up key a fresh
+/// bucket.
+pub async fn limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match limiter.check(&client(&request), Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(RETRY_AFTER, wait.as_secs_f64().ceil().to_string())],
+        )
+            .into_response(),
+    }
+}
+
+fn client(request: &Request) -> String {
+    let source_ip = match request.request_context_ref() {
+        Some(RequestContext::ApiGatewayV2(context)) => context.http.source_ip.clone(),
+        Some(RequestContext::ApiGatewayV1(context)) => context.identity.source_ip.clone(),
+        // The standalone server knows the peer from the connection.
+        _ => request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip().to_string()),
+    };
+
+    format!("ip:{}", source_ip.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::SocketAddr;
+    use std::time::{Duration, Instant};
+
+    use axum::body::Body;
+    use axum::extract::ConnectInfo;
+    use axum::http::Request;
+    use uuid::Uuid;
+
+    use super::{client, RateLimiter};
+    use crate::services::api_keys::API_KEY_HEADER;
+
+    #[test]
+    fn burst_then_refill() {
+        let limiter = RateLimiter::new(2, 1);
+        let start = Instant::now();
+
+        assert_eq!(limiter.check("grandma", start), Ok(()));
+        assert_eq!(limiter.check("grandma", start), Ok(()));
+        assert_eq!(limiter.check("grandma", start), Err(Duration::from_secs(1)));
+        assert_eq!(limiter.check("grandpa", start), Ok(()));
+        assert_eq!(
+            limiter.check("grandma", start + Duration::from_secs(1)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn refill_stops_at_burst() {
+        let limiter = RateLimiter::new(1, 10);
+        let start = Instant::now();
+
+        assert_eq!(limiter.check("grandma", start), Ok(()));
+        let later = start + Duration::from_secs(60);
+        assert_eq!(limiter.check("grandma", later), Ok(()));
+        assert!(limiter.check("grandma", later).is_err());
+    }
+
+    #[test]
+    fn client_by_connection_despite_api_key() {
+        let key = format!("mpk_{}_secret", Uuid::nil().simple());
+        let mut request = Request::get("/")
+            .header(API_KEY_HEADER, key)
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 4000))));
+
+        assert_eq!(client(&request), "ip:127.0.0.1");
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::request_models::{PostRecipe, PutRecipe};
+use super::Recipe;
+
+/// The most operations a single batch may contain.
+pub const MAX_OPERATIONS: usize = 500;
+
+/// The most operations an atomic batch may contain. Creates and updates also
+/// write a revision, so this is half the limit of a DynamoDB transaction.
+pub const MAX_ATOMIC_OPERATIONS: usize = 50;
+
+/// A list of operations on the recipes of the caller's household.
+#[derive(Debug, Default, Deserialize, PartialEq, ToSchema)]
+pub struct PostBatch {
+    pub(crate) operations: Vec<BatchOperation>,
+}
+
+/// A single operation of a batch, told apart by its `op` field.
+#[derive(Debug, Deserialize, PartialEq, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOperation {
+    /// Creates a recipe with a new id.
+    Create { recipe: PostRecipe },
+    /// Replaces the recipe with the id of the given recipe.
+    Update { recipe: PutRecipe },
+    /// Moves a recipe to the trash.
+    Delete { id: Uuid },
+}
+
+/// Query parameters accepted when running a batch.
+#[derive(Debug, Default, Deserialize, IntoParams, PartialEq)]
+#[into_params(parameter_in = Query)]
+pub struct BatchParams {
+    /// Runs every operation or none of them. Updates and deletes of recipes
+    /// that do not exist or are in the trash then fail the batch.
+    #[serde(default)]
+    pub(crate) atomic: bool,
+}
+
+/// A write to the recipe table, the form in which the operations of a batch
+/// reach the repository.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecipeWrite {
+    Create(Recipe),
+    Replace(Recipe),
+    /// Moves the recipe to the trash.
+    Trash {
+        household_id: String,
+        id: Uuid,
+    },
+}
+
+impl RecipeWrite {
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        match self {
+            RecipeWrite::Create(recipe) | RecipeWrite::Replace(recipe) => recipe.id,
+            RecipeWrite::Trash { id, .. } => *id,
+        }
+    }
+
+    /// The recipe as it is stored after the write, unless it is moved to the
+    /// trash.
+    #[must_use]
+    pub fn recipe(&self) -> Option<&Recipe> {
+        match self {
+            RecipeWrite::Create(recipe) | RecipeWrite::Replace(recipe) => Some(recipe),
+            RecipeWrite::Trash { .. } => None,
+        }
+    }
+}
+
+/// The outcomes of a batch, in the order of its operations.
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct BatchResponse {
+    pub(crate) results: Vec<BatchResult>,
+}
+
+/// The outcome of a single operation, with the status code it would have had
+/// as a request of its own.
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct BatchResult {
+    pub(crate) id: Uuid,
+    pub(crate) status: u16,
+}
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{Ingredient, Recipe};
+
+/// A recipe with the recipes its ingredients refer to expanded in place.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct ExpandedRecipe {
+    #[serde(flatten)]
+    pub(crate) recipe: Recipe,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) components: Vec<Component>,
+}
+
+impl From<Recipe> for ExpandedRecipe {
+    fn from(recipe: Recipe) -> Self {
+        Self {
+            recipe,
+            components: Vec::new(),
+        }
+    }
+}
+
+/// A component recipe, with its quantities scaled to the number of batches
+/// the referencing ingredient asks for.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct Component {
+    /// The position of the referencing ingredient in the parent's ingredients.
+    pub(crate) ingredient: usize,
+    pub(crate) batches: f64,
+    pub(crate) recipe: ExpandedRecipe,
+}
+
+/// How many levels of components a recipe can be expanded to.
+pub const MAX_DEPTH: usize = 8;
+/// How many components a recipe can be expanded to in total, counting every
+/// occurrence of a recipe that is referenced more than once.
+pub const MAX_COMPONENTS: usize = 200;
+
+/// Why a recipe could not be expanded.
+#[derive(Debug, PartialEq)]
+pub enum ExpandError {
+    /// The chain of recipes that leads back to a recipe already being
+    /// expanded.
+    Cycle { path: Vec<Uuid> },
+    /// The components nest deeper than [`MAX_DEPTH`].
+    TooDeep,
+    /// The components add up to more than [`MAX_COMPONENTS`].
+    TooLarge,
+}
+
+/// Returns the ids of the recipes directly referenced by a recipe's
+/// ingredients.
+pub fn component_ids(recipe: &Recipe) -> impl Iterator<Item = Uuid> + '_ {
+    recipe
+        .ingredients
+        .iter()
+        .filter_map(|ingredient| ingredient.recipe_id)
+}
+
+/// Recursively expands the components of a recipe using the already loaded
+/// `recipes`. References to recipes that were not loaded are left as plain
+/// ingredients.
+///
+/// # Errors
+///
+/// Returns the offending chain of recipes if a recipe ends up containing
+/// itself, or fails if the components nest too deep or are too many.
+pub fn expand(
+    root: &Recipe,
+    recipes: &HashMap<Uuid, Recipe>,
+) -> Result<ExpandedRecipe, ExpandError> {
+    expand_scaled(root, 1.0, recipes, &mut vec![root.id], &mut 0)
+}
+
+fn expand_scaled(
+    recipe: &Recipe,
+    scale: f64,
+    recipes: &HashMap<Uuid, Recipe>,
+    path: &mut Vec<Uuid>,
+    expanded_count: &mut usize,
+) -> Result<ExpandedRecipe, ExpandError> {
+    let mut scaled = recipe.clone();
+    for ingredient in &mut scaled.ingredients {
+        ingredient.quantity = ingredient.quantity.map(|quantity| quantity * scale);
+    }
+
+    let mut components = Vec::new();
+    for (index, ingredient) in scaled.ingredients.iter().enumerate() {
+        let Some(component) = ingredient.recipe_id.and_then(|id| recipes.get(&id)) else {
+            continue;
+        };
+        if path.contains(&component.id) {
+            let mut path = path.clone();
+            path.push(component.id);
+            return Err(ExpandError::Cycle { path });
+        }
+        if path.len() > MAX_DEPTH {
+            return Err(ExpandError::TooDeep);
+        }
+        *expanded_count += 1;
+        if *expanded_count > MAX_COMPONENTS {
+            return Err(ExpandError::TooLarge);
+        }
+
+        let batches = ingredient.quantity.unwrap_or(scale);
+        path.push(component.id);
+        let expanded = expand_scaled(component, batches, recipes, path, expanded_count)?;
+        path.pop();
+
+        components.push(Component {
+            ingredient: index,
+            batches,
+            recipe: expanded,
+        });
+    }
+
+    Ok(ExpandedRecipe {
+        recipe: scaled,
+        components,
+    })
+}
+
+/// Lists the plain ingredients of an expanded recipe, replacing every expanded
+/// component with its own ingredients.
+#[must_use]
+pub fn flatten(expanded: &ExpandedRecipe) -> Vec<Ingredient> {
+    let mut ingredients = Vec::new();
+    for (index, ingredient) in expanded.recipe.ingredients.iter().enumerate() {
+        match expanded
+            .components
+            .iter()
+            .find(|component| component.ingredient == index)
+        {
+            Some(component) => ingredients.extend(flatten(&component.recipe)),
+            None => ingredients.push(ingredient.clone()),
+        }
+    }
+
+    ingredients
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use uuid::Uuid;
+
+    use super::{expand, flatten, ExpandError, MAX_DEPTH};
+    use crate::recipe::{Ingredient, Recipe};
+
+    fn ingredient(name: &str, quantity: f64) -> Ingredient {
+        Ingredient {
+            name: name.to_owned(),
+            quantity: Some(quantity),
+            unit: Some("g".to_owned()),
+            recipe_id: None,
+        }
+    }
+
+    fn component(id: Uuid, batches: f64) -> Ingredient {
+        Ingredient {
+            name: "Component".to_owned(),
+            quantity: Some(batches),
+            unit: Some("batch".to_owned()),
+            recipe_id: Some(id),
+        }
+    }
+
+    fn recipe(id: u128, ingredients: Vec<Ingredient>) -> Recipe {
+        Recipe {
+            id: Uuid::from_u128(id),
+            ingredients,
+            ..Recipe::default()
+        }
+    }
+
+    #[test]
+    fn expand_scales_nested_components() {
+        let yeast = recipe(3, vec![ingredient("Yeast", 5.0)]);
+        let dough = recipe(
+            2,
+            vec![ingredient("Flour", 500.0), component(yeast.id, 1.0)],
+        );
+        let pizza = recipe(
+            1,
+            vec![component(dough.id, 2.0), ingredient("Cheese", 200.0)],
+        );
+        let recipes = HashMap::from([(dough.id, dough), (yeast.id, yeast)]);
+
+        let expanded = expand(&pizza, &recipes).unwrap();
+
+        assert_eq!(expanded.components.len(), 1);
+        assert_eq!(expanded.components[0].batches, 2.0);
+        let flat: Vec<(String, Option<f64>)> = flatten(&expanded)
+            .into_iter()
+            .map(|ingredient| (ingredient.name, ingredient.quantity))
+            .collect();
+        assert_eq!(
+            flat,
+            vec![
+                ("Flour".to_owned(), Some(1000.0)),
+                ("Yeast".to_owned(), Some(10.0)),
+                ("Cheese".to_owned(), Some(200.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn expand_detects_cycles() {
+        let a = recipe(1, vec![component(Uuid::from_u128(2), 1.0)]);
+        let b = recipe(2, vec![component(Uuid::from_u128(1), 1.0)]);
+        let recipes = HashMap::from([(a.id, a.clone()), (b.id, b)]);
+
+        let actual = expand(&a, &recipes);
+
+        assert_eq!(
+            actual,
+            Err(ExpandError::Cycle {
+                path: vec![Uuid::from_u128(1), Uuid::from_u128(2), Uuid::from_u128(1)],
+            })
+        );
+    }
+
+    #[test]
+    fn expand_leaves_missing_components() {
+        let pizza = recipe(1, vec![component(Uuid::from_u128(2), 1.0)]);
+
+        let expanded = expand(&pizza, &HashMap::new()).unwrap();
+
+        assert!(expanded.components.is_empty());
+        assert_eq!(flatten(&expanded), pizza.ingredients);
+    }
+
+    #[test]
+    fn expand_limits_depth() {
+        let chain: Vec<Recipe> = (0..=MAX_DEPTH as u128 + 1)
+            .map(|id| recipe(id, vec![component(Uuid::from_u128(id + 1), 1.0)]))
+            .collect();
+        let recipes = chain
+            .iter()
+            .map(|recipe| (recipe.id, recipe.clone()))
+            .collect();
+
+        let actual = expand(&chain[0], &recipes);
+
+        assert_eq!(actual, Err(ExpandError::TooDeep));
+    }
+
+    #[test]
+    fn expand_limits_fan_out() {
+        // Every level refers to the next one twice, doubling the components.
+        let levels: Vec<Recipe> = (0..MAX_DEPTH as u128)
+            .map(|id| {
+                let next = component(Uuid::from_u128(id + 1), 1.0);
+                recipe(id, vec![next.clone(), next])
+            })
+            .collect();
+        let recipes = levels
+            .iter()
+            .map(|recipe| (recipe.id, recipe.clone()))
+            .collect();
+
+        let actual = expand(&levels[0], &recipes);
+
+        assert_eq!(actual, Err(ExpandError::TooLarge));
+    }
+}
@@ -1,21 +1,77 @@
 use uuid::Uuid;
 
-use super::request_models::{PatchRecipe, PostRecipe, PutRecipe};
+use super::batch::{BatchOperation, RecipeWrite};
+use super::request_models::{ForkRecipe, PatchRecipe, PostRecipe, PutRecipe};
+use super::revision::{self, RecipeRevision, RevisionResponse};
 use super::Recipe;
 
 #[must_use]
-pub fn map_post_recipe(id: Uuid, value: &PostRecipe) -> Recipe {
+pub fn map_post_recipe(id: Uuid, household_id: &str, value: &PostRecipe) -> Recipe {
     Recipe {
+        household_id: household_id.to_owned(),
         id,
         name: value.name.clone(),
+        parent_id: None,
+        ingredients: value.ingredients.clone(),
+        steps: value.steps.clone(),
+        total_minutes: value.total_minutes,
+        deleted_at: None,
+        expires_at: None,
+        stats: None,
     }
 }
 
 #[must_use]
-pub fn map_put_recipe(value: &PutRecipe) -> Recipe {
+pub fn map_put_recipe(household_id: &str, value: &PutRecipe) -> Recipe {
     Recipe {
+        household_id: household_id.to_owned(),
         id: value.id,
         name: value.name.clone(),
+        parent_id: None,
+        ingredients: value.ingredients.clone(),
+        steps: value.steps.clone(),
+        total_minutes: value.total_minutes,
+        deleted_at: None,
+        expires_at: None,
+        stats: None,
+    }
+}
+
+/// Copies a recipe as a new variant of it.
+#[must_use]
+pub fn map_fork_recipe(id: Uuid, parent: &Recipe, value: &ForkRecipe) -> Recipe {
+    Recipe {
+        household_id: parent.household_id.clone(),
+        id,
+        name: value
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("{} (variant)", parent.name)),
+        parent_id: Some(parent.id),
+        ingredients: parent.ingredients.clone(),
+        steps: parent.steps.clone(),
+        total_minutes: parent.total_minutes,
+        deleted_at: None,
+        expires_at: None,
+        stats: None,
+    }
+}
+
+/// Maps an operation of a batch to the write it stands for. Created recipes
+/// get a new id.
+#[must_use]
+pub fn map_batch_operation(household_id: &str, value: &BatchOperation) -> RecipeWrite {
+    match value {
+        BatchOperation::Create { recipe } => {
+            RecipeWrite::Create(map_post_recipe(Uuid::new_v4(), household_id, recipe))
+        },
+        BatchOperation::Update { recipe } => {
+            RecipeWrite::Replace(map_put_recipe(household_id, recipe))
+        },
+        BatchOperation::Delete { id } => RecipeWrite::Trash {
+            household_id: household_id.to_owned(),
+            id: *id,
+        },
     }
 }
 
@@ -23,6 +79,46 @@ pub fn update_recipe(recipe: &mut Recipe, value: &PatchRecipe) {
     if let Some(new_name) = &value.name {
         recipe.name = new_name.to_string();
     }
+    if let Some(ingredients) = &value.ingredients {
+        recipe.ingredients = ingredients.clone();
+    }
+    if let Some(steps) = &value.steps {
+        recipe.steps = steps.clone();
+    }
+    if let Some(total_minutes) = value.total_minutes {
+        recipe.total_minutes = Some(total_minutes);
+    }
+}
+
+/// Maps a stored revision to its response, deriving its changes from the
+/// revision that came before it.
+#[must_use]
+pub fn map_revision(
+    value: &RecipeRevision,
+    previous: Option<&RecipeRevision>,
+    include_recipe: bool,
+) -> RevisionResponse {
+    RevisionResponse {
+        revision: value.revision,
+        author: value.author.clone(),
+        created_at: value.created_at,
+        changes: revision::diff(previous.map(|previous| &previous.recipe), &value.recipe),
+        recipe: include_recipe.then(|| value.recipe.clone()),
+    }
+}
+
+/// Maps the full history of a recipe, oldest first, to responses without the
+/// recipe snapshots.
+#[must_use]
+pub fn map_revisions(values: &[RecipeRevision]) -> Vec<RevisionResponse> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let previous = index.checked_sub(1).and_then(|index| values.get(index));
+            map_revision(value, previous, false)
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -30,8 +126,9 @@ mod test {
     use uuid::Uuid;
 
     use super::Recipe;
-    use crate::recipe::mapper;
-    use crate::recipe::request_models::{PatchRecipe, PostRecipe};
+    use crate::recipe::request_models::{ForkRecipe, PatchRecipe, PostRecipe};
+    use crate::recipe::revision::RecipeRevision;
+    use crate::recipe::{mapper, Ingredient, Step};
 
     const ID: Uuid = Uuid::nil();
     const NAME: &str = "Name";
@@ -40,14 +137,16 @@ mod test {
     fn create_from_request() {
         let create_request = PostRecipe {
             name: NAME.to_owned(),
+            ..PostRecipe::default()
         };
 
-        let recipe = mapper::map_post_recipe(Uuid::nil(), &create_request);
+        let recipe = mapper::map_post_recipe(Uuid::nil(), "", &create_request);
 
         assert_eq!(
             Recipe {
                 id: ID,
-                name: NAME.to_owned()
+                name: NAME.to_owned(),
+                ..Recipe::default()
             },
             recipe
         );
@@ -57,11 +156,13 @@ mod test {
     fn update_from_request() {
         let update_request = PatchRecipe {
             name: Some(NAME.to_owned()),
+            ..PatchRecipe::default()
         };
 
         let mut recipe = Recipe {
             id: ID,
             name: "Will change".to_owned(),
+            ..Recipe::default()
         };
 
         mapper::update_recipe(&mut recipe, &update_request);
@@ -69,7 +170,8 @@ mod test {
         assert_eq!(
             Recipe {
                 id: ID,
-                name: NAME.to_owned()
+                name: NAME.to_owned(),
+                ..Recipe::default()
             },
             recipe
         );
@@ -77,11 +179,12 @@ mod test {
 
     #[test]
     fn update_from_request_none() {
-        let update_request = PatchRecipe { name: None };
+        let update_request = PatchRecipe::default();
 
         let mut recipe = Recipe {
             id: ID,
             name: NAME.to_owned(),
+            ..Recipe::default()
         };
 
         mapper::update_recipe(&mut recipe, &update_request);
@@ -89,9 +192,63 @@ mod test {
         assert_eq!(
             Recipe {
                 id: ID,
-                name: NAME.to_owned()
+                name: NAME.to_owned(),
+                ..Recipe::default()
             },
             recipe
         );
     }
+
+    #[test]
+    fn fork_from_parent() {
+        let parent = Recipe {
+            ingredients: vec![Ingredient {
+                name: "Chili".to_owned(),
+                quantity: Some(2.0),
+                unit: None,
+                recipe_id: None,
+            }],
+            steps: vec![Step {
+                instruction: "Simmer".to_owned(),
+                ..Step::default()
+            }],
+            ..Recipe::default()
+        };
+
+        let variant = mapper::map_fork_recipe(Uuid::from_u128(1), &parent, &ForkRecipe::default());
+
+        assert_eq!(variant.id, Uuid::from_u128(1));
+        assert_eq!(variant.parent_id, Some(parent.id));
+        assert_eq!(variant.name, "Basic Recipe (variant)");
+        assert_eq!(variant.ingredients, parent.ingredients);
+        assert_eq!(variant.steps, parent.steps);
+    }
+
+    #[test]
+    fn map_revision_history() {
+        let revisions: Vec<RecipeRevision> = ["Original", "Original", "Tweaked"]
+            .iter()
+            .enumerate()
+            .map(|(index, name)| RecipeRevision {
+                recipe_id: ID,
+                revision: index as u32 + 1,
+                author: None,
+                created_at: 0,
+                recipe: Recipe {
+                    id: ID,
+                    name: (*name).to_owned(),
+                    ..Recipe::default()
+                },
+            })
+            .collect();
+
+        let responses = mapper::map_revisions(&revisions);
+
+        let changed: Vec<usize> = responses
+            .iter()
+            .map(|response| response.changes.len())
+            .collect();
+        assert_eq!(changed, vec![4, 0, 1]);
+        assert!(responses.iter().all(|response| response.recipe.is_none()));
+    }
 }
@@ -1,21 +1,300 @@
+use std::future::Future;
+
+use axum::async_trait;
+use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+use self::batch::RecipeWrite;
+use self::revision::RecipeRevision;
+use self::share::ShareLink;
+use crate::cook_log::CookStats;
+use crate::kitchen::Cookware;
+
+pub mod batch;
+pub mod component;
 pub mod mapper;
+pub mod patch;
 pub mod repository;
 pub mod request_models;
+pub mod revision;
+pub mod share;
+pub mod substitution;
+pub mod units;
+pub mod validation;
+pub mod variant;
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
 pub struct Recipe {
+    /// The household that owns the recipe, the partition key of the table.
+    #[serde(default)]
+    pub(crate) household_id: String,
     pub(crate) id: Uuid,
     pub(crate) name: String,
+    /// The recipe this one was forked from, if it is a variant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) parent_id: Option<Uuid>,
+    #[serde(default)]
+    pub(crate) ingredients: Vec<Ingredient>,
+    #[serde(default)]
+    pub(crate) steps: Vec<Step>,
+    /// How long the recipe takes from start to finish, in minutes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) total_minutes: Option<u32>,
+    /// When the recipe was moved to the trash, in seconds since the epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) deleted_at: Option<u64>,
+    /// When a trashed recipe is purged. Used as the table's time to live.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) expires_at: Option<u64>,
+    /// How often and how well the household has cooked the recipe. Filled in
+    /// from the cook log when the recipe is read on its own or listed in an
+    /// order of the cook log. Never stored, and left out of the responses to
+    /// writes.
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub(crate) stats: Option<CookStats>,
+}
+
+impl Recipe {
+    #[must_use]
+    pub fn is_deleted(&self) -> bool { self.deleted_at.is_some() }
 }
 
 impl Default for Recipe {
     fn default() -> Self {
         Self {
+            household_id: String::new(),
             id: Uuid::nil(),
             name: "Basic Recipe".to_owned(),
+            parent_id: None,
+            ingredients: Vec::new(),
+            steps: Vec::new(),
+            total_minutes: None,
+            deleted_at: None,
+            expires_at: None,
+            stats: None,
         }
     }
 }
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct Ingredient {
+    pub(crate) name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) quantity: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) unit: Option<String>,
+    /// Another recipe this ingredient stands for, measured in batches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) recipe_id: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct Step {
+    pub(crate) instruction: String,
+    /// How long the step takes, in minutes. Steps without a duration take no
+    /// time in a cook timeline.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) minutes: Option<u32>,
+    /// Whether the step runs unattended, like baking or resting, leaving the
+    /// cook free for other steps.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) passive: bool,
+    /// The indexes of the earlier steps this one waits for. Defaults to the
+    /// step right before it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) after: Option<Vec<usize>>,
+    /// The cookware the step needs while it runs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) equipment: Vec<Cookware>,
+}
+
+impl Step {
+    /// The indexes of the steps that must be finished before the step at the
+    /// given index can start.
+    #[must_use]
+    pub fn dependencies(&self, index: usize) -> Vec<usize> {
+        match &self.after {
+            Some(after) => after.clone(),
+            None => index.checked_sub(1).into_iter().collect(),
+        }
+    }
+}
+
+/// Storage for the revision history of recipes.
+///
+/// Histories are kept per household, so that a household reusing the id of a
+/// recipe of another household starts a history of its own.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait RevisionRepository: Send + Sync {
+    /// Returns every revision of a recipe, oldest first.
+    fn get_revisions(
+        &self,
+        household_id: &str,
+        recipe_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<RecipeRevision>, StatusCode>> + Send;
+    fn find_revision(
+        &self,
+        household_id: &str,
+        recipe_id: Uuid,
+        revision: u32,
+    ) -> impl Future<Output = Result<RecipeRevision, StatusCode>> + Send;
+    /// Saves the recipe and records a snapshot of it as its next revision, all
+    /// at once or not at all. Responds with conflict if other writes keep
+    /// taking the next revision number. The revision goes into the history of
+    /// the household the recipe belongs to.
+    fn save_revision(
+        &self,
+        recipe: &Recipe,
+        author: Option<String>,
+    ) -> impl Future<Output = Result<RecipeRevision, StatusCode>> + Send;
+}
+
+/// Moves recipes to and from the trash. Trashed recipes are purged by the
+/// database once they expire.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait TrashRepository: Send + Sync {
+    /// Marks a recipe as deleted. Responds with not found if the recipe does
+    /// not exist or is already in the trash.
+    fn soft_delete(
+        &self,
+        household_id: &str,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Recipe, StatusCode>> + Send;
+    /// Takes a recipe back out of the trash. Responds with not found if the
+    /// recipe is not in the trash.
+    fn restore(
+        &self,
+        household_id: &str,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Recipe, StatusCode>> + Send;
+}
+
+/// Storage for the share links of recipes.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ShareRepository: Send + Sync {
+    fn create_share(&self, link: &ShareLink)
+        -> impl Future<Output = Result<(), StatusCode>> + Send;
+    /// Finds the link with the given token. Expired links are reported as not
+    /// found.
+    fn find_share(&self, token: &str)
+        -> impl Future<Output = Result<ShareLink, StatusCode>> + Send;
+    /// Returns every link to a recipe, including expired links the database
+    /// has not purged yet.
+    fn get_shares(
+        &self,
+        recipe_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<ShareLink>, StatusCode>> + Send;
+    fn delete_share(&self, token: &str) -> impl Future<Output = Result<(), StatusCode>> + Send;
+}
+
+/// Writes many recipes with as few requests to the database as possible.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait BatchRepository: Send + Sync {
+    /// Runs the writes and reports the outcome of each, in order. Every recipe
+    /// that is created or replaced gets a new revision by `author` along with
+    /// it.
+    ///
+    /// Creates fail with conflict if the recipe exists, replaces and moves to
+    /// the trash with not found if it does not or is already in the trash.
+    ///
+    /// Unless `atomic`, the writes are independent of each other. When
+    /// `atomic`, either every write succeeds or none does, and the writes that
+    /// did not fail themselves fail with failed dependency.
+    fn write_batch(
+        &self,
+        writes: &[RecipeWrite],
+        atomic: bool,
+        author: Option<String>,
+    ) -> impl Future<Output = Result<Vec<Result<(), StatusCode>>, StatusCode>> + Send;
+}
+
+#[cfg(test)]
+mockall::mock! {
+    pub RecipeRepository {}
+
+    impl crate::Repository<Recipe> for RecipeRepository {
+        fn get_all(
+            &self,
+            household_id: &str,
+        ) -> impl Future<Output = Result<Vec<Recipe>, StatusCode>> + Send;
+        fn find_by_id(
+            &self,
+            household_id: &str,
+            id: Uuid,
+        ) -> impl Future<Output = Result<Recipe, StatusCode>> + Send;
+        fn find_by_ids(
+            &self,
+            household_id: &str,
+            ids: &[Uuid],
+        ) -> impl Future<Output = Result<Vec<Recipe>, StatusCode>> + Send;
+        fn save(
+            &self,
+            item: &Recipe,
+        ) -> impl Future<Output = Result<Option<Recipe>, StatusCode>> + Send;
+        fn delete_by_id(
+            &self,
+            household_id: &str,
+            id: Uuid,
+        ) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    }
+
+    impl RevisionRepository for RecipeRepository {
+        fn get_revisions(
+            &self,
+            household_id: &str,
+            recipe_id: Uuid,
+        ) -> impl Future<Output = Result<Vec<RecipeRevision>, StatusCode>> + Send;
+        fn find_revision(
+            &self,
+            household_id: &str,
+            recipe_id: Uuid,
+            revision: u32,
+        ) -> impl Future<Output = Result<RecipeRevision, StatusCode>> + Send;
+        fn save_revision(
+            &self,
+            recipe: &Recipe,
+            author: Option<String>,
+        ) -> impl Future<Output = Result<RecipeRevision, StatusCode>> + Send;
+    }
+
+    impl TrashRepository for RecipeRepository {
+        fn soft_delete(
+            &self,
+            household_id: &str,
+            id: Uuid,
+        ) -> impl Future<Output = Result<Recipe, StatusCode>> + Send;
+        fn restore(
+            &self,
+            household_id: &str,
+            id: Uuid,
+        ) -> impl Future<Output = Result<Recipe, StatusCode>> + Send;
+    }
+
+    impl ShareRepository for RecipeRepository {
+        fn create_share(&self, link: &ShareLink) -> impl Future<Output = Result<(), StatusCode>> + Send;
+        fn find_share(
+            &self,
+            token: &str,
+        ) -> impl Future<Output = Result<ShareLink, StatusCode>> + Send;
+        fn get_shares(
+            &self,
+            recipe_id: Uuid,
+        ) -> impl Future<Output = Result<Vec<ShareLink>, StatusCode>> + Send;
+        fn delete_share(&self, token: &str) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    }
+
+    impl BatchRepository for RecipeRepository {
+        fn write_batch(
+            &self,
+            writes: &[RecipeWrite],
+            atomic: bool,
+            author: Option<String>,
+        ) -> impl Future<Output = Result<Vec<Result<(), StatusCode>>, StatusCode>> + Send;
+    }
+}
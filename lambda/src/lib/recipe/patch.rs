@@ -0,0 +1,319 @@
+use axum::extract::{FromRequest, Request};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::{async_trait, Json};
+use json_patch::{Patch, PatchErrorKind};
+use serde_json::Value;
+
+use super::request_models::PatchRecipe;
+use super::{mapper, Recipe};
+use crate::validation::{RequestError, Validate, ValidatedJson, ValidationRejection, Violations};
+
+pub const MERGE_PATCH: &str = "application/merge-patch+json";
+pub const JSON_PATCH: &str = "application/json-patch+json";
+
+/// A change to a stored recipe, in whichever format the content type of the
+/// request names.
+#[derive(Debug)]
+pub enum RecipePatch {
+    /// Plain JSON with the fields to replace.
+    Fields(PatchRecipe),
+    /// An RFC 7396 JSON Merge Patch.
+    Merge(Value),
+    /// An RFC 6902 JSON Patch, a list of operations.
+    Operations(Patch),
+}
+
+impl RecipePatch {
+    /// Applies the patch to a copy of the recipe and checks the result against
+    /// the rules for recipes.
+    ///
+    /// # Errors
+    ///
+    /// Returns conflict if a `test` operation fails. Returns the violations if
+    /// an operation cannot be applied, the result is not a recipe, breaks
+    /// some rules or changes a field that the server owns.
+    pub fn apply(&self, recipe: &Recipe) -> Result<Recipe, RequestError> {
+        let patched = match self {
+            RecipePatch::Fields(fields) => {
+                let mut patched = recipe.clone();
+                mapper::update_recipe(&mut patched, fields);
+                patched
+            },
+            RecipePatch::Merge(patch) => {
+                let mut document = to_document(recipe)?;
+                json_patch::merge(&mut document, patch);
+                from_document(document)?
+            },
+            RecipePatch::Operations(patch) => {
+                let mut document = to_document(recipe)?;
+                json_patch::patch(&mut document, patch).map_err(|err| match err.kind {
+                    PatchErrorKind::TestFailed => RequestError::Status(StatusCode::CONFLICT),
+                    kind => {
+                        Violations::single(&format!("/{}/path", err.operation), &kind.to_string())
+                            .into()
+                    },
+                })?;
+                from_document(document)?
+            },
+        };
+
+        let mut violations = Violations::default();
+        check_unchanged(recipe, &patched, &mut violations);
+        patched.validate_at("", &mut violations);
+        if !violations.is_empty() {
+            return Err(violations.into());
+        }
+
+        Ok(patched)
+    }
+}
+
+fn to_document(recipe: &Recipe) -> Result<Value, StatusCode> {
+    serde_json::to_value(recipe).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn from_document(document: Value) -> Result<Recipe, Violations> {
+    serde_json::from_value(document).map_err(|err| Violations::single("", &err.to_string()))
+}
+
+/// Rejects patches that touch the fields that identify a recipe or track its
+/// place in the trash.
+fn check_unchanged(original: &Recipe, patched: &Recipe, violations: &mut Violations) {
+    let unchanged = |same: bool| {
+        if same {
+            Ok(())
+        } else {
+            Err("must not be changed".to_owned())
+        }
+    };
+    violations.check(
+        "/household_id",
+        unchanged(original.household_id == patched.household_id),
+    );
+    violations.check("/id", unchanged(original.id == patched.id));
+    violations.check(
+        "/parent_id",
+        unchanged(original.parent_id == patched.parent_id),
+    );
+    violations.check(
+        "/deleted_at",
+        unchanged(original.deleted_at == patched.deleted_at),
+    );
+    violations.check(
+        "/expires_at",
+        unchanged(original.expires_at == patched.expires_at),
+    );
+}
+
+#[async_trait]
+impl<S> FromRequest<S> for RecipePatch
+where
+    S: Send + Sync,
+{
+    type Rejection = ValidationRejection;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let content_type = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+
+        match content_type.as_str() {
+            MERGE_PATCH => {
+                let Json(patch) = Json::from_request(request, state)
+                    .await
+                    .map_err(ValidationRejection::Json)?;
+                Ok(Self::Merge(patch))
+            },
+            JSON_PATCH => {
+                let Json(patch) = Json::from_request(request, state)
+                    .await
+                    .map_err(ValidationRejection::Json)?;
+                Ok(Self::Operations(patch))
+            },
+            _ => {
+                let ValidatedJson(fields) = ValidatedJson::from_request(request, state).await?;
+                Ok(Self::Fields(fields))
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::extract::FromRequest;
+    use axum::http::header::CONTENT_TYPE;
+    use axum::http::{Request, StatusCode};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::{RecipePatch, MERGE_PATCH};
+    use crate::recipe::{Ingredient, Recipe, Step};
+    use crate::validation::RequestError;
+
+    fn recipe() -> Recipe {
+        Recipe {
+            household_id: "grandma".to_owned(),
+            id: Uuid::nil(),
+            name: "Bread".to_owned(),
+            ingredients: vec![Ingredient {
+                name: "Flour".to_owned(),
+                quantity: Some(500.0),
+                unit: Some("g".to_owned()),
+                recipe_id: None,
+            }],
+            steps: vec![Step {
+                instruction: "Knead".to_owned(),
+                ..Step::default()
+            }],
+            ..Recipe::default()
+        }
+    }
+
+    fn pointers(error: RequestError) -> Vec<String> {
+        match error {
+            RequestError::Invalid(violations) => violations
+                .errors()
+                .iter()
+                .map(|violation| violation.pointer.clone())
+                .collect(),
+            RequestError::Status(status) => panic!("unexpected status {status}"),
+        }
+    }
+
+    async fn extract(content_type: &str, body: &str) -> RecipePatch {
+        let request = Request::patch("/")
+            .header(CONTENT_TYPE, content_type)
+            .body(Body::from(body.to_owned()))
+            .unwrap();
+
+        RecipePatch::from_request(request, &()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn extracts_by_content_type() {
+        assert!(matches!(
+            extract("application/json", r#"{ "name": "Rolls" }"#).await,
+            RecipePatch::Fields(_)
+        ));
+        assert!(matches!(
+            extract(MERGE_PATCH, r#"{ "name": "Rolls" }"#).await,
+            RecipePatch::Merge(_)
+        ));
+        assert!(matches!(
+            extract(
+                "Application/JSON-Patch+JSON; charset=utf-8",
+                r#"[{ "op": "remove", "path": "/steps/0" }]"#
+            )
+            .await,
+            RecipePatch::Operations(_)
+        ));
+    }
+
+    #[test]
+    fn merge_patch() {
+        let patch = RecipePatch::Merge(json!({
+            "name": "Rolls",
+            "steps": [{ "instruction": "Shape" }, { "instruction": "Bake" }],
+        }));
+
+        let patched = patch.apply(&recipe()).unwrap();
+
+        assert_eq!(patched.name, "Rolls");
+        assert_eq!(patched.ingredients, recipe().ingredients);
+        assert_eq!(patched.steps.len(), 2);
+    }
+
+    #[test]
+    fn json_patch_on_arrays() {
+        let patch = RecipePatch::Operations(
+            serde_json::from_value(json!([
+                { "op": "test", "path": "/ingredients/0/name", "value": "Flour" },
+                { "op": "replace", "path": "/ingredients/0/quantity", "value": 450 },
+                { "op": "add", "path": "/ingredients/-", "value": { "name": "Salt" } },
+                { "op": "remove", "path": "/ingredients/0/unit" },
+                { "op": "add", "path": "/steps/0", "value": { "instruction": "Mix" } },
+            ]))
+            .unwrap(),
+        );
+
+        let patched = patch.apply(&recipe()).unwrap();
+
+        assert_eq!(patched.ingredients[0].quantity, Some(450.0));
+        assert_eq!(patched.ingredients[0].unit, None);
+        assert_eq!(patched.ingredients[1].name, "Salt");
+        assert_eq!(patched.steps[0].instruction, "Mix");
+        assert_eq!(patched.steps[1].instruction, "Knead");
+    }
+
+    #[test]
+    fn failed_test_operation_conflicts() {
+        let patch = RecipePatch::Operations(
+            serde_json::from_value(json!([
+                { "op": "test", "path": "/name", "value": "Cake" },
+                { "op": "replace", "path": "/name", "value": "Rolls" },
+            ]))
+            .unwrap(),
+        );
+
+        assert_eq!(
+            patch.apply(&recipe()).err(),
+            Some(RequestError::Status(StatusCode::CONFLICT))
+        );
+    }
+
+    #[test]
+    fn invalid_operation_points_into_patch() {
+        let patch = RecipePatch::Operations(
+            serde_json::from_value(json!([
+                { "op": "replace", "path": "/name", "value": "Rolls" },
+                { "op": "remove", "path": "/steps/3" },
+            ]))
+            .unwrap(),
+        );
+
+        assert_eq!(
+            pointers(patch.apply(&recipe()).unwrap_err()),
+            vec!["/1/path"]
+        );
+    }
+
+    #[test]
+    fn result_is_validated() {
+        let patch = RecipePatch::Merge(json!({
+            "name": " ",
+            "ingredients": [{ "name": "Flour", "quantity": -1 }],
+        }));
+
+        assert_eq!(
+            pointers(patch.apply(&recipe()).unwrap_err()),
+            vec!["/name", "/ingredients/0/quantity"]
+        );
+    }
+
+    #[test]
+    fn result_must_be_a_recipe() {
+        let patch = RecipePatch::Merge(json!({ "name": null }));
+
+        assert_eq!(pointers(patch.apply(&recipe()).unwrap_err()), vec![""]);
+    }
+
+    #[test]
+    fn server_owned_fields_are_kept() {
+        let patch = RecipePatch::Merge(json!({
+            "id": Uuid::new_v4(),
+            "household_id": "grandpa",
+            "deleted_at": 1,
+        }));
+
+        assert_eq!(
+            pointers(patch.apply(&recipe()).unwrap_err()),
+            vec!["/household_id", "/id", "/deleted_at"]
+        );
+    }
+}
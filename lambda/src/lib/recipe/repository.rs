@@ -1,63 +1,114 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 use aws_config::SdkConfig;
 use aws_sdk_dynamodb::operation::delete_item::DeleteItemError;
 use aws_sdk_dynamodb::operation::get_item::GetItemError;
-use aws_sdk_dynamodb::types::AttributeValue;
+use aws_sdk_dynamodb::operation::transact_write_items::TransactWriteItemsError;
+use aws_sdk_dynamodb::operation::update_item::UpdateItemError;
+use aws_sdk_dynamodb::types::{AttributeValue, Put, TransactWriteItem, Update};
 use axum::http::StatusCode;
-use serde_dynamo::aws_sdk_dynamodb_1::{from_item, to_item};
+use futures_util::{stream, StreamExt};
+use serde_dynamo::aws_sdk_dynamodb_1::{from_item, from_items, to_item};
 use uuid::Uuid;
 
-use super::Recipe;
-use crate::aws_client::{DynamoDbClient, DynamoDbClientImpl};
-use crate::Repository;
+use super::batch::RecipeWrite;
+use super::revision::RecipeRevision;
+use super::share::ShareLink;
+use super::{BatchRepository, Recipe, RevisionRepository, ShareRepository, TrashRepository};
+use crate::aws_client::{
+    batch_get_all,
+    query_all,
+    DynamoDbClient,
+    DynamoDbClientImpl,
+    QueryExpression,
+    UpdateExpression,
+};
+use crate::{clock, Repository};
+
+/// The global secondary index of the share table, partitioned by recipe.
+pub const SHARE_RECIPE_INDEX: &str = "recipe_id-index";
+
+/// How many revision numbers a save tries before giving up when other writes
+/// keep taking them first.
+const REVISION_ATTEMPTS: usize = 3;
+
+/// How many operations of a batch that is not atomic run at the same time.
+const WRITE_CONCURRENCY: usize = 10;
+
+/// The reason DynamoDB gives for a write whose condition did not hold.
+const CONDITION_FAILED: &str = "ConditionalCheckFailed";
+
+/// How long a recipe stays in the trash unless configured otherwise.
+pub const DEFAULT_TRASH_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
 
 #[derive(Clone)]
 pub struct DynamoDbRecipe {
     client: Arc<dyn DynamoDbClient>,
     table_name: String,
+    revision_table_name: String,
+    share_table_name: String,
+    trash_retention: Duration,
 }
 
 impl DynamoDbRecipe {
     #[must_use]
-    pub fn new(sdk_config: &SdkConfig, table_name: &str) -> Self {
+    pub fn new(
+        sdk_config: &SdkConfig,
+        table_name: &str,
+        revision_table_name: &str,
+        share_table_name: &str,
+    ) -> Self {
         Self {
             client: Arc::new(DynamoDbClientImpl::new(sdk_config)),
             table_name: table_name.to_owned(),
+            revision_table_name: revision_table_name.to_owned(),
+            share_table_name: share_table_name.to_owned(),
+            trash_retention: DEFAULT_TRASH_RETENTION,
         }
     }
 
+    #[must_use]
+    pub fn with_trash_retention(mut self, trash_retention: Duration) -> Self {
+        self.trash_retention = trash_retention;
+        self
+    }
+
     #[allow(dead_code)]
     fn mock(client: Arc<dyn DynamoDbClient>, table_name: &str) -> Self {
         Self {
             client,
             table_name: table_name.to_owned(),
+            revision_table_name: format!("{table_name}-revisions"),
+            share_table_name: format!("{table_name}-shares"),
+            trash_retention: DEFAULT_TRASH_RETENTION,
         }
     }
 }
 
 impl Repository<Recipe> for DynamoDbRecipe {
-    async fn get_all(&self) -> Result<Vec<Recipe>, StatusCode> {
-        let scan_result = self
-            .client
-            .scan(&self.table_name)
+    async fn get_all(&self, household_id: &str) -> Result<Vec<Recipe>, StatusCode> {
+        let query = QueryExpression {
+            key_condition: "household_id = :household_id".to_owned(),
+            values: HashMap::from([(
+                ":household_id".to_owned(),
+                AttributeValue::S(household_id.to_owned()),
+            )]),
+            scan_forward: true,
+            ..QueryExpression::default()
+        };
+        let items = query_all(self.client.as_ref(), &self.table_name, query)
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        let items: Vec<Recipe> = scan_result
-            .items()
-            .iter()
-            .flat_map(|item| from_item(item.clone()))
-            .collect();
-
-        Ok(items)
+        Ok(items.into_iter().flat_map(from_item).collect())
     }
 
-    async fn find_by_id(&self, id: Uuid) -> Result<Recipe, StatusCode> {
+    async fn find_by_id(&self, household_id: &str, id: Uuid) -> Result<Recipe, StatusCode> {
         let get_item_result = self
             .client
-            .get_item(&self.table_name, DynamoDbRecipe::get_key(id))
+            .get_item(&self.table_name, DynamoDbRecipe::get_key(household_id, id))
             .await
             .map_err(|err| match err {
                 GetItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
@@ -73,6 +124,29 @@ impl Repository<Recipe> for DynamoDbRecipe {
         Ok(recipe)
     }
 
+    async fn find_by_ids(
+        &self,
+        household_id: &str,
+        ids: &[Uuid],
+    ) -> Result<Vec<Recipe>, StatusCode> {
+        // DynamoDB rejects batches that ask for the same key twice.
+        let mut seen = HashSet::new();
+        let ids: Vec<Uuid> = ids.iter().copied().filter(|id| seen.insert(*id)).collect();
+        let keys = ids
+            .iter()
+            .map(|id| DynamoDbRecipe::get_key(household_id, *id))
+            .collect();
+        let items = batch_get_all(self.client.as_ref(), &self.table_name, keys)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        let mut recipes: Vec<Recipe> =
+            from_items(items).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        recipes.sort_by_key(|recipe| ids.iter().position(|id| *id == recipe.id));
+
+        Ok(recipes)
+    }
+
     async fn save(&self, recipe: &Recipe) -> Result<Option<Recipe>, StatusCode> {
         let item = to_item(recipe).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
         let output = self
@@ -87,9 +161,13 @@ impl Repository<Recipe> for DynamoDbRecipe {
         }
     }
 
-    async fn delete_by_id(&self, id: Uuid) -> Result<(), StatusCode> {
+    async fn delete_by_id(&self, household_id: &str, id: Uuid) -> Result<(), StatusCode> {
         self.client
-            .delete_item(&self.table_name, DynamoDbRecipe::get_key(id))
+            .delete_item(
+                &self.table_name,
+                DynamoDbRecipe::get_key(household_id, id),
+                "id",
+            )
             .await
             .map_err(|err| match err {
                 DeleteItemError::ConditionalCheckFailedException(_)
@@ -101,9 +179,402 @@ impl Repository<Recipe> for DynamoDbRecipe {
     }
 }
 
+impl RevisionRepository for DynamoDbRecipe {
+    async fn get_revisions(
+        &self,
+        household_id: &str,
+        recipe_id: Uuid,
+    ) -> Result<Vec<RecipeRevision>, StatusCode> {
+        let items = query_all(
+            self.client.as_ref(),
+            &self.revision_table_name,
+            DynamoDbRecipe::revision_query(household_id, recipe_id, true),
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        from_items(items).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    async fn find_revision(
+        &self,
+        household_id: &str,
+        recipe_id: Uuid,
+        revision: u32,
+    ) -> Result<RecipeRevision, StatusCode> {
+        let get_item_result = self
+            .client
+            .get_item(
+                &self.revision_table_name,
+                DynamoDbRecipe::get_revision_key(household_id, recipe_id, revision),
+            )
+            .await
+            .map_err(|err| match err {
+                GetItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+        let item = get_item_result
+            .item()
+            .ok_or(StatusCode::NOT_FOUND)?
+            .to_owned();
+
+        from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    async fn save_revision(
+        &self,
+        recipe: &Recipe,
+        author: Option<String>,
+    ) -> Result<RecipeRevision, StatusCode> {
+        let items = vec![self.recipe_item(recipe, None)?];
+        match self
+            .transact_revised(items, &[recipe], author.as_deref())
+            .await?
+        {
+            Transaction::Written(mut revisions) => Ok(revisions.remove(0)),
+            // The unconditional put can only lose to a concurrent write.
+            Transaction::Cancelled(_) => Err(StatusCode::CONFLICT),
+        }
+    }
+}
+
+impl TrashRepository for DynamoDbRecipe {
+    async fn soft_delete(&self, household_id: &str, id: Uuid) -> Result<Recipe, StatusCode> {
+        let now = clock::now();
+        let update = UpdateExpression {
+            expression: "SET deleted_at = :now, expires_at = :expires_at".to_owned(),
+            condition: Some("attribute_exists(id) AND attribute_not_exists(deleted_at)".to_owned()),
+            values: HashMap::from([
+                (":now".to_owned(), AttributeValue::N(now.to_string())),
+                (
+                    ":expires_at".to_owned(),
+                    AttributeValue::N((now + self.trash_retention.as_secs()).to_string()),
+                ),
+            ]),
+            ..UpdateExpression::default()
+        };
+
+        self.update(household_id, id, update).await
+    }
+
+    async fn restore(&self, household_id: &str, id: Uuid) -> Result<Recipe, StatusCode> {
+        let update = UpdateExpression {
+            expression: "REMOVE deleted_at, expires_at".to_owned(),
+            condition: Some("attribute_exists(deleted_at) AND expires_at > :now".to_owned()),
+            values: HashMap::from([(
+                ":now".to_owned(),
+                AttributeValue::N(clock::now().to_string()),
+            )]),
+            ..UpdateExpression::default()
+        };
+
+        self.update(household_id, id, update).await
+    }
+}
+
+impl ShareRepository for DynamoDbRecipe {
+    async fn create_share(&self, link: &ShareLink) -> Result<(), StatusCode> {
+        let item = to_item(link).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.client
+            .put_new_item(&self.share_table_name, item, "token")
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(())
+    }
+
+    async fn find_share(&self, token: &str) -> Result<ShareLink, StatusCode> {
+        let output = self
+            .client
+            .get_item(&self.share_table_name, DynamoDbRecipe::get_share_key(token))
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let item = output.item().ok_or(StatusCode::NOT_FOUND)?.to_owned();
+        let link: ShareLink = from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        // Expired links linger until the table's TTL removes them.
+        if link.is_expired(clock::now()) {
+            return Err(StatusCode::NOT_FOUND);
+        }
+
+        Ok(link)
+    }
+
+    async fn get_shares(&self, recipe_id: Uuid) -> Result<Vec<ShareLink>, StatusCode> {
+        let query = QueryExpression {
+            key_condition: "recipe_id = :recipe_id".to_owned(),
+            index_name: Some(SHARE_RECIPE_INDEX.to_owned()),
+            values: HashMap::from([(
+                ":recipe_id".to_owned(),
+                AttributeValue::S(recipe_id.as_hyphenated().to_string()),
+            )]),
+            scan_forward: true,
+            ..QueryExpression::default()
+        };
+        let items = query_all(self.client.as_ref(), &self.share_table_name, query)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(items.into_iter().flat_map(from_item).collect())
+    }
+
+    async fn delete_share(&self, token: &str) -> Result<(), StatusCode> {
+        self.client
+            .delete_item(
+                &self.share_table_name,
+                DynamoDbRecipe::get_share_key(token),
+                "token",
+            )
+            .await
+            .map_err(|err| match err {
+                DeleteItemError::ConditionalCheckFailedException(_)
+                | DeleteItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+
+        Ok(())
+    }
+}
+
+impl BatchRepository for DynamoDbRecipe {
+    async fn write_batch(
+        &self,
+        writes: &[RecipeWrite],
+        atomic: bool,
+        author: Option<String>,
+    ) -> Result<Vec<Result<(), StatusCode>>, StatusCode> {
+        if atomic {
+            return self.transact(writes, author.as_deref()).await;
+        }
+
+        let writes: Vec<_> = writes
+            .iter()
+            .map(|write| self.write_one(write, author.as_deref()))
+            .collect();
+
+        Ok(stream::iter(writes)
+            .buffered(WRITE_CONCURRENCY)
+            .collect()
+            .await)
+    }
+}
+
+/// How a transaction of recipe writes and the revisions they record ended.
+enum Transaction {
+    Written(Vec<RecipeRevision>),
+    /// A condition of the writes failed, with the reason code of each write.
+    Cancelled(Vec<Option<String>>),
+}
+
 impl DynamoDbRecipe {
-    fn get_key(id: Uuid) -> HashMap<String, AttributeValue> {
+    async fn transact(
+        &self,
+        writes: &[RecipeWrite],
+        author: Option<&str>,
+    ) -> Result<Vec<Result<(), StatusCode>>, StatusCode> {
+        let items = writes
+            .iter()
+            .map(|write| self.transact_item(write))
+            .collect::<Result<Vec<_>, _>>()?;
+        let recipes: Vec<&Recipe> = writes.iter().filter_map(RecipeWrite::recipe).collect();
+
+        match self.transact_revised(items, &recipes, author).await? {
+            Transaction::Written(_) => Ok(vec![Ok(()); writes.len()]),
+            Transaction::Cancelled(codes) => Ok(writes
+                .iter()
+                .enumerate()
+                .map(|(index, write)| {
+                    Err(match codes.get(index).and_then(Option::as_deref) {
+                        Some(CONDITION_FAILED) => match write {
+                            RecipeWrite::Create(_) => StatusCode::CONFLICT,
+                            _ => StatusCode::NOT_FOUND,
+                        },
+                        Some("None") | None => StatusCode::FAILED_DEPENDENCY,
+                        Some(_) => StatusCode::SERVICE_UNAVAILABLE,
+                    })
+                })
+                .collect()),
+        }
+    }
+
+    async fn write_one(&self, write: &RecipeWrite, author: Option<&str>) -> Result<(), StatusCode> {
+        self.transact(std::slice::from_ref(write), author)
+            .await?
+            .remove(0)
+    }
+
+    /// Runs the items together with a new revision of each of the recipes, all
+    /// at once or not at all. When other writes take the revision numbers
+    /// first, the items are tried again with later numbers.
+    async fn transact_revised(
+        &self,
+        items: Vec<TransactWriteItem>,
+        recipes: &[&Recipe],
+        author: Option<&str>,
+    ) -> Result<Transaction, StatusCode> {
+        for _ in 0..REVISION_ATTEMPTS {
+            let mut revisions = Vec::with_capacity(recipes.len());
+            for recipe in recipes {
+                revisions.push(
+                    self.next_revision(recipe, author.map(str::to_owned))
+                        .await?,
+                );
+            }
+            let mut all_items = items.clone();
+            for revision in &revisions {
+                all_items.push(self.revision_item(revision)?);
+            }
+
+            let reasons: Vec<Option<String>> =
+                match self.client.transact_write_items(all_items).await {
+                    Ok(_) => return Ok(Transaction::Written(revisions)),
+                    Err(TransactWriteItemsError::TransactionCanceledException(err)) => err
+                        .cancellation_reasons()
+                        .iter()
+                        .map(|reason| reason.code().map(str::to_owned))
+                        .collect(),
+                    Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+                };
+            let (codes, revision_codes) = reasons.split_at(items.len().min(reasons.len()));
+            let failed = |code: &Option<String>| code.as_deref() == Some(CONDITION_FAILED);
+            // Only retry when nothing but the revision numbers stood in the way.
+            if codes.iter().any(failed) || !revision_codes.iter().any(failed) {
+                return Ok(Transaction::Cancelled(codes.to_vec()));
+            }
+        }
+
+        Err(StatusCode::CONFLICT)
+    }
+
+    fn transact_item(&self, write: &RecipeWrite) -> Result<TransactWriteItem, StatusCode> {
+        match write {
+            RecipeWrite::Create(recipe) => {
+                self.recipe_item(recipe, Some("attribute_not_exists(id)"))
+            },
+            // Recipes in the trash have to be restored before they can be
+            // replaced.
+            RecipeWrite::Replace(recipe) => self.recipe_item(
+                recipe,
+                Some("attribute_exists(id) AND attribute_not_exists(deleted_at)"),
+            ),
+            RecipeWrite::Trash { household_id, id } => {
+                let now = clock::now();
+                let update = Update::builder()
+                    .table_name(&self.table_name)
+                    .set_key(Some(DynamoDbRecipe::get_key(household_id, *id)))
+                    .update_expression("SET deleted_at = :now, expires_at = :expires_at")
+                    .condition_expression(
+                        "attribute_exists(id) AND attribute_not_exists(deleted_at)",
+                    )
+                    .expression_attribute_values(":now", AttributeValue::N(now.to_string()))
+                    .expression_attribute_values(
+                        ":expires_at",
+                        AttributeValue::N((now + self.trash_retention.as_secs()).to_string()),
+                    )
+                    .build()
+                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+                Ok(TransactWriteItem::builder().update(update).build())
+            },
+        }
+    }
+
+    fn recipe_item(
+        &self,
+        recipe: &Recipe,
+        condition: Option<&str>,
+    ) -> Result<TransactWriteItem, StatusCode> {
+        let put = Put::builder()
+            .table_name(&self.table_name)
+            .set_item(Some(
+                to_item(recipe).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+            ))
+            .set_condition_expression(condition.map(str::to_owned))
+            .build()
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(TransactWriteItem::builder().put(put).build())
+    }
+
+    fn revision_item(&self, revision: &RecipeRevision) -> Result<TransactWriteItem, StatusCode> {
+        let mut item = to_item(revision).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        item.insert(
+            "recipe_key".to_owned(),
+            AttributeValue::S(DynamoDbRecipe::revision_partition(
+                &revision.recipe.household_id,
+                revision.recipe_id,
+            )),
+        );
+        let put = Put::builder()
+            .table_name(&self.revision_table_name)
+            .set_item(Some(item))
+            .condition_expression("attribute_not_exists(recipe_key)")
+            .build()
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(TransactWriteItem::builder().put(put).build())
+    }
+
+    /// Numbers a snapshot of the recipe after its latest revision.
+    async fn next_revision(
+        &self,
+        recipe: &Recipe,
+        author: Option<String>,
+    ) -> Result<RecipeRevision, StatusCode> {
+        let query = QueryExpression {
+            limit: Some(1),
+            ..DynamoDbRecipe::revision_query(&recipe.household_id, recipe.id, false)
+        };
+        let latest = self
+            .client
+            .query(&self.revision_table_name, query)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let latest: Vec<RecipeRevision> =
+            from_items(latest.items().to_vec()).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(RecipeRevision {
+            recipe_id: recipe.id,
+            revision: latest.first().map_or(1, |latest| latest.revision + 1),
+            author,
+            created_at: clock::now(),
+            recipe: recipe.clone(),
+        })
+    }
+
+    async fn update(
+        &self,
+        household_id: &str,
+        id: Uuid,
+        update: UpdateExpression,
+    ) -> Result<Recipe, StatusCode> {
+        let output = self
+            .client
+            .update_item(
+                &self.table_name,
+                DynamoDbRecipe::get_key(household_id, id),
+                update,
+            )
+            .await
+            .map_err(|err| match err {
+                UpdateItemError::ConditionalCheckFailedException(_)
+                | UpdateItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+
+        output
+            .attributes
+            .map(from_item)
+            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn get_key(household_id: &str, id: Uuid) -> HashMap<String, AttributeValue> {
         let mut key = HashMap::new();
+        key.insert(
+            "household_id".to_string(),
+            AttributeValue::S(household_id.to_owned()),
+        );
         key.insert(
             "id".to_string(),
             AttributeValue::S(id.as_hyphenated().to_string()),
@@ -111,39 +582,93 @@ impl DynamoDbRecipe {
 
         key
     }
+
+    fn get_share_key(token: &str) -> HashMap<String, AttributeValue> {
+        HashMap::from([("token".to_owned(), AttributeValue::S(token.to_owned()))])
+    }
+
+    /// Keys the history of a recipe by its household as well, as recipe ids are
+    /// chosen by clients and may be reused across households.
+    fn revision_partition(household_id: &str, recipe_id: Uuid) -> String {
+        format!("{household_id}#{}", recipe_id.as_hyphenated())
+    }
+
+    fn get_revision_key(
+        household_id: &str,
+        recipe_id: Uuid,
+        revision: u32,
+    ) -> HashMap<String, AttributeValue> {
+        let mut key = HashMap::new();
+        key.insert(
+            "recipe_key".to_string(),
+            AttributeValue::S(DynamoDbRecipe::revision_partition(household_id, recipe_id)),
+        );
+        key.insert(
+            "revision".to_string(),
+            AttributeValue::N(revision.to_string()),
+        );
+
+        key
+    }
+
+    fn revision_query(household_id: &str, recipe_id: Uuid, scan_forward: bool) -> QueryExpression {
+        QueryExpression {
+            key_condition: "recipe_key = :recipe_key".to_owned(),
+            values: HashMap::from([(
+                ":recipe_key".to_owned(),
+                AttributeValue::S(DynamoDbRecipe::revision_partition(household_id, recipe_id)),
+            )]),
+            scan_forward,
+            ..QueryExpression::default()
+        }
+    }
 }
 
 #[cfg(test)]
+// The baseline tests assert with `match` and the mocked client returns the
+// large SDK error types.
+#[allow(clippy::match_like_matches_macro, clippy::result_large_err)]
 mod test {
 
+    use aws_sdk_dynamodb::operation::batch_get_item::BatchGetItemOutput;
     use aws_sdk_dynamodb::operation::delete_item::DeleteItemOutput;
     use aws_sdk_dynamodb::operation::get_item::GetItemOutput;
     use aws_sdk_dynamodb::operation::put_item::{PutItemError, PutItemOutput};
-    use aws_sdk_dynamodb::operation::scan::ScanOutput;
+    use aws_sdk_dynamodb::operation::query::QueryOutput;
+    use aws_sdk_dynamodb::operation::transact_write_items::TransactWriteItemsOutput;
+    use aws_sdk_dynamodb::operation::update_item::UpdateItemOutput;
     use aws_sdk_dynamodb::types::error::{
         ConditionalCheckFailedException,
         ResourceNotFoundException,
+        TransactionCanceledException,
     };
-    use mockall::predicate::eq;
+    use aws_sdk_dynamodb::types::{CancellationReason, KeysAndAttributes};
+    use mockall::predicate::{eq, function};
 
     use super::*;
     use crate::aws_client::MockDynamoDbClient as DynamoDbClient;
 
+    const HOUSEHOLD: &str = "household";
+
     #[tokio::test]
     async fn test_get_no_error() {
         let recipe = Recipe {
             id: Uuid::nil(),
             name: "Name".to_owned(),
+            ..Recipe::default()
         };
         let item = to_item(&recipe).unwrap();
         let mut mock = DynamoDbClient::default();
         mock.expect_get_item()
-            .with(eq("recipes"), eq(DynamoDbRecipe::get_key(Uuid::nil())))
+            .with(
+                eq("recipes"),
+                eq(DynamoDbRecipe::get_key(HOUSEHOLD, Uuid::nil())),
+            )
             .return_once(|_, _| Ok(GetItemOutput::builder().set_item(Some(item)).build()));
 
         let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
 
-        let result = repo.find_by_id(Uuid::nil()).await;
+        let result = repo.find_by_id(HOUSEHOLD, Uuid::nil()).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), recipe);
     }
@@ -152,7 +677,10 @@ mod test {
     async fn test_get_not_found() {
         let mut mock = DynamoDbClient::default();
         mock.expect_get_item()
-            .with(eq("recipes"), eq(DynamoDbRecipe::get_key(Uuid::nil())))
+            .with(
+                eq("recipes"),
+                eq(DynamoDbRecipe::get_key(HOUSEHOLD, Uuid::nil())),
+            )
             .return_once(|_, _| {
                 Err(GetItemError::ResourceNotFoundException(
                     ResourceNotFoundException::builder().build(),
@@ -161,7 +689,7 @@ mod test {
 
         let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
 
-        let result = repo.find_by_id(Uuid::nil()).await;
+        let result = repo.find_by_id(HOUSEHOLD, Uuid::nil()).await;
         assert!(result.is_err());
         let test = match result {
             Err(StatusCode::NOT_FOUND) => true,
@@ -175,6 +703,7 @@ mod test {
         let recipe = Recipe {
             id: Uuid::nil(),
             name: "Name".to_owned(),
+            ..Recipe::default()
         };
         let item = to_item(&recipe).unwrap();
         let mut mock = DynamoDbClient::default();
@@ -196,6 +725,7 @@ mod test {
         let recipe = Recipe {
             id: Uuid::nil(),
             name: "Name".to_owned(),
+            ..Recipe::default()
         };
         let item = to_item(&recipe).unwrap();
         let mut mock = DynamoDbClient::default();
@@ -220,12 +750,16 @@ mod test {
     async fn test_delete() {
         let mut mock = DynamoDbClient::default();
         mock.expect_delete_item()
-            .with(eq("recipes"), eq(DynamoDbRecipe::get_key(Uuid::nil())))
-            .return_once(move |_, _| Ok(DeleteItemOutput::builder().build()));
+            .with(
+                eq("recipes"),
+                eq(DynamoDbRecipe::get_key(HOUSEHOLD, Uuid::nil())),
+                eq("id"),
+            )
+            .return_once(move |_, _, _| Ok(DeleteItemOutput::builder().build()));
 
         let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
 
-        let result = repo.delete_by_id(Uuid::nil()).await;
+        let result = repo.delete_by_id(HOUSEHOLD, Uuid::nil()).await;
         assert!(result.is_ok())
     }
 
@@ -233,8 +767,12 @@ mod test {
     async fn test_delete_error_resource_not_found() {
         let mut mock = DynamoDbClient::default();
         mock.expect_delete_item()
-            .with(eq("recipes"), eq(DynamoDbRecipe::get_key(Uuid::nil())))
-            .return_once(move |_, _| {
+            .with(
+                eq("recipes"),
+                eq(DynamoDbRecipe::get_key(HOUSEHOLD, Uuid::nil())),
+                eq("id"),
+            )
+            .return_once(move |_, _, _| {
                 Err(DeleteItemError::ResourceNotFoundException(
                     ResourceNotFoundException::builder().build(),
                 ))
@@ -242,7 +780,7 @@ mod test {
 
         let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
 
-        let result = repo.delete_by_id(Uuid::nil()).await;
+        let result = repo.delete_by_id(HOUSEHOLD, Uuid::nil()).await;
         assert!(result.is_err_and(|err| match err {
             StatusCode::NOT_FOUND => true,
             _ => false,
@@ -253,8 +791,12 @@ mod test {
     async fn test_delete_error_condition_unmet() {
         let mut mock = DynamoDbClient::default();
         mock.expect_delete_item()
-            .with(eq("recipes"), eq(DynamoDbRecipe::get_key(Uuid::nil())))
-            .return_once(move |_, _| {
+            .with(
+                eq("recipes"),
+                eq(DynamoDbRecipe::get_key(HOUSEHOLD, Uuid::nil())),
+                eq("id"),
+            )
+            .return_once(move |_, _, _| {
                 Err(DeleteItemError::ConditionalCheckFailedException(
                     ConditionalCheckFailedException::builder().build(),
                 ))
@@ -262,7 +804,7 @@ mod test {
 
         let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
 
-        let result = repo.delete_by_id(Uuid::nil()).await;
+        let result = repo.delete_by_id(HOUSEHOLD, Uuid::nil()).await;
         assert!(result.is_err_and(|err| match err {
             StatusCode::NOT_FOUND => true,
             _ => false,
@@ -270,20 +812,386 @@ mod test {
     }
 
     #[tokio::test]
-    async fn test_scan() {
+    async fn test_get_all_pages() {
+        let first = Recipe {
+            household_id: HOUSEHOLD.to_owned(),
+            ..Recipe::default()
+        };
+        let second = Recipe {
+            id: Uuid::from_u128(1),
+            ..first.clone()
+        };
+        let first_item = to_item(&first).unwrap();
+        let second_item = to_item(&second).unwrap();
+        let last_key = DynamoDbRecipe::get_key(HOUSEHOLD, Uuid::nil());
         let mut mock = DynamoDbClient::default();
+        mock.expect_query()
+            .with(
+                eq("recipes"),
+                function(|query: &QueryExpression| query.exclusive_start_key.is_none()),
+            )
+            .return_once(move |_, _| {
+                Ok(QueryOutput::builder()
+                    .items(first_item)
+                    .set_last_evaluated_key(Some(last_key))
+                    .build())
+            });
+        mock.expect_query()
+            .with(
+                eq("recipes"),
+                function(|query: &QueryExpression| {
+                    query.exclusive_start_key.is_some()
+                        && query.values.get(":household_id")
+                            == Some(&AttributeValue::S(HOUSEHOLD.to_owned()))
+                }),
+            )
+            .return_once(move |_, _| Ok(QueryOutput::builder().items(second_item).build()));
+
+        let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
+
+        let result = repo.get_all(HOUSEHOLD).await;
+        assert!(result.is_ok_and(|collection| collection == vec![first, second]));
+    }
+
+    fn cancelled(codes: &[&str]) -> TransactWriteItemsError {
+        let mut err = TransactionCanceledException::builder();
+        for code in codes {
+            err = err.cancellation_reasons(CancellationReason::builder().code(*code).build());
+        }
+        TransactWriteItemsError::TransactionCanceledException(err.build())
+    }
+
+    #[tokio::test]
+    async fn test_save_first_revision() {
+        let recipe = Recipe::default();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_query()
+            .with(
+                eq("recipes-revisions"),
+                function(|query: &QueryExpression| !query.scan_forward && query.limit == Some(1)),
+            )
+            .return_once(|_, _| Ok(QueryOutput::builder().build()));
+        mock.expect_transact_write_items()
+            .with(function(|items: &Vec<TransactWriteItem>| {
+                items.len() == 2
+                    && items[0]
+                        .put()
+                        .is_some_and(|put| put.condition_expression().is_none())
+            }))
+            .return_once(|_| Ok(TransactWriteItemsOutput::builder().build()));
+
+        let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
+
+        let result = repo.save_revision(&recipe, None).await;
+        assert!(result.is_ok_and(|revision| revision.revision == 1 && revision.recipe == recipe));
+    }
+
+    #[tokio::test]
+    async fn test_save_next_revision() {
+        let recipe = Recipe::default();
+        let latest = to_item(RecipeRevision {
+            recipe_id: Uuid::nil(),
+            revision: 3,
+            author: None,
+            created_at: 0,
+            recipe: recipe.clone(),
+        })
+        .unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_query()
+            .return_once(|_, _| Ok(QueryOutput::builder().items(latest).build()));
+        mock.expect_transact_write_items()
+            .return_once(|_| Ok(TransactWriteItemsOutput::builder().build()));
+
+        let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
+
+        let result = repo
+            .save_revision(&recipe, Some("grandma".to_owned()))
+            .await;
+        assert!(result.is_ok_and(|revision| {
+            revision.revision == 4 && revision.author.as_deref() == Some("grandma")
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_save_revision_conflict() {
+        let mut mock = DynamoDbClient::default();
+        mock.expect_query()
+            .times(REVISION_ATTEMPTS)
+            .returning(|_, _| Ok(QueryOutput::builder().build()));
+        mock.expect_transact_write_items()
+            .times(REVISION_ATTEMPTS)
+            .returning(|_| Err(cancelled(&["None", "ConditionalCheckFailed"])));
+
+        let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
+
+        let result = repo.save_revision(&Recipe::default(), None).await;
+        assert!(result.is_err_and(|err| matches!(err, StatusCode::CONFLICT)));
+    }
+
+    #[tokio::test]
+    async fn test_save_revision_retries_taken_number() {
+        let mut mock = DynamoDbClient::default();
+        mock.expect_query()
+            .times(2)
+            .returning(|_, _| Ok(QueryOutput::builder().build()));
+        mock.expect_transact_write_items()
+            .with(function(|items: &Vec<TransactWriteItem>| {
+                items[0]
+                    .put()
+                    .is_some_and(|put| put.table_name() == "recipes")
+                    && items[1]
+                        .put()
+                        .is_some_and(|put| put.table_name() == "recipes-revisions")
+            }))
+            .times(1)
+            .returning(|_| Err(cancelled(&["None", "ConditionalCheckFailed"])));
+        mock.expect_transact_write_items()
+            .times(1)
+            .returning(|_| Ok(TransactWriteItemsOutput::builder().build()));
+
+        let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
+
+        let result = repo.save_revision(&Recipe::default(), None).await;
+        assert!(result.is_ok_and(|revision| revision.revision == 1));
+    }
+
+    #[tokio::test]
+    async fn test_save_revision_in_history_of_household() {
         let recipe = Recipe {
-            id: Uuid::nil(),
-            name: "Name".to_owned(),
+            household_id: "other".to_owned(),
+            ..Recipe::default()
+        };
+        let key = AttributeValue::S(format!("other#{}", Uuid::nil()));
+        let mut mock = DynamoDbClient::default();
+        mock.expect_query()
+            .withf({
+                let key = key.clone();
+                move |_, query: &QueryExpression| query.values.get(":recipe_key") == Some(&key)
+            })
+            .return_once(|_, _| Ok(QueryOutput::builder().build()));
+        mock.expect_transact_write_items()
+            .with(function(move |items: &Vec<TransactWriteItem>| {
+                items[1]
+                    .put()
+                    .is_some_and(|put| put.item().get("recipe_key") == Some(&key))
+            }))
+            .return_once(|_| Ok(TransactWriteItemsOutput::builder().build()));
+
+        let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
+
+        let result = repo.save_revision(&recipe, None).await;
+        assert!(result.is_ok_and(|revision| revision.revision == 1));
+    }
+
+    #[tokio::test]
+    async fn test_find_revision_not_found() {
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .with(
+                eq("recipes-revisions"),
+                eq(DynamoDbRecipe::get_revision_key(
+                    "household",
+                    Uuid::nil(),
+                    2,
+                )),
+            )
+            .return_once(|_, _| Ok(GetItemOutput::builder().build()));
+
+        let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
+
+        let result = repo.find_revision("household", Uuid::nil(), 2).await;
+        assert!(result.is_err_and(|err| matches!(err, StatusCode::NOT_FOUND)));
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete() {
+        let recipe = Recipe {
+            deleted_at: Some(100),
+            expires_at: Some(100 + DEFAULT_TRASH_RETENTION.as_secs()),
+            ..Recipe::default()
         };
         let item = to_item(&recipe).unwrap();
-        mock.expect_scan()
-            .with(eq("recipes"))
-            .return_once(move |_| Ok(ScanOutput::builder().items(item).build()));
+        let mut mock = DynamoDbClient::default();
+        mock.expect_update_item()
+            .with(
+                eq("recipes"),
+                eq(DynamoDbRecipe::get_key(HOUSEHOLD, Uuid::nil())),
+                function(|update: &UpdateExpression| {
+                    update.expression.starts_with("SET deleted_at")
+                        && update.values.contains_key(":expires_at")
+                }),
+            )
+            .return_once(|_, _, _| {
+                Ok(UpdateItemOutput::builder()
+                    .set_attributes(Some(item))
+                    .build())
+            });
+
+        let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
+
+        let result = repo.soft_delete(HOUSEHOLD, Uuid::nil()).await;
+        assert!(result.is_ok_and(|recipe| recipe.is_deleted()));
+    }
+
+    #[tokio::test]
+    async fn test_restore_not_in_trash() {
+        let mut mock = DynamoDbClient::default();
+        mock.expect_update_item().return_once(|_, _, _| {
+            Err(UpdateItemError::ConditionalCheckFailedException(
+                ConditionalCheckFailedException::builder().build(),
+            ))
+        });
+
+        let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
+
+        let result = repo.restore(HOUSEHOLD, Uuid::nil()).await;
+        assert!(result.is_err_and(|err| matches!(err, StatusCode::NOT_FOUND)));
+    }
+
+    #[tokio::test]
+    async fn test_find_share_expired() {
+        let link = ShareLink {
+            token: "token".to_owned(),
+            expires_at: Some(1),
+            ..ShareLink::default()
+        };
+        let item = to_item(&link).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .with(
+                eq("recipes-shares"),
+                eq(DynamoDbRecipe::get_share_key("token")),
+            )
+            .return_once(|_, _| Ok(GetItemOutput::builder().set_item(Some(item)).build()));
+
+        let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
+
+        let result = repo.find_share("token").await;
+        assert_eq!(result, Err(StatusCode::NOT_FOUND));
+    }
+    fn batch_writes(count: u128) -> Vec<RecipeWrite> {
+        (0..count)
+            .map(|id| {
+                RecipeWrite::Create(Recipe {
+                    household_id: HOUSEHOLD.to_owned(),
+                    id: Uuid::from_u128(id),
+                    ..Recipe::default()
+                })
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_write_batch_reports_each_write() {
+        let writes = vec![
+            batch_writes(1).remove(0),
+            RecipeWrite::Replace(Recipe {
+                id: Uuid::from_u128(5),
+                ..Recipe::default()
+            }),
+            RecipeWrite::Trash {
+                household_id: HOUSEHOLD.to_owned(),
+                id: Uuid::from_u128(7),
+            },
+        ];
+        let mut mock = DynamoDbClient::default();
+        mock.expect_query()
+            .returning(|_, _| Ok(QueryOutput::builder().build()));
+        // The replaced recipe does not exist.
+        mock.expect_transact_write_items()
+            .times(3)
+            .returning(|items: Vec<TransactWriteItem>| {
+                let replace = items[0].put().is_some_and(|put| {
+                    put.condition_expression()
+                        .is_some_and(|condition| condition.starts_with("attribute_exists"))
+                });
+                if replace {
+                    Err(cancelled(&["ConditionalCheckFailed", "None"]))
+                } else {
+                    Ok(TransactWriteItemsOutput::builder().build())
+                }
+            });
+
+        let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
+
+        let result = repo.write_batch(&writes, false, None).await;
+        assert_eq!(result, Ok(vec![Ok(()), Err(StatusCode::NOT_FOUND), Ok(())]));
+    }
+
+    #[tokio::test]
+    async fn test_write_batch_atomic_reports_failed_condition() {
+        let writes = vec![
+            batch_writes(1).remove(0),
+            RecipeWrite::Trash {
+                household_id: HOUSEHOLD.to_owned(),
+                id: Uuid::from_u128(7),
+            },
+        ];
+        let mut mock = DynamoDbClient::default();
+        mock.expect_query()
+            .times(1)
+            .return_once(|_, _| Ok(QueryOutput::builder().build()));
+        mock.expect_transact_write_items()
+            .with(function(|items: &Vec<TransactWriteItem>| {
+                items[0].put().is_some()
+                    && items[1].update().is_some()
+                    && items[2]
+                        .put()
+                        .is_some_and(|put| put.table_name() == "recipes-revisions")
+            }))
+            .return_once(|_| Err(cancelled(&["None", "ConditionalCheckFailed", "None"])));
+
+        let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
+
+        let result = repo.write_batch(&writes, true, None).await;
+        assert_eq!(
+            result,
+            Ok(vec![
+                Err(StatusCode::FAILED_DEPENDENCY),
+                Err(StatusCode::NOT_FOUND)
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_by_ids_in_order_of_ids() {
+        let recipe = |id: u128| {
+            to_item(Recipe {
+                id: Uuid::from_u128(id),
+                ..Recipe::default()
+            })
+            .unwrap()
+        };
+        let (first, second) = (recipe(1), recipe(2));
+        let unprocessed = KeysAndAttributes::builder()
+            .keys(DynamoDbRecipe::get_key(HOUSEHOLD, Uuid::from_u128(1)))
+            .build()
+            .unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_batch_get_item()
+            .withf(|table, keys| table == "recipes" && keys.len() == 3)
+            .times(1)
+            .return_once(move |_, _| {
+                Ok(BatchGetItemOutput::builder()
+                    .responses("recipes", vec![second])
+                    .unprocessed_keys("recipes", unprocessed)
+                    .build())
+            });
+        mock.expect_batch_get_item()
+            .withf(|_, keys| *keys == [DynamoDbRecipe::get_key(HOUSEHOLD, Uuid::from_u128(1))])
+            .times(1)
+            .return_once(move |_, _| {
+                Ok(BatchGetItemOutput::builder()
+                    .responses("recipes", vec![first])
+                    .build())
+            });
 
         let repo = DynamoDbRecipe::mock(Arc::new(mock), "recipes");
 
-        let result = repo.get_all().await;
-        assert!(result.is_ok_and(|collection| collection.contains(&recipe) && collection.len() == 1))
+        let ids = [1, 2, 3, 1].map(Uuid::from_u128);
+        let result = repo.find_by_ids(HOUSEHOLD, &ids).await.unwrap();
+        let ids: Vec<Uuid> = result.iter().map(|recipe| recipe.id).collect();
+        assert_eq!(ids, vec![Uuid::from_u128(1), Uuid::from_u128(2)]);
     }
 }
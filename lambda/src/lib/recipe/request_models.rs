@@ -1,43 +1,153 @@
+use serde::de::IntoDeserializer;
 use serde::{Deserialize, Deserializer};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Debug, Deserialize, PartialEq)]
+use super::substitution::Diet;
+use super::{Ingredient, Step};
+use crate::cook_log::RecipeSort;
+
+#[derive(Debug, Deserialize, PartialEq, ToSchema)]
 pub struct PostRecipe {
     pub(super) name: String,
+    #[serde(default)]
+    pub(super) ingredients: Vec<Ingredient>,
+    #[serde(default)]
+    pub(super) steps: Vec<Step>,
+    #[serde(default)]
+    pub(super) total_minutes: Option<u32>,
 }
 
 impl Default for PostRecipe {
     fn default() -> Self {
         Self {
             name: "Name".to_owned(),
+            ingredients: Vec::new(),
+            steps: Vec::new(),
+            total_minutes: None,
         }
     }
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Default, Deserialize, PartialEq, ToSchema)]
 pub struct PatchRecipe {
-    #[serde(default, deserialize_with = "deserialize_option_string")]
+    #[serde(default)]
     pub(super) name: Option<String>,
+    #[serde(default)]
+    pub(super) ingredients: Option<Vec<Ingredient>>,
+    #[serde(default)]
+    pub(super) steps: Option<Vec<Step>>,
+    #[serde(default)]
+    pub(super) total_minutes: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, ToSchema)]
+pub struct PutRecipe {
+    pub(super) id: Uuid,
+    pub(super) name: String,
+    #[serde(default)]
+    pub(super) ingredients: Vec<Ingredient>,
+    #[serde(default)]
+    pub(super) steps: Vec<Step>,
+    #[serde(default)]
+    pub(super) total_minutes: Option<u32>,
+}
+
+/// Optional overrides for a recipe forked from another.
+#[derive(Debug, Default, Deserialize, PartialEq, ToSchema)]
+pub struct ForkRecipe {
+    #[serde(default)]
+    pub(super) name: Option<String>,
+}
+
+/// Query parameters accepted when listing recipes.
+#[derive(Debug, Default, Deserialize, IntoParams, PartialEq)]
+#[into_params(parameter_in = Query)]
+pub struct ListRecipes {
+    /// Lists only the recipes with these ids, separated by commas.
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    #[param(value_type = Option<String>)]
+    pub(crate) ids: Option<Vec<Uuid>>,
+    /// Lists only the recipes the household owns the cookware for, once it
+    /// has said what it owns.
+    #[serde(default)]
+    pub(crate) cookable: bool,
+    /// Orders the recipes by the cook log: `rating`, `least_recent` or
+    /// `suggested`, which favors well-liked dishes not cooked for a while.
+    /// Only sorted recipes come with their stats.
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    pub(crate) sort: Option<RecipeSort>,
 }
 
-fn deserialize_option_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+fn deserialize_comma_separated<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
 where
     D: Deserializer<'de>,
+    T: Deserialize<'de>,
 {
-    let option: Option<String> = Option::deserialize(deserializer)?;
-    Ok(option.filter(|s| !s.is_empty()))
+    let Some(value) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| T::deserialize(item.to_owned().into_deserializer()))
+        .collect::<Result<_, _>>()
+        .map(Some)
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
-pub struct PutRecipe {
-    pub(super) id: Uuid,
-    pub(super) name: String,
+/// Query parameters accepted when suggesting substitutions.
+#[derive(Debug, Default, Deserialize, IntoParams, PartialEq)]
+#[into_params(parameter_in = Query)]
+pub struct ListSubstitutions {
+    /// What the household has at hand, separated by commas. Ingredients that
+    /// are not in it are treated as missing. Without it, nothing is missing.
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    #[param(value_type = Option<String>)]
+    pub(crate) pantry: Option<Vec<String>>,
+    /// The diets to cook for, separated by commas.
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    #[param(value_type = Option<String>)]
+    pub(crate) diet: Option<Vec<Diet>>,
+}
+
+/// Query parameters accepted when reading a single recipe.
+#[derive(Debug, Default, Deserialize, IntoParams, PartialEq)]
+#[into_params(parameter_in = Query)]
+pub struct ReadRecipe {
+    /// Expands ingredients that refer to other recipes, recursively.
+    #[serde(default)]
+    pub(crate) expand: bool,
+}
+
+/// Query parameters accepted when deleting a recipe.
+#[derive(Debug, Default, Deserialize, IntoParams, PartialEq)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteRecipe {
+    /// Skips the trash and removes the recipe immediately.
+    #[serde(default)]
+    pub(crate) permanent: bool,
+}
+
+/// Options for a new share link.
+#[derive(Debug, Default, Deserialize, PartialEq, ToSchema)]
+pub struct PostShare {
+    /// Makes the link stop working after this many days.
+    #[serde(default)]
+    pub(crate) expires_in_days: Option<u32>,
 }
 
 #[cfg(test)]
 mod test {
+    use axum::extract::Query;
+    use axum::http::Uri;
+    use uuid::Uuid;
+
     use super::PostRecipe;
-    use crate::recipe::request_models::PatchRecipe;
+    use crate::recipe::request_models::{ForkRecipe, ListRecipes, ListSubstitutions, PatchRecipe};
+    use crate::recipe::substitution::Diet;
 
     const JSON: &str = r#"{
         "name": "Basic Recipe"
@@ -50,6 +160,7 @@ mod test {
         let actual: PostRecipe = serde_json::from_str(JSON).unwrap();
         let expected = PostRecipe {
             name: NAME.to_owned(),
+            ..PostRecipe::default()
         };
 
         assert_eq!(expected, actual);
@@ -65,6 +176,7 @@ mod test {
         let actual: PatchRecipe = serde_json::from_str(JSON).unwrap();
         let expected = PatchRecipe {
             name: Some(NAME.to_owned()),
+            ..PatchRecipe::default()
         };
 
         assert_eq!(expected, actual);
@@ -73,16 +185,74 @@ mod test {
     #[test]
     fn deserialize_patch_recipe_request_none() {
         let actual: PatchRecipe = serde_json::from_str("{}").unwrap();
-        let expected = PatchRecipe { name: None };
+        let expected = PatchRecipe::default();
 
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn deserialize_patch_recipe_request_none_empty() {
+    fn deserialize_patch_recipe_request_empty_is_kept_for_validation() {
         let actual: PatchRecipe = serde_json::from_str("{\"name\": \"\"}").unwrap();
-        let expected = PatchRecipe { name: None };
+        let expected = PatchRecipe {
+            name: Some(String::new()),
+            ..PatchRecipe::default()
+        };
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn deserialize_post_recipe_request_with_ingredients() {
+        let json = r#"{
+            "name": "Basic Recipe",
+            "ingredients": [{ "name": "Flour", "quantity": 500, "unit": "g" }],
+            "steps": [{ "instruction": "Knead" }]
+        }"#;
+
+        let actual: PostRecipe = serde_json::from_str(json).unwrap();
+
+        assert_eq!(actual.ingredients[0].quantity, Some(500.0));
+        assert_eq!(actual.ingredients[0].unit.as_deref(), Some("g"));
+        assert_eq!(actual.steps[0].instruction, "Knead");
+    }
+
+    #[test]
+    fn deserialize_fork_recipe_request_none() {
+        let actual: ForkRecipe = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(ForkRecipe::default(), actual);
+    }
+
+    #[test]
+    fn deserialize_list_recipes_ids() {
+        let ids = |uri: &'static str| {
+            Query::<ListRecipes>::try_from_uri(&Uri::from_static(uri))
+                .map(|Query(params)| params.ids)
+        };
+
+        assert_eq!(
+            ids("/?ids=00000000-0000-0000-0000-000000000001,%2000000000-0000-0000-0000-000000000002,")
+                .unwrap(),
+            Some(vec![Uuid::from_u128(1), Uuid::from_u128(2)])
+        );
+        assert!(ids("/?ids=pizza").is_err());
+        assert_eq!(ids("/").unwrap(), None);
+    }
+
+    #[test]
+    fn deserialize_list_substitutions() {
+        let Query(params) = Query::<ListSubstitutions>::try_from_uri(&Uri::from_static(
+            "/?pantry=flour,whole%20milk&diet=vegan,nut_free",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            params.pantry,
+            Some(vec!["flour".to_owned(), "whole milk".to_owned()])
+        );
+        assert_eq!(params.diet, Some(vec![Diet::Vegan, Diet::NutFree]));
+        assert!(
+            Query::<ListSubstitutions>::try_from_uri(&Uri::from_static("/?diet=keto")).is_err()
+        );
+    }
 }
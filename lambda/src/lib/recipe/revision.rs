@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Recipe;
+
+/// An immutable snapshot of a recipe, taken every time the recipe is written.
+///
+/// Revisions are numbered from one per recipe. Only the full snapshot is
+/// stored, the changes a revision made are derived by comparing it with the
+/// revision before it.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RecipeRevision {
+    pub(crate) recipe_id: Uuid,
+    pub(crate) revision: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) author: Option<String>,
+    pub(crate) created_at: u64,
+    pub(crate) recipe: Recipe,
+}
+
+/// A single top-level field of a recipe that differs between two revisions.
+///
+/// A field that did not exist on one side is reported as `null`.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct FieldChange {
+    pub(crate) field: String,
+    pub(crate) old: Value,
+    pub(crate) new: Value,
+}
+
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct RevisionResponse {
+    pub(crate) revision: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) author: Option<String>,
+    pub(crate) created_at: u64,
+    pub(crate) changes: Vec<FieldChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) recipe: Option<Recipe>,
+}
+
+/// Lists the fields that changed going from `previous` to `current`, sorted by
+/// field name. Every field of `current` is reported when there is no previous
+/// recipe. The owning household is not part of the recipe's content and is
+/// never reported.
+#[must_use]
+pub fn diff(previous: Option<&Recipe>, current: &Recipe) -> Vec<FieldChange> {
+    let old = previous.map(as_object).unwrap_or_default();
+    let new = as_object(current);
+
+    let mut fields: Vec<&String> = old.keys().chain(new.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old_value = old.get(field).cloned().unwrap_or(Value::Null);
+            let new_value = new.get(field).cloned().unwrap_or(Value::Null);
+            (old_value != new_value).then(|| FieldChange {
+                field: field.clone(),
+                old: old_value,
+                new: new_value,
+            })
+        })
+        .collect()
+}
+
+fn as_object(recipe: &Recipe) -> Map<String, Value> {
+    match serde_json::to_value(recipe) {
+        Ok(Value::Object(mut map)) => {
+            map.remove("household_id");
+            map
+        },
+        _ => Map::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::{diff, FieldChange};
+    use crate::recipe::Recipe;
+
+    #[test]
+    fn diff_without_previous() {
+        let recipe = Recipe::default();
+
+        let changes = diff(None, &recipe);
+
+        let fields: Vec<&str> = changes.iter().map(|change| change.field.as_str()).collect();
+        assert_eq!(fields, vec!["id", "ingredients", "name", "steps"]);
+        assert!(changes.iter().all(|change| change.old.is_null()));
+        assert_eq!(
+            changes[0],
+            FieldChange {
+                field: "id".to_owned(),
+                old: json!(null),
+                new: json!(Uuid::nil()),
+            }
+        );
+    }
+
+    #[test]
+    fn diff_only_changed_fields() {
+        let previous = Recipe::default();
+        let current = Recipe {
+            name: "Grandma's Recipe".to_owned(),
+            ..Recipe::default()
+        };
+
+        let changes = diff(Some(&previous), &current);
+
+        assert_eq!(
+            changes,
+            vec![FieldChange {
+                field: "name".to_owned(),
+                old: json!("Basic Recipe"),
+                new: json!("Grandma's Recipe"),
+            }]
+        );
+    }
+
+    #[test]
+    fn diff_identical() {
+        let recipe = Recipe::default();
+
+        assert!(diff(Some(&recipe), &recipe).is_empty());
+    }
+}
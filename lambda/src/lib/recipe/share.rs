@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{Ingredient, Recipe, Step};
+
+/// A link that lets anyone holding its token read a single recipe without an
+/// account, until it is revoked or expires.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct ShareLink {
+    pub(crate) token: String,
+    pub(crate) household_id: String,
+    pub(crate) recipe_id: Uuid,
+    pub(crate) created_by: String,
+    pub(crate) created_at: u64,
+    /// When the link stops working. Used as the table's time to live.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) expires_at: Option<u64>,
+}
+
+impl ShareLink {
+    #[must_use]
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// Mints a new share token. Version 4 uuids are drawn from a cryptographically
+/// secure generator, so the token cannot be guessed from other tokens.
+#[must_use]
+pub fn new_token() -> String { Uuid::new_v4().simple().to_string() }
+
+/// The parts of a recipe that are shown to whoever holds a share link. Nothing
+/// that identifies the household is included.
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct SharedRecipe {
+    pub(crate) name: String,
+    pub(crate) ingredients: Vec<Ingredient>,
+    pub(crate) steps: Vec<Step>,
+}
+
+impl From<Recipe> for SharedRecipe {
+    fn from(recipe: Recipe) -> Self {
+        Self {
+            name: recipe.name,
+            ingredients: recipe.ingredients,
+            steps: recipe.steps,
+        }
+    }
+}
+
+/// Renders a shared recipe as a standalone HTML page.
+#[must_use]
+pub fn render_html(recipe: &SharedRecipe) -> String {
+    let name = escape_html(&recipe.name);
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{name}</title>\n</head>\n<body>\n<h1>{name}</h1>\n"
+    );
+
+    if !recipe.ingredients.is_empty() {
+        html.push_str("<h2>Ingredients</h2>\n<ul>\n");
+        for ingredient in &recipe.ingredients {
+            let amount = [
+                ingredient.quantity.map(|quantity| quantity.to_string()),
+                ingredient.unit.clone(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+            let item = if amount.is_empty() {
+                escape_html(&ingredient.name)
+            } else {
+                format!("{} {}", escape_html(&amount), escape_html(&ingredient.name))
+            };
+            html.push_str(&format!("<li>{item}</li>\n"));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    if !recipe.steps.is_empty() {
+        html.push_str("<h2>Steps</h2>\n<ol>\n");
+        for step in &recipe.steps {
+            html.push_str(&format!("<li>{}</li>\n", escape_html(&step.instruction)));
+        }
+        html.push_str("</ol>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::{new_token, render_html, ShareLink, SharedRecipe};
+    use crate::recipe::{Ingredient, Recipe, Step};
+
+    #[test]
+    fn tokens_are_unique() {
+        assert_ne!(new_token(), new_token());
+        assert_eq!(new_token().len(), 32);
+    }
+
+    #[test]
+    fn expiry() {
+        let link = ShareLink {
+            expires_at: Some(10),
+            ..ShareLink::default()
+        };
+
+        assert!(!link.is_expired(9));
+        assert!(link.is_expired(10));
+        assert!(!ShareLink::default().is_expired(u64::MAX));
+    }
+
+    #[test]
+    fn render_escapes_content() {
+        let recipe = Recipe {
+            name: "Mac & <Cheese>".to_owned(),
+            ingredients: vec![Ingredient {
+                name: "Macaroni".to_owned(),
+                quantity: Some(200.0),
+                unit: Some("g".to_owned()),
+                recipe_id: None,
+            }],
+            steps: vec![Step {
+                instruction: "Boil \"al dente\"".to_owned(),
+                ..Step::default()
+            }],
+            ..Recipe::default()
+        };
+
+        let html = render_html(&SharedRecipe::from(recipe));
+
+        assert!(html.contains("<h1>Mac &amp; &lt;Cheese&gt;</h1>"));
+        assert!(html.contains("<li>200 g Macaroni</li>"));
+        assert!(html.contains("<li>Boil &quot;al dente&quot;</li>"));
+    }
+}
@@ -0,0 +1,550 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::units::{self, round};
+use super::{Ingredient, Recipe};
+
+/// Diets an ingredient can conflict with.
+#[derive(
+    Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Diet {
+    Vegan,
+    Vegetarian,
+    DairyFree,
+    EggFree,
+    GlutenFree,
+    NutFree,
+}
+
+use Diet::{DairyFree, EggFree, GlutenFree, NutFree, Vegan, Vegetarian};
+
+const DAIRY: &[Diet] = &[Vegan, DairyFree];
+const MEAT: &[Diet] = &[Vegan, Vegetarian];
+
+/// Foods and the diets they do not suit. The longest name found in an
+/// ingredient wins, so that almond milk is not taken for milk.
+const FOODS: &[(&str, &[Diet])] = &[
+    ("milk", DAIRY),
+    ("buttermilk", DAIRY),
+    ("butter", DAIRY),
+    ("cream", DAIRY),
+    ("sour cream", DAIRY),
+    ("cheese", DAIRY),
+    ("yogurt", DAIRY),
+    ("greek yogurt", DAIRY),
+    ("egg", &[Vegan, EggFree]),
+    ("honey", &[Vegan]),
+    ("gelatin", MEAT),
+    ("chicken", MEAT),
+    ("chicken stock", MEAT),
+    ("beef", MEAT),
+    ("pork", MEAT),
+    ("bacon", MEAT),
+    ("fish", MEAT),
+    ("anchovy", MEAT),
+    ("flour", &[GlutenFree]),
+    ("breadcrumb", &[GlutenFree]),
+    ("pasta", &[GlutenFree]),
+    ("soy sauce", &[GlutenFree]),
+    ("almond", &[NutFree]),
+    ("walnut", &[NutFree]),
+    ("pecan", &[NutFree]),
+    ("hazelnut", &[NutFree]),
+    ("peanut", &[NutFree]),
+    ("peanut butter", &[NutFree]),
+    ("almond milk", &[NutFree]),
+    ("almond flour", &[NutFree]),
+    ("oat milk", &[]),
+    ("soy milk", &[]),
+    ("coconut milk", &[]),
+    ("coconut cream", &[]),
+    ("vegan butter", &[]),
+    ("cream of tartar", &[]),
+    ("rice flour", &[]),
+    ("gluten free flour blend", &[]),
+    ("sunflower seed flour", &[]),
+    ("sunflower seed butter", &[]),
+];
+
+/// An amount in a swap, in a unit or as a count.
+struct Amount {
+    name: &'static str,
+    quantity: f64,
+    unit: Option<&'static str>,
+}
+
+const fn amount(name: &'static str, quantity: f64, unit: Option<&'static str>) -> Amount {
+    Amount {
+        name,
+        quantity,
+        unit,
+    }
+}
+
+/// An ingredient and what can stand in for that much of it.
+struct Swap {
+    original: Amount,
+    replacement: &'static [Amount],
+    note: Option<&'static str>,
+}
+
+const CUP: Option<&str> = Some("cup");
+const TBSP: Option<&str> = Some("tbsp");
+const TSP: Option<&str> = Some("tsp");
+
+/// The known swaps, the preferred ones first.
+const SWAPS: &[Swap] = &[
+    Swap {
+        original: amount("buttermilk", 1.0, CUP),
+        replacement: &[amount("milk", 1.0, CUP), amount("lemon juice", 1.0, TBSP)],
+        note: Some("Stir and let it stand for five minutes."),
+    },
+    Swap {
+        original: amount("buttermilk", 1.0, CUP),
+        replacement: &[
+            amount("oat milk", 1.0, CUP),
+            amount("lemon juice", 1.0, TBSP),
+        ],
+        note: Some("Stir and let it stand for five minutes."),
+    },
+    Swap {
+        original: amount("milk", 1.0, CUP),
+        replacement: &[amount("oat milk", 1.0, CUP)],
+        note: None,
+    },
+    Swap {
+        original: amount("milk", 1.0, CUP),
+        replacement: &[amount("soy milk", 1.0, CUP)],
+        note: None,
+    },
+    Swap {
+        original: amount("butter", 1.0, CUP),
+        replacement: &[amount("vegan butter", 1.0, CUP)],
+        note: None,
+    },
+    Swap {
+        original: amount("butter", 1.0, CUP),
+        replacement: &[amount("coconut oil", 1.0, CUP)],
+        note: Some("Use it solid for pastry and melted for batters."),
+    },
+    Swap {
+        original: amount("cream", 1.0, CUP),
+        replacement: &[amount("coconut cream", 1.0, CUP)],
+        note: None,
+    },
+    Swap {
+        original: amount("cream", 1.0, CUP),
+        replacement: &[amount("milk", 0.75, CUP), amount("butter", 0.25, CUP)],
+        note: Some("Melt the butter into the milk. It does not whip."),
+    },
+    Swap {
+        original: amount("sour cream", 1.0, CUP),
+        replacement: &[amount("greek yogurt", 1.0, CUP)],
+        note: None,
+    },
+    Swap {
+        original: amount("egg", 1.0, None),
+        replacement: &[
+            amount("ground flaxseed", 1.0, TBSP),
+            amount("water", 3.0, TBSP),
+        ],
+        note: Some("Let it thicken for five minutes. Works best in baking."),
+    },
+    Swap {
+        original: amount("egg", 1.0, None),
+        replacement: &[amount("unsweetened applesauce", 0.25, CUP)],
+        note: Some("Works best in cakes and muffins."),
+    },
+    Swap {
+        original: amount("honey", 1.0, CUP),
+        replacement: &[amount("maple syrup", 1.0, CUP)],
+        note: None,
+    },
+    Swap {
+        original: amount("flour", 1.0, CUP),
+        replacement: &[amount("gluten free flour blend", 1.0, CUP)],
+        note: None,
+    },
+    Swap {
+        original: amount("cake flour", 1.0, CUP),
+        replacement: &[amount("flour", 0.875, CUP), amount("cornstarch", 2.0, TBSP)],
+        note: Some("Sift them together."),
+    },
+    Swap {
+        original: amount("self raising flour", 1.0, CUP),
+        replacement: &[
+            amount("flour", 1.0, CUP),
+            amount("baking powder", 1.5, TSP),
+            amount("salt", 0.25, TSP),
+        ],
+        note: None,
+    },
+    Swap {
+        original: amount("almond flour", 1.0, CUP),
+        replacement: &[amount("sunflower seed flour", 1.0, CUP)],
+        note: Some("Baking soda can turn it green, add a little lemon juice."),
+    },
+    Swap {
+        original: amount("baking powder", 1.0, TSP),
+        replacement: &[
+            amount("baking soda", 0.25, TSP),
+            amount("cream of tartar", 0.5, TSP),
+        ],
+        note: None,
+    },
+    Swap {
+        original: amount("brown sugar", 1.0, CUP),
+        replacement: &[amount("sugar", 1.0, CUP), amount("molasses", 1.0, TBSP)],
+        note: None,
+    },
+    Swap {
+        original: amount("lemon juice", 1.0, TBSP),
+        replacement: &[amount("lime juice", 1.0, TBSP)],
+        note: None,
+    },
+    Swap {
+        original: amount("lemon juice", 1.0, TBSP),
+        replacement: &[amount("white wine vinegar", 0.5, TBSP)],
+        note: None,
+    },
+    Swap {
+        original: amount("soy sauce", 1.0, TBSP),
+        replacement: &[amount("tamari", 1.0, TBSP)],
+        note: None,
+    },
+    Swap {
+        original: amount("chicken stock", 1.0, CUP),
+        replacement: &[amount("vegetable stock", 1.0, CUP)],
+        note: None,
+    },
+    Swap {
+        original: amount("peanut butter", 1.0, CUP),
+        replacement: &[amount("sunflower seed butter", 1.0, CUP)],
+        note: None,
+    },
+];
+
+/// Ingredients to swap in a recipe and the recipe with the swaps made.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct SubstitutionPreview {
+    pub(crate) suggestions: Vec<Suggestion>,
+    /// The recipe with every ingredient that has a swap replaced by the first
+    /// one. Steps are left as they are.
+    pub(crate) recipe: Recipe,
+}
+
+/// An ingredient that is missing or conflicts with a diet.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct Suggestion {
+    /// The index of the ingredient in the recipe.
+    pub(crate) index: usize,
+    pub(crate) ingredient: String,
+    /// Whether the ingredient is missing from the pantry.
+    pub(crate) missing: bool,
+    /// The diets the ingredient conflicts with.
+    pub(crate) conflicts: Vec<Diet>,
+    /// What can stand in for the ingredient, the best first. Empty if no swap
+    /// is known.
+    pub(crate) options: Vec<SwapOption>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct SwapOption {
+    /// The ingredients to use instead, scaled to the amount in the recipe.
+    /// Amounts that cannot be converted are left out.
+    pub(crate) ingredients: Vec<Ingredient>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) note: Option<String>,
+    /// Whether every ingredient of the swap is in the pantry.
+    pub(crate) in_pantry: bool,
+}
+
+/// Suggests swaps for the ingredients of a recipe that are missing from the
+/// pantry or conflict with any of the diets. Without a pantry, no ingredient
+/// is missing. Swaps that conflict with the diets themselves are left out,
+/// and swaps made entirely of what is in the pantry come first.
+#[must_use]
+pub fn suggest(recipe: &Recipe, pantry: Option<&[String]>, diets: &[Diet]) -> SubstitutionPreview {
+    let pantry: Option<Vec<Vec<String>>> =
+        pantry.map(|pantry| pantry.iter().map(|item| words(item)).collect());
+    let in_pantry = |name: &str| {
+        pantry.as_ref().is_none_or(|pantry| {
+            let name = words(name);
+            pantry.iter().any(|item| contains(&name, item))
+        })
+    };
+    let suits = |name: &str| conflicts(name, diets).is_empty();
+
+    let mut suggestions = Vec::new();
+    let mut ingredients = Vec::new();
+    for (index, ingredient) in recipe.ingredients.iter().enumerate() {
+        let missing = !in_pantry(&ingredient.name);
+        let conflicts = conflicts(&ingredient.name, diets);
+        if ingredient.recipe_id.is_some() || (!missing && conflicts.is_empty()) {
+            ingredients.push(ingredient.clone());
+            continue;
+        }
+
+        let mut options: Vec<SwapOption> = swaps_for(&ingredient.name)
+            .filter(|swap| swap.replacement.iter().all(|part| suits(part.name)))
+            .map(|swap| SwapOption {
+                ingredients: scale(swap, ingredient),
+                note: swap.note.map(str::to_owned),
+                in_pantry: swap.replacement.iter().all(|part| in_pantry(part.name)),
+            })
+            .collect();
+        options.sort_by_key(|option| !option.in_pantry);
+
+        match options.first() {
+            Some(option) => ingredients.extend(option.ingredients.iter().cloned()),
+            None => ingredients.push(ingredient.clone()),
+        }
+        suggestions.push(Suggestion {
+            index,
+            ingredient: ingredient.name.clone(),
+            missing,
+            conflicts,
+            options,
+        });
+    }
+
+    SubstitutionPreview {
+        suggestions,
+        recipe: Recipe {
+            ingredients,
+            ..recipe.clone()
+        },
+    }
+}
+
+/// The diets an ingredient conflicts with, by the longest food found in its
+/// name.
+fn conflicts(name: &str, diets: &[Diet]) -> Vec<Diet> {
+    let name = words(name);
+    let food = FOODS
+        .iter()
+        .map(|(food, conflicts)| (words(food), *conflicts))
+        .filter(|(food, _)| contains(&name, food))
+        .max_by_key(|(food, _)| food.len());
+
+    food.map(|(_, conflicts)| {
+        diets
+            .iter()
+            .copied()
+            .filter(|diet| conflicts.contains(diet))
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// The swaps for the longest known ingredient found in the name, so that cake
+/// flour is not swapped like flour.
+fn swaps_for(name: &str) -> impl Iterator<Item = &'static Swap> {
+    let name = words(name);
+    let longest = SWAPS
+        .iter()
+        .map(|swap| words(swap.original.name))
+        .filter(|original| contains(&name, original))
+        .max_by_key(Vec::len);
+
+    SWAPS
+        .iter()
+        .filter(move |swap| longest.as_ref() == Some(&words(swap.original.name)))
+}
+
+/// Scales the replacement to the amount of the ingredient. Replacements
+/// measured like the original take the swap's ratio in the unit of the
+/// recipe, even when it does not convert to the unit of the swap, like butter
+/// weighed in grams. Others are scaled when the units convert.
+fn scale(swap: &Swap, ingredient: &Ingredient) -> Vec<Ingredient> {
+    let factor = ingredient.quantity.and_then(|quantity| {
+        match (ingredient.unit.as_deref(), swap.original.unit) {
+            (None, None) => Some(quantity / swap.original.quantity),
+            (Some(unit), Some(original)) => units::convert(quantity, unit, original)
+                .map(|quantity| quantity / swap.original.quantity),
+            _ => None,
+        }
+    });
+
+    swap.replacement
+        .iter()
+        .map(|part| {
+            let (quantity, unit) = match (ingredient.quantity, factor) {
+                (Some(quantity), _) if part.unit == swap.original.unit => (
+                    Some(round(quantity * part.quantity / swap.original.quantity)),
+                    ingredient.unit.clone(),
+                ),
+                (_, Some(factor)) => (
+                    Some(round(part.quantity * factor)),
+                    part.unit.map(str::to_owned),
+                ),
+                _ => (None, None),
+            };
+            Ingredient {
+                name: part.name.to_owned(),
+                quantity,
+                unit,
+                recipe_id: None,
+            }
+        })
+        .collect()
+}
+
+/// Splits a name into lowercase singular words, so that names can be matched
+/// regardless of case, punctuation and plurals.
+fn words(name: &str) -> Vec<String> {
+    name.split(|character: char| !character.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let word = word.to_lowercase();
+            match word.strip_suffix('s') {
+                Some(stem) if stem.len() > 2 && !stem.ends_with('s') => stem.to_owned(),
+                _ => word,
+            }
+        })
+        .collect()
+}
+
+/// Whether the words of a name contain the words of another in order.
+fn contains(name: &[String], other: &[String]) -> bool {
+    !other.is_empty() && name.windows(other.len()).any(|window| window == other)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{conflicts, suggest, Diet};
+    use crate::recipe::{Ingredient, Recipe};
+
+    fn ingredient(name: &str, quantity: Option<f64>, unit: Option<&str>) -> Ingredient {
+        Ingredient {
+            name: name.to_owned(),
+            quantity,
+            unit: unit.map(str::to_owned),
+            recipe_id: None,
+        }
+    }
+
+    fn pancakes() -> Recipe {
+        Recipe {
+            name: "Pancakes".to_owned(),
+            ingredients: vec![
+                ingredient("All-purpose flour", Some(250.0), Some("g")),
+                ingredient("Buttermilk", Some(500.0), Some("ml")),
+                ingredient("Eggs", Some(2.0), None),
+            ],
+            ..Recipe::default()
+        }
+    }
+
+    #[test]
+    fn longest_food_wins() {
+        assert_eq!(conflicts("Whole milk", &[Diet::Vegan]), vec![Diet::Vegan]);
+        assert!(conflicts("Almond milk", &[Diet::Vegan]).is_empty());
+        assert_eq!(
+            conflicts("Almond milk", &[Diet::NutFree]),
+            vec![Diet::NutFree]
+        );
+        assert!(conflicts("Buttermilk", &[Diet::GlutenFree]).is_empty());
+    }
+
+    #[test]
+    fn swap_missing_ingredients_with_what_is_in_the_pantry() {
+        let pantry = ["flour", "milk", "lemon juice", "eggs"].map(str::to_owned);
+
+        let preview = suggest(&pancakes(), Some(&pantry), &[]);
+
+        assert_eq!(preview.suggestions.len(), 1);
+        let suggestion = &preview.suggestions[0];
+        assert_eq!((suggestion.index, suggestion.missing), (1, true));
+        assert!(suggestion.options[0].in_pantry);
+        assert_eq!(
+            suggestion.options[0].ingredients,
+            vec![
+                ingredient("milk", Some(500.0), Some("ml")),
+                ingredient("lemon juice", Some(2.11), Some("tbsp")),
+            ]
+        );
+        assert_eq!(preview.recipe.ingredients.len(), 4);
+        assert_eq!(preview.recipe.ingredients[1].name, "milk");
+    }
+
+    #[test]
+    fn swap_for_a_diet() {
+        let preview = suggest(&pancakes(), None, &[Diet::Vegan]);
+
+        let swaps: Vec<(&str, Vec<&str>)> = preview
+            .suggestions
+            .iter()
+            .map(|suggestion| {
+                (
+                    suggestion.ingredient.as_str(),
+                    suggestion.options[0]
+                        .ingredients
+                        .iter()
+                        .map(|ingredient| ingredient.name.as_str())
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            swaps,
+            vec![
+                ("Buttermilk", vec!["oat milk", "lemon juice"]),
+                ("Eggs", vec!["ground flaxseed", "water"]),
+            ]
+        );
+        assert_eq!(
+            preview.suggestions[1].options[0].ingredients[0].quantity,
+            Some(2.0)
+        );
+    }
+
+    #[test]
+    fn swap_in_the_unit_of_the_recipe() {
+        let recipe = Recipe {
+            ingredients: vec![
+                ingredient("Butter", Some(113.0), Some("g")),
+                ingredient("Honey", Some(1.0), Some("jar")),
+            ],
+            ..Recipe::default()
+        };
+
+        let preview = suggest(&recipe, None, &[Diet::Vegan]);
+
+        assert_eq!(
+            preview.suggestions[0].options[0].ingredients,
+            vec![ingredient("vegan butter", Some(113.0), Some("g"))]
+        );
+        assert_eq!(
+            preview.suggestions[1].options[0].ingredients,
+            vec![ingredient("maple syrup", Some(1.0), Some("jar"))]
+        );
+    }
+
+    #[test]
+    fn unknown_amounts_are_left_out() {
+        let recipe = Recipe {
+            ingredients: vec![
+                ingredient("Honey", None, None),
+                ingredient("Buttermilk", Some(200.0), Some("g")),
+            ],
+            ..Recipe::default()
+        };
+
+        let preview = suggest(&recipe, None, &[Diet::Vegan]);
+
+        assert_eq!(
+            preview.suggestions[0].options[0].ingredients,
+            vec![ingredient("maple syrup", None, None)]
+        );
+        assert_eq!(
+            preview.suggestions[1].options[0].ingredients,
+            vec![
+                ingredient("oat milk", Some(200.0), Some("g")),
+                ingredient("lemon juice", None, None),
+            ]
+        );
+    }
+}
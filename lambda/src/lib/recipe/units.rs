@@ -0,0 +1,93 @@
+/// What a unit measures. Units can only be converted into units of the same
+/// dimension.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Dimension {
+    Volume,
+    Mass,
+}
+
+/// A unit of measure, with its size in millilitres or grams.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Unit {
+    pub dimension: Dimension,
+    pub size: f64,
+}
+
+/// The units recipes are usually written in, by the names and abbreviations
+/// they go by. US customary measures are used for cups and spoons.
+const UNITS: &[(&[&str], Dimension, f64)] = &[
+    (&["ml", "millilitre", "milliliter"], Dimension::Volume, 1.0),
+    (&["cl", "centilitre", "centiliter"], Dimension::Volume, 10.0),
+    (&["dl", "decilitre", "deciliter"], Dimension::Volume, 100.0),
+    (&["l", "litre", "liter"], Dimension::Volume, 1000.0),
+    (&["tsp", "teaspoon"], Dimension::Volume, 4.928_92),
+    (&["tbsp", "tablespoon"], Dimension::Volume, 14.786_8),
+    (&["fl oz", "fluid ounce"], Dimension::Volume, 29.573_5),
+    (&["cup"], Dimension::Volume, 236.588),
+    (&["pint"], Dimension::Volume, 473.176),
+    (&["mg", "milligram"], Dimension::Mass, 0.001),
+    (&["g", "gram"], Dimension::Mass, 1.0),
+    (&["kg", "kilogram"], Dimension::Mass, 1000.0),
+    (&["oz", "ounce"], Dimension::Mass, 28.349_5),
+    (&["lb", "pound"], Dimension::Mass, 453.592),
+];
+
+/// Looks up a unit by name, ignoring case, surrounding whitespace, a trailing
+/// period and the plural.
+#[must_use]
+pub fn parse(name: &str) -> Option<Unit> {
+    let name = name.trim().trim_end_matches('.').to_lowercase();
+    let singular = name.strip_suffix('s').unwrap_or(&name);
+
+    UNITS
+        .iter()
+        .find(|(names, _, _)| names.contains(&name.as_str()) || names.contains(&singular))
+        .map(|(_, dimension, size)| Unit {
+            dimension: *dimension,
+            size: *size,
+        })
+}
+
+/// Converts a quantity from one unit into another. Responds with `None` if
+/// either unit is unknown or they measure different things.
+#[must_use]
+pub fn convert(quantity: f64, from: &str, to: &str) -> Option<f64> {
+    let from = parse(from)?;
+    let to = parse(to)?;
+
+    (from.dimension == to.dimension).then(|| quantity * from.size / to.size)
+}
+
+/// Rounds a converted quantity to at most two decimals, so that conversions
+/// read like something a cook would measure.
+#[must_use]
+pub fn round(quantity: f64) -> f64 { (quantity * 100.0).round() / 100.0 }
+
+#[cfg(test)]
+mod test {
+    use super::{convert, parse, round, Dimension};
+
+    #[test]
+    fn parse_names_and_plurals() {
+        assert_eq!(
+            parse("Cups").map(|unit| unit.dimension),
+            Some(Dimension::Volume)
+        );
+        assert_eq!(parse(" tbsp. ").map(|unit| unit.size), Some(14.786_8));
+        assert_eq!(
+            parse("lbs").map(|unit| unit.dimension),
+            Some(Dimension::Mass)
+        );
+        assert_eq!(parse("fl oz").map(|unit| unit.size), Some(29.573_5));
+        assert_eq!(parse("pinch"), None);
+    }
+
+    #[test]
+    fn convert_within_a_dimension() {
+        assert_eq!(convert(1.0, "cup", "tbsp").map(round), Some(16.0));
+        assert_eq!(convert(250.0, "ml", "l"), Some(0.25));
+        assert_eq!(convert(1.0, "lb", "g").map(round), Some(453.59));
+        assert_eq!(convert(1.0, "cup", "g"), None);
+        assert_eq!(convert(1.0, "handful", "g"), None);
+    }
+}
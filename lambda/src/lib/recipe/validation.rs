@@ -0,0 +1,431 @@
+use std::collections::HashSet;
+
+use super::batch::{BatchOperation, PostBatch, MAX_OPERATIONS};
+use super::request_models::{ForkRecipe, PatchRecipe, PostRecipe, PutRecipe};
+use super::{Ingredient, Recipe, Step};
+use crate::kitchen::Cookware;
+use crate::validation::{max_length, not_blank, positive, Validate, Violations};
+
+pub const MAX_NAME_LENGTH: usize = 200;
+pub const MAX_UNIT_LENGTH: usize = 32;
+pub const MAX_INSTRUCTION_LENGTH: usize = 2000;
+pub const MAX_INGREDIENTS: usize = 100;
+pub const MAX_STEPS: usize = 100;
+/// A week, long enough for cured and fermented recipes.
+pub const MAX_TOTAL_MINUTES: u32 = 7 * 24 * 60;
+pub const MAX_EQUIPMENT: usize = 10;
+/// Hot enough for a pizza oven.
+pub const MAX_CELSIUS: u16 = 500;
+
+/// Names of recipes and ingredients must not be blank, whether they are given
+/// in full or as part of a patch.
+fn check_name(pointer: &str, name: &str, violations: &mut Violations) {
+    let pointer = format!("{pointer}/name");
+    violations.check(&pointer, not_blank(name));
+    violations.check(&pointer, max_length(name, MAX_NAME_LENGTH));
+}
+
+fn check_content(
+    pointer: &str,
+    ingredients: Option<&[Ingredient]>,
+    steps: Option<&[Step]>,
+    violations: &mut Violations,
+) {
+    if let Some(ingredients) = ingredients {
+        violations.check_each(
+            &format!("{pointer}/ingredients"),
+            ingredients,
+            MAX_INGREDIENTS,
+        );
+    }
+    if let Some(steps) = steps {
+        violations.check_each(&format!("{pointer}/steps"), steps, MAX_STEPS);
+        // Steps may only wait for earlier steps, so they can never wait for
+        // each other.
+        for (index, step) in steps.iter().enumerate() {
+            if let Some(after) = &step.after {
+                violations.check(
+                    &format!("{pointer}/steps/{index}/after"),
+                    if after.iter().all(|dependency| *dependency < index) {
+                        Ok(())
+                    } else {
+                        Err("must refer to earlier steps".to_owned())
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Checks the fields every kind of recipe body shares. Fields that are left
+/// out, as in a patch, are not checked.
+fn check_recipe(
+    pointer: &str,
+    name: Option<&str>,
+    ingredients: Option<&[Ingredient]>,
+    steps: Option<&[Step]>,
+    total_minutes: Option<u32>,
+    violations: &mut Violations,
+) {
+    if let Some(name) = name {
+        check_name(pointer, name, violations);
+    }
+    check_content(pointer, ingredients, steps, violations);
+    check_minutes(
+        &format!("{pointer}/total_minutes"),
+        total_minutes,
+        violations,
+    );
+}
+
+fn check_minutes(pointer: &str, minutes: Option<u32>, violations: &mut Violations) {
+    if let Some(minutes) = minutes {
+        violations.check(
+            pointer,
+            if minutes == 0 {
+                Err("must be greater than zero".to_owned())
+            } else if minutes > MAX_TOTAL_MINUTES {
+                Err(format!("must be at most {MAX_TOTAL_MINUTES}"))
+            } else {
+                Ok(())
+            },
+        );
+    }
+}
+
+impl Validate for Ingredient {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        check_name(pointer, &self.name, violations);
+        if let Some(quantity) = self.quantity {
+            violations.check(&format!("{pointer}/quantity"), positive(quantity));
+        }
+        if let Some(unit) = &self.unit {
+            let pointer = format!("{pointer}/unit");
+            violations.check(&pointer, not_blank(unit));
+            violations.check(&pointer, max_length(unit, MAX_UNIT_LENGTH));
+        }
+    }
+}
+
+impl Validate for Step {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        let instruction_pointer = format!("{pointer}/instruction");
+        violations.check(&instruction_pointer, not_blank(&self.instruction));
+        violations.check(
+            &instruction_pointer,
+            max_length(&self.instruction, MAX_INSTRUCTION_LENGTH),
+        );
+        check_minutes(&format!("{pointer}/minutes"), self.minutes, violations);
+        violations.check_each(
+            &format!("{pointer}/equipment"),
+            &self.equipment,
+            MAX_EQUIPMENT,
+        );
+    }
+}
+
+impl Validate for Cookware {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        if let Some(celsius) = self.celsius {
+            violations.check(
+                &format!("{pointer}/celsius"),
+                if celsius > MAX_CELSIUS {
+                    Err(format!("must be at most {MAX_CELSIUS}"))
+                } else {
+                    Ok(())
+                },
+            );
+        }
+        if self.count == Some(0) {
+            violations.check(
+                &format!("{pointer}/count"),
+                Err("must be greater than zero".to_owned()),
+            );
+        }
+    }
+}
+
+impl Validate for Recipe {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        check_recipe(
+            pointer,
+            Some(&self.name),
+            Some(&self.ingredients),
+            Some(&self.steps),
+            self.total_minutes,
+            violations,
+        );
+    }
+}
+
+impl Validate for PostRecipe {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        check_recipe(
+            pointer,
+            Some(&self.name),
+            Some(&self.ingredients),
+            Some(&self.steps),
+            self.total_minutes,
+            violations,
+        );
+    }
+}
+
+impl Validate for PutRecipe {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        check_recipe(
+            pointer,
+            Some(&self.name),
+            Some(&self.ingredients),
+            Some(&self.steps),
+            self.total_minutes,
+            violations,
+        );
+    }
+}
+
+impl Validate for PatchRecipe {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        check_recipe(
+            pointer,
+            self.name.as_deref(),
+            self.ingredients.as_deref(),
+            self.steps.as_deref(),
+            self.total_minutes,
+            violations,
+        );
+    }
+}
+
+impl Validate for ForkRecipe {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        if let Some(name) = &self.name {
+            check_name(pointer, name, violations);
+        }
+    }
+}
+
+impl Validate for BatchOperation {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        match self {
+            BatchOperation::Create { recipe } => {
+                recipe.validate_at(&format!("{pointer}/recipe"), violations)
+            },
+            BatchOperation::Update { recipe } => {
+                recipe.validate_at(&format!("{pointer}/recipe"), violations)
+            },
+            BatchOperation::Delete { .. } => {},
+        }
+    }
+}
+
+impl Validate for PostBatch {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        let pointer = format!("{pointer}/operations");
+        violations.check_each(&pointer, &self.operations, MAX_OPERATIONS);
+
+        // DynamoDB rejects batches that write the same item twice.
+        let mut seen = HashSet::new();
+        for (index, operation) in self.operations.iter().enumerate() {
+            let (field, id) = match operation {
+                BatchOperation::Create { .. } => continue,
+                BatchOperation::Update { recipe } => ("recipe/id", recipe.id),
+                BatchOperation::Delete { id } => ("id", *id),
+            };
+            if !seen.insert(id) {
+                violations.check(
+                    &format!("{pointer}/{index}/{field}"),
+                    Err("must not repeat the recipe of an earlier operation".to_owned()),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use super::{MAX_INGREDIENTS, MAX_TOTAL_MINUTES};
+    use crate::kitchen::{Appliance, Cookware};
+    use crate::recipe::batch::{BatchOperation, PostBatch};
+    use crate::recipe::request_models::{PatchRecipe, PostRecipe, PutRecipe};
+    use crate::recipe::{Ingredient, Step};
+    use crate::validation::Validate;
+
+    fn ingredient(name: &str, quantity: Option<f64>) -> Ingredient {
+        Ingredient {
+            name: name.to_owned(),
+            quantity,
+            unit: None,
+            recipe_id: None,
+        }
+    }
+
+    #[test]
+    fn valid_recipe() {
+        let recipe = PostRecipe {
+            ingredients: vec![ingredient("Flour", Some(500.0))],
+            steps: vec![Step {
+                instruction: "Knead".to_owned(),
+                ..Step::default()
+            }],
+            ..PostRecipe::default()
+        };
+
+        assert_eq!(recipe.validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_recipe_reports_pointers() {
+        let recipe = PostRecipe {
+            name: "  ".to_owned(),
+            ingredients: vec![ingredient("Flour", Some(500.0)), ingredient("", Some(-1.0))],
+            ..PostRecipe::default()
+        };
+
+        let violations = recipe.validate().unwrap_err();
+
+        let pointers: Vec<&str> = violations
+            .errors()
+            .iter()
+            .map(|violation| violation.pointer.as_str())
+            .collect();
+        assert_eq!(
+            pointers,
+            vec!["/name", "/ingredients/1/name", "/ingredients/1/quantity"]
+        );
+    }
+
+    #[test]
+    fn too_many_ingredients() {
+        let recipe = PostRecipe {
+            ingredients: vec![ingredient("Salt", None); MAX_INGREDIENTS + 1],
+            ..PostRecipe::default()
+        };
+
+        assert!(recipe.validate().is_err());
+    }
+
+    #[test]
+    fn patch_with_blank_name() {
+        let patch = PatchRecipe {
+            name: Some(String::new()),
+            ..PatchRecipe::default()
+        };
+
+        assert!(patch.validate().is_err());
+        assert_eq!(PatchRecipe::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn total_minutes_in_range() {
+        let recipe = |total_minutes| PostRecipe {
+            total_minutes: Some(total_minutes),
+            ..PostRecipe::default()
+        };
+
+        assert_eq!(recipe(90).validate(), Ok(()));
+        assert!(recipe(0).validate().is_err());
+        assert!(recipe(MAX_TOTAL_MINUTES + 1).validate().is_err());
+    }
+
+    #[test]
+    fn steps_wait_for_earlier_steps() {
+        let step = |minutes, after: Option<Vec<usize>>| Step {
+            instruction: "Rest".to_owned(),
+            minutes,
+            passive: true,
+            after,
+            ..Step::default()
+        };
+        let recipe = PostRecipe {
+            steps: vec![
+                step(Some(10), None),
+                step(Some(0), Some(vec![1])),
+                step(None, Some(vec![0, 1])),
+            ],
+            ..PostRecipe::default()
+        };
+
+        let violations = recipe.validate().unwrap_err();
+
+        let pointers: Vec<&str> = violations
+            .errors()
+            .iter()
+            .map(|violation| violation.pointer.as_str())
+            .collect();
+        assert_eq!(pointers, vec!["/steps/1/minutes", "/steps/1/after"]);
+    }
+
+    #[test]
+    fn equipment_in_range() {
+        let recipe = PostRecipe {
+            steps: vec![Step {
+                instruction: "Bake".to_owned(),
+                equipment: vec![
+                    Cookware {
+                        appliance: Appliance::Oven,
+                        celsius: Some(900),
+                        count: None,
+                    },
+                    Cookware {
+                        appliance: Appliance::Burner,
+                        celsius: None,
+                        count: Some(0),
+                    },
+                ],
+                ..Step::default()
+            }],
+            ..PostRecipe::default()
+        };
+
+        let violations = recipe.validate().unwrap_err();
+
+        let pointers: Vec<&str> = violations
+            .errors()
+            .iter()
+            .map(|violation| violation.pointer.as_str())
+            .collect();
+        assert_eq!(
+            pointers,
+            vec!["/steps/0/equipment/0/celsius", "/steps/0/equipment/1/count"]
+        );
+    }
+
+    #[test]
+    fn batch_reports_pointers_and_repeated_recipes() {
+        let batch = PostBatch {
+            operations: vec![
+                BatchOperation::Create {
+                    recipe: PostRecipe {
+                        name: String::new(),
+                        ..PostRecipe::default()
+                    },
+                },
+                BatchOperation::Update {
+                    recipe: PutRecipe {
+                        id: Uuid::nil(),
+                        name: "Bread".to_owned(),
+                        ingredients: Vec::new(),
+                        steps: Vec::new(),
+                        total_minutes: None,
+                    },
+                },
+                BatchOperation::Delete { id: Uuid::nil() },
+            ],
+        };
+
+        let violations = batch.validate().unwrap_err();
+
+        let pointers: Vec<&str> = violations
+            .errors()
+            .iter()
+            .map(|violation| violation.pointer.as_str())
+            .collect();
+        assert_eq!(
+            pointers,
+            vec!["/operations/0/recipe/name", "/operations/2/id"]
+        );
+    }
+}
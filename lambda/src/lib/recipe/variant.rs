@@ -0,0 +1,218 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{Ingredient, Recipe, Step};
+
+/// The differences between a variant and the recipe it was forked from.
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct RecipeDiff {
+    pub(crate) parent_id: Uuid,
+    pub(crate) variant_id: Uuid,
+    pub(crate) ingredients: Vec<IngredientChange>,
+    pub(crate) steps: Vec<StepChange>,
+}
+
+/// An ingredient that differs between the parent and the variant. Ingredients
+/// are matched by name, ignoring case and surrounding whitespace.
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum IngredientChange {
+    Added {
+        ingredient: Ingredient,
+    },
+    Removed {
+        ingredient: Ingredient,
+    },
+    Changed {
+        parent: Ingredient,
+        variant: Ingredient,
+    },
+}
+
+/// A step that only exists on one side. Positions are one-based and refer to
+/// the parent's steps for removals and the variant's steps for additions.
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum StepChange {
+    Added { position: usize, step: Step },
+    Removed { position: usize, step: Step },
+}
+
+#[must_use]
+pub fn diff(parent: &Recipe, variant: &Recipe) -> RecipeDiff {
+    RecipeDiff {
+        parent_id: parent.id,
+        variant_id: variant.id,
+        ingredients: diff_ingredients(&parent.ingredients, &variant.ingredients),
+        steps: diff_steps(&parent.steps, &variant.steps),
+    }
+}
+
+fn diff_ingredients(parent: &[Ingredient], variant: &[Ingredient]) -> Vec<IngredientChange> {
+    let key = |ingredient: &Ingredient| ingredient.name.trim().to_lowercase();
+
+    let mut changes: Vec<IngredientChange> = parent
+        .iter()
+        .filter_map(
+            |old| match variant.iter().find(|new| key(new) == key(old)) {
+                None => Some(IngredientChange::Removed {
+                    ingredient: old.clone(),
+                }),
+                Some(new) if new != old => Some(IngredientChange::Changed {
+                    parent: old.clone(),
+                    variant: new.clone(),
+                }),
+                Some(_) => None,
+            },
+        )
+        .collect();
+
+    changes.extend(
+        variant
+            .iter()
+            .filter(|new| !parent.iter().any(|old| key(old) == key(new)))
+            .map(|new| IngredientChange::Added {
+                ingredient: new.clone(),
+            }),
+    );
+
+    changes
+}
+
+/// Diffs steps by their longest common subsequence so that inserting a step
+/// does not report every step after it as changed.
+fn diff_steps(parent: &[Step], variant: &[Step]) -> Vec<StepChange> {
+    let mut lengths = vec![vec![0usize; variant.len() + 1]; parent.len() + 1];
+    for i in (0..parent.len()).rev() {
+        for j in (0..variant.len()).rev() {
+            lengths[i][j] = if parent[i] == variant[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < parent.len() || j < variant.len() {
+        if i < parent.len() && j < variant.len() && parent[i] == variant[j] {
+            i += 1;
+            j += 1;
+        } else if j < variant.len() && (i == parent.len() || lengths[i][j + 1] >= lengths[i + 1][j])
+        {
+            changes.push(StepChange::Added {
+                position: j + 1,
+                step: variant[j].clone(),
+            });
+            j += 1;
+        } else {
+            changes.push(StepChange::Removed {
+                position: i + 1,
+                step: parent[i].clone(),
+            });
+            i += 1;
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use super::{diff, IngredientChange, StepChange};
+    use crate::recipe::{Ingredient, Recipe, Step};
+
+    fn ingredient(name: &str, quantity: f64) -> Ingredient {
+        Ingredient {
+            name: name.to_owned(),
+            quantity: Some(quantity),
+            unit: None,
+            recipe_id: None,
+        }
+    }
+
+    fn step(instruction: &str) -> Step {
+        Step {
+            instruction: instruction.to_owned(),
+            ..Step::default()
+        }
+    }
+
+    #[test]
+    fn diff_ingredients() {
+        let parent = Recipe {
+            ingredients: vec![ingredient("Chili", 1.0), ingredient("Cream", 1.0)],
+            ..Recipe::default()
+        };
+        let variant = Recipe {
+            id: Uuid::from_u128(1),
+            parent_id: Some(Uuid::nil()),
+            ingredients: vec![ingredient("chili ", 3.0), ingredient("Coconut Milk", 1.0)],
+            ..Recipe::default()
+        };
+
+        let actual = diff(&parent, &variant);
+
+        assert_eq!(
+            actual.ingredients,
+            vec![
+                IngredientChange::Changed {
+                    parent: ingredient("Chili", 1.0),
+                    variant: ingredient("chili ", 3.0),
+                },
+                IngredientChange::Removed {
+                    ingredient: ingredient("Cream", 1.0),
+                },
+                IngredientChange::Added {
+                    ingredient: ingredient("Coconut Milk", 1.0),
+                },
+            ]
+        );
+        assert!(actual.steps.is_empty());
+    }
+
+    #[test]
+    fn diff_steps() {
+        let parent = Recipe {
+            steps: vec![step("Chop"), step("Simmer"), step("Serve")],
+            ..Recipe::default()
+        };
+        let variant = Recipe {
+            steps: vec![step("Chop"), step("Pressure cook"), step("Serve")],
+            ..Recipe::default()
+        };
+
+        let actual = diff(&parent, &variant);
+
+        assert_eq!(
+            actual.steps,
+            vec![
+                StepChange::Added {
+                    position: 2,
+                    step: step("Pressure cook"),
+                },
+                StepChange::Removed {
+                    position: 2,
+                    step: step("Simmer"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_identical() {
+        let recipe = Recipe {
+            ingredients: vec![ingredient("Salt", 1.0)],
+            steps: vec![step("Season")],
+            ..Recipe::default()
+        };
+
+        let actual = diff(&recipe, &recipe);
+
+        assert!(actual.ingredients.is_empty() && actual.steps.is_empty());
+    }
+}
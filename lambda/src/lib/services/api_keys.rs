@@ -0,0 +1,319 @@
+use axum::extract::{Path, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use uuid::Uuid;
+
+use crate::api_key::request_models::PostApiKey;
+use crate::api_key::response_models::{ApiKeyResponse, CreatedApiKey};
+use crate::api_key::{self, ApiKey, ApiKeyRepository};
+use crate::auth::Principal;
+use crate::clock;
+use crate::household::Role;
+use crate::services::ApplicationContext;
+
+/// The header scripts send their API key in.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Middleware that authenticates requests carrying an API key and stores the
+/// key's household and role as a [`Principal`] request extension. Requests
+/// without a key are passed on to interactive authentication.
+pub async fn authenticate_api_key<T>(
+    State(state): State<ApplicationContext<T>>,
+    mut request: Request,
+    next: Next,
+) -> Response
+where
+    T: ApiKeyRepository,
+{
+    let Some(value) = request.headers().get(API_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+
+    let principal = match verify(&state.repo, value.to_str().ok()).await {
+        Ok(principal) => principal,
+        Err(status) => return status.into_response(),
+    };
+
+    request.extensions_mut().insert(principal);
+    next.run(request).await
+}
+
+async fn verify<T>(repo: &T, key: Option<&str>) -> Result<Principal, StatusCode>
+where
+    T: ApiKeyRepository,
+{
+    let (id, secret) = key
+        .and_then(api_key::parse)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let key = match repo.find_key(id).await {
+        Ok(key) => key,
+        Err(StatusCode::NOT_FOUND) => return Err(StatusCode::UNAUTHORIZED),
+        Err(status) => return Err(status),
+    };
+    if !key.verify(secret) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let now = clock::now();
+    if key.needs_touch(now) {
+        // The timestamp is informational, failing to record it must not keep
+        // the script from working.
+        let _ = repo.touch_key(id, now).await;
+    }
+
+    Ok(Principal::from_api_key(&key))
+}
+
+/// Attempts to create an API key for the caller's household. The key itself is
+/// only part of this response. Callers can only create keys that are allowed
+/// to do no more than they can themselves.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn create<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Json(payload): Json<PostApiKey>,
+) -> Result<(StatusCode, Json<CreatedApiKey>), StatusCode>
+where
+    T: ApiKeyRepository,
+{
+    require_interactive(&principal)?;
+    principal.require(payload.scope.role())?;
+    if payload.name.trim().is_empty() {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    let (key, full_key) = ApiKey::generate(
+        principal.household_id(),
+        principal.subject(),
+        payload.name.trim(),
+        payload.scope,
+        clock::now(),
+    );
+    state.repo.save_key(&key).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedApiKey {
+            details: key.into(),
+            key: full_key,
+        }),
+    ))
+}
+
+/// Lists the API keys of the caller's household.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn list<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+) -> Result<Json<Vec<ApiKeyResponse>>, StatusCode>
+where
+    T: ApiKeyRepository,
+{
+    require_interactive(&principal)?;
+    let keys = state.repo.get_keys(principal.household_id()).await?;
+
+    Ok(Json(keys.into_iter().map(ApiKeyResponse::from).collect()))
+}
+
+/// Attempts to revoke an API key of the caller's household. Keys can be
+/// revoked by whoever created them and by owners.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn revoke<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, StatusCode>
+where
+    T: ApiKeyRepository,
+{
+    require_interactive(&principal)?;
+    let key = state.repo.find_key(id).await?;
+    if key.household_id != principal.household_id() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    if key.created_by != principal.subject() {
+        principal.require(Role::Owner)?;
+    }
+
+    state.repo.delete_key(id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Keys are managed by people, a leaked key must not be able to mint more.
+pub(crate) fn require_interactive(principal: &Principal) -> Result<(), StatusCode> {
+    if principal.api_key().is_some() {
+        Err(StatusCode::FORBIDDEN)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use axum::Json;
+    use mockall::predicate::{always, eq, function};
+    use uuid::Uuid;
+
+    use super::verify;
+    use crate::api_key::request_models::PostApiKey;
+    use crate::api_key::{self, ApiKey, MockApiKeyRepository, Scope};
+    use crate::auth::Principal;
+    use crate::household::{Member, Role};
+    use crate::services::{self, ApplicationContext};
+
+    fn principal(role: Role) -> Principal {
+        let member = Member {
+            household_id: "grandma".to_owned(),
+            role,
+            ..Member::default()
+        };
+        Principal::new("grandpa").with_membership(&member)
+    }
+
+    #[tokio::test]
+    async fn verify_valid_key() {
+        let (key, full_key) = ApiKey::generate("grandma", "grandpa", "Kitchen", Scope::Read, 0);
+        let id = key.id;
+        let mut mock_repo = MockApiKeyRepository::new();
+        mock_repo
+            .expect_find_key()
+            .with(eq(id))
+            .return_once(|_| Box::pin(async { Ok(key) }));
+        mock_repo
+            .expect_touch_key()
+            .with(eq(id), always())
+            .return_once(|_, _| Box::pin(async { Ok(()) }));
+
+        let actual = verify(&mock_repo, Some(&full_key)).await.unwrap();
+
+        assert_eq!(actual.household_id(), "grandma");
+        assert_eq!(actual.role(), Role::Viewer);
+        assert_eq!(actual.api_key(), Some(id));
+    }
+
+    #[tokio::test]
+    async fn verify_wrong_secret() {
+        let (key, _) = ApiKey::generate("grandma", "grandpa", "Kitchen", Scope::Read, 0);
+        let full_key = format!("{}{}_guess", api_key::KEY_PREFIX, key.id.simple());
+        let mut mock_repo = MockApiKeyRepository::new();
+        mock_repo
+            .expect_find_key()
+            .return_once(|_| Box::pin(async { Ok(key) }));
+        mock_repo.expect_touch_key().never();
+
+        let actual = verify(&mock_repo, Some(&full_key)).await;
+
+        assert_eq!(actual, Err(StatusCode::UNAUTHORIZED));
+    }
+
+    #[tokio::test]
+    async fn verify_revoked_key() {
+        let mut mock_repo = MockApiKeyRepository::new();
+        mock_repo
+            .expect_find_key()
+            .return_once(|_| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+        let full_key = format!("{}{}_secret", api_key::KEY_PREFIX, Uuid::nil().simple());
+
+        let actual = verify(&mock_repo, Some(&full_key)).await;
+
+        assert_eq!(actual, Err(StatusCode::UNAUTHORIZED));
+    }
+
+    #[tokio::test]
+    async fn create_ok() {
+        let mut mock_repo = MockApiKeyRepository::new();
+        mock_repo
+            .expect_save_key()
+            .with(function(|key: &ApiKey| {
+                key.household_id.eq("grandma") && key.scope == Scope::ReadWrite
+            }))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = Json(PostApiKey {
+            name: "Kitchen".to_owned(),
+            scope: Scope::ReadWrite,
+        });
+
+        let actual = services::api_keys::create(state, principal(Role::Editor), payload).await;
+
+        let (status, Json(created)) = actual.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(
+            api_key::parse(&created.key).map(|(id, _)| id),
+            Some(created.details.id)
+        );
+    }
+
+    #[tokio::test]
+    async fn create_read_write_as_viewer() {
+        let mut mock_repo = MockApiKeyRepository::new();
+        mock_repo.expect_save_key().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = Json(PostApiKey {
+            name: "Kitchen".to_owned(),
+            scope: Scope::ReadWrite,
+        });
+
+        let actual = services::api_keys::create(state, principal(Role::Viewer), payload).await;
+
+        assert_eq!(actual.err(), Some(StatusCode::FORBIDDEN));
+    }
+
+    #[tokio::test]
+    async fn create_with_api_key() {
+        let mut mock_repo = MockApiKeyRepository::new();
+        mock_repo.expect_save_key().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+        let key = ApiKey {
+            scope: Scope::ReadWrite,
+            ..ApiKey::default()
+        };
+        let payload = Json(PostApiKey {
+            name: "Another".to_owned(),
+            scope: Scope::Read,
+        });
+
+        let actual =
+            services::api_keys::create(state, Principal::from_api_key(&key), payload).await;
+
+        assert_eq!(actual.err(), Some(StatusCode::FORBIDDEN));
+    }
+
+    #[tokio::test]
+    async fn revoke_key_of_other_member() {
+        let mut mock_repo = MockApiKeyRepository::new();
+        mock_repo.expect_find_key().return_once(|_| {
+            Box::pin(async {
+                Ok(ApiKey {
+                    household_id: "grandma".to_owned(),
+                    created_by: "aunt".to_owned(),
+                    ..ApiKey::default()
+                })
+            })
+        });
+        mock_repo.expect_delete_key().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual =
+            services::api_keys::revoke(state, principal(Role::Editor), Path(Uuid::nil())).await;
+
+        assert_eq!(actual, Err(StatusCode::FORBIDDEN));
+    }
+}
@@ -0,0 +1,265 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use uuid::Uuid;
+
+use crate::auth::Principal;
+use crate::cook_log::request_models::PostCookEntry;
+use crate::cook_log::{self, CookEntry, CookLogRepository};
+use crate::date::format_day;
+use crate::household::Role;
+use crate::recipe::Recipe;
+use crate::services::recipes::find_active;
+use crate::services::ApplicationContext;
+use crate::validation::ValidatedJson;
+use crate::{clock, Repository};
+
+/// Records that the caller cooked a recipe, with how well it turned out.
+/// Recipes in the trash are reported as not found.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    post,
+    path = "/{id}/cook-log",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe")),
+    request_body = PostCookEntry,
+    responses(
+        (status = 201, description = "The entry was recorded", body = CookEntry),
+        (status = 403, description = "The caller cannot change recipes"),
+        (status = 404, description = "The recipe does not exist"),
+        (status = 422, description = "The entry breaks some rules", body = Violations),
+    ),
+)]
+pub async fn record<T, L>(
+    State(state): State<ApplicationContext<T>>,
+    State(log_state): State<ApplicationContext<L>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<PostCookEntry>,
+) -> Result<(StatusCode, Json<CookEntry>), StatusCode>
+where
+    T: Repository<Recipe>,
+    L: CookLogRepository,
+{
+    principal.require(Role::Editor)?;
+    find_active(&state.repo, principal.household_id(), id).await?;
+
+    let entry = CookEntry {
+        household_id: principal.household_id().to_owned(),
+        id: Uuid::new_v4(),
+        recipe_id: id,
+        cooked_on: payload
+            .cooked_on
+            .unwrap_or_else(|| format_day(cook_log::today(clock::now()))),
+        rating: payload.rating,
+        notes: payload.notes,
+        modifications: payload.modifications,
+        subject: principal.subject().to_owned(),
+    };
+    log_state.repo.save_entry(&entry).await?;
+
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+/// Lists the times the household cooked a recipe, the most recent first.
+/// Recipes in the trash are reported as not found.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    get,
+    path = "/{id}/cook-log",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe")),
+    responses(
+        (status = 200, description = "The entries of the recipe", body = [CookEntry]),
+        (status = 404, description = "The recipe does not exist"),
+    ),
+)]
+pub async fn list<T, L>(
+    State(state): State<ApplicationContext<T>>,
+    State(log_state): State<ApplicationContext<L>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<CookEntry>>, StatusCode>
+where
+    T: Repository<Recipe>,
+    L: CookLogRepository,
+{
+    find_active(&state.repo, principal.household_id(), id).await?;
+    let entries = log_state
+        .repo
+        .get_recipe_entries(principal.household_id(), id)
+        .await?;
+
+    Ok(Json(entries))
+}
+
+/// Removes an entry from the cook log of a recipe.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error. Responds with not found if the entry is not one of the
+/// recipe.
+#[utoipa::path(
+    delete,
+    path = "/{id}/cook-log/{entry_id}",
+    tag = "recipes",
+    params(
+        ("id" = Uuid, Path, description = "The id of the recipe"),
+        ("entry_id" = Uuid, Path, description = "The id of the entry"),
+    ),
+    responses(
+        (status = 204, description = "The entry was removed"),
+        (status = 403, description = "The caller cannot change recipes"),
+        (status = 404, description = "The entry is not one of the recipe"),
+    ),
+)]
+pub async fn delete_one<L>(
+    State(state): State<ApplicationContext<L>>,
+    principal: Principal,
+    Path((id, entry_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, StatusCode>
+where
+    L: CookLogRepository,
+{
+    principal.require(Role::Editor)?;
+    let entries = state
+        .repo
+        .get_recipe_entries(principal.household_id(), id)
+        .await?;
+    if !entries.iter().any(|entry| entry.id == entry_id) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    state
+        .repo
+        .delete_entry(principal.household_id(), entry_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod test {
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use mockall::predicate::{eq, function};
+    use uuid::Uuid;
+
+    use crate::auth::Principal;
+    use crate::cook_log::request_models::PostCookEntry;
+    use crate::cook_log::{CookEntry, MockCookLogRepository};
+    use crate::household::{Member, Role};
+    use crate::recipe::{MockRecipeRepository, Recipe};
+    use crate::services::{self, ApplicationContext};
+    use crate::validation::ValidatedJson;
+
+    const HOUSEHOLD: &str = "grandma";
+
+    #[tokio::test]
+    async fn record_cooked_recipe() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        let mut mock_log = MockCookLogRepository::new();
+        mock_log
+            .expect_save_entry()
+            .with(function(|entry: &CookEntry| {
+                entry.household_id == HOUSEHOLD
+                    && entry.cooked_on == "2025-05-14"
+                    && entry.rating == 4
+                    && entry.subject == HOUSEHOLD
+            }))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        let payload = PostCookEntry {
+            cooked_on: Some("2025-05-14".to_owned()),
+            rating: 4,
+            ..PostCookEntry::default()
+        };
+
+        let actual = services::cook_log::record(
+            State(ApplicationContext { repo: mock_repo }),
+            State(ApplicationContext { repo: mock_log }),
+            Principal::new(HOUSEHOLD),
+            Path(Uuid::nil()),
+            ValidatedJson(payload),
+        )
+        .await;
+
+        assert!(actual.is_ok_and(|(status, _)| status == StatusCode::CREATED));
+    }
+
+    #[tokio::test]
+    async fn viewer_cannot_record() {
+        let mut mock_log = MockCookLogRepository::new();
+        mock_log.expect_save_entry().never();
+        let viewer = Principal::new("viewer").with_membership(&Member {
+            subject: "viewer".to_owned(),
+            household_id: HOUSEHOLD.to_owned(),
+            role: Role::Viewer,
+            ..Member::default()
+        });
+
+        let actual = services::cook_log::record(
+            State(ApplicationContext {
+                repo: MockRecipeRepository::new(),
+            }),
+            State(ApplicationContext { repo: mock_log }),
+            viewer,
+            Path(Uuid::nil()),
+            ValidatedJson(PostCookEntry::default()),
+        )
+        .await;
+
+        assert_eq!(actual.err(), Some(StatusCode::FORBIDDEN));
+    }
+
+    #[tokio::test]
+    async fn list_entries_of_recipe_of_another_household() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+        let mut mock_log = MockCookLogRepository::new();
+        mock_log.expect_get_recipe_entries().never();
+
+        let actual = services::cook_log::list(
+            State(ApplicationContext { repo: mock_repo }),
+            State(ApplicationContext { repo: mock_log }),
+            Principal::new(HOUSEHOLD),
+            Path(Uuid::nil()),
+        )
+        .await;
+
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn delete_entry_of_another_recipe() {
+        let mut mock_log = MockCookLogRepository::new();
+        mock_log
+            .expect_get_recipe_entries()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(vec![CookEntry::default()]) }));
+        mock_log.expect_delete_entry().never();
+
+        let actual = services::cook_log::delete_one(
+            State(ApplicationContext { repo: mock_log }),
+            Principal::new(HOUSEHOLD),
+            Path((Uuid::nil(), Uuid::from_u128(1))),
+        )
+        .await;
+
+        assert_eq!(actual, Err(StatusCode::NOT_FOUND));
+    }
+}
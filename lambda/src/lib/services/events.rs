@@ -0,0 +1,295 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures_util::stream::{self, Stream};
+use serde_json::Value;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use uuid::Uuid;
+
+use crate::auth::Principal;
+use crate::live::{ChangeKind, EventLog, LiveEvent, Topic};
+
+/// The header browsers send the id of the last event they saw in when they
+/// reconnect.
+pub const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// How often an idle stream sends a comment, so that proxies keep it open.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// The most of a response body that is read to find the id of a created
+/// resource.
+const MAX_BODY_BYTES: usize = 6 * 1024 * 1024;
+
+/// Streams changes to the recipes, plans and shopping lists of the caller's
+/// household as server-sent events named after what changed, e.g.
+/// `shopping_list.updated`. Clients that reconnect with a `Last-Event-ID`
+/// header get the events they missed first. If those are no longer kept, a
+/// `reset` event tells them to read everything again.
+pub async fn stream(
+    State(log): State<Arc<EventLog>>,
+    principal: Principal,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let last_sequence = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok());
+    let subscription = log.subscribe(last_sequence);
+
+    let mut pending: VecDeque<Event> = subscription
+        .replay
+        .iter()
+        .filter(|event| event.household_id == principal.household_id())
+        .map(to_event)
+        .collect();
+    if subscription.gap {
+        pending.push_front(reset());
+    }
+    let stream = stream::unfold(
+        (
+            pending,
+            subscription.receiver,
+            principal.household_id().to_owned(),
+        ),
+        |(mut pending, mut receiver, household_id)| async move {
+            let event = match pending.pop_front() {
+                Some(event) => event,
+                None => next_event(&mut receiver, &household_id).await?,
+            };
+            Some((Ok(event), (pending, receiver, household_id)))
+        },
+    );
+
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(KEEP_ALIVE_INTERVAL))
+}
+
+/// Waits for the next event of the household. Subscribers that fall too far
+/// behind are told to read everything again.
+async fn next_event(receiver: &mut Receiver<LiveEvent>, household_id: &str) -> Option<Event> {
+    loop {
+        match receiver.recv().await {
+            Ok(event) if event.household_id == household_id => return Some(to_event(&event)),
+            Ok(_) => {},
+            Err(RecvError::Lagged(_)) => return Some(reset()),
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+fn to_event(event: &LiveEvent) -> Event {
+    Event::default()
+        .id(event.sequence.to_string())
+        .event(event.name())
+        .data(serde_json::to_string(event).unwrap_or_default())
+}
+
+fn reset() -> Event { Event::default().event("reset").data("{}") }
+
+/// Middleware that publishes a live event for every successful request that
+/// changes a recipe, plan or shopping list. It runs below idempotency, so that
+/// replayed responses are not published again.
+pub async fn publish_changes(
+    State(log): State<Arc<EventLog>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    if !matches!(
+        method,
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    ) {
+        return next.run(request).await;
+    }
+    let path = request.uri().path().to_owned();
+    let household_id = request
+        .extensions()
+        .get::<Principal>()
+        .map(|principal| principal.household_id().to_owned());
+
+    let response = next.run(request).await;
+    let Some(household_id) = household_id else {
+        return response;
+    };
+    let Some(change) = classify(&method, &path, response.status()) else {
+        return response;
+    };
+    if change.id.is_some() {
+        log.publish(&household_id, change.topic, change.kind, change.id);
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let Ok(body) = to_bytes(body, MAX_BODY_BYTES).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    log.publish(&household_id, change.topic, change.kind, body_id(&body));
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// What a request changed, as far as its method, path and status tell.
+#[derive(Debug, PartialEq)]
+struct Change {
+    topic: Topic,
+    kind: ChangeKind,
+    /// Missing if the id is only part of the response.
+    id: Option<Uuid>,
+}
+
+fn classify(method: &Method, path: &str, status: StatusCode) -> Option<Change> {
+    if !status.is_success() {
+        return None;
+    }
+
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    let topic = match segments.clone().next() {
+        Some("shopping-lists") => {
+            segments.next();
+            Topic::ShoppingList
+        },
+        Some("plans") => {
+            segments.next();
+            if segments.clone().next() == Some("calendar-feed") {
+                return None;
+            }
+            Topic::Plan
+        },
+        Some(
+            "api-keys" | "webhooks" | "households" | "events" | "shared" | "timeline" | "kitchen",
+        ) => return None,
+        _ => Topic::Recipe,
+    };
+    let segments: Vec<&str> = segments.collect();
+    let position = segments
+        .iter()
+        .position(|segment| Uuid::parse_str(segment).is_ok());
+    let path_id = position.and_then(|position| Uuid::parse_str(segments[position]).ok());
+    let action = position.and_then(|position| segments.get(position + 1).copied());
+
+    // Forks are new recipes, other actions below an item change that item.
+    let creates = status == StatusCode::CREATED && (path_id.is_none() || action == Some("fork"));
+    let change = if creates {
+        Change {
+            topic,
+            kind: ChangeKind::Created,
+            id: None,
+        }
+    } else if *method == Method::DELETE && path_id.is_some() && action.is_none() {
+        Change {
+            topic,
+            kind: ChangeKind::Deleted,
+            id: path_id,
+        }
+    } else {
+        Change {
+            topic,
+            kind: ChangeKind::Updated,
+            id: path_id,
+        }
+    };
+
+    Some(change)
+}
+
+/// The id of the resource in a response body, if it is a single resource.
+fn body_id(body: &[u8]) -> Option<Uuid> {
+    serde_json::from_slice::<Value>(body)
+        .ok()?
+        .get("id")?
+        .as_str()
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
+#[cfg(test)]
+mod test {
+    use axum::http::{Method, StatusCode};
+    use uuid::Uuid;
+
+    use super::{body_id, classify, Change};
+    use crate::live::{ChangeKind, Topic};
+
+    const ID: &str = "0b5a6f83-0c5c-4d16-b5a4-7c8a0e1e2c1f";
+
+    fn change(topic: Topic, kind: ChangeKind, id: Option<&str>) -> Option<Change> {
+        Some(Change {
+            topic,
+            kind,
+            id: id.map(|id| Uuid::parse_str(id).unwrap()),
+        })
+    }
+
+    #[test]
+    fn classify_recipes() {
+        assert_eq!(
+            classify(&Method::POST, "/", StatusCode::CREATED),
+            change(Topic::Recipe, ChangeKind::Created, None)
+        );
+        assert_eq!(
+            classify(&Method::PATCH, &format!("/{ID}"), StatusCode::OK),
+            change(Topic::Recipe, ChangeKind::Updated, Some(ID))
+        );
+        assert_eq!(
+            classify(&Method::DELETE, &format!("/{ID}"), StatusCode::NO_CONTENT),
+            change(Topic::Recipe, ChangeKind::Deleted, Some(ID))
+        );
+        assert_eq!(
+            classify(&Method::POST, &format!("/{ID}/fork"), StatusCode::CREATED),
+            change(Topic::Recipe, ChangeKind::Created, None)
+        );
+        assert_eq!(
+            classify(&Method::POST, "/batch", StatusCode::OK),
+            change(Topic::Recipe, ChangeKind::Updated, None)
+        );
+    }
+
+    #[test]
+    fn classify_shopping_lists() {
+        assert_eq!(
+            classify(
+                &Method::POST,
+                &format!("/shopping-lists/{ID}/items"),
+                StatusCode::CREATED
+            ),
+            change(Topic::ShoppingList, ChangeKind::Updated, Some(ID))
+        );
+        assert_eq!(
+            classify(
+                &Method::PATCH,
+                &format!("/shopping-lists/{ID}/items/{}", Uuid::nil()),
+                StatusCode::OK
+            ),
+            change(Topic::ShoppingList, ChangeKind::Updated, Some(ID))
+        );
+    }
+
+    #[test]
+    fn classify_ignores_failures_and_other_resources() {
+        assert_eq!(
+            classify(&Method::PATCH, &format!("/{ID}"), StatusCode::CONFLICT),
+            None
+        );
+        assert_eq!(
+            classify(&Method::POST, "/webhooks", StatusCode::CREATED),
+            None
+        );
+        assert_eq!(classify(&Method::POST, "/timeline", StatusCode::OK), None);
+    }
+
+    #[test]
+    fn id_from_body() {
+        assert_eq!(
+            body_id(format!(r#"{{"id":"{ID}","name":"Bread"}}"#).as_bytes()),
+            Uuid::parse_str(ID).ok()
+        );
+        assert_eq!(body_id(b"[]"), None);
+    }
+}
@@ -0,0 +1,520 @@
+use axum::extract::{Path, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use uuid::Uuid;
+
+use crate::auth::Principal;
+use crate::clock;
+use crate::household::request_models::{JoinHousehold, PostInvitation, PutMember};
+use crate::household::response_models::HouseholdResponse;
+use crate::household::{HouseholdRepository, Invitation, Member, Role, INVITATION_LIFETIME};
+use crate::services::ApplicationContext;
+
+/// Middleware that makes an authenticated caller act for the household they
+/// are a member of, with their role in it. Callers without a membership keep
+/// acting as the owner of their own household. Callers with an API key keep
+/// acting for the household of the key, with no more than the current role of
+/// whoever created the key.
+pub async fn resolve_membership<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    mut request: Request,
+    next: Next,
+) -> Response
+where
+    T: HouseholdRepository,
+{
+    let principal = if principal.api_key().is_some() {
+        match creator_role(&state.repo, &principal).await {
+            Ok(role) => principal.with_role_at_most(role),
+            Err(status) => return status.into_response(),
+        }
+    } else {
+        match state.repo.find_member(principal.subject()).await {
+            Ok(member) => principal.with_membership(&member),
+            Err(StatusCode::NOT_FOUND) => principal,
+            Err(status) => return status.into_response(),
+        }
+    };
+
+    request.extensions_mut().insert(principal);
+    next.run(request).await
+}
+
+/// The role the creator of the caller's API key currently has in the key's
+/// household. Keys stop working once their creator leaves the household.
+async fn creator_role<T>(repo: &T, principal: &Principal) -> Result<Role, StatusCode>
+where
+    T: HouseholdRepository,
+{
+    let creator = principal.key_creator().ok_or(StatusCode::UNAUTHORIZED)?;
+
+    current_role(repo, creator, principal.household_id())
+        .await?
+        .ok_or(StatusCode::UNAUTHORIZED)
+}
+
+/// The role a subject currently has in a household, if they still belong to
+/// it. Subjects without a membership own the household named after them.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub(crate) async fn current_role<T>(
+    repo: &T,
+    subject: &str,
+    household_id: &str,
+) -> Result<Option<Role>, StatusCode>
+where
+    T: HouseholdRepository,
+{
+    match repo.find_member(subject).await {
+        Ok(member) if member.household_id == household_id => Ok(Some(member.role)),
+        Err(StatusCode::NOT_FOUND) if subject == household_id => Ok(Some(Role::Owner)),
+        Ok(_) | Err(StatusCode::NOT_FOUND) => Ok(None),
+        Err(status) => Err(status),
+    }
+}
+
+/// Reads the household of the caller along with its members.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn read_current<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+) -> Result<Json<HouseholdResponse>, StatusCode>
+where
+    T: HouseholdRepository,
+{
+    let mut members = state.repo.get_members(principal.household_id()).await?;
+    if members.is_empty() {
+        members.push(Member {
+            subject: principal.subject().to_owned(),
+            household_id: principal.household_id().to_owned(),
+            role: principal.role(),
+            joined_at: 0,
+        });
+    }
+
+    Ok(Json(HouseholdResponse {
+        id: principal.household_id().to_owned(),
+        role: principal.role(),
+        members,
+    }))
+}
+
+/// Attempts to create an invitation to join the caller's household. Only
+/// owners can invite, and nobody can be invited as an owner.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn invite<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Json(payload): Json<PostInvitation>,
+) -> Result<(StatusCode, Json<Invitation>), StatusCode>
+where
+    T: HouseholdRepository,
+{
+    principal.require(Role::Owner)?;
+    if payload.role == Role::Owner {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    let now = clock::now();
+    // The owner of a household of their own has no membership yet, record it
+    // so that they are listed along with the people they invite.
+    match state.repo.find_member(principal.subject()).await {
+        Ok(_) => {},
+        Err(StatusCode::NOT_FOUND) => {
+            state
+                .repo
+                .save_member(&Member {
+                    subject: principal.subject().to_owned(),
+                    household_id: principal.household_id().to_owned(),
+                    role: Role::Owner,
+                    joined_at: now,
+                })
+                .await?;
+        },
+        Err(err) => return Err(err),
+    }
+
+    let invitation = Invitation {
+        token: Uuid::new_v4(),
+        household_id: principal.household_id().to_owned(),
+        role: payload.role,
+        created_by: principal.subject().to_owned(),
+        expires_at: now + INVITATION_LIFETIME.as_secs(),
+    };
+    state.repo.save_invitation(&invitation).await?;
+
+    Ok((StatusCode::CREATED, Json(invitation)))
+}
+
+/// Attempts to join a household with an invitation, which is used up in the
+/// process. An owner cannot leave a household that has other members.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn join<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Json(payload): Json<JoinHousehold>,
+) -> Result<Json<Member>, StatusCode>
+where
+    T: HouseholdRepository,
+{
+    if principal.role() == Role::Owner {
+        let members = state.repo.get_members(principal.household_id()).await?;
+        if members
+            .iter()
+            .any(|member| member.subject != principal.subject())
+        {
+            return Err(StatusCode::CONFLICT);
+        }
+    }
+
+    // Check the household before using up the invitation, so that it still
+    // works for whoever it was meant for.
+    let invitation = state.repo.find_invitation(payload.token).await?;
+    if invitation.household_id == principal.household_id() {
+        return Err(StatusCode::CONFLICT);
+    }
+    let invitation = state.repo.take_invitation(payload.token).await?;
+
+    let member = Member {
+        subject: principal.subject().to_owned(),
+        household_id: invitation.household_id,
+        role: invitation.role,
+        joined_at: clock::now(),
+    };
+    state.repo.save_member(&member).await?;
+
+    Ok(Json(member))
+}
+
+/// Attempts to change the role of another member of the caller's household.
+/// Only owners can change roles.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn update_member<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(subject): Path<String>,
+    Json(payload): Json<PutMember>,
+) -> Result<Json<Member>, StatusCode>
+where
+    T: HouseholdRepository,
+{
+    principal.require(Role::Owner)?;
+    if subject == principal.subject() {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let member = Member {
+        role: payload.role,
+        ..find_member(&state.repo, &principal, &subject).await?
+    };
+    state.repo.save_member(&member).await?;
+
+    Ok(Json(member))
+}
+
+/// Attempts to remove a member from the caller's household. Owners can remove
+/// anyone but themselves, everyone else can only leave.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn remove_member<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(subject): Path<String>,
+) -> Result<StatusCode, StatusCode>
+where
+    T: HouseholdRepository,
+{
+    if subject == principal.subject() {
+        if principal.role() == Role::Owner {
+            return Err(StatusCode::CONFLICT);
+        }
+    } else {
+        principal.require(Role::Owner)?;
+        find_member(&state.repo, &principal, &subject).await?;
+    }
+
+    state.repo.delete_member(&subject).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Finds a member of the caller's household. Members of other households are
+/// reported as not found.
+async fn find_member<T>(
+    repo: &T,
+    principal: &Principal,
+    subject: &str,
+) -> Result<Member, StatusCode>
+where
+    T: HouseholdRepository,
+{
+    let member = repo.find_member(subject).await?;
+    if member.household_id == principal.household_id() {
+        Ok(member)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use axum::Json;
+    use mockall::predicate::{eq, function};
+    use uuid::Uuid;
+
+    use super::creator_role;
+    use crate::api_key::{ApiKey, Scope};
+    use crate::auth::Principal;
+    use crate::household::request_models::{JoinHousehold, PostInvitation, PutMember};
+    use crate::household::{Invitation, Member, MockHouseholdRepository, Role};
+    use crate::services::{self, ApplicationContext};
+
+    fn member(subject: &str, household_id: &str, role: Role) -> Member {
+        Member {
+            subject: subject.to_owned(),
+            household_id: household_id.to_owned(),
+            role,
+            joined_at: 0,
+        }
+    }
+
+    fn principal(role: Role) -> Principal {
+        Principal::new("grandpa").with_membership(&member("grandpa", "grandma", role))
+    }
+
+    fn key_principal(created_by: &str) -> Principal {
+        Principal::from_api_key(&ApiKey {
+            household_id: "grandma".to_owned(),
+            scope: Scope::ReadWrite,
+            created_by: created_by.to_owned(),
+            ..ApiKey::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn key_of_demoted_creator() {
+        let mut mock_repo = MockHouseholdRepository::new();
+        mock_repo
+            .expect_find_member()
+            .with(eq("grandpa"))
+            .return_once(|_| Box::pin(async { Ok(member("grandpa", "grandma", Role::Viewer)) }));
+
+        let principal = key_principal("grandpa");
+        let role = creator_role(&mock_repo, &principal).await;
+
+        assert_eq!(role, Ok(Role::Viewer));
+        assert_eq!(
+            principal.with_role_at_most(Role::Viewer).role(),
+            Role::Viewer
+        );
+    }
+
+    #[tokio::test]
+    async fn key_of_removed_creator() {
+        let mut mock_repo = MockHouseholdRepository::new();
+        mock_repo
+            .expect_find_member()
+            .with(eq("grandpa"))
+            .return_once(|_| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+
+        let role = creator_role(&mock_repo, &key_principal("grandpa")).await;
+
+        assert_eq!(role, Err(StatusCode::UNAUTHORIZED));
+    }
+
+    #[tokio::test]
+    async fn key_of_owner_without_membership() {
+        let mut mock_repo = MockHouseholdRepository::new();
+        mock_repo
+            .expect_find_member()
+            .return_once(|_| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+
+        let role = creator_role(&mock_repo, &key_principal("grandma")).await;
+
+        assert_eq!(role, Ok(Role::Owner));
+    }
+
+    #[tokio::test]
+    async fn invite_records_owner() {
+        let mut mock_repo = MockHouseholdRepository::new();
+        mock_repo
+            .expect_find_member()
+            .with(eq("grandma"))
+            .return_once(|_| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+        mock_repo
+            .expect_save_member()
+            .with(function(|member: &Member| {
+                member.subject.eq("grandma") && member.role == Role::Owner
+            }))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        mock_repo
+            .expect_save_invitation()
+            .with(function(|invitation: &Invitation| {
+                invitation.household_id.eq("grandma") && invitation.role == Role::Viewer
+            }))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = Json(PostInvitation { role: Role::Viewer });
+
+        let actual = services::households::invite(state, Principal::new("grandma"), payload).await;
+
+        assert!(actual.is_ok_and(|(status, _)| status == StatusCode::CREATED));
+    }
+
+    #[tokio::test]
+    async fn invite_as_editor_forbidden() {
+        let mut mock_repo = MockHouseholdRepository::new();
+        mock_repo.expect_save_invitation().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = Json(PostInvitation { role: Role::Viewer });
+
+        let actual = services::households::invite(state, principal(Role::Editor), payload).await;
+
+        assert_eq!(actual.err(), Some(StatusCode::FORBIDDEN));
+    }
+
+    #[tokio::test]
+    async fn join_ok() {
+        let mut mock_repo = MockHouseholdRepository::new();
+        mock_repo
+            .expect_get_members()
+            .with(eq("grandpa"))
+            .return_once(|_| Box::pin(async { Ok(Vec::new()) }));
+        let invitation = Invitation {
+            household_id: "grandma".to_owned(),
+            role: Role::Viewer,
+            ..Invitation::default()
+        };
+        let taken = invitation.clone();
+        mock_repo
+            .expect_find_invitation()
+            .with(eq(Uuid::nil()))
+            .return_once(|_| Box::pin(async { Ok(invitation) }));
+        mock_repo
+            .expect_take_invitation()
+            .with(eq(Uuid::nil()))
+            .return_once(|_| Box::pin(async { Ok(taken) }));
+        mock_repo
+            .expect_save_member()
+            .with(function(|member: &Member| {
+                member.subject.eq("grandpa") && member.household_id.eq("grandma")
+            }))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = Json(JoinHousehold { token: Uuid::nil() });
+
+        let actual = services::households::join(state, Principal::new("grandpa"), payload).await;
+
+        assert!(actual.is_ok_and(|Json(member)| member.role == Role::Viewer));
+    }
+
+    #[tokio::test]
+    async fn join_owner_with_members() {
+        let mut mock_repo = MockHouseholdRepository::new();
+        mock_repo.expect_get_members().return_once(|_| {
+            Box::pin(async {
+                Ok(vec![
+                    member("grandpa", "grandpa", Role::Owner),
+                    member("aunt", "grandpa", Role::Editor),
+                ])
+            })
+        });
+        mock_repo.expect_take_invitation().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = Json(JoinHousehold { token: Uuid::nil() });
+
+        let actual = services::households::join(state, Principal::new("grandpa"), payload).await;
+
+        assert_eq!(actual.err(), Some(StatusCode::CONFLICT));
+    }
+
+    #[tokio::test]
+    async fn join_own_household_keeps_invitation() {
+        let mut mock_repo = MockHouseholdRepository::new();
+        mock_repo
+            .expect_get_members()
+            .return_once(|_| Box::pin(async { Ok(Vec::new()) }));
+        mock_repo.expect_find_invitation().return_once(|_| {
+            Box::pin(async {
+                Ok(Invitation {
+                    household_id: "grandpa".to_owned(),
+                    ..Invitation::default()
+                })
+            })
+        });
+        mock_repo.expect_take_invitation().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = Json(JoinHousehold { token: Uuid::nil() });
+
+        let actual = services::households::join(state, Principal::new("grandpa"), payload).await;
+
+        assert_eq!(actual.err(), Some(StatusCode::CONFLICT));
+    }
+
+    #[tokio::test]
+    async fn update_member_of_other_household() {
+        let mut mock_repo = MockHouseholdRepository::new();
+        mock_repo
+            .expect_find_member()
+            .with(eq("aunt"))
+            .return_once(|_| Box::pin(async { Ok(member("aunt", "elsewhere", Role::Viewer)) }));
+        mock_repo.expect_save_member().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::households::update_member(
+            state,
+            principal(Role::Owner),
+            Path("aunt".to_owned()),
+            Json(PutMember { role: Role::Editor }),
+        )
+        .await;
+
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn remove_self_as_viewer() {
+        let mut mock_repo = MockHouseholdRepository::new();
+        mock_repo
+            .expect_delete_member()
+            .with(eq("grandpa"))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::households::remove_member(
+            state,
+            principal(Role::Viewer),
+            Path("grandpa".to_owned()),
+        )
+        .await;
+
+        assert_eq!(actual, Ok(StatusCode::NO_CONTENT));
+    }
+}
@@ -0,0 +1,350 @@
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::auth::Principal;
+use crate::clock;
+use crate::idempotency::{
+    self,
+    IdempotencyRecord,
+    IdempotencyRepository,
+    StoredResponse,
+    IDEMPOTENCY_KEY_HEADER,
+    IDEMPOTENT_REPLAYED_HEADER,
+    MAX_KEY_LENGTH,
+};
+use crate::services::ApplicationContext;
+
+/// The most of a request body that is read to fingerprint it, the payload
+/// limit of Lambda. Smaller limits are left to the extractors.
+const MAX_BODY_BYTES: usize = 6 * 1024 * 1024;
+
+/// What to do with a request that carries an idempotency key.
+#[derive(Debug, PartialEq)]
+enum Begin {
+    /// The key was claimed for this request, which has to be run.
+    Proceed(IdempotencyRecord),
+    /// The request was answered before.
+    Replay(StoredResponse),
+}
+
+/// Middleware that makes POST requests with an `Idempotency-Key` header safe
+/// to retry. The first response for a key is stored for a day and replayed to
+/// retries with the same method, path and body. Reusing a key for a different
+/// request is rejected with unprocessable entity, retrying while the first
+/// request is still running with conflict. Server errors are not stored, so
+/// that the request can be retried.
+pub async fn idempotent<T>(
+    State(state): State<ApplicationContext<T>>,
+    request: Request,
+    next: Next,
+) -> Response
+where
+    T: IdempotencyRepository,
+{
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let Some(value) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    let Some(key) = parse_key(value) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let Some(principal) = request.extensions().get::<Principal>() else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    let key = idempotency::scoped_key(principal.household_id(), principal.subject(), key);
+
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_BODY_BYTES).await else {
+        return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+    };
+    let path = parts
+        .uri
+        .path_and_query()
+        .map_or(parts.uri.path(), |path| path.as_str());
+    let fingerprint = idempotency::fingerprint(parts.method.as_str(), path, &body);
+
+    match begin(&state.repo, &key, &fingerprint, clock::now()).await {
+        Ok(Begin::Proceed(record)) => {
+            let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+            finish(&state.repo, record, response).await
+        },
+        Ok(Begin::Replay(stored)) => replay(stored),
+        Err(status) => status.into_response(),
+    }
+}
+
+fn parse_key(value: &HeaderValue) -> Option<&str> {
+    value
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LENGTH)
+}
+
+/// Looks up the key and claims it unless the request was made before.
+async fn begin<T>(repo: &T, key: &str, fingerprint: &str, now: u64) -> Result<Begin, StatusCode>
+where
+    T: IdempotencyRepository,
+{
+    match repo.find_record(key).await {
+        Ok(record) if record.is_expired(now) => match repo.release(key).await {
+            Ok(()) | Err(StatusCode::NOT_FOUND) => {},
+            Err(status) => return Err(status),
+        },
+        Ok(record) if record.fingerprint != fingerprint => {
+            return Err(StatusCode::UNPROCESSABLE_ENTITY)
+        },
+        Ok(IdempotencyRecord {
+            response: Some(stored),
+            ..
+        }) => return Ok(Begin::Replay(stored)),
+        Ok(_) => return Err(StatusCode::CONFLICT),
+        Err(StatusCode::NOT_FOUND) => {},
+        Err(status) => return Err(status),
+    }
+
+    let record = IdempotencyRecord::pending(key, fingerprint, now);
+    // Another request with the same key may have claimed it in the meantime.
+    repo.claim(&record).await?;
+
+    Ok(Begin::Proceed(record))
+}
+
+/// Stores the response for later retries, or gives up the key if it should not
+/// be replayed.
+async fn finish<T>(repo: &T, mut record: IdempotencyRecord, response: Response) -> Response
+where
+    T: IdempotencyRepository,
+{
+    if response.status().is_server_error() {
+        let _ = repo.release(&record.key).await;
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let Ok(body) = to_bytes(body, usize::MAX).await else {
+        let _ = repo.release(&record.key).await;
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let stored = String::from_utf8(body.to_vec())
+        .ok()
+        .map(|text| StoredResponse {
+            status: parts.status.as_u16(),
+            content_type: parts
+                .headers
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned),
+            body: text,
+        });
+
+    record.response = stored;
+    // A response that cannot be stored is still delivered, retries will then
+    // run the request again.
+    if record.response.is_none() || repo.complete(&record).await.is_err() {
+        let _ = repo.release(&record.key).await;
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let mut response = (status, stored.body).into_response();
+    let headers = response.headers_mut();
+    headers.remove(CONTENT_TYPE);
+    if let Some(value) = stored
+        .content_type
+        .and_then(|content_type| HeaderValue::from_str(&content_type).ok())
+    {
+        headers.insert(CONTENT_TYPE, value);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+
+    response
+}
+
+#[cfg(test)]
+mod test {
+    use axum::body::{to_bytes, Body};
+    use axum::http::header::CONTENT_TYPE;
+    use axum::http::{HeaderValue, StatusCode};
+    use axum::response::{IntoResponse, Response};
+    use mockall::predicate::{eq, function};
+
+    use super::{begin, finish, parse_key, replay, Begin};
+    use crate::idempotency::{
+        IdempotencyRecord,
+        MockIdempotencyRepository,
+        StoredResponse,
+        IDEMPOTENT_REPLAYED_HEADER,
+    };
+
+    fn completed() -> IdempotencyRecord {
+        IdempotencyRecord {
+            response: Some(StoredResponse {
+                status: 201,
+                content_type: Some("application/json".to_owned()),
+                body: r#"{"id":"1"}"#.to_owned(),
+            }),
+            ..IdempotencyRecord::pending("retry", "abc", 100)
+        }
+    }
+
+    #[test]
+    fn parse_malformed_key() {
+        assert_eq!(
+            parse_key(&HeaderValue::from_static(" retry ")),
+            Some("retry")
+        );
+        assert_eq!(parse_key(&HeaderValue::from_static(" ")), None);
+        assert_eq!(
+            parse_key(&HeaderValue::from_str(&"k".repeat(256)).unwrap()),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn begin_claims_new_key() {
+        let mut mock_repo = MockIdempotencyRepository::new();
+        mock_repo
+            .expect_find_record()
+            .return_once(|_| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+        mock_repo
+            .expect_claim()
+            .with(eq(IdempotencyRecord::pending("retry", "abc", 100)))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+
+        let actual = begin(&mock_repo, "retry", "abc", 100).await;
+
+        assert_eq!(
+            actual,
+            Ok(Begin::Proceed(IdempotencyRecord::pending(
+                "retry", "abc", 100
+            )))
+        );
+    }
+
+    #[tokio::test]
+    async fn begin_replays_completed_request() {
+        let mut mock_repo = MockIdempotencyRepository::new();
+        mock_repo
+            .expect_find_record()
+            .return_once(|_| Box::pin(async { Ok(completed()) }));
+
+        let actual = begin(&mock_repo, "retry", "abc", 200).await;
+
+        assert_eq!(actual, Ok(Begin::Replay(completed().response.unwrap())));
+    }
+
+    #[tokio::test]
+    async fn begin_rejects_different_request() {
+        let mut mock_repo = MockIdempotencyRepository::new();
+        mock_repo
+            .expect_find_record()
+            .return_once(|_| Box::pin(async { Ok(completed()) }));
+
+        let actual = begin(&mock_repo, "retry", "def", 200).await;
+
+        assert_eq!(actual, Err(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    #[tokio::test]
+    async fn begin_conflicts_with_running_request() {
+        let mut mock_repo = MockIdempotencyRepository::new();
+        mock_repo.expect_find_record().return_once(|_| {
+            Box::pin(async { Ok(IdempotencyRecord::pending("retry", "abc", 100)) })
+        });
+
+        let actual = begin(&mock_repo, "retry", "abc", 200).await;
+
+        assert_eq!(actual, Err(StatusCode::CONFLICT));
+    }
+
+    #[tokio::test]
+    async fn begin_reclaims_expired_key() {
+        let mut mock_repo = MockIdempotencyRepository::new();
+        mock_repo
+            .expect_find_record()
+            .return_once(|_| Box::pin(async { Ok(completed()) }));
+        mock_repo
+            .expect_release()
+            .with(eq("retry"))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        mock_repo
+            .expect_claim()
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        let now = completed().expires_at;
+
+        let actual = begin(&mock_repo, "retry", "def", now).await;
+
+        assert_eq!(
+            actual,
+            Ok(Begin::Proceed(IdempotencyRecord::pending(
+                "retry", "def", now
+            )))
+        );
+    }
+
+    #[tokio::test]
+    async fn finish_stores_response() {
+        let mut mock_repo = MockIdempotencyRepository::new();
+        mock_repo
+            .expect_complete()
+            .with(function(|record: &IdempotencyRecord| {
+                *record == completed()
+            }))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        let response = Response::builder()
+            .status(StatusCode::CREATED)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"id":"1"}"#))
+            .unwrap();
+
+        let actual = finish(
+            &mock_repo,
+            IdempotencyRecord::pending("retry", "abc", 100),
+            response,
+        )
+        .await;
+
+        assert_eq!(actual.status(), StatusCode::CREATED);
+        let body = to_bytes(actual.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], br#"{"id":"1"}"#);
+    }
+
+    #[tokio::test]
+    async fn finish_releases_key_on_server_error() {
+        let mut mock_repo = MockIdempotencyRepository::new();
+        mock_repo
+            .expect_release()
+            .with(eq("retry"))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+
+        let actual = finish(
+            &mock_repo,
+            IdempotencyRecord::pending("retry", "abc", 100),
+            StatusCode::SERVICE_UNAVAILABLE.into_response(),
+        )
+        .await;
+
+        assert_eq!(actual.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn replay_stored_response() {
+        let actual = replay(completed().response.unwrap());
+
+        assert_eq!(actual.status(), StatusCode::CREATED);
+        assert_eq!(actual.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(actual.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        let body = to_bytes(actual.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], br#"{"id":"1"}"#);
+    }
+}
@@ -0,0 +1,117 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+
+use crate::auth::Principal;
+use crate::household::Role;
+use crate::kitchen::request_models::PutKitchen;
+use crate::kitchen::{Kitchen, KitchenRepository};
+use crate::services::ApplicationContext;
+use crate::validation::ValidatedJson;
+
+/// Returns the appliances the caller's household owns. Households that have
+/// not said what they own are reported as not found.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn read<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+) -> Result<Json<Kitchen>, StatusCode>
+where
+    T: KitchenRepository,
+{
+    let kitchen = state.repo.find_kitchen(principal.household_id()).await?;
+
+    Ok(Json(kitchen))
+}
+
+/// Replaces the appliances the caller's household owns.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn replace<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<PutKitchen>,
+) -> Result<Json<Kitchen>, StatusCode>
+where
+    T: KitchenRepository,
+{
+    principal.require(Role::Editor)?;
+    let kitchen = Kitchen {
+        household_id: principal.household_id().to_owned(),
+        appliances: payload
+            .appliances
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .collect(),
+    };
+    state.repo.save_kitchen(&kitchen).await?;
+
+    Ok(Json(kitchen))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use mockall::predicate::eq;
+
+    use crate::auth::Principal;
+    use crate::household::{Member, Role};
+    use crate::kitchen::request_models::PutKitchen;
+    use crate::kitchen::{Appliance, Kitchen, MockKitchenRepository};
+    use crate::services::{self, ApplicationContext};
+    use crate::validation::ValidatedJson;
+
+    const HOUSEHOLD: &str = "grandma";
+
+    #[tokio::test]
+    async fn replace_leaves_out_missing_appliances() {
+        let expected = Kitchen {
+            household_id: HOUSEHOLD.to_owned(),
+            appliances: BTreeMap::from([(Appliance::Oven, 1)]),
+        };
+        let mut mock_repo = MockKitchenRepository::new();
+        mock_repo
+            .expect_save_kitchen()
+            .with(eq(expected.clone()))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = PutKitchen {
+            appliances: BTreeMap::from([(Appliance::Oven, 1), (Appliance::SousVide, 0)]),
+        };
+
+        let actual =
+            services::kitchens::replace(state, Principal::new(HOUSEHOLD), ValidatedJson(payload))
+                .await;
+
+        assert_eq!(actual.map(|json| json.0), Ok(expected));
+    }
+
+    #[tokio::test]
+    async fn replace_as_viewer() {
+        let mut mock_repo = MockKitchenRepository::new();
+        mock_repo.expect_save_kitchen().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+        let principal = Principal::new("grandpa").with_membership(&Member {
+            subject: "grandpa".to_owned(),
+            household_id: HOUSEHOLD.to_owned(),
+            role: Role::Viewer,
+            joined_at: 0,
+        });
+
+        let actual =
+            services::kitchens::replace(state, principal, ValidatedJson(PutKitchen::default()))
+                .await;
+
+        assert_eq!(actual.err(), Some(StatusCode::FORBIDDEN));
+    }
+}
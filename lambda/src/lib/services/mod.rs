@@ -1,4 +1,14 @@
+pub mod api_keys;
+pub mod cook_log;
+pub mod events;
+pub mod households;
+pub mod idempotency;
+pub mod kitchens;
+pub mod plans;
 pub mod recipes;
+pub mod shopping_lists;
+pub mod timeline;
+pub mod webhooks;
 
 #[derive(Clone)]
 pub struct ApplicationContext<T> {
@@ -0,0 +1,533 @@
+use std::collections::HashMap;
+
+use axum::extract::{Path, Query, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use uuid::Uuid;
+
+use crate::auth::Principal;
+use crate::household::{HouseholdRepository, Role};
+use crate::kitchen::{self, KitchenRepository};
+use crate::plan::calendar::{self, RecipeLinks};
+use crate::plan::conflicts::{self, MealConflicts};
+use crate::plan::request_models::{PostMealPlan, ReadCalendar};
+use crate::plan::{mapper, CalendarFeed, CalendarFeedRepository, MealPlan};
+use crate::recipe::Recipe;
+use crate::services::api_keys::require_interactive;
+use crate::services::households::current_role;
+use crate::services::ApplicationContext;
+use crate::validation::ValidatedJson;
+use crate::{clock, Repository};
+
+/// Lists the meal plans of the caller's household.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn list<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+) -> Result<Json<Vec<MealPlan>>, StatusCode>
+where
+    T: Repository<MealPlan>,
+{
+    let plans = state.repo.get_all(principal.household_id()).await?;
+
+    Ok(Json(plans))
+}
+
+/// Attempts to create a meal plan in the database.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn create<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<PostMealPlan>,
+) -> Result<(StatusCode, Json<MealPlan>), StatusCode>
+where
+    T: Repository<MealPlan>,
+{
+    principal.require(Role::Editor)?;
+    let plan = mapper::map_post_meal_plan(Uuid::new_v4(), principal.household_id(), &payload);
+    state.repo.save(&plan).await?;
+
+    Ok((StatusCode::CREATED, Json(plan)))
+}
+
+/// Attempts to find a meal plan in the database given the uuid.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn read_one<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<Json<MealPlan>, StatusCode>
+where
+    T: Repository<MealPlan>,
+{
+    let plan = state.repo.find_by_id(principal.household_id(), id).await?;
+
+    Ok(Json(plan))
+}
+
+/// Attempts to replace the contents of an existing meal plan.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn replace<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<PostMealPlan>,
+) -> Result<Json<MealPlan>, StatusCode>
+where
+    T: Repository<MealPlan>,
+{
+    principal.require(Role::Editor)?;
+    state.repo.find_by_id(principal.household_id(), id).await?;
+    let plan = mapper::map_post_meal_plan(id, principal.household_id(), &payload);
+    state.repo.save(&plan).await?;
+
+    Ok(Json(plan))
+}
+
+/// Attempts to delete a meal plan in the database given the uuid.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn delete_one<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, StatusCode>
+where
+    T: Repository<MealPlan>,
+{
+    principal.require(Role::Editor)?;
+    state
+        .repo
+        .delete_by_id(principal.household_id(), id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Finds the meals of a plan that need more cookware at once than the
+/// household's kitchen has, like two dishes served together that need the
+/// oven at different temperatures. Recipes in the trash are left out.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn conflicts<T, R, K>(
+    State(state): State<ApplicationContext<T>>,
+    State(recipe_state): State<ApplicationContext<R>>,
+    State(kitchen_state): State<ApplicationContext<K>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<MealConflicts>>, StatusCode>
+where
+    T: Repository<MealPlan>,
+    R: Repository<Recipe>,
+    K: KitchenRepository,
+{
+    let plan = state.repo.find_by_id(principal.household_id(), id).await?;
+    let recipe_ids: Vec<Uuid> = plan.slots.iter().map(|slot| slot.recipe_id).collect();
+    let recipes: HashMap<Uuid, Recipe> = recipe_state
+        .repo
+        .find_by_ids(principal.household_id(), &recipe_ids)
+        .await?
+        .into_iter()
+        .filter(|recipe| !recipe.is_deleted())
+        .map(|recipe| (recipe.id, recipe))
+        .collect();
+    let kitchen = kitchen::find_declared(&kitchen_state.repo, principal.household_id()).await?;
+
+    Ok(Json(conflicts::find(&plan, &recipes, kitchen.as_ref())))
+}
+
+/// Returns the caller's calendar feed, creating it on first use. Its token
+/// lets calendar apps subscribe to every plan of the household at
+/// `/plans/{id}/calendar.ics?token={token}`, and stays the same until it is
+/// revoked.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn create_feed<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+) -> Result<(StatusCode, Json<CalendarFeed>), StatusCode>
+where
+    T: CalendarFeedRepository,
+{
+    require_interactive(&principal)?;
+    match state
+        .repo
+        .find_member_feed(principal.household_id(), principal.subject())
+        .await
+    {
+        Ok(feed) => return Ok((StatusCode::OK, Json(feed))),
+        Err(StatusCode::NOT_FOUND) => {},
+        Err(status) => return Err(status),
+    }
+
+    let feed = CalendarFeed::generate(principal.household_id(), principal.subject(), clock::now());
+    state.repo.create_feed(&feed).await?;
+
+    Ok((StatusCode::CREATED, Json(feed)))
+}
+
+/// Attempts to revoke the caller's calendar feed. Calendars subscribed to it
+/// stop updating, and the next feed gets a new token.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn revoke_feed<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+) -> Result<StatusCode, StatusCode>
+where
+    T: CalendarFeedRepository,
+{
+    require_interactive(&principal)?;
+    let feed = state
+        .repo
+        .find_member_feed(principal.household_id(), principal.subject())
+        .await?;
+    state.repo.delete_feed(&feed.token).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Renders a meal plan as an iCalendar feed for whoever holds the calendar
+/// feed token of a member of its household. Recipes in the trash are left
+/// out of the events.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error. Unknown tokens and tokens of members who have since
+/// left the household are reported as not found.
+pub async fn calendar<T, R, H>(
+    State(state): State<ApplicationContext<T>>,
+    State(recipe_state): State<ApplicationContext<R>>,
+    State(household_state): State<ApplicationContext<H>>,
+    State(links): State<RecipeLinks>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ReadCalendar>,
+) -> Result<Response, StatusCode>
+where
+    T: Repository<MealPlan> + CalendarFeedRepository,
+    R: Repository<Recipe>,
+    H: HouseholdRepository,
+{
+    let feed = state.repo.find_feed(&query.token).await?;
+    current_role(&household_state.repo, &feed.subject, &feed.household_id)
+        .await?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let plan = state.repo.find_by_id(&feed.household_id, id).await?;
+    let recipe_ids: Vec<Uuid> = plan.slots.iter().map(|slot| slot.recipe_id).collect();
+    let recipes: HashMap<Uuid, Recipe> = recipe_state
+        .repo
+        .find_by_ids(&feed.household_id, &recipe_ids)
+        .await?
+        .into_iter()
+        .filter(|recipe| !recipe.is_deleted())
+        .map(|recipe| (recipe.id, recipe))
+        .collect();
+
+    let body = calendar::render(&plan, &recipes, &links, clock::now());
+    Ok(([(CONTENT_TYPE, calendar::CONTENT_TYPE)], body).into_response())
+}
+
+#[cfg(test)]
+mod test {
+    use axum::body::to_bytes;
+    use axum::extract::{Path, Query, State};
+    use axum::http::header::CONTENT_TYPE;
+    use axum::http::StatusCode;
+    use mockall::predicate::{eq, function};
+    use uuid::Uuid;
+
+    use crate::api_key::{ApiKey, Scope};
+    use crate::auth::Principal;
+    use crate::household::{Member, MockHouseholdRepository, Role};
+    use crate::kitchen::{Appliance, Cookware, MockKitchenRepository};
+    use crate::plan::calendar::RecipeLinks;
+    use crate::plan::request_models::ReadCalendar;
+    use crate::plan::{CalendarFeed, MealPlan, MealSlot, MockMealPlanRepository};
+    use crate::recipe::{MockRecipeRepository, Recipe, Step};
+    use crate::services::{self, ApplicationContext};
+
+    const HOUSEHOLD: &str = "grandma";
+
+    fn feed() -> CalendarFeed {
+        CalendarFeed {
+            token: "cal_secret".to_owned(),
+            household_id: HOUSEHOLD.to_owned(),
+            subject: HOUSEHOLD.to_owned(),
+            created_at: 100,
+        }
+    }
+
+    #[tokio::test]
+    async fn create_feed_returns_existing() {
+        let mut mock_repo = MockMealPlanRepository::new();
+        mock_repo
+            .expect_find_member_feed()
+            .with(eq(HOUSEHOLD), eq(HOUSEHOLD))
+            .return_once(|_, _| Box::pin(async { Ok(feed()) }));
+        mock_repo.expect_create_feed().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::plans::create_feed(state, Principal::new(HOUSEHOLD)).await;
+
+        let (status, feed) = actual.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(feed.token, "cal_secret");
+    }
+
+    #[tokio::test]
+    async fn create_feed_once() {
+        let mut mock_repo = MockMealPlanRepository::new();
+        mock_repo
+            .expect_find_member_feed()
+            .return_once(|_, _| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+        mock_repo
+            .expect_create_feed()
+            .with(function(|feed: &CalendarFeed| {
+                feed.token.starts_with("cal_") && feed.household_id == HOUSEHOLD
+            }))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::plans::create_feed(state, Principal::new(HOUSEHOLD)).await;
+
+        assert!(actual.is_ok_and(|(status, _)| status == StatusCode::CREATED));
+    }
+
+    #[tokio::test]
+    async fn create_feed_with_api_key() {
+        let mut mock_repo = MockMealPlanRepository::new();
+        mock_repo.expect_create_feed().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+        let principal = Principal::from_api_key(&ApiKey {
+            scope: Scope::Read,
+            ..ApiKey::default()
+        });
+
+        let actual = services::plans::create_feed(state, principal).await;
+
+        assert_eq!(actual.err(), Some(StatusCode::FORBIDDEN));
+    }
+
+    #[tokio::test]
+    async fn conflicts_of_meals_served_together() {
+        let plan = MealPlan {
+            household_id: HOUSEHOLD.to_owned(),
+            slots: vec![
+                MealSlot {
+                    recipe_id: Uuid::from_u128(1),
+                    ..MealSlot::default()
+                },
+                MealSlot {
+                    recipe_id: Uuid::from_u128(2),
+                    ..MealSlot::default()
+                },
+            ],
+            ..MealPlan::default()
+        };
+        let mut mock_repo = MockMealPlanRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(move |_, _| Box::pin(async move { Ok(plan) }));
+        let mixer = |id, passive| Recipe {
+            id: Uuid::from_u128(id),
+            steps: vec![Step {
+                instruction: "Knead".to_owned(),
+                minutes: Some(10),
+                passive,
+                equipment: vec![Cookware {
+                    appliance: Appliance::StandMixer,
+                    celsius: None,
+                    count: None,
+                }],
+                ..Step::default()
+            }],
+            ..Recipe::default()
+        };
+        let mut mock_recipes = MockRecipeRepository::new();
+        mock_recipes.expect_find_by_ids().return_once(move |_, _| {
+            Box::pin(async move { Ok(vec![mixer(1, true), mixer(2, true)]) })
+        });
+        let mut mock_kitchens = MockKitchenRepository::new();
+        mock_kitchens
+            .expect_find_kitchen()
+            .with(eq(HOUSEHOLD))
+            .return_once(|_| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+
+        let actual = services::plans::conflicts(
+            State(ApplicationContext { repo: mock_repo }),
+            State(ApplicationContext { repo: mock_recipes }),
+            State(ApplicationContext {
+                repo: mock_kitchens,
+            }),
+            Principal::new(HOUSEHOLD),
+            Path(Uuid::nil()),
+        )
+        .await
+        .unwrap();
+
+        // Both doughs knead unattended at the same time, in a single mixer.
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].serve_at, "2024-01-01T18:30");
+        assert_eq!(actual[0].conflicts[0].needed, 2);
+    }
+
+    #[tokio::test]
+    async fn calendar_leaves_out_trashed_recipes() {
+        let plan = MealPlan {
+            household_id: HOUSEHOLD.to_owned(),
+            slots: vec![
+                MealSlot {
+                    recipe_id: Uuid::from_u128(1),
+                    ..MealSlot::default()
+                },
+                MealSlot {
+                    recipe_id: Uuid::from_u128(2),
+                    ..MealSlot::default()
+                },
+            ],
+            ..MealPlan::default()
+        };
+        let mut mock_repo = MockMealPlanRepository::new();
+        mock_repo
+            .expect_find_feed()
+            .with(eq("cal_secret"))
+            .return_once(|_| Box::pin(async { Ok(feed()) }));
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(move |_, _| Box::pin(async move { Ok(plan) }));
+        let mut mock_recipes = MockRecipeRepository::new();
+        mock_recipes
+            .expect_find_by_ids()
+            .with(
+                eq(HOUSEHOLD),
+                eq(vec![Uuid::from_u128(1), Uuid::from_u128(2)]),
+            )
+            .return_once(|_, _| {
+                Box::pin(async {
+                    Ok(vec![
+                        Recipe {
+                            id: Uuid::from_u128(1),
+                            name: "Soup".to_owned(),
+                            ..Recipe::default()
+                        },
+                        Recipe {
+                            id: Uuid::from_u128(2),
+                            name: "Stew".to_owned(),
+                            deleted_at: Some(100),
+                            ..Recipe::default()
+                        },
+                    ])
+                })
+            });
+
+        let mut mock_households = MockHouseholdRepository::new();
+        mock_households
+            .expect_find_member()
+            .with(eq(HOUSEHOLD))
+            .return_once(|_| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+
+        let actual = services::plans::calendar(
+            State(ApplicationContext { repo: mock_repo }),
+            State(ApplicationContext { repo: mock_recipes }),
+            State(ApplicationContext {
+                repo: mock_households,
+            }),
+            State(RecipeLinks::new("https://api.example.com/recipes")),
+            Path(Uuid::nil()),
+            Query(ReadCalendar {
+                token: "cal_secret".to_owned(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            actual.headers().get(CONTENT_TYPE).unwrap(),
+            "text/calendar; charset=utf-8"
+        );
+        let body = to_bytes(actual.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("SUMMARY:Dinner: Soup\r\n"));
+        assert!(body.contains("SUMMARY:Dinner\r\n"));
+        assert!(!body.contains("Stew"));
+    }
+
+    #[tokio::test]
+    async fn calendar_of_former_member() {
+        let mut mock_repo = MockMealPlanRepository::new();
+        mock_repo.expect_find_feed().return_once(|_| {
+            Box::pin(async {
+                Ok(CalendarFeed {
+                    subject: "grandpa".to_owned(),
+                    ..feed()
+                })
+            })
+        });
+        mock_repo.expect_find_by_id().never();
+        let mut mock_households = MockHouseholdRepository::new();
+        mock_households
+            .expect_find_member()
+            .with(eq("grandpa"))
+            .return_once(|_| {
+                Box::pin(async {
+                    Ok(Member {
+                        subject: "grandpa".to_owned(),
+                        household_id: "grandpa".to_owned(),
+                        role: Role::Owner,
+                        ..Member::default()
+                    })
+                })
+            });
+
+        let actual = services::plans::calendar(
+            State(ApplicationContext { repo: mock_repo }),
+            State(ApplicationContext {
+                repo: MockRecipeRepository::new(),
+            }),
+            State(ApplicationContext {
+                repo: mock_households,
+            }),
+            State(RecipeLinks::new("https://api.example.com/recipes")),
+            Path(Uuid::nil()),
+            Query(ReadCalendar {
+                token: "cal_secret".to_owned(),
+            }),
+        )
+        .await;
+
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+}
@@ -1,26 +1,121 @@
-use axum::extract::{Path, State};
-use axum::http::StatusCode;
+use std::collections::{HashMap, HashSet};
+
+use axum::extract::{Path, Query, State};
+use axum::http::header::ACCEPT;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
 use axum::Json;
 use uuid::Uuid;
 
-use crate::recipe::request_models::{PatchRecipe, PostRecipe, PutRecipe};
-use crate::recipe::{mapper, Recipe};
+use crate::auth::Principal;
+use crate::cook_log::{self, CookLogRepository};
+use crate::household::Role;
+use crate::kitchen::{self, KitchenRepository};
+use crate::recipe::batch::{
+    BatchParams,
+    BatchResponse,
+    BatchResult,
+    PostBatch,
+    RecipeWrite,
+    MAX_ATOMIC_OPERATIONS,
+};
+use crate::recipe::component::{self, ExpandedRecipe};
+use crate::recipe::patch::RecipePatch;
+use crate::recipe::request_models::{
+    DeleteRecipe,
+    ForkRecipe,
+    ListRecipes,
+    ListSubstitutions,
+    PostRecipe,
+    PostShare,
+    PutRecipe,
+    ReadRecipe,
+};
+use crate::recipe::revision::RevisionResponse;
+use crate::recipe::share::{self, ShareLink, SharedRecipe};
+use crate::recipe::substitution::{self, SubstitutionPreview};
+use crate::recipe::variant::{self, RecipeDiff};
+use crate::recipe::{
+    mapper,
+    BatchRepository,
+    Recipe,
+    RevisionRepository,
+    ShareRepository,
+    TrashRepository,
+};
 use crate::services::ApplicationContext;
-use crate::Repository;
+use crate::validation::{RequestError, Validate, ValidatedJson, Violations};
+use crate::{clock, Repository};
 
 /// Lists all recipes from the database.
 ///
+/// With `ids`, only the recipes with those ids are listed, in the same order.
+/// Ids of recipes that do not exist are skipped. With `cookable`, recipes that
+/// need cookware the household does not own are left out. With `sort`, the
+/// recipes are ordered by their cook log instead and come with how often and
+/// how well the household has cooked them.
+///
 /// # Errors
 ///
 /// This function converts the result of the database operation to a status code
 /// wrapped in an error.
-pub async fn list<T>(
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "recipes",
+    params(ListRecipes),
+    responses((status = 200, description = "The recipes of the household", body = [Recipe])),
+)]
+pub async fn list<T, K, L>(
     State(state): State<ApplicationContext<T>>,
+    State(kitchen_state): State<ApplicationContext<K>>,
+    State(log_state): State<ApplicationContext<L>>,
+    principal: Principal,
+    Query(params): Query<ListRecipes>,
 ) -> Result<Json<Vec<Recipe>>, StatusCode>
 where
     T: Repository<Recipe>,
+    K: KitchenRepository,
+    L: CookLogRepository,
 {
-    let recipes = state.repo.get_all().await?;
+    let recipes = match params.ids {
+        Some(ids) => {
+            state
+                .repo
+                .find_by_ids(principal.household_id(), &ids)
+                .await?
+        },
+        None => state.repo.get_all(principal.household_id()).await?,
+    };
+
+    let kitchen = if params.cookable {
+        kitchen::find_declared(&kitchen_state.repo, principal.household_id()).await?
+    } else {
+        None
+    };
+    // The whole cook log is only worth reading when the recipes are ordered
+    // by it.
+    let mut stats = match params.sort {
+        Some(_) => cook_log::stats(&log_state.repo.get_entries(principal.household_id()).await?),
+        None => HashMap::new(),
+    };
+
+    let mut recipes: Vec<Recipe> = recipes
+        .into_iter()
+        .filter(|recipe| !recipe.is_deleted())
+        .filter(|recipe| {
+            kitchen
+                .as_ref()
+                .is_none_or(|kitchen| kitchen.can_cook(recipe))
+        })
+        .map(|recipe| Recipe {
+            stats: stats.remove(&recipe.id),
+            ..recipe
+        })
+        .collect();
+    if let Some(sort) = params.sort {
+        cook_log::sort(&mut recipes, sort, cook_log::today(clock::now()));
+    }
 
     Ok(Json(recipes))
 }
@@ -30,19 +125,50 @@ where
 /// # Errors
 ///
 /// This function converts the result of the database operation to a status code
-/// wrapped in an error.
+/// wrapped in an error. Responds with not found if the recipe is in the trash.
+#[utoipa::path(
+    put,
+    path = "/",
+    tag = "recipes",
+    request_body = PutRecipe,
+    responses(
+        (status = 200, description = "The recipe was replaced", body = Recipe),
+        (status = 201, description = "The recipe was created", body = Recipe),
+        (status = 403, description = "The caller cannot change recipes"),
+        (status = 404, description = "The recipe is in the trash"),
+        (status = 422, description = "The recipe breaks some rules", body = Violations),
+    ),
+)]
 pub async fn write<T>(
     State(state): State<ApplicationContext<T>>,
-    Json(payload): Json<PutRecipe>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<PutRecipe>,
 ) -> Result<(StatusCode, Json<Recipe>), StatusCode>
 where
-    T: Repository<Recipe>,
+    T: Repository<Recipe> + RevisionRepository,
 {
-    let recipe = mapper::map_put_recipe(&payload);
+    principal.require(Role::Editor)?;
+    let recipe = mapper::map_put_recipe(principal.household_id(), &payload);
 
-    let save_result: Option<Recipe> = state.repo.save(&recipe).await?;
+    let existing = match state
+        .repo
+        .find_by_id(principal.household_id(), recipe.id)
+        .await
+    {
+        Ok(existing) => Some(existing),
+        Err(StatusCode::NOT_FOUND) => None,
+        Err(err) => return Err(err),
+    };
+    // Recipes in the trash have to be restored before they can be changed.
+    if existing.as_ref().is_some_and(Recipe::is_deleted) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    state
+        .repo
+        .save_revision(&recipe, Some(principal.subject().to_owned()))
+        .await?;
 
-    match save_result {
+    match existing {
         Some(_) => Ok((StatusCode::OK, Json(recipe))),
         None => Ok((StatusCode::CREATED, Json(recipe))),
     }
@@ -54,116 +180,917 @@ where
 ///
 /// This function converts the result of the database operation to a status code
 /// wrapped in an error.
+#[utoipa::path(
+    post,
+    path = "/",
+    tag = "recipes",
+    request_body = PostRecipe,
+    responses(
+        (status = 201, description = "The recipe was created", body = Recipe),
+        (status = 403, description = "The caller cannot change recipes"),
+        (status = 422, description = "The recipe breaks some rules", body = Violations),
+    ),
+)]
 pub async fn create<T>(
     State(state): State<ApplicationContext<T>>,
-    Json(payload): Json<PostRecipe>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<PostRecipe>,
 ) -> Result<(StatusCode, Json<Recipe>), StatusCode>
 where
-    T: Repository<Recipe>,
+    T: Repository<Recipe> + RevisionRepository,
 {
-    let recipe = mapper::map_post_recipe(Uuid::new_v4(), &payload);
-    state.repo.save(&recipe).await?;
+    principal.require(Role::Editor)?;
+    let recipe = mapper::map_post_recipe(Uuid::new_v4(), principal.household_id(), &payload);
+    state
+        .repo
+        .save_revision(&recipe, Some(principal.subject().to_owned()))
+        .await?;
 
     Ok((StatusCode::CREATED, Json(recipe)))
 }
 
-/// Attempts to find a recipe in the database given the uuid.
+/// Attempts to find a recipe in the database given the uuid, with how often and
+/// how well the household has cooked it.
+///
+/// With `expand`, the recipes its ingredients refer to are included with scaled
+/// quantities.
 ///
 /// # Errors
 ///
 /// This function converts the result of the database operation to a status code
-/// wrapped in an error.
-pub async fn read_one<T>(
+/// wrapped in an error. Responds with unprocessable entity if the components of
+/// the recipe refer back to it, nest too deep or are too many.
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe"), ReadRecipe),
+    responses(
+        (status = 200, description = "The recipe", body = ExpandedRecipe),
+        (status = 404, description = "The recipe does not exist"),
+        (status = 422, description = "The components of the recipe refer back to it, nest too deep or are too many"),
+    ),
+)]
+pub async fn read_one<T, L>(
     State(state): State<ApplicationContext<T>>,
+    State(log_state): State<ApplicationContext<L>>,
+    principal: Principal,
     Path(id): Path<Uuid>,
-) -> Result<Json<Recipe>, StatusCode>
+    Query(params): Query<ReadRecipe>,
+) -> Result<Json<ExpandedRecipe>, StatusCode>
 where
     T: Repository<Recipe>,
+    L: CookLogRepository,
 {
-    let recipe = state.repo.find_by_id(id).await?;
+    let mut recipe = find_active(&state.repo, principal.household_id(), id).await?;
+    let entries = log_state
+        .repo
+        .get_recipe_entries(principal.household_id(), id)
+        .await?;
+    recipe.stats = cook_log::stats(&entries).remove(&id);
+    if !params.expand {
+        return Ok(Json(recipe.into()));
+    }
 
-    Ok(Json(recipe))
+    let components = load_components(
+        &state.repo,
+        principal.household_id(),
+        std::slice::from_ref(&recipe),
+    )
+    .await?;
+    let expanded =
+        component::expand(&recipe, &components).map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+
+    Ok(Json(expanded))
+}
+
+/// Finds a recipe, treating recipes in the trash as not found.
+pub(crate) async fn find_active<T>(
+    repo: &T,
+    household_id: &str,
+    id: Uuid,
+) -> Result<Recipe, StatusCode>
+where
+    T: Repository<Recipe>,
+{
+    let recipe = repo.find_by_id(household_id, id).await?;
+    if recipe.is_deleted() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    Ok(recipe)
+}
+
+/// Loads every recipe reachable through the components of `roots`, including
+/// the roots themselves. Components that no longer exist or are in the trash
+/// are skipped. Each level of components is read in a single batch, down to one
+/// level past the deepest that can be expanded.
+pub(crate) async fn load_components<T>(
+    repo: &T,
+    household_id: &str,
+    roots: &[Recipe],
+) -> Result<HashMap<Uuid, Recipe>, StatusCode>
+where
+    T: Repository<Recipe>,
+{
+    let mut recipes: HashMap<Uuid, Recipe> = roots
+        .iter()
+        .map(|recipe| (recipe.id, recipe.clone()))
+        .collect();
+    let mut missing = HashSet::new();
+    let mut pending: Vec<Uuid> = roots.iter().flat_map(component::component_ids).collect();
+
+    for _ in 0..=component::MAX_DEPTH {
+        pending.retain(|id| !recipes.contains_key(id) && !missing.contains(id));
+        if pending.is_empty() {
+            break;
+        }
+
+        let mut next = Vec::new();
+        for recipe in repo.find_by_ids(household_id, &pending).await? {
+            if !recipe.is_deleted() {
+                next.extend(component::component_ids(&recipe));
+                recipes.insert(recipe.id, recipe);
+            }
+        }
+        missing.extend(pending.into_iter().filter(|id| !recipes.contains_key(id)));
+        pending = next;
+    }
+
+    Ok(recipes)
 }
 
 /// Attempts to update a recipe in the database given the uuid.
 ///
+/// Besides plain JSON with the fields to replace, the body can be a JSON Merge
+/// Patch or a JSON Patch, applied to the recipe as it is returned by reads.
+///
 /// # Errors
 ///
 /// This function converts the result of the database operation to a status code
-/// wrapped in an error.
+/// wrapped in an error, or returns the rules the patched recipe breaks.
+#[utoipa::path(
+    patch,
+    path = "/{id}",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe")),
+    request_body(
+        content = PatchRecipe,
+        description = "The fields to replace. Also accepts application/merge-patch+json and application/json-patch+json documents",
+    ),
+    responses(
+        (status = 200, description = "The updated recipe", body = Recipe),
+        (status = 403, description = "The caller cannot change recipes"),
+        (status = 404, description = "The recipe does not exist"),
+        (status = 409, description = "A test operation of the patch failed"),
+        (status = 422, description = "The patched recipe breaks some rules", body = Violations),
+    ),
+)]
 pub async fn update<T>(
     State(state): State<ApplicationContext<T>>,
+    principal: Principal,
     Path(id): Path<Uuid>,
-    Json(payload): Json<PatchRecipe>,
-) -> Result<Json<Recipe>, StatusCode>
+    patch: RecipePatch,
+) -> Result<Json<Recipe>, RequestError>
 where
-    T: Repository<Recipe>,
+    T: Repository<Recipe> + RevisionRepository,
 {
-    let mut recipe = state.repo.find_by_id(id).await?;
-    mapper::update_recipe(&mut recipe, &payload);
+    principal.require(Role::Editor)?;
+    let recipe = find_active(&state.repo, principal.household_id(), id).await?;
+    let recipe = patch.apply(&recipe)?;
 
-    state.repo.save(&recipe).await?;
+    state
+        .repo
+        .save_revision(&recipe, Some(principal.subject().to_owned()))
+        .await?;
 
     Ok(Json(recipe))
 }
 
-/// Attempts to delete a recipe in the database given the uuid.
+/// Attempts to move a recipe in the database to the trash given the uuid.
+///
+/// With `permanent`, the recipe is removed immediately instead.
 ///
 /// # Errors
 ///
 /// This function converts the result of the database operation to a status code
 /// wrapped in an error.
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe"), DeleteRecipe),
+    responses(
+        (status = 204, description = "The recipe was deleted"),
+        (status = 403, description = "The caller cannot change recipes"),
+        (status = 404, description = "The recipe does not exist"),
+    ),
+)]
 pub async fn delete_one<T>(
     State(state): State<ApplicationContext<T>>,
+    principal: Principal,
     Path(id): Path<Uuid>,
+    Query(params): Query<DeleteRecipe>,
 ) -> Result<StatusCode, StatusCode>
+where
+    T: Repository<Recipe> + TrashRepository,
+{
+    principal.require(Role::Editor)?;
+    if params.permanent {
+        state
+            .repo
+            .delete_by_id(principal.household_id(), id)
+            .await?;
+    } else {
+        state.repo.soft_delete(principal.household_id(), id).await?;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Runs a batch of creates, updates and deletes in one request.
+///
+/// Responds with the outcome of every operation, in order. With `atomic`,
+/// either all operations succeed or none does.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error, or returns the rules the operations break.
+#[utoipa::path(
+    post,
+    path = "/batch",
+    tag = "recipes",
+    params(BatchParams),
+    request_body = PostBatch,
+    responses(
+        (status = 200, description = "The outcome of every operation", body = BatchResponse),
+        (status = 403, description = "The caller cannot change recipes"),
+        (status = 422, description = "Some operations break some rules", body = Violations),
+    ),
+)]
+pub async fn batch<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Query(params): Query<BatchParams>,
+    ValidatedJson(payload): ValidatedJson<PostBatch>,
+) -> Result<Json<BatchResponse>, RequestError>
+where
+    T: BatchRepository,
+{
+    principal.require(Role::Editor)?;
+    if params.atomic && payload.operations.len() > MAX_ATOMIC_OPERATIONS {
+        return Err(Violations::single(
+            "/operations",
+            &format!("must have at most {MAX_ATOMIC_OPERATIONS} items when atomic"),
+        )
+        .into());
+    }
+
+    let writes: Vec<RecipeWrite> = payload
+        .operations
+        .iter()
+        .map(|operation| mapper::map_batch_operation(principal.household_id(), operation))
+        .collect();
+    let outcomes = state
+        .repo
+        .write_batch(&writes, params.atomic, Some(principal.subject().to_owned()))
+        .await?;
+
+    let results = writes
+        .iter()
+        .zip(outcomes)
+        .map(|(write, outcome)| {
+            let status = match (outcome, write) {
+                (Err(status), _) => status,
+                (Ok(()), RecipeWrite::Create(_)) => StatusCode::CREATED,
+                (Ok(()), RecipeWrite::Replace(_)) => StatusCode::OK,
+                (Ok(()), RecipeWrite::Trash { .. }) => StatusCode::NO_CONTENT,
+            };
+            BatchResult {
+                id: write.id(),
+                status: status.as_u16(),
+            }
+        })
+        .collect();
+
+    Ok(Json(BatchResponse { results }))
+}
+
+/// Lists the recipes in the trash that have not been purged yet.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    get,
+    path = "/trash",
+    tag = "recipes",
+    responses((status = 200, description = "The recipes in the trash", body = [Recipe])),
+)]
+pub async fn list_trash<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+) -> Result<Json<Vec<Recipe>>, StatusCode>
+where
+    T: Repository<Recipe>,
+{
+    let now = clock::now();
+    let recipes = state
+        .repo
+        .get_all(principal.household_id())
+        .await?
+        .into_iter()
+        .filter(|recipe| recipe.is_deleted() && recipe.expires_at.is_none_or(|at| at > now))
+        .collect();
+
+    Ok(Json(recipes))
+}
+
+/// Attempts to take a recipe back out of the trash given the uuid.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    post,
+    path = "/trash/{id}/restore",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe")),
+    responses(
+        (status = 200, description = "The restored recipe", body = Recipe),
+        (status = 403, description = "The caller cannot change recipes"),
+        (status = 404, description = "The recipe is not in the trash"),
+    ),
+)]
+pub async fn restore_from_trash<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Recipe>, StatusCode>
+where
+    T: TrashRepository,
+{
+    principal.require(Role::Editor)?;
+    let recipe = state.repo.restore(principal.household_id(), id).await?;
+
+    Ok(Json(recipe))
+}
+
+/// Attempts to create a new recipe as a variant of an existing one.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error, or returns the rules the body breaks.
+#[utoipa::path(
+    post,
+    path = "/{id}/fork",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe")),
+    request_body = Option<ForkRecipe>,
+    responses(
+        (status = 201, description = "The new variant", body = Recipe),
+        (status = 403, description = "The caller cannot change recipes"),
+        (status = 404, description = "The recipe does not exist"),
+        (status = 422, description = "The body breaks some rules", body = Violations),
+    ),
+)]
+pub async fn fork<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+    payload: Option<Json<ForkRecipe>>,
+) -> Result<(StatusCode, Json<Recipe>), RequestError>
+where
+    T: Repository<Recipe> + RevisionRepository,
+{
+    principal.require(Role::Editor)?;
+    let parent = find_active(&state.repo, principal.household_id(), id).await?;
+    let Json(payload) = payload.unwrap_or_default();
+    // The body is optional, so it cannot be validated while it is extracted.
+    payload.validate()?;
+    let recipe = mapper::map_fork_recipe(Uuid::new_v4(), &parent, &payload);
+
+    state
+        .repo
+        .save_revision(&recipe, Some(principal.subject().to_owned()))
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(recipe)))
+}
+
+/// Lists the recipes that were forked directly from the given recipe.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    get,
+    path = "/{id}/variants",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe")),
+    responses(
+        (status = 200, description = "The variants of the recipe", body = [Recipe]),
+        (status = 404, description = "The recipe does not exist"),
+    ),
+)]
+pub async fn list_variants<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<Recipe>>, StatusCode>
+where
+    T: Repository<Recipe>,
+{
+    let parent = find_active(&state.repo, principal.household_id(), id).await?;
+    let variants = state
+        .repo
+        .get_all(principal.household_id())
+        .await?
+        .into_iter()
+        .filter(|recipe| recipe.parent_id == Some(parent.id) && !recipe.is_deleted())
+        .collect();
+
+    Ok(Json(variants))
+}
+
+/// Compares a variant with the recipe it was forked from.
+///
+/// Ingredients and steps are compared one by one. Responds with not found if
+/// the recipe is not a variant.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    get,
+    path = "/{id}/diff",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe")),
+    responses(
+        (status = 200, description = "The differences to the parent", body = RecipeDiff),
+        (status = 404, description = "The recipe does not exist or is not a variant"),
+    ),
+)]
+pub async fn diff_variant<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RecipeDiff>, StatusCode>
+where
+    T: Repository<Recipe>,
+{
+    let recipe = find_active(&state.repo, principal.household_id(), id).await?;
+    let parent_id = recipe.parent_id.ok_or(StatusCode::NOT_FOUND)?;
+    let parent = find_active(&state.repo, principal.household_id(), parent_id).await?;
+
+    Ok(Json(variant::diff(&parent, &recipe)))
+}
+
+/// Suggests swaps for the ingredients of a recipe that are missing from the
+/// pantry or conflict with the diets, with a preview of the recipe after the
+/// swaps.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    get,
+    path = "/{id}/substitutions",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe"), ListSubstitutions),
+    responses(
+        (status = 200, description = "The suggested swaps", body = SubstitutionPreview),
+        (status = 404, description = "The recipe does not exist"),
+    ),
+)]
+pub async fn substitutions<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ListSubstitutions>,
+) -> Result<Json<SubstitutionPreview>, StatusCode>
 where
     T: Repository<Recipe>,
 {
-    state.repo.delete_by_id(id).await?;
+    let recipe = find_active(&state.repo, principal.household_id(), id).await?;
+
+    Ok(Json(substitution::suggest(
+        &recipe,
+        params.pantry.as_deref(),
+        params.diet.as_deref().unwrap_or_default(),
+    )))
+}
+
+/// Lists the revision history of a recipe, oldest first.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    get,
+    path = "/{id}/revisions",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe")),
+    responses(
+        (status = 200, description = "The revisions of the recipe", body = [RevisionResponse]),
+        (status = 404, description = "The recipe does not exist"),
+    ),
+)]
+pub async fn list_revisions<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<RevisionResponse>>, StatusCode>
+where
+    T: Repository<Recipe> + RevisionRepository,
+{
+    find_active(&state.repo, principal.household_id(), id).await?;
+    let revisions = state
+        .repo
+        .get_revisions(principal.household_id(), id)
+        .await?;
+
+    Ok(Json(mapper::map_revisions(&revisions)))
+}
+
+/// Attempts to find a single revision of a recipe.
+///
+/// The recipe is included as it was at that revision.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    get,
+    path = "/{id}/revisions/{revision}",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe"), ("revision" = u32, Path, description = "The revision number, starting at one")),
+    responses(
+        (status = 200, description = "The revision", body = RevisionResponse),
+        (status = 404, description = "The recipe or revision does not exist"),
+    ),
+)]
+pub async fn read_revision<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path((id, revision)): Path<(Uuid, u32)>,
+) -> Result<Json<RevisionResponse>, StatusCode>
+where
+    T: Repository<Recipe> + RevisionRepository,
+{
+    find_active(&state.repo, principal.household_id(), id).await?;
+    let current = state
+        .repo
+        .find_revision(principal.household_id(), id, revision)
+        .await?;
+    let previous = match revision.checked_sub(1).filter(|previous| *previous > 0) {
+        Some(previous) => match state
+            .repo
+            .find_revision(principal.household_id(), id, previous)
+            .await
+        {
+            Ok(previous) => Some(previous),
+            Err(StatusCode::NOT_FOUND) => None,
+            Err(err) => return Err(err),
+        },
+        None => None,
+    };
+
+    Ok(Json(mapper::map_revision(
+        &current,
+        previous.as_ref(),
+        true,
+    )))
+}
+
+/// Attempts to restore a recipe to how it was at the given revision.
+///
+/// The restore is itself recorded as a new revision.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    post,
+    path = "/{id}/revisions/{revision}/restore",
+    tag = "recipes",
+    params(("id" = Uuid, Path, description = "The id of the recipe"), ("revision" = u32, Path, description = "The revision number, starting at one")),
+    responses(
+        (status = 200, description = "The restored recipe", body = Recipe),
+        (status = 403, description = "The caller cannot change recipes"),
+        (status = 404, description = "The recipe or revision does not exist"),
+    ),
+)]
+pub async fn restore_revision<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path((id, revision)): Path<(Uuid, u32)>,
+) -> Result<Json<Recipe>, StatusCode>
+where
+    T: Repository<Recipe> + RevisionRepository,
+{
+    principal.require(Role::Editor)?;
+    find_active(&state.repo, principal.household_id(), id).await?;
+    let recipe = Recipe {
+        household_id: principal.household_id().to_owned(),
+        ..state
+            .repo
+            .find_revision(principal.household_id(), id, revision)
+            .await?
+            .recipe
+    };
+
+    state
+        .repo
+        .save_revision(&recipe, Some(principal.subject().to_owned()))
+        .await?;
+
+    Ok(Json(recipe))
+}
+
+/// Attempts to create a link that lets anyone read the recipe.
+///
+/// No account is needed to follow the link, which optionally expires after a
+/// number of days.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    post,
+    path = "/{id}/shares",
+    tag = "shares",
+    params(("id" = Uuid, Path, description = "The id of the recipe")),
+    request_body = Option<PostShare>,
+    responses(
+        (status = 201, description = "The new share link", body = ShareLink),
+        (status = 403, description = "The caller cannot change recipes"),
+        (status = 404, description = "The recipe does not exist"),
+        (status = 422, description = "The link would expire immediately"),
+    ),
+)]
+pub async fn share<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+    payload: Option<Json<PostShare>>,
+) -> Result<(StatusCode, Json<ShareLink>), StatusCode>
+where
+    T: Repository<Recipe> + ShareRepository,
+{
+    principal.require(Role::Editor)?;
+    find_active(&state.repo, principal.household_id(), id).await?;
+    let Json(payload) = payload.unwrap_or_default();
+
+    let now = clock::now();
+    let expires_at = match payload.expires_in_days {
+        Some(0) => return Err(StatusCode::UNPROCESSABLE_ENTITY),
+        Some(days) => Some(now + u64::from(days) * 24 * 60 * 60),
+        None => None,
+    };
+    let link = ShareLink {
+        token: share::new_token(),
+        household_id: principal.household_id().to_owned(),
+        recipe_id: id,
+        created_by: principal.subject().to_owned(),
+        created_at: now,
+        expires_at,
+    };
+    state.repo.create_share(&link).await?;
+
+    Ok((StatusCode::CREATED, Json(link)))
+}
+
+/// Lists the share links of a recipe that still work.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    get,
+    path = "/{id}/shares",
+    tag = "shares",
+    params(("id" = Uuid, Path, description = "The id of the recipe")),
+    responses(
+        (status = 200, description = "The active share links of the recipe", body = [ShareLink]),
+        (status = 404, description = "The recipe does not exist"),
+    ),
+)]
+pub async fn list_shares<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<ShareLink>>, StatusCode>
+where
+    T: Repository<Recipe> + ShareRepository,
+{
+    find_active(&state.repo, principal.household_id(), id).await?;
+    let now = clock::now();
+    let links = state
+        .repo
+        .get_shares(id)
+        .await?
+        .into_iter()
+        .filter(|link| link.household_id == principal.household_id() && !link.is_expired(now))
+        .collect();
+
+    Ok(Json(links))
+}
+
+/// Attempts to revoke a share link of a recipe.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    delete,
+    path = "/{id}/shares/{token}",
+    tag = "shares",
+    params(("id" = Uuid, Path, description = "The id of the recipe"), ("token" = String, Path, description = "The token of the share link")),
+    responses(
+        (status = 204, description = "The share link was revoked"),
+        (status = 403, description = "The caller cannot change recipes"),
+        (status = 404, description = "The share link does not exist"),
+    ),
+)]
+pub async fn revoke_share<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path((id, token)): Path<(Uuid, String)>,
+) -> Result<StatusCode, StatusCode>
+where
+    T: ShareRepository,
+{
+    principal.require(Role::Editor)?;
+    let link = state.repo.find_share(&token).await?;
+    if link.recipe_id != id || link.household_id != principal.household_id() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    state.repo.delete_share(&token).await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Reads the recipe behind a share link.
+///
+/// Needs no authentication, the token is the only credential. Responds with an
+/// HTML page when the client accepts one, and with JSON otherwise.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    get,
+    path = "/shared/{token}",
+    tag = "shares",
+    params(("token" = String, Path, description = "The token of the share link")),
+    security(()),
+    responses(
+        (
+            status = 200,
+            description = "The shared recipe",
+            content(("application/json" = SharedRecipe), ("text/html" = String)),
+        ),
+        (status = 404, description = "The share link does not exist or expired"),
+    ),
+)]
+pub async fn read_shared<T>(
+    State(state): State<ApplicationContext<T>>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode>
+where
+    T: Repository<Recipe> + ShareRepository,
+{
+    let link = state.repo.find_share(&token).await?;
+    let recipe =
+        SharedRecipe::from(find_active(&state.repo, &link.household_id, link.recipe_id).await?);
+
+    let wants_html = headers
+        .get(ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"));
+    if wants_html {
+        Ok(Html(share::render_html(&recipe)).into_response())
+    } else {
+        Ok(Json(recipe).into_response())
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use axum::extract::{Path, State};
-    use axum::http::StatusCode;
+    use std::collections::BTreeMap;
+
+    use axum::extract::{Path, Query, State};
+    use axum::http::header::{ACCEPT, CONTENT_TYPE};
+    use axum::http::{HeaderMap, StatusCode};
     use axum::Json;
     use mockall::predicate::{eq, function};
     use uuid::Uuid;
 
-    use crate::recipe::request_models::PostRecipe;
-    use crate::recipe::Recipe;
+    use crate::auth::Principal;
+    use crate::cook_log::{CookEntry, MockCookLogRepository, RecipeSort};
+    use crate::household::{Member, Role};
+    use crate::kitchen::{Appliance, Cookware, Kitchen, MockKitchenRepository};
+    use crate::recipe::batch::{BatchParams, MAX_ATOMIC_OPERATIONS};
+    use crate::recipe::patch::RecipePatch;
+    use crate::recipe::request_models::{
+        DeleteRecipe,
+        ListRecipes,
+        ListSubstitutions,
+        PostRecipe,
+        ReadRecipe,
+    };
+    use crate::recipe::revision::RecipeRevision;
+    use crate::recipe::share::ShareLink;
+    use crate::recipe::substitution::Diet;
+    use crate::recipe::{Ingredient, MockRecipeRepository, Recipe, Step};
     use crate::services::{self, ApplicationContext};
-    use crate::MockRepository;
+    use crate::validation::{RequestError, ValidatedJson};
+
+    const HOUSEHOLD: &str = "grandma";
+
+    fn principal() -> Principal { Principal::new(HOUSEHOLD) }
+
+    /// A cook log with the given entries, for the household and any recipe.
+    fn cook_log(entries: Vec<CookEntry>) -> State<ApplicationContext<MockCookLogRepository>> {
+        let mut mock_log = MockCookLogRepository::new();
+        let clone = entries.clone();
+        mock_log.expect_get_entries().returning(move |_| {
+            let entries = clone.clone();
+            Box::pin(async move { Ok(entries) })
+        });
+        mock_log.expect_get_recipe_entries().returning(move |_, _| {
+            let entries = entries.clone();
+            Box::pin(async move { Ok(entries) })
+        });
+        State(ApplicationContext { repo: mock_log })
+    }
+
+    fn viewer() -> Principal {
+        Principal::new("grandpa").with_membership(&Member {
+            subject: "grandpa".to_owned(),
+            household_id: HOUSEHOLD.to_owned(),
+            role: Role::Viewer,
+            joined_at: 0,
+        })
+    }
+
+    fn revision(number: u32, name: &str) -> RecipeRevision {
+        RecipeRevision {
+            recipe_id: Uuid::nil(),
+            revision: number,
+            author: None,
+            created_at: 0,
+            recipe: Recipe {
+                id: Uuid::nil(),
+                name: name.to_owned(),
+                ..Recipe::default()
+            },
+        }
+    }
 
     #[tokio::test]
     async fn create_ok() {
-        let mut mock_repo: MockRepository<Recipe> = MockRepository::new();
+        let mut mock_repo = MockRecipeRepository::new();
         mock_repo
-            .expect_save()
-            .with(function(|recipe: &Recipe| recipe.name.eq("Name")))
-            .return_once(|_| Box::pin(async { Ok(None) }));
-        let state = State(ApplicationContext::<MockRepository<Recipe>> { repo: mock_repo });
-        let payload = Json(PostRecipe::default());
+            .expect_save_revision()
+            .withf(|recipe, author| recipe.name.eq("Name") && author.as_deref() == Some(HOUSEHOLD))
+            .return_once(|_, _| Box::pin(async { Ok(revision(1, "Name")) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = ValidatedJson(PostRecipe::default());
 
-        let actual = services::recipes::create(state, payload).await;
+        let actual = services::recipes::create(state, principal(), payload).await;
 
         assert!(actual.is_ok());
     }
 
     #[tokio::test]
     async fn create_error() {
-        let mut mock_repo: MockRepository<Recipe> = MockRepository::new();
+        let mut mock_repo = MockRecipeRepository::new();
         mock_repo
-            .expect_save()
-            .with(function(|recipe: &Recipe| recipe.name.eq("Name")))
-            .return_once(|_| Box::pin(async { Err(StatusCode::INTERNAL_SERVER_ERROR) }));
-        let state = State(ApplicationContext::<MockRepository<Recipe>> { repo: mock_repo });
-        let payload = Json(PostRecipe::default());
+            .expect_save_revision()
+            .withf(|recipe, _| recipe.name.eq("Name"))
+            .return_once(|_, _| Box::pin(async { Err(StatusCode::INTERNAL_SERVER_ERROR) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = ValidatedJson(PostRecipe::default());
 
-        let actual = services::recipes::create(state, payload).await;
+        let actual = services::recipes::create(state, principal(), payload).await;
 
         assert!(actual.is_err());
         assert_eq!(actual.err().unwrap(), StatusCode::INTERNAL_SERVER_ERROR);
@@ -171,25 +1098,882 @@ mod test {
 
     #[tokio::test]
     async fn read_one_ok() {
-        let mut mock_repo: MockRepository<Recipe> = MockRepository::new();
+        let mut mock_repo = MockRecipeRepository::new();
         let expected = Recipe {
             id: Uuid::nil(),
             name: "Name".to_owned(),
+            ..Recipe::default()
         };
         let clone = expected.clone();
         mock_repo
             .expect_find_by_id()
-            .with(eq(Uuid::nil()))
-            .return_once(move |_| Box::pin(async move { Ok(clone) }));
-        let state = State(ApplicationContext::<MockRepository<Recipe>> { repo: mock_repo });
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(move |_, _| Box::pin(async move { Ok(clone) }));
+        let state = State(ApplicationContext { repo: mock_repo });
 
-        let actual = services::recipes::read_one(state, Path(Uuid::nil())).await;
+        let actual = services::recipes::read_one(
+            state,
+            cook_log(Vec::new()),
+            principal(),
+            Path(Uuid::nil()),
+            Query(ReadRecipe::default()),
+        )
+        .await;
 
         match actual {
             Ok(Json(returned_recipe)) => {
-                assert_eq!(returned_recipe, expected);
+                assert_eq!(returned_recipe, expected.into());
             },
             _ => panic!("Expected Ok(Json(recipe)), got {:?}", actual),
         }
     }
+
+    fn component_of(id: u128, component: u128) -> Recipe {
+        Recipe {
+            id: Uuid::from_u128(id),
+            ingredients: vec![Ingredient {
+                name: "Component".to_owned(),
+                quantity: Some(2.0),
+                unit: Some("batch".to_owned()),
+                recipe_id: Some(Uuid::from_u128(component)),
+            }],
+            ..Recipe::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn read_one_expands_components() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::from_u128(1)))
+            .return_once(|_, _| Box::pin(async { Ok(component_of(1, 2)) }));
+        mock_repo
+            .expect_find_by_ids()
+            .withf(|household_id, ids| household_id == HOUSEHOLD && ids == [Uuid::from_u128(2)])
+            .return_once(|_, _| {
+                Box::pin(async {
+                    Ok(vec![Recipe {
+                        id: Uuid::from_u128(2),
+                        ..Recipe::default()
+                    }])
+                })
+            });
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::recipes::read_one(
+            state,
+            cook_log(Vec::new()),
+            principal(),
+            Path(Uuid::from_u128(1)),
+            Query(ReadRecipe { expand: true }),
+        )
+        .await;
+
+        match actual {
+            Ok(Json(expanded)) => {
+                assert_eq!(expanded.components.len(), 1);
+                assert_eq!(expanded.components[0].batches, 2.0);
+            },
+            _ => panic!("Expected Ok(Json(recipe)), got {:?}", actual),
+        }
+    }
+
+    #[tokio::test]
+    async fn read_one_cyclic_components() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::from_u128(1)))
+            .return_once(|_, _| Box::pin(async { Ok(component_of(1, 2)) }));
+        mock_repo
+            .expect_find_by_ids()
+            .withf(|_, ids| ids == [Uuid::from_u128(2)])
+            .return_once(|_, _| Box::pin(async { Ok(vec![component_of(2, 1)]) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::recipes::read_one(
+            state,
+            cook_log(Vec::new()),
+            principal(),
+            Path(Uuid::from_u128(1)),
+            Query(ReadRecipe { expand: true }),
+        )
+        .await;
+
+        assert_eq!(actual.err(), Some(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    #[tokio::test]
+    async fn read_one_in_trash() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo.expect_find_by_id().return_once(|_, _| {
+            Box::pin(async {
+                Ok(Recipe {
+                    deleted_at: Some(1),
+                    ..Recipe::default()
+                })
+            })
+        });
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::recipes::read_one(
+            state,
+            cook_log(Vec::new()),
+            principal(),
+            Path(Uuid::nil()),
+            Query(ReadRecipe::default()),
+        )
+        .await;
+
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn delete_one_moves_to_trash() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_soft_delete()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        mock_repo.expect_delete_by_id().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::recipes::delete_one(
+            state,
+            principal(),
+            Path(Uuid::nil()),
+            Query(DeleteRecipe::default()),
+        )
+        .await;
+
+        assert_eq!(actual, Ok(StatusCode::NO_CONTENT));
+    }
+
+    #[tokio::test]
+    async fn delete_one_permanent() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_delete_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(()) }));
+        mock_repo.expect_soft_delete().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::recipes::delete_one(
+            state,
+            principal(),
+            Path(Uuid::nil()),
+            Query(DeleteRecipe { permanent: true }),
+        )
+        .await;
+
+        assert_eq!(actual, Ok(StatusCode::NO_CONTENT));
+    }
+
+    #[tokio::test]
+    async fn list_and_trash_are_disjoint() {
+        let trashed = Recipe {
+            id: Uuid::from_u128(1),
+            deleted_at: Some(1),
+            expires_at: Some(u64::MAX),
+            ..Recipe::default()
+        };
+        let expired = Recipe {
+            id: Uuid::from_u128(2),
+            deleted_at: Some(1),
+            expires_at: Some(2),
+            ..Recipe::default()
+        };
+        let recipes = vec![Recipe::default(), trashed.clone(), expired];
+        let clone = recipes.clone();
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_get_all()
+            .times(1)
+            .return_once(move |_| Box::pin(async move { Ok(recipes) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let kitchens = State(ApplicationContext {
+            repo: MockKitchenRepository::new(),
+        });
+        let list = services::recipes::list(
+            state,
+            kitchens,
+            cook_log(Vec::new()),
+            principal(),
+            Query(ListRecipes::default()),
+        )
+        .await;
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_get_all()
+            .times(1)
+            .return_once(move |_| Box::pin(async move { Ok(clone) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let trash = services::recipes::list_trash(state, principal()).await;
+
+        assert!(list.is_ok_and(|Json(recipes)| recipes == vec![Recipe::default()]));
+        assert!(trash.is_ok_and(|Json(recipes)| recipes == vec![trashed]));
+    }
+
+    #[tokio::test]
+    async fn list_by_ids() {
+        let ids = vec![Uuid::from_u128(2), Uuid::from_u128(1)];
+        let found = vec![
+            Recipe {
+                id: Uuid::from_u128(2),
+                ..Recipe::default()
+            },
+            Recipe {
+                id: Uuid::from_u128(1),
+                deleted_at: Some(1),
+                ..Recipe::default()
+            },
+        ];
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo.expect_get_all().never();
+        mock_repo
+            .expect_find_by_ids()
+            .with(eq(HOUSEHOLD), eq(ids.clone()))
+            .return_once(move |_, _| Box::pin(async move { Ok(found) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let kitchens = State(ApplicationContext {
+            repo: MockKitchenRepository::new(),
+        });
+
+        let actual = services::recipes::list(
+            state,
+            kitchens,
+            cook_log(Vec::new()),
+            principal(),
+            Query(ListRecipes {
+                ids: Some(ids),
+                ..ListRecipes::default()
+            }),
+        )
+        .await;
+
+        assert!(actual
+            .is_ok_and(|Json(recipes)| recipes.len() == 1 && recipes[0].id == Uuid::from_u128(2)));
+    }
+
+    #[tokio::test]
+    async fn list_cookable() {
+        let sous_vide = Recipe {
+            id: Uuid::from_u128(1),
+            steps: vec![Step {
+                instruction: "Cook sous vide".to_owned(),
+                equipment: vec![Cookware {
+                    appliance: Appliance::SousVide,
+                    celsius: Some(57),
+                    count: None,
+                }],
+                ..Step::default()
+            }],
+            ..Recipe::default()
+        };
+        let recipes = vec![Recipe::default(), sous_vide];
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_get_all()
+            .return_once(move |_| Box::pin(async move { Ok(recipes) }));
+        let mut mock_kitchens = MockKitchenRepository::new();
+        mock_kitchens
+            .expect_find_kitchen()
+            .with(eq(HOUSEHOLD))
+            .return_once(|_| {
+                Box::pin(async {
+                    Ok(Kitchen {
+                        appliances: BTreeMap::from([(Appliance::Oven, 1)]),
+                        ..Kitchen::default()
+                    })
+                })
+            });
+
+        let actual = services::recipes::list(
+            State(ApplicationContext { repo: mock_repo }),
+            State(ApplicationContext {
+                repo: mock_kitchens,
+            }),
+            cook_log(Vec::new()),
+            principal(),
+            Query(ListRecipes {
+                cookable: true,
+                ..ListRecipes::default()
+            }),
+        )
+        .await;
+
+        assert!(actual.is_ok_and(|Json(recipes)| recipes == vec![Recipe::default()]));
+    }
+
+    #[tokio::test]
+    async fn list_suggested_with_stats() {
+        let recipes = vec![
+            Recipe {
+                id: Uuid::from_u128(1),
+                ..Recipe::default()
+            },
+            Recipe {
+                id: Uuid::from_u128(2),
+                ..Recipe::default()
+            },
+        ];
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_get_all()
+            .return_once(move |_| Box::pin(async move { Ok(recipes) }));
+        let entries = vec![CookEntry {
+            recipe_id: Uuid::from_u128(2),
+            cooked_on: "2020-01-01".to_owned(),
+            rating: 5,
+            ..CookEntry::default()
+        }];
+
+        let actual = services::recipes::list(
+            State(ApplicationContext { repo: mock_repo }),
+            State(ApplicationContext {
+                repo: MockKitchenRepository::new(),
+            }),
+            cook_log(entries),
+            principal(),
+            Query(ListRecipes {
+                sort: Some(RecipeSort::Suggested),
+                ..ListRecipes::default()
+            }),
+        )
+        .await;
+
+        let Ok(Json(recipes)) = actual else {
+            panic!("expected recipes, got {actual:?}");
+        };
+        assert_eq!(recipes[0].id, Uuid::from_u128(2));
+        assert_eq!(
+            recipes[0].stats.as_ref().map(|stats| stats.times_cooked),
+            Some(1)
+        );
+        assert_eq!(recipes[1].stats, None);
+    }
+
+    #[tokio::test]
+    async fn list_unsorted_skips_cook_log() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_get_all()
+            .return_once(|_| Box::pin(async { Ok(vec![Recipe::default()]) }));
+        let mut mock_log = MockCookLogRepository::new();
+        mock_log.expect_get_entries().never();
+
+        let actual = services::recipes::list(
+            State(ApplicationContext { repo: mock_repo }),
+            State(ApplicationContext {
+                repo: MockKitchenRepository::new(),
+            }),
+            State(ApplicationContext { repo: mock_log }),
+            principal(),
+            Query(ListRecipes::default()),
+        )
+        .await;
+
+        assert!(actual.is_ok_and(|Json(recipes)| recipes.len() == 1));
+    }
+
+    #[tokio::test]
+    async fn update_with_merge_patch() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        mock_repo
+            .expect_save_revision()
+            .withf(|recipe, _| recipe.name.eq("Rolls"))
+            .return_once(|_, _| Box::pin(async { Ok(revision(2, "Rolls")) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let patch = RecipePatch::Merge(serde_json::json!({ "name": "Rolls" }));
+
+        let actual = services::recipes::update(state, principal(), Path(Uuid::nil()), patch).await;
+
+        assert_eq!(actual.unwrap().name, "Rolls");
+    }
+
+    #[tokio::test]
+    async fn update_rejects_invalid_patch_before_saving() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        mock_repo.expect_save_revision().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+        let patch = RecipePatch::Merge(serde_json::json!({ "name": "" }));
+
+        let actual = services::recipes::update(state, principal(), Path(Uuid::nil()), patch).await;
+
+        assert!(matches!(actual, Err(RequestError::Invalid(_))));
+    }
+
+    #[tokio::test]
+    async fn batch_reports_each_outcome() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_write_batch()
+            .withf(|writes, atomic, author| {
+                writes.len() == 3 && !atomic && author.as_deref() == Some(HOUSEHOLD)
+            })
+            .return_once(|_, _, _| {
+                Box::pin(async { Ok(vec![Ok(()), Err(StatusCode::NOT_FOUND), Ok(())]) })
+            });
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = serde_json::from_value(serde_json::json!({
+            "operations": [
+                { "op": "create", "recipe": { "name": "Name" } },
+                { "op": "update", "recipe": { "id": Uuid::from_u128(1), "name": "Other" } },
+                { "op": "delete", "id": Uuid::from_u128(2) },
+            ]
+        }))
+        .unwrap();
+
+        let actual = services::recipes::batch(
+            state,
+            principal(),
+            Query(BatchParams::default()),
+            ValidatedJson(payload),
+        )
+        .await
+        .unwrap();
+
+        let statuses: Vec<u16> = actual.results.iter().map(|result| result.status).collect();
+        assert_eq!(statuses, vec![201, 404, 204]);
+        assert_eq!(actual.results[2].id, Uuid::from_u128(2));
+    }
+
+    #[tokio::test]
+    async fn batch_limits_atomic_operations() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo.expect_write_batch().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+        let operations = (0..=MAX_ATOMIC_OPERATIONS)
+            .map(|_| serde_json::json!({ "op": "create", "recipe": { "name": "Name" } }))
+            .collect::<Vec<_>>();
+        let payload =
+            serde_json::from_value(serde_json::json!({ "operations": operations })).unwrap();
+
+        let actual = services::recipes::batch(
+            state,
+            principal(),
+            Query(BatchParams { atomic: true }),
+            ValidatedJson(payload),
+        )
+        .await;
+
+        assert!(matches!(actual, Err(RequestError::Invalid(_))));
+    }
+
+    #[tokio::test]
+    async fn fork_ok() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        mock_repo
+            .expect_save_revision()
+            .withf(|recipe, _| recipe.parent_id == Some(Uuid::nil()) && recipe.id != Uuid::nil())
+            .return_once(|_, _| Box::pin(async { Ok(revision(1, "Basic Recipe (variant)")) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::recipes::fork(state, principal(), Path(Uuid::nil()), None).await;
+
+        match actual {
+            Ok((StatusCode::CREATED, Json(recipe))) => {
+                assert_eq!(recipe.name, "Basic Recipe (variant)");
+            },
+            _ => panic!("Expected Ok((CREATED, Json(recipe))), got {:?}", actual),
+        }
+    }
+
+    #[tokio::test]
+    async fn fork_blank_name() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        mock_repo.expect_save_revision().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = serde_json::from_str(r#"{ "name": " " }"#).unwrap();
+
+        let actual =
+            services::recipes::fork(state, principal(), Path(Uuid::nil()), Some(Json(payload)))
+                .await;
+
+        assert!(matches!(actual, Err(RequestError::Invalid(_))));
+    }
+
+    #[tokio::test]
+    async fn list_variants_filters_by_parent() {
+        let variant = Recipe {
+            id: Uuid::from_u128(1),
+            parent_id: Some(Uuid::nil()),
+            ..Recipe::default()
+        };
+        let unrelated = Recipe {
+            id: Uuid::from_u128(2),
+            ..Recipe::default()
+        };
+        let recipes = vec![Recipe::default(), variant.clone(), unrelated];
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        mock_repo
+            .expect_get_all()
+            .return_once(move |_| Box::pin(async move { Ok(recipes) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::recipes::list_variants(state, principal(), Path(Uuid::nil())).await;
+
+        assert!(actual.is_ok_and(|Json(variants)| variants == vec![variant]));
+    }
+
+    #[tokio::test]
+    async fn diff_variant_without_parent() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .times(1)
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::recipes::diff_variant(state, principal(), Path(Uuid::nil())).await;
+
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn substitutions_for_a_diet() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| {
+                Box::pin(async {
+                    Ok(Recipe {
+                        ingredients: vec![Ingredient {
+                            name: "Honey".to_owned(),
+                            quantity: Some(2.0),
+                            unit: Some("tbsp".to_owned()),
+                            recipe_id: None,
+                        }],
+                        ..Recipe::default()
+                    })
+                })
+            });
+        let state = State(ApplicationContext { repo: mock_repo });
+        let params = ListSubstitutions {
+            diet: Some(vec![Diet::Vegan]),
+            ..ListSubstitutions::default()
+        };
+
+        let actual =
+            services::recipes::substitutions(state, principal(), Path(Uuid::nil()), Query(params))
+                .await;
+
+        let Ok(Json(preview)) = actual else {
+            panic!("expected a preview, got {actual:?}");
+        };
+        assert_eq!(
+            preview.recipe.ingredients,
+            vec![Ingredient {
+                name: "maple syrup".to_owned(),
+                quantity: Some(2.0),
+                unit: Some("tbsp".to_owned()),
+                recipe_id: None,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn read_revision_includes_changes() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        mock_repo
+            .expect_find_revision()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()), eq(2))
+            .return_once(|_, _, _| Box::pin(async { Ok(revision(2, "Tweaked")) }));
+        mock_repo
+            .expect_find_revision()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()), eq(1))
+            .return_once(|_, _, _| Box::pin(async { Ok(revision(1, "Original")) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual =
+            services::recipes::read_revision(state, principal(), Path((Uuid::nil(), 2))).await;
+
+        match actual {
+            Ok(Json(response)) => {
+                assert_eq!(response.revision, 2);
+                assert_eq!(response.changes.len(), 1);
+                assert_eq!(response.recipe.unwrap().name, "Tweaked");
+            },
+            _ => panic!("Expected Ok(Json(revision)), got {:?}", actual),
+        }
+    }
+
+    #[tokio::test]
+    async fn restore_revision_ok() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        mock_repo
+            .expect_find_revision()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()), eq(1))
+            .return_once(|_, _, _| Box::pin(async { Ok(revision(1, "Original")) }));
+        mock_repo
+            .expect_save_revision()
+            .withf(|recipe, _| recipe.name.eq("Original"))
+            .return_once(|_, _| Box::pin(async { Ok(revision(3, "Original")) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual =
+            services::recipes::restore_revision(state, principal(), Path((Uuid::nil(), 1))).await;
+
+        assert!(actual.is_ok_and(|Json(recipe)| recipe.name.eq("Original")));
+    }
+
+    #[tokio::test]
+    async fn restore_revision_not_found() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        mock_repo
+            .expect_find_revision()
+            .return_once(|_, _, _| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+        mock_repo.expect_save_revision().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual =
+            services::recipes::restore_revision(state, principal(), Path((Uuid::nil(), 1))).await;
+
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn list_revisions_other_household() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+        mock_repo.expect_get_revisions().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::recipes::list_revisions(state, principal(), Path(Uuid::nil())).await;
+
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn list_revisions_of_id_reused_by_other_household() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq("other"), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        mock_repo
+            .expect_get_revisions()
+            .with(eq("other"), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(vec![revision(1, "Theirs")]) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual =
+            services::recipes::list_revisions(state, Principal::new("other"), Path(Uuid::nil()))
+                .await;
+
+        assert!(actual.is_ok_and(|Json(revisions)| revisions.len() == 1));
+    }
+
+    #[tokio::test]
+    async fn write_replaces_existing_recipe() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        mock_repo
+            .expect_save_revision()
+            .withf(|recipe, _| recipe.name.eq("Name"))
+            .return_once(|_, _| Box::pin(async { Ok(revision(2, "Name")) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let put = serde_json::from_str(
+            r#"{ "id": "00000000-0000-0000-0000-000000000000", "name": "Name" }"#,
+        )
+        .unwrap();
+
+        let actual = services::recipes::write(state, principal(), ValidatedJson(put)).await;
+
+        assert!(actual.is_ok_and(|(status, _)| status == StatusCode::OK));
+    }
+
+    #[tokio::test]
+    async fn write_in_trash() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo.expect_find_by_id().return_once(|_, _| {
+            Box::pin(async {
+                Ok(Recipe {
+                    deleted_at: Some(1),
+                    ..Recipe::default()
+                })
+            })
+        });
+        mock_repo.expect_save_revision().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+        let put = serde_json::from_str(
+            r#"{ "id": "00000000-0000-0000-0000-000000000000", "name": "Name" }"#,
+        )
+        .unwrap();
+
+        let actual = services::recipes::write(state, principal(), ValidatedJson(put)).await;
+
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn viewer_cannot_write() {
+        // Without expectations, any call to the repository fails the test.
+        let state = || {
+            State(ApplicationContext {
+                repo: MockRecipeRepository::new(),
+            })
+        };
+        let put = serde_json::from_str(
+            r#"{ "id": "00000000-0000-0000-0000-000000000000", "name": "Name" }"#,
+        )
+        .unwrap();
+        let patch = serde_json::from_str(r#"{ "name": "Name" }"#).unwrap();
+
+        let written = services::recipes::write(state(), viewer(), ValidatedJson(put)).await;
+        let updated = services::recipes::update(
+            state(),
+            viewer(),
+            Path(Uuid::nil()),
+            RecipePatch::Fields(patch),
+        )
+        .await;
+        let deleted = services::recipes::delete_one(
+            state(),
+            viewer(),
+            Path(Uuid::nil()),
+            Query(DeleteRecipe::default()),
+        )
+        .await;
+
+        assert_eq!(written.err(), Some(StatusCode::FORBIDDEN));
+        assert_eq!(updated.err(), Some(StatusCode::FORBIDDEN.into()));
+        assert_eq!(deleted.err(), Some(StatusCode::FORBIDDEN));
+    }
+
+    fn share_link(recipe_id: Uuid) -> ShareLink {
+        ShareLink {
+            token: "token".to_owned(),
+            household_id: HOUSEHOLD.to_owned(),
+            recipe_id,
+            ..ShareLink::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn share_ok() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        mock_repo
+            .expect_create_share()
+            .with(function(|link: &ShareLink| {
+                link.recipe_id == Uuid::nil() && link.expires_at.is_some() && link.token.len() == 32
+            }))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = serde_json::from_str(r#"{ "expires_in_days": 7 }"#).unwrap();
+
+        let actual =
+            services::recipes::share(state, principal(), Path(Uuid::nil()), Some(Json(payload)))
+                .await;
+
+        assert!(actual.is_ok_and(|(status, _)| status == StatusCode::CREATED));
+    }
+
+    #[tokio::test]
+    async fn read_shared_as_html() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_share()
+            .with(eq("token"))
+            .return_once(|_| Box::pin(async { Ok(share_link(Uuid::nil())) }));
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Ok(Recipe::default()) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, "text/html,application/xhtml+xml".parse().unwrap());
+
+        let actual = services::recipes::read_shared(state, Path("token".to_owned()), headers).await;
+
+        let response = actual.unwrap();
+        assert!(response.headers()[CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/html"));
+    }
+
+    #[tokio::test]
+    async fn read_shared_in_trash() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_share()
+            .return_once(|_| Box::pin(async { Ok(share_link(Uuid::nil())) }));
+        mock_repo.expect_find_by_id().return_once(|_, _| {
+            Box::pin(async {
+                Ok(Recipe {
+                    deleted_at: Some(1),
+                    ..Recipe::default()
+                })
+            })
+        });
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual =
+            services::recipes::read_shared(state, Path("token".to_owned()), HeaderMap::new()).await;
+
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn revoke_share_of_other_recipe() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_share()
+            .return_once(|_| Box::pin(async { Ok(share_link(Uuid::from_u128(1))) }));
+        mock_repo.expect_delete_share().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::recipes::revoke_share(
+            state,
+            principal(),
+            Path((Uuid::nil(), "token".to_owned())),
+        )
+        .await;
+
+        assert_eq!(actual, Err(StatusCode::NOT_FOUND));
+    }
 }
@@ -0,0 +1,347 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use uuid::Uuid;
+
+use crate::auth::Principal;
+use crate::household::Role;
+use crate::recipe::{component, Recipe};
+use crate::services::recipes::load_components;
+use crate::services::ApplicationContext;
+use crate::shopping_list::request_models::{
+    PatchShoppingListItem,
+    PostShoppingList,
+    PostShoppingListFromRecipes,
+    PostShoppingListItem,
+};
+use crate::shopping_list::response_models::ShoppingListResponse;
+use crate::shopping_list::{mapper, ChecklistRepository, ShoppingList, ShoppingListItem};
+use crate::Repository;
+
+/// Lists all shopping lists from the database, grouped by aisle.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn list<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+) -> Result<Json<Vec<ShoppingListResponse>>, StatusCode>
+where
+    T: Repository<ShoppingList>,
+{
+    let lists = state.repo.get_all(principal.household_id()).await?;
+
+    Ok(Json(lists.iter().map(mapper::group_by_aisle).collect()))
+}
+
+/// Attempts to create a shopping list in the database.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn create<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Json(payload): Json<PostShoppingList>,
+) -> Result<(StatusCode, Json<ShoppingListResponse>), StatusCode>
+where
+    T: Repository<ShoppingList>,
+{
+    principal.require(Role::Editor)?;
+    let list = mapper::map_post_shopping_list(Uuid::new_v4(), principal.household_id(), &payload);
+    state.repo.save(&list).await?;
+
+    Ok((StatusCode::CREATED, Json(mapper::group_by_aisle(&list))))
+}
+
+/// Attempts to create a shopping list from the ingredients of the given
+/// recipes, including the ingredients of any recipes they are made from.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error. Responds with unprocessable entity if the components of
+/// a recipe refer back to it, nest too deep or are too many.
+pub async fn create_from_recipes<T, R>(
+    State(state): State<ApplicationContext<T>>,
+    State(recipe_state): State<ApplicationContext<R>>,
+    principal: Principal,
+    Json(payload): Json<PostShoppingListFromRecipes>,
+) -> Result<(StatusCode, Json<ShoppingListResponse>), StatusCode>
+where
+    T: Repository<ShoppingList>,
+    R: Repository<Recipe>,
+{
+    principal.require(Role::Editor)?;
+    let recipes = recipe_state
+        .repo
+        .find_by_ids(principal.household_id(), &payload.recipe_ids)
+        .await?;
+    if payload
+        .recipe_ids
+        .iter()
+        .any(|id| !recipes.iter().any(|recipe| recipe.id == *id))
+    {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let components =
+        load_components(&recipe_state.repo, principal.household_id(), &recipes).await?;
+    let mut ingredients = Vec::new();
+    for recipe in &recipes {
+        let expanded =
+            component::expand(recipe, &components).map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+        ingredients.extend(component::flatten(&expanded));
+    }
+
+    let list = mapper::map_recipe_ingredients(
+        Uuid::new_v4(),
+        principal.household_id(),
+        &payload,
+        &ingredients,
+    );
+    state.repo.save(&list).await?;
+
+    Ok((StatusCode::CREATED, Json(mapper::group_by_aisle(&list))))
+}
+
+/// Attempts to find a shopping list in the database given the uuid.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn read_one<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ShoppingListResponse>, StatusCode>
+where
+    T: Repository<ShoppingList>,
+{
+    let list = state.repo.find_by_id(principal.household_id(), id).await?;
+
+    Ok(Json(mapper::group_by_aisle(&list)))
+}
+
+/// Attempts to delete a shopping list in the database given the uuid.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn delete_one<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, StatusCode>
+where
+    T: Repository<ShoppingList>,
+{
+    principal.require(Role::Editor)?;
+    state
+        .repo
+        .delete_by_id(principal.household_id(), id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Attempts to add an item to an existing shopping list.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn add_item<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<PostShoppingListItem>,
+) -> Result<(StatusCode, Json<ShoppingListItem>), StatusCode>
+where
+    T: ChecklistRepository,
+{
+    principal.require(Role::Editor)?;
+    let item = mapper::map_post_shopping_list_item(Uuid::new_v4(), &payload);
+    state
+        .repo
+        .add_item(principal.household_id(), id, &item)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(item)))
+}
+
+/// Attempts to check or uncheck a single item on a shopping list.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn check_item<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path((id, item_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<PatchShoppingListItem>,
+) -> Result<Json<ShoppingListItem>, StatusCode>
+where
+    T: ChecklistRepository,
+{
+    principal.require(Role::Editor)?;
+    let item = state
+        .repo
+        .set_checked(principal.household_id(), id, item_id, payload.checked)
+        .await?;
+
+    Ok(Json(item))
+}
+
+#[cfg(test)]
+mod test {
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use axum::Json;
+    use mockall::predicate::{eq, function};
+    use uuid::Uuid;
+
+    use crate::auth::Principal;
+    use crate::recipe::{Ingredient, Recipe};
+    use crate::services::{self, ApplicationContext};
+    use crate::shopping_list::request_models::{PostShoppingList, PostShoppingListFromRecipes};
+    use crate::shopping_list::{MockChecklistRepository, ShoppingList, ShoppingListItem};
+    use crate::MockRepository;
+
+    const HOUSEHOLD: &str = "grandma";
+
+    fn principal() -> Principal { Principal::new(HOUSEHOLD) }
+
+    #[tokio::test]
+    async fn create_ok() {
+        let mut mock_repo: MockRepository<ShoppingList> = MockRepository::new();
+        mock_repo
+            .expect_save()
+            .with(function(|list: &ShoppingList| {
+                list.name.eq("Groceries") && list.household_id.eq(HOUSEHOLD)
+            }))
+            .return_once(|_| Box::pin(async { Ok(None) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = Json(PostShoppingList::default());
+
+        let actual = services::shopping_lists::create(state, principal(), payload).await;
+
+        assert!(actual.is_ok_and(|(status, _)| status == StatusCode::CREATED));
+    }
+
+    #[tokio::test]
+    async fn create_from_recipes_flattens_components() {
+        let dough = Recipe {
+            id: Uuid::from_u128(2),
+            ingredients: vec![Ingredient {
+                name: "Flour".to_owned(),
+                quantity: Some(500.0),
+                unit: Some("g".to_owned()),
+                recipe_id: None,
+            }],
+            ..Recipe::default()
+        };
+        let pizza = Recipe {
+            id: Uuid::from_u128(1),
+            ingredients: vec![Ingredient {
+                name: "Pizza dough".to_owned(),
+                quantity: Some(2.0),
+                unit: Some("batch".to_owned()),
+                recipe_id: Some(dough.id),
+            }],
+            ..Recipe::default()
+        };
+        let mut mock_recipes: MockRepository<Recipe> = MockRepository::new();
+        let pizza_id = pizza.id;
+        mock_recipes
+            .expect_find_by_ids()
+            .withf(move |household_id, ids| household_id == HOUSEHOLD && ids == [pizza_id])
+            .return_once(move |_, _| Box::pin(async move { Ok(vec![pizza]) }));
+        let dough_id = dough.id;
+        mock_recipes
+            .expect_find_by_ids()
+            .withf(move |_, ids| ids == [dough_id])
+            .return_once(move |_, _| Box::pin(async move { Ok(vec![dough]) }));
+        let mut mock_lists: MockRepository<ShoppingList> = MockRepository::new();
+        mock_lists
+            .expect_save()
+            .with(function(|list: &ShoppingList| {
+                list.items
+                    .values()
+                    .all(|item| item.name.eq("Flour") && item.quantity.as_deref() == Some("1000 g"))
+                    && list.items.len() == 1
+            }))
+            .return_once(|_| Box::pin(async { Ok(None) }));
+        let payload: PostShoppingListFromRecipes = serde_json::from_str(
+            r#"{ "name": "Pizza night", "recipe_ids": ["00000000-0000-0000-0000-000000000001"] }"#,
+        )
+        .unwrap();
+
+        let actual = services::shopping_lists::create_from_recipes(
+            State(ApplicationContext { repo: mock_lists }),
+            State(ApplicationContext { repo: mock_recipes }),
+            principal(),
+            Json(payload),
+        )
+        .await;
+
+        match actual {
+            Ok((StatusCode::CREATED, Json(list))) => {
+                assert_eq!(list.aisles[0].aisle, "pantry");
+            },
+            _ => panic!("Expected Ok((CREATED, Json(list))), got {:?}", actual),
+        }
+    }
+
+    #[tokio::test]
+    async fn read_one_not_found() {
+        let mut mock_repo: MockRepository<ShoppingList> = MockRepository::new();
+        mock_repo
+            .expect_find_by_id()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual =
+            services::shopping_lists::read_one(state, principal(), Path(Uuid::nil())).await;
+
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn check_item_ok() {
+        let mut mock_repo = MockChecklistRepository::new();
+        mock_repo
+            .expect_set_checked()
+            .with(eq(HOUSEHOLD), eq(Uuid::nil()), eq(Uuid::nil()), eq(true))
+            .return_once(|_, _, _, _| {
+                Box::pin(async {
+                    Ok(ShoppingListItem {
+                        checked: true,
+                        ..ShoppingListItem::default()
+                    })
+                })
+            });
+        let state = State(ApplicationContext { repo: mock_repo });
+        let payload = serde_json::from_str("{\"checked\": true}").unwrap();
+
+        let actual = services::shopping_lists::check_item(
+            state,
+            principal(),
+            Path((Uuid::nil(), Uuid::nil())),
+            Json(payload),
+        )
+        .await;
+
+        match actual {
+            Ok(Json(item)) => assert!(item.checked),
+            _ => panic!("Expected Ok(Json(item)), got {:?}", actual),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+
+use crate::auth::Principal;
+use crate::date::parse_date_time;
+use crate::kitchen::{self, KitchenRepository};
+use crate::recipe::Recipe;
+use crate::services::ApplicationContext;
+use crate::timeline::request_models::PostTimeline;
+use crate::timeline::{self, Timeline};
+use crate::validation::ValidatedJson;
+use crate::Repository;
+
+/// Schedules the steps of the given recipes so that they are all ready when
+/// the meal is served, and flags the moments they need more cookware than the
+/// household's kitchen has. Recipes in the trash are reported as not found.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+#[utoipa::path(
+    post,
+    path = "/timeline",
+    tag = "recipes",
+    request_body = PostTimeline,
+    responses(
+        (status = 200, description = "The scheduled steps", body = Timeline),
+        (status = 404, description = "One of the recipes does not exist"),
+        (status = 422, description = "The meal breaks some rules", body = Violations),
+    ),
+)]
+pub async fn schedule<T, K>(
+    State(state): State<ApplicationContext<T>>,
+    State(kitchen_state): State<ApplicationContext<K>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<PostTimeline>,
+) -> Result<Json<Timeline>, StatusCode>
+where
+    T: Repository<Recipe>,
+    K: KitchenRepository,
+{
+    let serve_at = parse_date_time(&payload.serve_at).ok_or(StatusCode::UNPROCESSABLE_ENTITY)?;
+    let recipes: Vec<Recipe> = state
+        .repo
+        .find_by_ids(principal.household_id(), &payload.recipe_ids)
+        .await?
+        .into_iter()
+        .filter(|recipe| !recipe.is_deleted())
+        .collect();
+    if payload
+        .recipe_ids
+        .iter()
+        .any(|id| !recipes.iter().any(|recipe| recipe.id == *id))
+    {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let kitchen = kitchen::find_declared(&kitchen_state.repo, principal.household_id()).await?;
+
+    Ok(Json(timeline::schedule(
+        &recipes,
+        serve_at,
+        kitchen.as_ref(),
+    )))
+}
+
+#[cfg(test)]
+mod test {
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use mockall::predicate::eq;
+    use uuid::Uuid;
+
+    use crate::auth::Principal;
+    use crate::kitchen::{Appliance, Cookware, Kitchen, MockKitchenRepository};
+    use crate::recipe::{MockRecipeRepository, Recipe, Step};
+    use crate::services::{self, ApplicationContext};
+    use crate::timeline::request_models::PostTimeline;
+    use crate::validation::ValidatedJson;
+
+    const HOUSEHOLD: &str = "grandma";
+
+    fn payload(recipe_ids: Vec<Uuid>) -> ValidatedJson<PostTimeline> {
+        ValidatedJson(PostTimeline {
+            recipe_ids,
+            serve_at: "2025-11-27T17:00".to_owned(),
+        })
+    }
+
+    #[tokio::test]
+    async fn schedule_recipes() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo
+            .expect_find_by_ids()
+            .with(eq(HOUSEHOLD), eq(vec![Uuid::from_u128(1)]))
+            .return_once(|_, _| {
+                Box::pin(async {
+                    Ok(vec![Recipe {
+                        id: Uuid::from_u128(1),
+                        steps: vec![Step {
+                            instruction: "Roast".to_owned(),
+                            minutes: Some(90),
+                            passive: true,
+                            equipment: vec![Cookware {
+                                appliance: Appliance::Oven,
+                                celsius: Some(180),
+                                count: None,
+                            }],
+                            ..Step::default()
+                        }],
+                        ..Recipe::default()
+                    }])
+                })
+            });
+        let mut mock_kitchens = MockKitchenRepository::new();
+        mock_kitchens
+            .expect_find_kitchen()
+            .with(eq(HOUSEHOLD))
+            .return_once(|_| Box::pin(async { Ok(Kitchen::default()) }));
+
+        let actual = services::timeline::schedule(
+            State(ApplicationContext { repo: mock_repo }),
+            State(ApplicationContext {
+                repo: mock_kitchens,
+            }),
+            Principal::new(HOUSEHOLD),
+            payload(vec![Uuid::from_u128(1)]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(actual.starts_at, "2025-11-27T15:30");
+        assert_eq!(actual.tasks.len(), 1);
+        assert!(!actual.tasks[0].active);
+        // The household said it owns no oven.
+        assert_eq!(actual.conflicts.len(), 1);
+        assert_eq!(actual.conflicts[0].available, 0);
+    }
+
+    #[tokio::test]
+    async fn schedule_trashed_recipe() {
+        let mut mock_repo = MockRecipeRepository::new();
+        mock_repo.expect_find_by_ids().return_once(|_, _| {
+            Box::pin(async {
+                Ok(vec![Recipe {
+                    id: Uuid::from_u128(1),
+                    deleted_at: Some(100),
+                    ..Recipe::default()
+                }])
+            })
+        });
+        let mut mock_kitchens = MockKitchenRepository::new();
+        mock_kitchens.expect_find_kitchen().never();
+
+        let actual = services::timeline::schedule(
+            State(ApplicationContext { repo: mock_repo }),
+            State(ApplicationContext {
+                repo: mock_kitchens,
+            }),
+            Principal::new(HOUSEHOLD),
+            payload(vec![Uuid::from_u128(1)]),
+        )
+        .await;
+
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+}
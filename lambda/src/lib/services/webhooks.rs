@@ -0,0 +1,237 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use uuid::Uuid;
+
+use crate::auth::Principal;
+use crate::clock;
+use crate::household::Role;
+use crate::services::api_keys::require_interactive;
+use crate::services::ApplicationContext;
+use crate::validation::{RequestError, ValidatedJson, Violations};
+use crate::webhook::request_models::PostWebhook;
+use crate::webhook::response_models::{CreatedWebhook, DeliveryAttemptResponse, WebhookResponse};
+use crate::webhook::{Webhook, WebhookRepository, MAX_WEBHOOKS};
+
+/// Attempts to register a webhook for the caller's household. The signing
+/// secret is only part of this response. Webhooks send the household's
+/// recipes elsewhere, so only owners can manage them.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn create<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<PostWebhook>,
+) -> Result<(StatusCode, Json<CreatedWebhook>), RequestError>
+where
+    T: WebhookRepository,
+{
+    require_interactive(&principal)?;
+    principal.require(Role::Owner)?;
+    let existing = state.repo.get_webhooks(principal.household_id()).await?;
+    if existing.len() >= MAX_WEBHOOKS {
+        return Err(Violations::single(
+            "",
+            &format!("a household can have at most {MAX_WEBHOOKS} webhooks"),
+        )
+        .into());
+    }
+
+    let webhook = Webhook::generate(
+        principal.household_id(),
+        principal.subject(),
+        &payload.url,
+        &payload.events,
+        clock::now(),
+    );
+    state.repo.save_webhook(&webhook).await?;
+
+    let secret = webhook.secret.clone();
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedWebhook {
+            details: webhook.into(),
+            secret,
+        }),
+    ))
+}
+
+/// Lists the webhooks of the caller's household.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn list<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+) -> Result<Json<Vec<WebhookResponse>>, StatusCode>
+where
+    T: WebhookRepository,
+{
+    require_interactive(&principal)?;
+    principal.require(Role::Owner)?;
+    let webhooks = state.repo.get_webhooks(principal.household_id()).await?;
+
+    Ok(Json(
+        webhooks.into_iter().map(WebhookResponse::from).collect(),
+    ))
+}
+
+/// Attempts to remove a webhook of the caller's household. Events that are
+/// being delivered may still reach it.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn delete_one<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, StatusCode>
+where
+    T: WebhookRepository,
+{
+    require_interactive(&principal)?;
+    principal.require(Role::Owner)?;
+    state
+        .repo
+        .delete_webhook(principal.household_id(), id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Lists the latest attempts to deliver events to a webhook of the caller's
+/// household, newest first.
+///
+/// # Errors
+///
+/// This function converts the result of the database operation to a status code
+/// wrapped in an error.
+pub async fn list_deliveries<T>(
+    State(state): State<ApplicationContext<T>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<DeliveryAttemptResponse>>, StatusCode>
+where
+    T: WebhookRepository,
+{
+    require_interactive(&principal)?;
+    principal.require(Role::Owner)?;
+    let webhook = state
+        .repo
+        .find_webhook(principal.household_id(), id)
+        .await?;
+    let attempts = state.repo.get_attempts(webhook.id).await?;
+
+    Ok(Json(
+        attempts
+            .into_iter()
+            .map(DeliveryAttemptResponse::from)
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use mockall::predicate::{eq, function};
+    use uuid::Uuid;
+
+    use crate::auth::Principal;
+    use crate::household::{Member, Role};
+    use crate::services::{self, ApplicationContext};
+    use crate::validation::{RequestError, ValidatedJson};
+    use crate::webhook::request_models::PostWebhook;
+    use crate::webhook::{EventType, MockWebhookRepository, Webhook, MAX_WEBHOOKS};
+
+    fn principal(role: Role) -> Principal {
+        let member = Member {
+            household_id: "grandma".to_owned(),
+            role,
+            ..Member::default()
+        };
+        Principal::new("grandpa").with_membership(&member)
+    }
+
+    fn payload() -> ValidatedJson<PostWebhook> {
+        ValidatedJson(PostWebhook {
+            url: "https://example.com/hook".to_owned(),
+            events: vec![EventType::PlanUpdated],
+        })
+    }
+
+    #[tokio::test]
+    async fn create_ok() {
+        let mut mock_repo = MockWebhookRepository::new();
+        mock_repo
+            .expect_get_webhooks()
+            .with(eq("grandma"))
+            .return_once(|_| Box::pin(async { Ok(Vec::new()) }));
+        mock_repo
+            .expect_save_webhook()
+            .with(function(|webhook: &Webhook| {
+                webhook.household_id.eq("grandma") && webhook.events == [EventType::PlanUpdated]
+            }))
+            .return_once(|_| Box::pin(async { Ok(()) }));
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::webhooks::create(state, principal(Role::Owner), payload()).await;
+
+        let (status, created) = actual.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(created.secret.starts_with("whsec_"));
+        assert_eq!(created.details.url, "https://example.com/hook");
+    }
+
+    #[tokio::test]
+    async fn create_as_editor() {
+        let mut mock_repo = MockWebhookRepository::new();
+        mock_repo.expect_save_webhook().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::webhooks::create(state, principal(Role::Editor), payload()).await;
+
+        assert_eq!(
+            actual.err(),
+            Some(RequestError::Status(StatusCode::FORBIDDEN))
+        );
+    }
+
+    #[tokio::test]
+    async fn create_beyond_limit() {
+        let mut mock_repo = MockWebhookRepository::new();
+        mock_repo
+            .expect_get_webhooks()
+            .return_once(|_| Box::pin(async { Ok(vec![Webhook::default(); MAX_WEBHOOKS]) }));
+        mock_repo.expect_save_webhook().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual = services::webhooks::create(state, principal(Role::Owner), payload()).await;
+
+        assert!(matches!(actual, Err(RequestError::Invalid(_))));
+    }
+
+    #[tokio::test]
+    async fn list_deliveries_of_other_household() {
+        let mut mock_repo = MockWebhookRepository::new();
+        mock_repo
+            .expect_find_webhook()
+            .with(eq("grandma"), eq(Uuid::nil()))
+            .return_once(|_, _| Box::pin(async { Err(StatusCode::NOT_FOUND) }));
+        mock_repo.expect_get_attempts().never();
+        let state = State(ApplicationContext { repo: mock_repo });
+
+        let actual =
+            services::webhooks::list_deliveries(state, principal(Role::Owner), Path(Uuid::nil()))
+                .await;
+
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+}
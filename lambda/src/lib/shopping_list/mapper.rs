@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashMap};
+
+use uuid::Uuid;
+
+use super::request_models::{PostShoppingList, PostShoppingListFromRecipes, PostShoppingListItem};
+use super::response_models::{AisleGroup, ShoppingListResponse};
+use super::{ShoppingList, ShoppingListItem, DEFAULT_AISLE, DEFAULT_AISLES};
+use crate::recipe::Ingredient;
+
+/// Keywords used to guess the aisle of an ingredient from its name. The first
+/// aisle with a keyword contained in the name wins.
+const AISLE_KEYWORDS: [(&str, &[&str]); 8] = [
+    (
+        "produce",
+        &[
+            "apple", "banana", "basil", "carrot", "celery", "eggplant", "garlic", "ginger", "herb",
+            "lemon", "lettuce", "lime", "onion", "parsley", "pepper", "potato", "spinach",
+            "tomato",
+        ],
+    ),
+    (
+        "meat",
+        &[
+            "bacon", "beef", "chicken", "ham", "lamb", "pork", "sausage", "steak", "turkey",
+        ],
+    ),
+    (
+        "seafood",
+        &["cod", "fish", "prawn", "salmon", "shrimp", "tuna"],
+    ),
+    (
+        "dairy",
+        &[
+            "butter", "cheese", "cream", "egg", "milk", "yogurt", "yoghurt",
+        ],
+    ),
+    ("bakery", &["bagel", "bread", "bun", "roll", "tortilla"]),
+    ("frozen", &["frozen"]),
+    (
+        "pantry",
+        &[
+            "bean", "flour", "oil", "pasta", "rice", "salt", "sauce", "spice", "stock", "sugar",
+            "vinegar", "yeast",
+        ],
+    ),
+    (
+        "beverages",
+        &["beer", "coffee", "juice", "soda", "tea", "wine"],
+    ),
+];
+
+#[must_use]
+pub fn map_post_shopping_list(
+    id: Uuid,
+    household_id: &str,
+    value: &PostShoppingList,
+) -> ShoppingList {
+    let aisles = map_aisles(value.aisles.as_deref());
+    let items = value
+        .items
+        .iter()
+        .map(|item| map_post_shopping_list_item(Uuid::new_v4(), item))
+        .map(|item| (item.id, item))
+        .collect();
+
+    ShoppingList {
+        household_id: household_id.to_owned(),
+        id,
+        name: value.name.clone(),
+        aisles,
+        items,
+    }
+}
+
+#[must_use]
+pub fn map_post_shopping_list_item(id: Uuid, value: &PostShoppingListItem) -> ShoppingListItem {
+    ShoppingListItem {
+        id,
+        name: value.name.clone(),
+        quantity: value.quantity.clone(),
+        aisle: value
+            .aisle
+            .as_deref()
+            .map_or(DEFAULT_AISLE.to_owned(), normalize_aisle),
+        checked: false,
+    }
+}
+
+/// Groups the items of a list by aisle.
+///
+/// Aisles are ordered by the list's taxonomy, followed by any aisles the
+/// taxonomy does not know about in alphabetical order. Items within an aisle
+/// are ordered by name and empty aisles are left out.
+#[must_use]
+pub fn group_by_aisle(list: &ShoppingList) -> ShoppingListResponse {
+    let mut groups: BTreeMap<(usize, &str), Vec<ShoppingListItem>> = BTreeMap::new();
+    for item in list.items.values() {
+        let position = list
+            .aisles
+            .iter()
+            .position(|aisle| aisle.eq(&item.aisle))
+            .unwrap_or(usize::MAX);
+        groups
+            .entry((position, item.aisle.as_str()))
+            .or_default()
+            .push(item.clone());
+    }
+
+    let aisles = groups
+        .into_iter()
+        .map(|((_, aisle), mut items)| {
+            items.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+            AisleGroup {
+                aisle: aisle.to_owned(),
+                items,
+            }
+        })
+        .collect();
+
+    ShoppingListResponse {
+        id: list.id,
+        name: list.name.clone(),
+        aisles,
+    }
+}
+
+/// Builds a shopping list from already flattened recipe ingredients. The same
+/// ingredient in the same unit is combined into a single item and every item is
+/// placed in the aisle its name suggests.
+#[must_use]
+pub fn map_recipe_ingredients(
+    id: Uuid,
+    household_id: &str,
+    value: &PostShoppingListFromRecipes,
+    ingredients: &[Ingredient],
+) -> ShoppingList {
+    let aisles = map_aisles(value.aisles.as_deref());
+
+    let mut combined: Vec<(String, Option<f64>, Option<String>)> = Vec::new();
+    let mut positions: HashMap<(String, Option<String>), usize> = HashMap::new();
+    for ingredient in ingredients {
+        let unit = ingredient.unit.as_deref().map(str::trim);
+        let key = (
+            ingredient.name.trim().to_lowercase(),
+            unit.map(str::to_lowercase),
+        );
+        match positions.get(&key) {
+            Some(&position) => {
+                let quantity = &mut combined[position].1;
+                *quantity = match (*quantity, ingredient.quantity) {
+                    (Some(total), Some(quantity)) => Some(total + quantity),
+                    (total, quantity) => total.or(quantity),
+                };
+            },
+            None => {
+                positions.insert(key, combined.len());
+                combined.push((
+                    ingredient.name.trim().to_owned(),
+                    ingredient.quantity,
+                    unit.map(str::to_owned),
+                ));
+            },
+        }
+    }
+
+    let items = combined
+        .into_iter()
+        .map(|(name, quantity, unit)| {
+            let aisle = classify_aisle(&name)
+                .filter(|aisle| aisles.iter().any(|known| known == aisle))
+                .unwrap_or(DEFAULT_AISLE)
+                .to_owned();
+            ShoppingListItem {
+                id: Uuid::new_v4(),
+                quantity: format_quantity(quantity, unit.as_deref()),
+                name,
+                aisle,
+                checked: false,
+            }
+        })
+        .map(|item| (item.id, item))
+        .collect();
+
+    ShoppingList {
+        household_id: household_id.to_owned(),
+        id,
+        name: value.name.clone(),
+        aisles,
+        items,
+    }
+}
+
+fn classify_aisle(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    AISLE_KEYWORDS
+        .iter()
+        .find(|(_, keywords)| keywords.iter().any(|keyword| name.contains(keyword)))
+        .map(|(aisle, _)| *aisle)
+}
+
+fn format_quantity(quantity: Option<f64>, unit: Option<&str>) -> Option<String> {
+    let quantity = quantity.map(|quantity| ((quantity * 100.0).round() / 100.0).to_string());
+    match (quantity, unit) {
+        (Some(quantity), Some(unit)) => Some(format!("{quantity} {unit}")),
+        (Some(quantity), None) => Some(quantity),
+        (None, Some(unit)) => Some(unit.to_owned()),
+        (None, None) => None,
+    }
+}
+
+fn map_aisles(aisles: Option<&[String]>) -> Vec<String> {
+    match aisles {
+        Some(aisles) => aisles.iter().map(|aisle| normalize_aisle(aisle)).collect(),
+        None => DEFAULT_AISLES.iter().map(ToString::to_string).collect(),
+    }
+}
+
+fn normalize_aisle(aisle: &str) -> String { aisle.trim().to_lowercase() }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use uuid::Uuid;
+
+    use super::{ShoppingList, ShoppingListItem};
+    use crate::recipe::Ingredient;
+    use crate::shopping_list::request_models::{
+        PostShoppingList,
+        PostShoppingListFromRecipes,
+        PostShoppingListItem,
+    };
+    use crate::shopping_list::{mapper, DEFAULT_AISLE};
+
+    fn item(id: u128, name: &str, aisle: &str) -> ShoppingListItem {
+        ShoppingListItem {
+            id: Uuid::from_u128(id),
+            name: name.to_owned(),
+            quantity: None,
+            aisle: aisle.to_owned(),
+            checked: false,
+        }
+    }
+
+    #[test]
+    fn create_from_request() {
+        let create_request = PostShoppingList {
+            name: "Weekly Shop".to_owned(),
+            aisles: Some(vec![" Produce ".to_owned(), "Dairy".to_owned()]),
+            items: vec![PostShoppingListItem {
+                name: "Apples".to_owned(),
+                quantity: Some("6".to_owned()),
+                aisle: Some("PRODUCE".to_owned()),
+            }],
+        };
+
+        let list = mapper::map_post_shopping_list(Uuid::nil(), "household", &create_request);
+
+        assert_eq!(list.id, Uuid::nil());
+        assert_eq!(list.name, "Weekly Shop");
+        assert_eq!(list.aisles, vec!["produce", "dairy"]);
+        assert_eq!(list.items.len(), 1);
+        let item = list.items.values().next().unwrap();
+        assert_eq!(item.name, "Apples");
+        assert_eq!(item.aisle, "produce");
+        assert!(!item.checked);
+    }
+
+    #[test]
+    fn create_item_without_aisle() {
+        let item =
+            mapper::map_post_shopping_list_item(Uuid::nil(), &PostShoppingListItem::default());
+
+        assert_eq!(item.aisle, DEFAULT_AISLE);
+    }
+
+    #[test]
+    fn group_by_taxonomy_order() {
+        let items = [
+            item(1, "Milk", "dairy"),
+            item(2, "Bananas", "produce"),
+            item(3, "Apples", "produce"),
+            item(4, "Candles", "party"),
+        ];
+        let list = ShoppingList {
+            items: HashMap::from(items.map(|item| (item.id, item))),
+            ..ShoppingList::default()
+        };
+
+        let grouped = mapper::group_by_aisle(&list);
+
+        let aisles: Vec<&str> = grouped.aisles.iter().map(|g| g.aisle.as_str()).collect();
+        assert_eq!(aisles, vec!["produce", "dairy", "party"]);
+        let produce: Vec<&str> = grouped.aisles[0]
+            .items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect();
+        assert_eq!(produce, vec!["Apples", "Bananas"]);
+    }
+
+    fn ingredient(name: &str, quantity: Option<f64>, unit: Option<&str>) -> Ingredient {
+        Ingredient {
+            name: name.to_owned(),
+            quantity,
+            unit: unit.map(str::to_owned),
+            recipe_id: None,
+        }
+    }
+
+    #[test]
+    fn combine_recipe_ingredients() {
+        let ingredients = [
+            ingredient("Flour", Some(500.0), Some("g")),
+            ingredient("Chicken thighs", Some(4.0), None),
+            ingredient("flour ", Some(250.0), Some("g")),
+            ingredient("Flour", Some(1.0), Some("cup")),
+            ingredient("Saffron", None, None),
+        ];
+
+        let list = mapper::map_recipe_ingredients(
+            Uuid::nil(),
+            "household",
+            &PostShoppingListFromRecipes::default(),
+            &ingredients,
+        );
+
+        let mut items: Vec<(&str, Option<&str>, &str)> = list
+            .items
+            .values()
+            .map(|item| {
+                (
+                    item.name.as_str(),
+                    item.quantity.as_deref(),
+                    item.aisle.as_str(),
+                )
+            })
+            .collect();
+        items.sort_unstable();
+        assert_eq!(
+            items,
+            vec![
+                ("Chicken thighs", Some("4"), "meat"),
+                ("Flour", Some("1 cup"), "pantry"),
+                ("Flour", Some("750 g"), "pantry"),
+                ("Saffron", None, DEFAULT_AISLE),
+            ]
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::future::Future;
+
+use axum::async_trait;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub mod mapper;
+pub mod repository;
+pub mod request_models;
+pub mod response_models;
+
+/// The aisle taxonomy used when a shopping list does not provide its own.
+pub const DEFAULT_AISLES: [&str; 10] = [
+    "produce",
+    "meat",
+    "seafood",
+    "dairy",
+    "bakery",
+    "frozen",
+    "pantry",
+    "beverages",
+    "household",
+    "other",
+];
+
+/// The aisle an item is placed in when none is given.
+pub const DEFAULT_AISLE: &str = "other";
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ShoppingList {
+    /// The household that owns the list, the partition key of the table.
+    #[serde(default)]
+    pub(crate) household_id: String,
+    pub(crate) id: Uuid,
+    pub(crate) name: String,
+    pub(crate) aisles: Vec<String>,
+    pub(crate) items: HashMap<Uuid, ShoppingListItem>,
+}
+
+impl Default for ShoppingList {
+    fn default() -> Self {
+        Self {
+            household_id: String::new(),
+            id: Uuid::nil(),
+            name: "Groceries".to_owned(),
+            aisles: DEFAULT_AISLES.iter().map(ToString::to_string).collect(),
+            items: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ShoppingListItem {
+    pub(crate) id: Uuid,
+    pub(crate) name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) quantity: Option<String>,
+    pub(crate) aisle: String,
+    pub(crate) checked: bool,
+}
+
+impl Default for ShoppingListItem {
+    fn default() -> Self {
+        Self {
+            id: Uuid::nil(),
+            name: "Milk".to_owned(),
+            quantity: None,
+            aisle: "dairy".to_owned(),
+            checked: false,
+        }
+    }
+}
+
+/// Item level operations on a shopping list.
+///
+/// These touch a single item in place rather than rewriting the whole list so
+/// that several people can check items off at the same time without
+/// clobbering each other's changes.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ChecklistRepository: Send + Sync {
+    fn add_item(
+        &self,
+        household_id: &str,
+        list_id: Uuid,
+        item: &ShoppingListItem,
+    ) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    fn set_checked(
+        &self,
+        household_id: &str,
+        list_id: Uuid,
+        item_id: Uuid,
+        checked: bool,
+    ) -> impl Future<Output = Result<ShoppingListItem, StatusCode>> + Send;
+}
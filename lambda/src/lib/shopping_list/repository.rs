@@ -0,0 +1,344 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use aws_config::SdkConfig;
+use aws_sdk_dynamodb::operation::delete_item::DeleteItemError;
+use aws_sdk_dynamodb::operation::get_item::GetItemError;
+use aws_sdk_dynamodb::operation::update_item::UpdateItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use axum::http::StatusCode;
+use serde_dynamo::aws_sdk_dynamodb_1::{from_item, from_items, to_attribute_value, to_item};
+use uuid::Uuid;
+
+use super::{ChecklistRepository, ShoppingList, ShoppingListItem};
+use crate::aws_client::{
+    batch_get_all,
+    query_all,
+    DynamoDbClient,
+    DynamoDbClientImpl,
+    QueryExpression,
+    UpdateExpression,
+};
+use crate::Repository;
+
+#[derive(Clone)]
+pub struct DynamoDbShoppingList {
+    client: Arc<dyn DynamoDbClient>,
+    table_name: String,
+}
+
+impl DynamoDbShoppingList {
+    #[must_use]
+    pub fn new(sdk_config: &SdkConfig, table_name: &str) -> Self {
+        Self {
+            client: Arc::new(DynamoDbClientImpl::new(sdk_config)),
+            table_name: table_name.to_owned(),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn mock(client: Arc<dyn DynamoDbClient>, table_name: &str) -> Self {
+        Self {
+            client,
+            table_name: table_name.to_owned(),
+        }
+    }
+}
+
+impl Repository<ShoppingList> for DynamoDbShoppingList {
+    async fn get_all(&self, household_id: &str) -> Result<Vec<ShoppingList>, StatusCode> {
+        let query = QueryExpression {
+            key_condition: "household_id = :household_id".to_owned(),
+            values: HashMap::from([(
+                ":household_id".to_owned(),
+                AttributeValue::S(household_id.to_owned()),
+            )]),
+            scan_forward: true,
+            ..QueryExpression::default()
+        };
+        let items = query_all(self.client.as_ref(), &self.table_name, query)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(items.into_iter().flat_map(from_item).collect())
+    }
+
+    async fn find_by_id(&self, household_id: &str, id: Uuid) -> Result<ShoppingList, StatusCode> {
+        let get_item_result = self
+            .client
+            .get_item(
+                &self.table_name,
+                DynamoDbShoppingList::get_key(household_id, id),
+            )
+            .await
+            .map_err(|err| match err {
+                GetItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+        let item = get_item_result
+            .item()
+            .ok_or(StatusCode::NOT_FOUND)?
+            .to_owned();
+
+        let list = from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(list)
+    }
+
+    async fn find_by_ids(
+        &self,
+        household_id: &str,
+        ids: &[Uuid],
+    ) -> Result<Vec<ShoppingList>, StatusCode> {
+        // DynamoDB rejects batches that ask for the same key twice.
+        let mut seen = HashSet::new();
+        let ids: Vec<Uuid> = ids.iter().copied().filter(|id| seen.insert(*id)).collect();
+        let keys = ids
+            .iter()
+            .map(|id| DynamoDbShoppingList::get_key(household_id, *id))
+            .collect();
+        let items = batch_get_all(self.client.as_ref(), &self.table_name, keys)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        let mut lists: Vec<ShoppingList> =
+            from_items(items).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        lists.sort_by_key(|list| ids.iter().position(|id| *id == list.id));
+
+        Ok(lists)
+    }
+
+    async fn save(&self, list: &ShoppingList) -> Result<Option<ShoppingList>, StatusCode> {
+        let item = to_item(list).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let output = self
+            .client
+            .put_item(&self.table_name, item)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        match output.attributes {
+            Some(item) => from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR),
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_by_id(&self, household_id: &str, id: Uuid) -> Result<(), StatusCode> {
+        self.client
+            .delete_item(
+                &self.table_name,
+                DynamoDbShoppingList::get_key(household_id, id),
+                "id",
+            )
+            .await
+            .map_err(|err| match err {
+                DeleteItemError::ConditionalCheckFailedException(_)
+                | DeleteItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+
+        Ok(())
+    }
+}
+
+impl ChecklistRepository for DynamoDbShoppingList {
+    async fn add_item(
+        &self,
+        household_id: &str,
+        list_id: Uuid,
+        item: &ShoppingListItem,
+    ) -> Result<(), StatusCode> {
+        let value = to_attribute_value(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let update = UpdateExpression {
+            expression: "SET #items.#item = :item".to_owned(),
+            condition: Some("attribute_exists(id)".to_owned()),
+            names: DynamoDbShoppingList::item_names(item.id),
+            values: HashMap::from([(":item".to_owned(), value)]),
+        };
+
+        self.client
+            .update_item(
+                &self.table_name,
+                DynamoDbShoppingList::get_key(household_id, list_id),
+                update,
+            )
+            .await
+            .map_err(DynamoDbShoppingList::map_update_error)?;
+
+        Ok(())
+    }
+
+    async fn set_checked(
+        &self,
+        household_id: &str,
+        list_id: Uuid,
+        item_id: Uuid,
+        checked: bool,
+    ) -> Result<ShoppingListItem, StatusCode> {
+        let mut names = DynamoDbShoppingList::item_names(item_id);
+        names.insert("#checked".to_owned(), "checked".to_owned());
+        let update = UpdateExpression {
+            expression: "SET #items.#item.#checked = :checked".to_owned(),
+            condition: Some("attribute_exists(#items.#item)".to_owned()),
+            names,
+            values: HashMap::from([(":checked".to_owned(), AttributeValue::Bool(checked))]),
+        };
+
+        let output = self
+            .client
+            .update_item(
+                &self.table_name,
+                DynamoDbShoppingList::get_key(household_id, list_id),
+                update,
+            )
+            .await
+            .map_err(DynamoDbShoppingList::map_update_error)?;
+        let mut list: ShoppingList = output
+            .attributes
+            .map(from_item)
+            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        list.items
+            .remove(&item_id)
+            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl DynamoDbShoppingList {
+    fn get_key(household_id: &str, id: Uuid) -> HashMap<String, AttributeValue> {
+        let mut key = HashMap::new();
+        key.insert(
+            "household_id".to_string(),
+            AttributeValue::S(household_id.to_owned()),
+        );
+        key.insert(
+            "id".to_string(),
+            AttributeValue::S(id.as_hyphenated().to_string()),
+        );
+
+        key
+    }
+
+    fn item_names(item_id: Uuid) -> HashMap<String, String> {
+        HashMap::from([
+            ("#items".to_owned(), "items".to_owned()),
+            ("#item".to_owned(), item_id.as_hyphenated().to_string()),
+        ])
+    }
+
+    fn map_update_error(err: UpdateItemError) -> StatusCode {
+        match err {
+            UpdateItemError::ConditionalCheckFailedException(_)
+            | UpdateItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::result_large_err)]
+mod test {
+    use aws_sdk_dynamodb::operation::get_item::GetItemOutput;
+    use aws_sdk_dynamodb::operation::update_item::UpdateItemOutput;
+    use aws_sdk_dynamodb::types::error::ConditionalCheckFailedException;
+    use mockall::predicate::{eq, function};
+
+    use super::*;
+    use crate::aws_client::MockDynamoDbClient as DynamoDbClient;
+
+    const HOUSEHOLD: &str = "household";
+
+    fn list_with_item() -> ShoppingList {
+        let item = ShoppingListItem::default();
+        ShoppingList {
+            items: HashMap::from([(item.id, item)]),
+            ..ShoppingList::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_no_error() {
+        let list = list_with_item();
+        let item = to_item(&list).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .with(
+                eq("shopping-lists"),
+                eq(DynamoDbShoppingList::get_key(HOUSEHOLD, Uuid::nil())),
+            )
+            .return_once(|_, _| Ok(GetItemOutput::builder().set_item(Some(item)).build()));
+
+        let repo = DynamoDbShoppingList::mock(Arc::new(mock), "shopping-lists");
+
+        let result = repo.find_by_id(HOUSEHOLD, Uuid::nil()).await;
+        assert_eq!(result.unwrap(), list);
+    }
+
+    #[tokio::test]
+    async fn test_set_checked() {
+        let mut list = list_with_item();
+        list.items.get_mut(&Uuid::nil()).unwrap().checked = true;
+        let item = to_item(&list).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_update_item()
+            .with(
+                eq("shopping-lists"),
+                eq(DynamoDbShoppingList::get_key(HOUSEHOLD, Uuid::nil())),
+                function(|update: &UpdateExpression| {
+                    update.values.get(":checked") == Some(&AttributeValue::Bool(true))
+                        && update.names.get("#item") == Some(&Uuid::nil().to_string())
+                }),
+            )
+            .return_once(|_, _, _| {
+                Ok(UpdateItemOutput::builder()
+                    .set_attributes(Some(item))
+                    .build())
+            });
+
+        let repo = DynamoDbShoppingList::mock(Arc::new(mock), "shopping-lists");
+
+        let result = repo
+            .set_checked(HOUSEHOLD, Uuid::nil(), Uuid::nil(), true)
+            .await;
+        assert!(result.is_ok_and(|item| item.checked));
+    }
+
+    #[tokio::test]
+    async fn test_set_checked_missing_item() {
+        let mut mock = DynamoDbClient::default();
+        mock.expect_update_item().return_once(|_, _, _| {
+            Err(UpdateItemError::ConditionalCheckFailedException(
+                ConditionalCheckFailedException::builder().build(),
+            ))
+        });
+
+        let repo = DynamoDbShoppingList::mock(Arc::new(mock), "shopping-lists");
+
+        let result = repo
+            .set_checked(HOUSEHOLD, Uuid::nil(), Uuid::nil(), true)
+            .await;
+        assert!(result.is_err_and(|err| matches!(err, StatusCode::NOT_FOUND)));
+    }
+
+    #[tokio::test]
+    async fn test_add_item() {
+        let mut mock = DynamoDbClient::default();
+        mock.expect_update_item()
+            .with(
+                eq("shopping-lists"),
+                eq(DynamoDbShoppingList::get_key(HOUSEHOLD, Uuid::nil())),
+                function(|update: &UpdateExpression| {
+                    update.values.contains_key(":item")
+                        && update.condition.as_deref() == Some("attribute_exists(id)")
+                }),
+            )
+            .return_once(|_, _, _| Ok(UpdateItemOutput::builder().build()));
+
+        let repo = DynamoDbShoppingList::mock(Arc::new(mock), "shopping-lists");
+
+        let result = repo
+            .add_item(HOUSEHOLD, Uuid::nil(), &ShoppingListItem::default())
+            .await;
+        assert!(result.is_ok());
+    }
+}
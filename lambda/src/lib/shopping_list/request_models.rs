@@ -0,0 +1,117 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct PostShoppingList {
+    pub(super) name: String,
+    #[serde(default)]
+    pub(super) aisles: Option<Vec<String>>,
+    #[serde(default)]
+    pub(super) items: Vec<PostShoppingListItem>,
+}
+
+impl Default for PostShoppingList {
+    fn default() -> Self {
+        Self {
+            name: "Groceries".to_owned(),
+            aisles: None,
+            items: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct PostShoppingListItem {
+    pub(super) name: String,
+    #[serde(default)]
+    pub(super) quantity: Option<String>,
+    #[serde(default)]
+    pub(super) aisle: Option<String>,
+}
+
+impl Default for PostShoppingListItem {
+    fn default() -> Self {
+        Self {
+            name: "Milk".to_owned(),
+            quantity: None,
+            aisle: None,
+        }
+    }
+}
+
+/// A shopping list made of the ingredients of one or more recipes.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct PostShoppingListFromRecipes {
+    pub(super) name: String,
+    #[serde(default)]
+    pub(super) aisles: Option<Vec<String>>,
+    pub(crate) recipe_ids: Vec<Uuid>,
+}
+
+impl Default for PostShoppingListFromRecipes {
+    fn default() -> Self {
+        Self {
+            name: "Groceries".to_owned(),
+            aisles: None,
+            recipe_ids: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct PatchShoppingListItem {
+    pub(crate) checked: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{PatchShoppingListItem, PostShoppingList, PostShoppingListItem};
+
+    const JSON: &str = r#"{
+        "name": "Weekly Shop",
+        "items": [
+            { "name": "Apples", "quantity": "6", "aisle": "produce" },
+            { "name": "Bread" }
+        ]
+    }"#;
+
+    #[test]
+    fn deserialize_post_shopping_list_request() {
+        let actual: PostShoppingList = serde_json::from_str(JSON).unwrap();
+        let expected = PostShoppingList {
+            name: "Weekly Shop".to_owned(),
+            aisles: None,
+            items: vec![
+                PostShoppingListItem {
+                    name: "Apples".to_owned(),
+                    quantity: Some("6".to_owned()),
+                    aisle: Some("produce".to_owned()),
+                },
+                PostShoppingListItem {
+                    name: "Bread".to_owned(),
+                    quantity: None,
+                    aisle: None,
+                },
+            ],
+        };
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn deserialize_post_shopping_list_request_none() {
+        assert!(serde_json::from_str::<PostShoppingList>("{}").is_err());
+    }
+
+    #[test]
+    fn deserialize_patch_shopping_list_item_request() {
+        let actual: PatchShoppingListItem = serde_json::from_str("{\"checked\": true}").unwrap();
+
+        assert_eq!(PatchShoppingListItem { checked: true }, actual);
+    }
+
+    #[test]
+    fn deserialize_patch_shopping_list_item_request_none() {
+        assert!(serde_json::from_str::<PatchShoppingListItem>("{}").is_err());
+    }
+}
@@ -0,0 +1,19 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::ShoppingListItem;
+
+/// A shopping list as it is returned to clients, with its items grouped by
+/// aisle in the order of the list's taxonomy.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ShoppingListResponse {
+    pub(crate) id: Uuid,
+    pub(crate) name: String,
+    pub(crate) aisles: Vec<AisleGroup>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AisleGroup {
+    pub(crate) aisle: String,
+    pub(crate) items: Vec<ShoppingListItem>,
+}
@@ -0,0 +1,183 @@
+use std::collections::BTreeSet;
+
+use super::Placement;
+use crate::kitchen::{Appliance, Kitchen};
+use crate::recipe::Recipe;
+
+/// More of an appliance asked for at once than the kitchen has, in minutes
+/// before the meal is served.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Overlap {
+    pub(super) appliance: Appliance,
+    pub(super) needed: u32,
+    pub(super) available: u32,
+    pub(super) start: u32,
+    pub(super) end: u32,
+    /// The recipes and steps that need the appliance, by index.
+    pub(super) steps: Vec<(usize, usize)>,
+}
+
+/// Finds the moments where the placed steps need more of an appliance than
+/// the kitchen has. Steps that set an appliance to the same temperature share
+/// it, so two dishes in the oven at 180°C fit while one at 180°C and one at
+/// 220°C need two ovens. Kitchens the household has not described are assumed
+/// to have the usual appliances.
+pub(super) fn detect(
+    recipes: &[Recipe],
+    placements: &[Placement],
+    kitchen: Option<&Kitchen>,
+) -> Vec<Overlap> {
+    let mut uses = Vec::new();
+    for placement in placements
+        .iter()
+        .filter(|placement| placement.start > placement.end)
+    {
+        let step = &recipes[placement.recipe].steps[placement.step];
+        uses.extend(step.equipment.iter().map(|cookware| (placement, cookware)));
+    }
+    let appliances: BTreeSet<Appliance> = uses
+        .iter()
+        .map(|(_, cookware)| cookware.appliance)
+        .collect();
+
+    let mut overlaps = Vec::new();
+    for appliance in appliances {
+        let available = kitchen.map_or(appliance.assumed_count(), |kitchen| {
+            kitchen.count(appliance)
+        });
+        let uses: Vec<_> = uses
+            .iter()
+            .filter(|(_, cookware)| cookware.appliance == appliance)
+            .collect();
+        // Whatever runs at a moment started at or before it, so checking
+        // when each step starts finds every overlap.
+        let mut starts: Vec<u32> = uses.iter().map(|(placement, _)| placement.start).collect();
+        starts.sort_unstable_by(|a, b| b.cmp(a));
+        starts.dedup();
+
+        let mut previous: Option<Vec<(usize, usize)>> = None;
+        for moment in starts {
+            let running: Vec<_> = uses
+                .iter()
+                .filter(|(placement, _)| placement.start >= moment && moment > placement.end)
+                .collect();
+            let temperatures: BTreeSet<u16> = running
+                .iter()
+                .filter_map(|(_, cookware)| cookware.celsius)
+                .collect();
+            let unheated: u32 = running
+                .iter()
+                .filter(|(_, cookware)| cookware.celsius.is_none())
+                .map(|(_, cookware)| cookware.count())
+                .sum();
+            let needed = unheated + u32::try_from(temperatures.len()).unwrap_or(u32::MAX);
+            if needed <= available {
+                previous = None;
+                continue;
+            }
+
+            let mut steps: Vec<(usize, usize)> = running
+                .iter()
+                .map(|(placement, _)| (placement.recipe, placement.step))
+                .collect();
+            steps.sort_unstable();
+            steps.dedup();
+            if previous.as_ref() == Some(&steps) {
+                continue;
+            }
+            previous = Some(steps.clone());
+            overlaps.push(Overlap {
+                appliance,
+                needed,
+                available,
+                start: moment,
+                end: running
+                    .iter()
+                    .map(|(placement, _)| placement.end)
+                    .max()
+                    .unwrap_or(0),
+                steps,
+            });
+        }
+    }
+
+    overlaps
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::detect;
+    use crate::kitchen::{Appliance, Cookware, Kitchen};
+    use crate::recipe::{Recipe, Step};
+    use crate::timeline::Placement;
+
+    fn oven(celsius: Option<u16>) -> Cookware {
+        Cookware {
+            appliance: Appliance::Oven,
+            celsius,
+            count: None,
+        }
+    }
+
+    fn recipe(equipment: Vec<Cookware>) -> Recipe {
+        Recipe {
+            steps: vec![Step {
+                instruction: "Bake".to_owned(),
+                equipment,
+                ..Step::default()
+            }],
+            ..Recipe::default()
+        }
+    }
+
+    fn placement(recipe: usize, start: u32, end: u32) -> Placement {
+        Placement {
+            recipe,
+            step: 0,
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn oven_at_two_temperatures() {
+        let recipes = [
+            recipe(vec![oven(Some(180))]),
+            recipe(vec![oven(Some(220))]),
+            recipe(vec![oven(Some(180))]),
+        ];
+        let placements = [
+            placement(0, 60, 0),
+            placement(1, 30, 10),
+            placement(2, 90, 50),
+        ];
+
+        let overlaps = detect(&recipes, &placements, None);
+
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].appliance, Appliance::Oven);
+        assert_eq!((overlaps[0].needed, overlaps[0].available), (2, 1));
+        assert_eq!((overlaps[0].start, overlaps[0].end), (30, 10));
+        assert_eq!(overlaps[0].steps, vec![(0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn burners_of_the_kitchen() {
+        let burners = |count| Cookware {
+            appliance: Appliance::Burner,
+            celsius: None,
+            count: Some(count),
+        };
+        let recipes = [recipe(vec![burners(2)]), recipe(vec![burners(1)])];
+        let placements = [placement(0, 20, 0), placement(1, 20, 0)];
+        let kitchen = Kitchen {
+            appliances: BTreeMap::from([(Appliance::Burner, 2)]),
+            ..Kitchen::default()
+        };
+
+        assert!(detect(&recipes, &placements, None).is_empty());
+        assert_eq!(detect(&recipes, &placements, Some(&kitchen))[0].needed, 3);
+    }
+}
@@ -0,0 +1,366 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::date::format_date_time;
+use crate::kitchen::{Appliance, Kitchen};
+use crate::recipe::Recipe;
+
+mod conflicts;
+pub mod request_models;
+
+/// The steps of the recipes of a meal, scheduled so that everything is ready
+/// when the meal is served.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct Timeline {
+    /// When the meal is served, as `YYYY-MM-DDTHH:MM` in local time.
+    pub(crate) serve_at: String,
+    /// When the first step starts.
+    pub(crate) starts_at: String,
+    /// The steps in the order they start.
+    pub(crate) tasks: Vec<Task>,
+    /// The moments where the steps need more cookware than the kitchen has.
+    pub(crate) conflicts: Vec<Conflict>,
+}
+
+/// A step of a recipe placed in a timeline.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct Task {
+    pub(crate) recipe_id: Uuid,
+    pub(crate) recipe_name: String,
+    /// The index of the step in the recipe.
+    pub(crate) step: usize,
+    pub(crate) instruction: String,
+    /// Whether the step keeps the cook busy.
+    pub(crate) active: bool,
+    pub(crate) starts_at: String,
+    pub(crate) ends_at: String,
+}
+
+/// Steps that need more of an appliance at once than the kitchen has.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct Conflict {
+    pub(crate) appliance: Appliance,
+    /// How many of the appliance the steps need at once.
+    pub(crate) needed: u32,
+    /// How many of the appliance the kitchen has.
+    pub(crate) available: u32,
+    pub(crate) starts_at: String,
+    pub(crate) ends_at: String,
+    pub(crate) steps: Vec<StepReference>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct StepReference {
+    pub(crate) recipe_id: Uuid,
+    /// The index of the step in the recipe.
+    pub(crate) step: usize,
+}
+
+/// A step placed before the meal, in minutes before it is served.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Placement {
+    recipe: usize,
+    step: usize,
+    start: u32,
+    end: u32,
+}
+
+/// A step of one of the recipes, with its dependencies across all of them.
+struct Job {
+    recipe: usize,
+    step: usize,
+    minutes: u32,
+    active: bool,
+    dependencies: Vec<usize>,
+}
+
+/// Schedules the steps of the recipes backwards from when the meal is served,
+/// in minutes since the epoch. Every step starts after the steps it waits
+/// for, and there is a single cook, so active steps never overlap while
+/// passive steps run alongside anything. Steps that need more cookware than
+/// the kitchen has are reported rather than moved.
+#[must_use]
+pub fn schedule(recipes: &[Recipe], serve_at: u64, kitchen: Option<&Kitchen>) -> Timeline {
+    let mut placements = place(recipes);
+    placements.sort_by_key(|placement| {
+        (
+            std::cmp::Reverse(placement.start),
+            placement.recipe,
+            placement.step,
+        )
+    });
+    let lead_time = placements.first().map_or(0, |placement| placement.start);
+    let clock =
+        |minutes_before: u32| format_date_time(serve_at.saturating_sub(u64::from(minutes_before)));
+
+    let tasks = placements
+        .iter()
+        .map(|placement| {
+            let recipe = &recipes[placement.recipe];
+            let step = &recipe.steps[placement.step];
+            Task {
+                recipe_id: recipe.id,
+                recipe_name: recipe.name.clone(),
+                step: placement.step,
+                instruction: step.instruction.clone(),
+                active: !step.passive,
+                starts_at: clock(placement.start),
+                ends_at: clock(placement.end),
+            }
+        })
+        .collect();
+    let conflicts = conflicts::detect(recipes, &placements, kitchen)
+        .into_iter()
+        .map(|overlap| Conflict {
+            appliance: overlap.appliance,
+            needed: overlap.needed,
+            available: overlap.available,
+            starts_at: clock(overlap.start),
+            ends_at: clock(overlap.end),
+            steps: overlap
+                .steps
+                .into_iter()
+                .map(|(recipe, step)| StepReference {
+                    recipe_id: recipes[recipe].id,
+                    step,
+                })
+                .collect(),
+        })
+        .collect();
+
+    Timeline {
+        serve_at: clock(0),
+        starts_at: clock(lead_time),
+        tasks,
+        conflicts,
+    }
+}
+
+/// Places the steps from the last to the first, each as late as the steps
+/// waiting for it and the cook allow. Passive steps are placed as soon as
+/// they can be, since they never keep the cook from anything. Among active
+/// steps, the one with the longest chain of steps before it goes closest to
+/// serving, so that long preparations are not pushed back further.
+fn place(recipes: &[Recipe]) -> Vec<Placement> {
+    let mut jobs = Vec::new();
+    for (recipe_index, recipe) in recipes.iter().enumerate() {
+        let offset = jobs.len();
+        for (step_index, step) in recipe.steps.iter().enumerate() {
+            jobs.push(Job {
+                recipe: recipe_index,
+                step: step_index,
+                minutes: step.minutes.unwrap_or(0),
+                active: !step.passive,
+                // Only earlier steps count, which rules out cycles.
+                dependencies: step
+                    .dependencies(step_index)
+                    .into_iter()
+                    .filter(|dependency| *dependency < step_index)
+                    .map(|dependency| offset + dependency)
+                    .collect(),
+            });
+        }
+    }
+
+    // The longest chain of steps ending with each step, in minutes.
+    let mut lead_up = vec![0; jobs.len()];
+    let mut waiting = vec![0; jobs.len()];
+    for (index, job) in jobs.iter().enumerate() {
+        let before = job
+            .dependencies
+            .iter()
+            .map(|dependency| lead_up[*dependency])
+            .max()
+            .unwrap_or(0);
+        lead_up[index] = before + job.minutes;
+        for dependency in &job.dependencies {
+            waiting[*dependency] += 1;
+        }
+    }
+
+    // The latest each step may end, pushed back by the steps waiting for it.
+    let mut latest_end = vec![0; jobs.len()];
+    let mut placed: Vec<Option<Placement>> = vec![None; jobs.len()];
+    let mut busy: Vec<(u32, u32)> = Vec::new();
+    for _ in 0..jobs.len() {
+        let ready =
+            (0..jobs.len()).filter(|index| placed[*index].is_none() && waiting[*index] == 0);
+        let Some(index) = ready.max_by_key(|index| {
+            (
+                !jobs[*index].active,
+                lead_up[*index],
+                std::cmp::Reverse(*index),
+            )
+        }) else {
+            break;
+        };
+        let job = &jobs[index];
+
+        let mut end = latest_end[index];
+        if job.active && job.minutes > 0 {
+            while let Some(&(_, start)) = busy
+                .iter()
+                .find(|(busy_end, busy_start)| end < *busy_start && *busy_end < end + job.minutes)
+            {
+                end = start;
+            }
+            busy.push((end, end + job.minutes));
+        }
+        let start = end + job.minutes;
+
+        placed[index] = Some(Placement {
+            recipe: job.recipe,
+            step: job.step,
+            start,
+            end,
+        });
+        for dependency in &job.dependencies {
+            waiting[*dependency] -= 1;
+            latest_end[*dependency] = latest_end[*dependency].max(start);
+        }
+    }
+
+    placed.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use super::schedule;
+    use crate::date::parse_date_time;
+    use crate::recipe::{Recipe, Step};
+
+    fn step(instruction: &str, minutes: u32, passive: bool) -> Step {
+        Step {
+            instruction: instruction.to_owned(),
+            minutes: Some(minutes),
+            passive,
+            ..Step::default()
+        }
+    }
+
+    fn recipe(id: u128, name: &str, steps: Vec<Step>) -> Recipe {
+        Recipe {
+            id: Uuid::from_u128(id),
+            name: name.to_owned(),
+            steps,
+            ..Recipe::default()
+        }
+    }
+
+    fn times(timeline: &super::Timeline) -> Vec<(&str, &str, &str)> {
+        timeline
+            .tasks
+            .iter()
+            .map(|task| {
+                (
+                    task.instruction.as_str(),
+                    task.starts_at.as_str(),
+                    task.ends_at.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn interleave_recipes_with_a_single_cook() {
+        let turkey = recipe(
+            1,
+            "Turkey",
+            vec![
+                step("Stuff", 20, false),
+                step("Roast", 180, true),
+                step("Rest", 30, true),
+            ],
+        );
+        let potatoes = recipe(
+            2,
+            "Mashed potatoes",
+            vec![
+                step("Peel", 15, false),
+                step("Boil", 20, true),
+                step("Mash", 10, false),
+            ],
+        );
+
+        let timeline = schedule(
+            &[turkey, potatoes],
+            parse_date_time("2025-11-27T17:00").unwrap(),
+            None,
+        );
+
+        assert_eq!(timeline.serve_at, "2025-11-27T17:00");
+        assert_eq!(timeline.starts_at, "2025-11-27T13:10");
+        assert_eq!(
+            times(&timeline),
+            vec![
+                ("Stuff", "2025-11-27T13:10", "2025-11-27T13:30"),
+                ("Roast", "2025-11-27T13:30", "2025-11-27T16:30"),
+                ("Peel", "2025-11-27T16:15", "2025-11-27T16:30"),
+                ("Rest", "2025-11-27T16:30", "2025-11-27T17:00"),
+                ("Boil", "2025-11-27T16:30", "2025-11-27T16:50"),
+                ("Mash", "2025-11-27T16:50", "2025-11-27T17:00"),
+            ]
+        );
+        assert!(!timeline.tasks[1].active);
+    }
+
+    #[test]
+    fn active_steps_never_overlap() {
+        let salad = recipe(
+            1,
+            "Salad",
+            vec![step("Wash", 10, false), step("Dress", 5, false)],
+        );
+        let soup = recipe(2, "Soup", vec![step("Chop", 15, false)]);
+
+        let timeline = schedule(
+            &[salad, soup],
+            parse_date_time("2025-01-01T12:00").unwrap(),
+            None,
+        );
+
+        assert_eq!(
+            times(&timeline),
+            vec![
+                ("Wash", "2025-01-01T11:30", "2025-01-01T11:40"),
+                ("Chop", "2025-01-01T11:40", "2025-01-01T11:55"),
+                ("Dress", "2025-01-01T11:55", "2025-01-01T12:00"),
+            ]
+        );
+    }
+
+    #[test]
+    fn steps_wait_for_their_dependencies() {
+        let bread = recipe(
+            1,
+            "Bread",
+            vec![
+                step("Mix", 10, false),
+                step("Heat the oven", 5, false),
+                Step {
+                    after: Some(vec![0, 1]),
+                    ..step("Bake", 40, true)
+                },
+                Step {
+                    after: Some(Vec::new()),
+                    ..step("Whip butter", 5, false)
+                },
+            ],
+        );
+
+        let timeline = schedule(&[bread], parse_date_time("2025-01-01T12:00").unwrap(), None);
+
+        assert_eq!(
+            times(&timeline),
+            vec![
+                ("Mix", "2025-01-01T11:05", "2025-01-01T11:15"),
+                ("Heat the oven", "2025-01-01T11:15", "2025-01-01T11:20"),
+                ("Bake", "2025-01-01T11:20", "2025-01-01T12:00"),
+                ("Whip butter", "2025-01-01T11:55", "2025-01-01T12:00"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::date::parse_date_time;
+use crate::validation::{max_items, not_empty, Validate, Violations};
+
+/// Enough for the dishes of a holiday dinner.
+pub const MAX_RECIPES: usize = 20;
+
+/// The recipes of a meal and when it is served.
+#[derive(Debug, Default, Deserialize, PartialEq, ToSchema)]
+pub struct PostTimeline {
+    pub(crate) recipe_ids: Vec<Uuid>,
+    /// When the meal is served, as `YYYY-MM-DDTHH:MM` in local time.
+    pub(crate) serve_at: String,
+}
+
+impl Validate for PostTimeline {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        let recipes_pointer = format!("{pointer}/recipe_ids");
+        violations.check(&recipes_pointer, not_empty(&self.recipe_ids));
+        violations.check(&recipes_pointer, max_items(&self.recipe_ids, MAX_RECIPES));
+        violations.check(
+            &format!("{pointer}/serve_at"),
+            parse_date_time(&self.serve_at)
+                .map(|_| ())
+                .ok_or_else(|| "must be a date and time as YYYY-MM-DDTHH:MM".to_owned()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use super::PostTimeline;
+    use crate::validation::Validate;
+
+    #[test]
+    fn validate_recipes_and_serve_time() {
+        let timeline = PostTimeline {
+            recipe_ids: Vec::new(),
+            serve_at: "2025-11-27 17:00".to_owned(),
+        };
+
+        let violations = timeline.validate().unwrap_err();
+
+        let pointers: Vec<&str> = violations
+            .errors()
+            .iter()
+            .map(|violation| violation.pointer.as_str())
+            .collect();
+        assert_eq!(pointers, vec!["/recipe_ids", "/serve_at"]);
+        let valid = PostTimeline {
+            recipe_ids: vec![Uuid::nil()],
+            serve_at: "2025-11-27T17:00".to_owned(),
+        };
+        assert_eq!(valid.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,328 @@
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{FromRequest, Request};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{async_trait, Json};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// A rule a single field of a request broke, located with a JSON pointer into
+/// the request body.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct Violation {
+    pub(crate) pointer: String,
+    pub(crate) message: String,
+}
+
+/// Every rule a request broke, so that clients can report them all at once.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, ToSchema)]
+pub struct Violations {
+    errors: Vec<Violation>,
+}
+
+impl Violations {
+    /// A single violation at `pointer`.
+    #[must_use]
+    pub fn single(pointer: &str, message: &str) -> Self {
+        Self {
+            errors: vec![Violation {
+                pointer: pointer.to_owned(),
+                message: message.to_owned(),
+            }],
+        }
+    }
+
+    /// Records a violation at `pointer` if the rule failed.
+    pub fn check(&mut self, pointer: &str, rule: Result<(), String>) {
+        if let Err(message) = rule {
+            self.errors.push(Violation {
+                pointer: pointer.to_owned(),
+                message,
+            });
+        }
+    }
+
+    /// Validates at most `max` items, each one at its index below `pointer`.
+    pub fn check_each<T: Validate>(&mut self, pointer: &str, items: &[T], max: usize) {
+        self.check(pointer, max_items(items, max));
+        for (index, item) in items.iter().enumerate() {
+            item.validate_at(&format!("{pointer}/{index}"), self);
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool { self.errors.is_empty() }
+
+    #[must_use]
+    pub fn errors(&self) -> &[Violation] { &self.errors }
+}
+
+/// Responds with unprocessable entity and the violations as the body.
+impl IntoResponse for Violations {
+    fn into_response(self) -> Response {
+        (StatusCode::UNPROCESSABLE_ENTITY, Json(self)).into_response()
+    }
+}
+
+/// Why a handler failed, for handlers that can only check some rules once they
+/// have loaded what the request refers to.
+#[derive(Debug, PartialEq)]
+pub enum RequestError {
+    Status(StatusCode),
+    Invalid(Violations),
+}
+
+impl From<StatusCode> for RequestError {
+    fn from(value: StatusCode) -> Self { Self::Status(value) }
+}
+
+impl From<Violations> for RequestError {
+    fn from(value: Violations) -> Self { Self::Invalid(value) }
+}
+
+impl IntoResponse for RequestError {
+    fn into_response(self) -> Response {
+        match self {
+            RequestError::Status(status) => status.into_response(),
+            RequestError::Invalid(violations) => violations.into_response(),
+        }
+    }
+}
+
+/// A request model, or part of one, with rules beyond the shape serde checks.
+pub trait Validate {
+    /// Records every rule broken by the value found at `pointer`.
+    fn validate_at(&self, pointer: &str, violations: &mut Violations);
+
+    /// Checks the value as a whole request body.
+    ///
+    /// # Errors
+    ///
+    /// Returns every rule that was broken.
+    fn validate(&self) -> Result<(), Violations> {
+        let mut violations = Violations::default();
+        self.validate_at("", &mut violations);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+/// Fails if the text is empty or only whitespace.
+///
+/// # Errors
+///
+/// Describes the broken rule.
+pub fn not_blank(value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        Err("must not be blank".to_owned())
+    } else {
+        Ok(())
+    }
+}
+
+/// Fails if the text has more than `max` characters.
+///
+/// # Errors
+///
+/// Describes the broken rule.
+pub fn max_length(value: &str, max: usize) -> Result<(), String> {
+    if value.chars().count() > max {
+        Err(format!("must be at most {max} characters long"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Fails if there are no items.
+///
+/// # Errors
+///
+/// Describes the broken rule.
+pub fn not_empty<T>(items: &[T]) -> Result<(), String> {
+    if items.is_empty() {
+        Err("must not be empty".to_owned())
+    } else {
+        Ok(())
+    }
+}
+
+/// Fails if there are more than `max` items.
+///
+/// # Errors
+///
+/// Describes the broken rule.
+pub fn max_items<T>(items: &[T], max: usize) -> Result<(), String> {
+    if items.len() > max {
+        Err(format!("must have at most {max} items"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Fails unless the number is finite and greater than zero.
+///
+/// # Errors
+///
+/// Describes the broken rule.
+pub fn positive(value: f64) -> Result<(), String> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err("must be greater than zero".to_owned())
+    }
+}
+
+/// A JSON request body that has been checked against its rules before the
+/// handler runs.
+#[derive(Debug)]
+pub struct ValidatedJson<T>(pub T);
+
+/// Why a [`ValidatedJson`] body was rejected.
+#[derive(Debug)]
+pub enum ValidationRejection {
+    /// The body is not JSON of the expected shape.
+    Json(JsonRejection),
+    /// The body has the expected shape but breaks some rules.
+    Invalid(Violations),
+}
+
+impl IntoResponse for ValidationRejection {
+    fn into_response(self) -> Response {
+        match self {
+            ValidationRejection::Json(rejection) => rejection.into_response(),
+            ValidationRejection::Invalid(violations) => violations.into_response(),
+        }
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ValidationRejection;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(ValidationRejection::Json)?;
+        value.validate().map_err(ValidationRejection::Invalid)?;
+
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use axum::body::Body;
+    use axum::extract::FromRequest;
+    use axum::http::header::CONTENT_TYPE;
+    use axum::http::{Request, StatusCode};
+    use axum::response::IntoResponse;
+    use serde::Deserialize;
+
+    use super::{
+        max_length,
+        not_blank,
+        not_empty,
+        positive,
+        Validate,
+        ValidatedJson,
+        Violation,
+        Violations,
+    };
+
+    #[derive(Debug, Deserialize)]
+    struct Pantry {
+        name: String,
+        shelves: Vec<Shelf>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Shelf {
+        label: String,
+    }
+
+    impl Validate for Shelf {
+        fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+            violations.check(&format!("{pointer}/label"), not_blank(&self.label));
+        }
+    }
+
+    impl Validate for Pantry {
+        fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+            violations.check(&format!("{pointer}/name"), max_length(&self.name, 3));
+            violations.check_each(&format!("{pointer}/shelves"), &self.shelves, 1);
+        }
+    }
+
+    #[test]
+    fn rules() {
+        assert!(not_blank(" a ").is_ok());
+        assert!(not_blank(" \t").is_err());
+        assert!(max_length("äöü", 3).is_ok());
+        assert!(max_length("abcd", 3).is_err());
+        assert!(not_empty(&[1]).is_ok());
+        assert!(not_empty::<u8>(&[]).is_err());
+        assert!(positive(0.5).is_ok());
+        assert!(positive(0.0).is_err());
+        assert!(positive(f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn reports_every_violation() {
+        let pantry = Pantry {
+            name: "Cellar".to_owned(),
+            shelves: vec![
+                Shelf {
+                    label: "Top".to_owned(),
+                },
+                Shelf {
+                    label: " ".to_owned(),
+                },
+            ],
+        };
+
+        let violations = pantry.validate().unwrap_err();
+
+        let pointers: Vec<&str> = violations
+            .errors()
+            .iter()
+            .map(|violation| violation.pointer.as_str())
+            .collect();
+        assert_eq!(pointers, vec!["/name", "/shelves", "/shelves/1/label"]);
+    }
+
+    #[tokio::test]
+    async fn extractor_rejects_invalid_body() {
+        let request = Request::post("/")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{ "name": "Cellar", "shelves": [] }"#))
+            .unwrap();
+
+        let rejection = ValidatedJson::<Pantry>::from_request(request, &())
+            .await
+            .unwrap_err();
+
+        let response = rejection.into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let expected = serde_json::json!({
+            "errors": [Violation {
+                pointer: "/name".to_owned(),
+                message: "must be at most 3 characters long".to_owned(),
+            }]
+        });
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            expected
+        );
+    }
+}
@@ -0,0 +1,783 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::async_trait;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use futures_util::future::join_all;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use reqwest::{Client, Url};
+use tokio::time::Instant;
+use tracing::{error, warn};
+
+use super::{
+    literal_address,
+    loopback_allowed,
+    reachable,
+    sign,
+    DeliveryAttempt,
+    EventType,
+    Webhook,
+    WebhookEvent,
+    WebhookRepository,
+    EVENT_HEADER,
+    EVENT_ID_HEADER,
+    SIGNATURE_HEADER,
+};
+use crate::change_feed::{
+    BatchItemFailure,
+    ChangeHandler,
+    ChangeKind,
+    OperationType,
+    RecipeChange,
+    StreamEvent,
+    StreamRecord,
+    StreamResponse,
+};
+use crate::clock;
+use crate::plan::MealPlan;
+
+/// How often an event is sent to a webhook before giving up on it.
+pub const DELIVERY_ATTEMPTS: u32 = 4;
+
+/// How long to wait before the second attempt, doubled for every further one.
+pub const DELIVERY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// How long a receiver has to respond to a delivery.
+pub const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long delivering an event to all webhooks may take, retries included.
+/// Bounds how long a slow receiver holds up the records after it.
+pub const DELIVERY_DEADLINE: Duration = Duration::from_secs(20);
+
+/// Sends a signed event to a receiver, responding with the status code the
+/// receiver answered with or why it could not be reached.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait WebhookSender: Send + Sync {
+    fn send(
+        &self,
+        url: &str,
+        headers: Vec<(&'static str, String)>,
+        body: &str,
+    ) -> impl Future<Output = Result<u16, String>> + Send;
+}
+
+#[derive(Clone)]
+pub struct ReqwestSender {
+    client: Client,
+    allow_loopback: bool,
+}
+
+impl ReqwestSender {
+    /// Reaches the loopback interface only if [`loopback_allowed`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized.
+    #[must_use]
+    pub fn new() -> Self { Self::with_loopback(loopback_allowed()) }
+
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized.
+    #[must_use]
+    pub fn with_loopback(allow_loopback: bool) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(DELIVERY_TIMEOUT)
+                // A receiver must not be able to point deliveries elsewhere.
+                .redirect(Policy::none())
+                .dns_resolver(Arc::new(ReachableResolver { allow_loopback }))
+                .user_agent("meal-planner-webhooks")
+                .build()
+                .expect("the HTTP client can be built"),
+            allow_loopback,
+        }
+    }
+}
+
+impl Default for ReqwestSender {
+    fn default() -> Self { Self::new() }
+}
+
+impl WebhookSender for ReqwestSender {
+    async fn send(
+        &self,
+        url: &str,
+        headers: Vec<(&'static str, String)>,
+        body: &str,
+    ) -> Result<u16, String> {
+        let url = Url::parse(url).map_err(|err| err.to_string())?;
+        if literal_address(&url).is_some_and(|address| !reachable(address, self.allow_loopback)) {
+            return Err("the receiver has a private address".to_owned());
+        }
+        let mut request = self
+            .client
+            .post(url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.to_owned());
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
+        request
+            .send()
+            .await
+            .map(|response| response.status().as_u16())
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Looks up the hosts of receivers, leaving out the addresses events must not
+/// be sent to. Checking where deliveries connect, rather than only the URL a
+/// webhook was registered with, keeps a host from being pointed at a private
+/// address later.
+struct ReachableResolver {
+    allow_loopback: bool,
+}
+
+impl Resolve for ReachableResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let allow_loopback = self.allow_loopback;
+        let host = name.as_str().to_owned();
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|address| reachable(address.ip(), allow_loopback))
+                .collect();
+            if addresses.is_empty() {
+                return Err(format!("{host} has no public address").into());
+            }
+
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Sends events to the webhooks of a household that subscribed to them,
+/// retrying failed deliveries with exponential backoff and recording every
+/// attempt.
+#[derive(Clone)]
+pub struct WebhookPublisher<R, S> {
+    repo: R,
+    sender: S,
+    backoff: Duration,
+    deadline: Duration,
+}
+
+impl<R, S> WebhookPublisher<R, S>
+where
+    R: WebhookRepository,
+    S: WebhookSender,
+{
+    #[must_use]
+    pub fn new(repo: R, sender: S) -> Self {
+        Self {
+            repo,
+            sender,
+            backoff: DELIVERY_BACKOFF,
+            deadline: DELIVERY_DEADLINE,
+        }
+    }
+
+    #[must_use]
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    #[must_use]
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Delivers the event to every webhook of its household that subscribed
+    /// to its type at once. Receivers that keep failing or are still failing
+    /// when the deadline passes are given up on, so that they cannot hold up
+    /// the events of others.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the webhooks of the household cannot be read.
+    pub async fn publish(&self, event: &WebhookEvent) -> Result<(), StatusCode> {
+        let webhooks = self.repo.get_webhooks(&event.household_id).await?;
+        let body = serde_json::to_string(event).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let deadline = Instant::now() + self.deadline;
+
+        let subscribed: Vec<&Webhook> = webhooks
+            .iter()
+            .filter(|webhook| webhook.subscribes_to(event.event_type))
+            .collect();
+        let delivered = join_all(
+            subscribed
+                .iter()
+                .map(|webhook| self.deliver(webhook, event, &body, deadline)),
+        )
+        .await;
+
+        for (webhook, delivered) in subscribed.iter().zip(delivered) {
+            if !delivered {
+                warn!(
+                    webhook_id = %webhook.id,
+                    event_id = event.id,
+                    "Gave up delivering event"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Announces the changes read from the stream of the meal plan table in
+    /// order. Like the change feed of recipes, records that cannot be decoded
+    /// are skipped, and when publishing fails the remaining records are left
+    /// for Lambda to retry, starting with the failed one.
+    pub async fn publish_plan_changes(&self, event: &StreamEvent) -> StreamResponse {
+        for record in &event.records {
+            let plan_event = match plan_event(record) {
+                Ok(Some(plan_event)) => plan_event,
+                Ok(None) => continue,
+                Err(_) => {
+                    warn!(
+                        event_id = record.event_id,
+                        sequence_number = record.dynamodb.sequence_number,
+                        "Skipping stream record that is not a meal plan"
+                    );
+                    continue;
+                },
+            };
+
+            if let Err(status) = self.publish(&plan_event).await {
+                error!(
+                    sequence_number = record.dynamodb.sequence_number,
+                    %status,
+                    "Failed to publish meal plan change"
+                );
+                return StreamResponse {
+                    batch_item_failures: vec![BatchItemFailure {
+                        item_identifier: record.dynamodb.sequence_number.clone(),
+                    }],
+                };
+            }
+        }
+
+        StreamResponse::default()
+    }
+
+    async fn deliver(
+        &self,
+        webhook: &Webhook,
+        event: &WebhookEvent,
+        body: &str,
+        deadline: Instant,
+    ) -> bool {
+        for attempt in 1..=DELIVERY_ATTEMPTS {
+            if attempt > 1 {
+                let backoff = self.backoff * 2u32.pow(attempt - 2);
+                if Instant::now() + backoff >= deadline {
+                    return false;
+                }
+                tokio::time::sleep(backoff).await;
+            }
+
+            let now = clock::now();
+            let headers = vec![
+                (SIGNATURE_HEADER, sign(&webhook.secret, now, body)),
+                (EVENT_HEADER, event.event_type.as_str().to_owned()),
+                (EVENT_ID_HEADER, event.id.clone()),
+            ];
+            let result =
+                tokio::time::timeout_at(deadline, self.sender.send(&webhook.url, headers, body))
+                    .await
+                    .unwrap_or_else(|_| Err("the delivery deadline passed".to_owned()));
+
+            let mut record = DeliveryAttempt::new(webhook.id, event, attempt, now);
+            match &result {
+                Ok(status) => {
+                    record.status = Some(*status);
+                    record.succeeded = (200..300).contains(status);
+                },
+                Err(error) => record.error = Some(error.clone()),
+            }
+            if let Err(status) = self.repo.record_attempt(&record).await {
+                warn!(webhook_id = %webhook.id, %status, "Failed to record delivery attempt");
+            }
+
+            if record.succeeded || !should_retry(&result) {
+                return record.succeeded;
+            }
+        }
+
+        false
+    }
+}
+
+/// Retries when the receiver could not be reached or might succeed later, but
+/// not when it rejected the event.
+fn should_retry(result: &Result<u16, String>) -> bool {
+    match result {
+        Ok(status) => *status >= 500 || *status == 408 || *status == 429,
+        Err(_) => true,
+    }
+}
+
+/// The event announced to webhooks for a change to a recipe, if any. Recipes
+/// in the trash count as deleted, so that changes to them are not announced
+/// again.
+#[must_use]
+pub fn recipe_event(change: &RecipeChange) -> Option<WebhookEvent> {
+    let event_type = match change.kind() {
+        ChangeKind::Created | ChangeKind::Restored => EventType::RecipeCreated,
+        ChangeKind::Updated if change.recipe().is_deleted() => return None,
+        ChangeKind::Updated => EventType::RecipeUpdated,
+        ChangeKind::Trashed => EventType::RecipeDeleted,
+        ChangeKind::Deleted if change.recipe().is_deleted() => return None,
+        ChangeKind::Deleted => EventType::RecipeDeleted,
+    };
+
+    Some(WebhookEvent {
+        id: format!("evt_{}", change.sequence_number),
+        event_type,
+        household_id: change.household_id().to_owned(),
+        created_at: change.changed_at.unwrap_or_else(clock::now),
+        data: serde_json::json!({ "recipe": change.recipe() }),
+    })
+}
+
+/// The event announced to webhooks for a record of the stream of the meal plan
+/// table. Plans are announced when they are created or changed, but not when
+/// they are deleted.
+///
+/// # Errors
+///
+/// Responds with unprocessable entity if the record lacks its new image or the
+/// image is not a meal plan.
+pub fn plan_event(record: &StreamRecord) -> Result<Option<WebhookEvent>, StatusCode> {
+    if record.event_name == OperationType::Remove {
+        return Ok(None);
+    }
+    let plan: MealPlan = record
+        .dynamodb
+        .new_image
+        .clone()
+        .map(serde_dynamo::from_item)
+        .transpose()
+        .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?
+        .ok_or(StatusCode::UNPROCESSABLE_ENTITY)?;
+
+    Ok(Some(WebhookEvent {
+        id: format!("evt_{}", record.dynamodb.sequence_number),
+        event_type: EventType::PlanUpdated,
+        household_id: plan.household_id.clone(),
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        created_at: record
+            .dynamodb
+            .approximate_creation_date_time
+            .map_or_else(clock::now, |seconds| seconds as u64),
+        data: serde_json::json!({ "plan": plan }),
+    }))
+}
+
+#[async_trait]
+impl<R, S> ChangeHandler for WebhookPublisher<R, S>
+where
+    R: WebhookRepository,
+    S: WebhookSender,
+{
+    fn name(&self) -> &'static str { "webhooks" }
+
+    async fn handle(&self, change: &RecipeChange) -> Result<(), StatusCode> {
+        match recipe_event(change) {
+            Some(event) => self.publish(&event).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use mockall::predicate::{always, eq, function};
+    use reqwest::dns::Resolve;
+    use uuid::Uuid;
+
+    use super::{
+        plan_event,
+        recipe_event,
+        MockWebhookSender,
+        ReachableResolver,
+        ReqwestSender,
+        WebhookPublisher,
+        WebhookSender,
+        DELIVERY_ATTEMPTS,
+    };
+    use crate::change_feed::test::event;
+    use crate::change_feed::{BatchItemFailure, RecipeChange, StreamEvent, StreamResponse};
+    use crate::webhook::{
+        sign,
+        DeliveryAttempt,
+        EventType,
+        MockWebhookRepository,
+        Webhook,
+        WebhookEvent,
+        SIGNATURE_HEADER,
+    };
+
+    /// Stream records of the meal plan table: a plan is created, a meal is
+    /// added to it and it is deleted.
+    const PLAN_STREAM: &str = include_str!("../change_feed/fixtures/plan_stream.json");
+
+    fn plan_stream() -> StreamEvent { serde_json::from_str(PLAN_STREAM).unwrap() }
+
+    fn webhook(events: Vec<EventType>) -> Webhook {
+        Webhook {
+            household_id: "grandma".to_owned(),
+            id: Uuid::nil(),
+            url: "https://example.com/hook".to_owned(),
+            events,
+            secret: "whsec_test".to_owned(),
+            ..Webhook::default()
+        }
+    }
+
+    fn recipe_event_at(index: usize) -> Option<WebhookEvent> {
+        recipe_event(&RecipeChange::from_record(&event().records[index]).unwrap())
+    }
+
+    fn repo_with(webhooks: Vec<Webhook>) -> MockWebhookRepository {
+        let mut mock_repo = MockWebhookRepository::new();
+        mock_repo
+            .expect_get_webhooks()
+            .with(eq("grandma"))
+            .return_once(|_| Box::pin(async { Ok(webhooks) }));
+        mock_repo
+    }
+
+    #[test]
+    fn recipe_events_of_recorded_changes() {
+        let types: Vec<Option<EventType>> = (0..5)
+            .map(|index| recipe_event_at(index).map(|event| event.event_type))
+            .collect();
+
+        assert_eq!(
+            types,
+            vec![
+                Some(EventType::RecipeCreated),
+                Some(EventType::RecipeUpdated),
+                Some(EventType::RecipeDeleted),
+                Some(EventType::RecipeCreated),
+                None,
+            ]
+        );
+        let created = recipe_event_at(0).unwrap();
+        assert_eq!(created.id, "evt_100");
+        assert_eq!(created.data["recipe"]["name"], "Bread");
+    }
+
+    #[test]
+    fn plan_events_of_recorded_changes() {
+        let events: Vec<Option<WebhookEvent>> = plan_stream()
+            .records
+            .iter()
+            .map(|record| plan_event(record).unwrap())
+            .collect();
+
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| event.as_ref().map(|event| event.event_type))
+                .collect::<Vec<_>>(),
+            vec![
+                Some(EventType::PlanUpdated),
+                Some(EventType::PlanUpdated),
+                None
+            ]
+        );
+        let updated = events[1].as_ref().unwrap();
+        assert_eq!(updated.id, "evt_600");
+        assert_eq!(updated.household_id, "grandma");
+        assert_eq!(updated.created_at, 1_760_000_060);
+        assert_eq!(updated.data["plan"]["slots"][0]["date"], "2025-10-09");
+    }
+
+    #[test]
+    fn plan_event_of_other_item() {
+        let mut stream = plan_stream();
+        stream.records[0].dynamodb.new_image =
+            Some(serde_json::from_str(r#"{ "household_id": { "S": "grandma" } }"#).unwrap());
+
+        assert_eq!(
+            plan_event(&stream.records[0]),
+            Err(StatusCode::UNPROCESSABLE_ENTITY)
+        );
+    }
+
+    #[tokio::test]
+    async fn publish_plan_changes_to_subscribed_webhooks() {
+        let mut mock_repo = MockWebhookRepository::new();
+        mock_repo
+            .expect_get_webhooks()
+            .with(eq("grandma"))
+            .times(2)
+            .returning(|_| {
+                Box::pin(async {
+                    Ok(vec![
+                        webhook(vec![EventType::PlanUpdated]),
+                        webhook(vec![EventType::RecipeCreated]),
+                    ])
+                })
+            });
+        mock_repo
+            .expect_record_attempt()
+            .with(function(|attempt: &DeliveryAttempt| attempt.succeeded))
+            .times(2)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let mut mock_sender = MockWebhookSender::new();
+        mock_sender
+            .expect_send()
+            .times(2)
+            .returning(|_, _, _| Box::pin(async { Ok(204) }));
+
+        let publisher = WebhookPublisher::new(mock_repo, mock_sender);
+
+        assert_eq!(
+            publisher.publish_plan_changes(&plan_stream()).await,
+            StreamResponse::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn publish_plan_changes_stops_at_failed_record() {
+        let mut mock_repo = MockWebhookRepository::new();
+        mock_repo
+            .expect_get_webhooks()
+            .times(1)
+            .returning(|_| Box::pin(async { Err(StatusCode::SERVICE_UNAVAILABLE) }));
+
+        let publisher = WebhookPublisher::new(mock_repo, MockWebhookSender::new());
+
+        assert_eq!(
+            publisher.publish_plan_changes(&plan_stream()).await,
+            StreamResponse {
+                batch_item_failures: vec![BatchItemFailure {
+                    item_identifier: "500".to_owned(),
+                }],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn publish_to_subscribed_webhooks() {
+        let created = recipe_event_at(0).unwrap();
+        let mut mock_repo = repo_with(vec![
+            webhook(vec![EventType::RecipeCreated]),
+            webhook(vec![EventType::PlanUpdated]),
+        ]);
+        mock_repo
+            .expect_record_attempt()
+            .with(function(|attempt: &DeliveryAttempt| {
+                attempt.succeeded && attempt.attempt == 1 && attempt.event_id == "evt_100"
+            }))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let mut mock_sender = MockWebhookSender::new();
+        mock_sender
+            .expect_send()
+            .with(eq("https://example.com/hook"), always(), always())
+            .times(1)
+            .returning(|_, _, _| Box::pin(async { Ok(204) }));
+
+        let publisher = WebhookPublisher::new(mock_repo, mock_sender);
+
+        assert_eq!(publisher.publish(&created).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn publish_retries_failed_deliveries() {
+        let created = recipe_event_at(0).unwrap();
+        let mut mock_repo = repo_with(vec![webhook(vec![EventType::RecipeCreated])]);
+        mock_repo
+            .expect_record_attempt()
+            .times(DELIVERY_ATTEMPTS as usize)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let mut mock_sender = MockWebhookSender::new();
+        let mut calls = 0;
+        mock_sender
+            .expect_send()
+            .times(DELIVERY_ATTEMPTS as usize)
+            .returning(move |_, _, _| {
+                calls += 1;
+                let result = if calls == 1 {
+                    Err("connection refused".to_owned())
+                } else {
+                    Ok(503)
+                };
+                Box::pin(async move { result })
+            });
+
+        let publisher = WebhookPublisher::new(mock_repo, mock_sender).with_backoff(Duration::ZERO);
+
+        assert_eq!(publisher.publish(&created).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn publish_delivers_at_once() {
+        let created = recipe_event_at(0).unwrap();
+        let mut mock_repo = repo_with(vec![
+            webhook(vec![EventType::RecipeCreated]),
+            webhook(vec![EventType::RecipeCreated]),
+        ]);
+        mock_repo
+            .expect_record_attempt()
+            .with(function(|attempt: &DeliveryAttempt| attempt.succeeded))
+            .times(2)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let mut mock_sender = MockWebhookSender::new();
+        mock_sender.expect_send().times(2).returning(|_, _, _| {
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                Ok(204)
+            })
+        });
+
+        let publisher =
+            WebhookPublisher::new(mock_repo, mock_sender).with_deadline(Duration::from_millis(300));
+
+        assert_eq!(publisher.publish(&created).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn publish_gives_up_at_deadline() {
+        let created = recipe_event_at(0).unwrap();
+        let mut mock_repo = repo_with(vec![webhook(vec![EventType::RecipeCreated])]);
+        mock_repo
+            .expect_record_attempt()
+            .with(function(|attempt: &DeliveryAttempt| {
+                attempt.error.as_deref() == Some("the delivery deadline passed")
+            }))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let mut mock_sender = MockWebhookSender::new();
+        mock_sender.expect_send().times(1).returning(|_, _, _| {
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(204)
+            })
+        });
+
+        let publisher = WebhookPublisher::new(mock_repo, mock_sender)
+            .with_backoff(Duration::ZERO)
+            .with_deadline(Duration::from_millis(50));
+
+        assert_eq!(publisher.publish(&created).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn publish_gives_up_on_rejected_delivery() {
+        let created = recipe_event_at(0).unwrap();
+        let mut mock_repo = repo_with(vec![webhook(vec![EventType::RecipeCreated])]);
+        mock_repo
+            .expect_record_attempt()
+            .with(function(|attempt: &DeliveryAttempt| {
+                attempt.status == Some(410) && !attempt.succeeded
+            }))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let mut mock_sender = MockWebhookSender::new();
+        mock_sender
+            .expect_send()
+            .times(1)
+            .returning(|_, _, _| Box::pin(async { Ok(410) }));
+
+        let publisher = WebhookPublisher::new(mock_repo, mock_sender);
+
+        assert_eq!(publisher.publish(&created).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn publish_fails_without_webhooks() {
+        let mut mock_repo = MockWebhookRepository::new();
+        mock_repo
+            .expect_get_webhooks()
+            .return_once(|_| Box::pin(async { Err(StatusCode::INTERNAL_SERVER_ERROR) }));
+
+        let publisher = WebhookPublisher::new(mock_repo, MockWebhookSender::new());
+
+        assert_eq!(
+            publisher.publish(&recipe_event_at(0).unwrap()).await,
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        );
+    }
+
+    /// Sends a delivery to a receiver listening on a local port, which checks
+    /// the signature like a real one would.
+    #[tokio::test]
+    async fn reqwest_sender_delivers_signed_event() {
+        let received = Arc::new(Mutex::new(None));
+        let receiver = Router::new().route(
+            "/hook",
+            post({
+                let received = Arc::clone(&received);
+                move |headers: HeaderMap, body: String| async move {
+                    let signature = headers[SIGNATURE_HEADER].to_str().unwrap().to_owned();
+                    *received.lock().unwrap() = Some((signature, body));
+                    StatusCode::NO_CONTENT
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, receiver).await });
+
+        let signature = sign("whsec_test", 1_700_000_000, "{}");
+        let status = ReqwestSender::with_loopback(true)
+            .send(
+                &format!("http://{address}/hook"),
+                vec![(SIGNATURE_HEADER, signature.clone())],
+                "{}",
+            )
+            .await;
+
+        assert_eq!(status, Ok(204));
+        assert_eq!(
+            received.lock().unwrap().clone(),
+            Some((signature, "{}".to_owned()))
+        );
+    }
+
+    #[tokio::test]
+    async fn reqwest_sender_refuses_private_addresses() {
+        let sender = ReqwestSender::with_loopback(false);
+
+        for url in [
+            "http://127.0.0.1:9001/2018-06-01/runtime/invocation/next",
+            "http://localhost:9001/2018-06-01/runtime/invocation/next",
+            "http://169.254.169.254/latest/meta-data",
+        ] {
+            assert!(sender.send(url, Vec::new(), "{}").await.is_err(), "{url}");
+        }
+    }
+
+    #[tokio::test]
+    async fn resolver_leaves_out_loopback() {
+        let resolve = |allow_loopback: bool| {
+            ReachableResolver { allow_loopback }.resolve("localhost".parse().unwrap())
+        };
+
+        assert!(resolve(false).await.is_err());
+        assert!(resolve(true)
+            .await
+            .unwrap()
+            .all(|address| address.ip().is_loopback()));
+    }
+}
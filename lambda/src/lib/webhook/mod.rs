@@ -0,0 +1,332 @@
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+
+use axum::async_trait;
+use axum::http::StatusCode;
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+
+pub mod delivery;
+pub mod repository;
+pub mod request_models;
+pub mod response_models;
+
+/// Every signing secret starts with this prefix, so that leaked secrets are
+/// easy to spot.
+pub const SECRET_PREFIX: &str = "whsec_";
+
+/// The most webhooks a household can register.
+pub const MAX_WEBHOOKS: usize = 10;
+
+/// How long delivery attempts are kept for inspection.
+pub const DELIVERY_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Carries the timestamp and signature of a delivery, see [`sign`].
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+
+/// Carries the type of the delivered event.
+pub const EVENT_HEADER: &str = "x-webhook-event";
+
+/// Carries the id of the delivered event, the same for every attempt, so that
+/// receivers can drop events they have already seen.
+pub const EVENT_ID_HEADER: &str = "x-webhook-id";
+
+/// Set to `true` to let webhooks reach receivers on the loopback interface
+/// over plain HTTP, for trying them out on a development machine. Never set it
+/// on Lambda, whose runtime API listens on the loopback interface.
+pub const ALLOW_LOOPBACK_VARIABLE: &str = "WEBHOOK_ALLOW_LOOPBACK";
+
+/// The kinds of events a webhook can subscribe to.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum EventType {
+    #[serde(rename = "recipe.created")]
+    RecipeCreated,
+    #[serde(rename = "recipe.updated")]
+    RecipeUpdated,
+    #[serde(rename = "recipe.deleted")]
+    RecipeDeleted,
+    #[serde(rename = "plan.updated")]
+    PlanUpdated,
+}
+
+impl EventType {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventType::RecipeCreated => "recipe.created",
+            EventType::RecipeUpdated => "recipe.updated",
+            EventType::RecipeDeleted => "recipe.deleted",
+            EventType::PlanUpdated => "plan.updated",
+        }
+    }
+}
+
+/// An endpoint of another system that is notified of changes in a household.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Webhook {
+    pub(crate) household_id: String,
+    pub(crate) id: Uuid,
+    pub(crate) url: String,
+    pub(crate) events: Vec<EventType>,
+    /// Signs the deliveries. Kept as is, since signing needs it, and shown
+    /// once when the webhook is created.
+    pub(crate) secret: String,
+    pub(crate) created_by: String,
+    pub(crate) created_at: u64,
+}
+
+impl Webhook {
+    /// Creates a webhook with a fresh signing secret.
+    #[must_use]
+    pub fn generate(
+        household_id: &str,
+        created_by: &str,
+        url: &str,
+        events: &[EventType],
+        now: u64,
+    ) -> Self {
+        let mut subscribed = Vec::new();
+        for event in events {
+            if !subscribed.contains(event) {
+                subscribed.push(*event);
+            }
+        }
+
+        Self {
+            household_id: household_id.to_owned(),
+            id: Uuid::new_v4(),
+            url: url.to_owned(),
+            events: subscribed,
+            // Version 4 uuids are drawn from a cryptographically secure
+            // generator, two of them make for a secret of 244 random bits.
+            secret: format!(
+                "{SECRET_PREFIX}{}{}",
+                Uuid::new_v4().simple(),
+                Uuid::new_v4().simple()
+            ),
+            created_by: created_by.to_owned(),
+            created_at: now,
+        }
+    }
+
+    #[must_use]
+    pub fn subscribes_to(&self, event: EventType) -> bool { self.events.contains(&event) }
+}
+
+/// An event as it is sent to webhooks.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct WebhookEvent {
+    /// Stays the same when a delivery is retried.
+    pub(crate) id: String,
+    #[serde(rename = "type")]
+    pub(crate) event_type: EventType,
+    pub(crate) household_id: String,
+    /// When the change was made, in seconds since the epoch.
+    pub(crate) created_at: u64,
+    pub(crate) data: serde_json::Value,
+}
+
+/// A single attempt to deliver an event to a webhook.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct DeliveryAttempt {
+    pub(crate) webhook_id: Uuid,
+    /// Orders the attempts of a webhook by time, the sort key of the table.
+    pub(crate) attempt_id: String,
+    pub(crate) event_id: String,
+    pub(crate) event_type: String,
+    /// Counts from one for every event.
+    pub(crate) attempt: u32,
+    pub(crate) attempted_at: u64,
+    /// The status the endpoint responded with, if it responded at all.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+    pub(crate) succeeded: bool,
+    /// When DynamoDB removes the attempt.
+    pub(crate) expires_at: u64,
+}
+
+impl DeliveryAttempt {
+    #[must_use]
+    pub fn new(webhook_id: Uuid, event: &WebhookEvent, attempt: u32, now: u64) -> Self {
+        Self {
+            webhook_id,
+            attempt_id: format!("{now:011}#{}#{attempt}", event.id),
+            event_id: event.id.clone(),
+            event_type: event.event_type.as_str().to_owned(),
+            attempt,
+            attempted_at: now,
+            status: None,
+            error: None,
+            succeeded: false,
+            expires_at: now + DELIVERY_RETENTION.as_secs(),
+        }
+    }
+}
+
+/// Signs a delivery the way the signature header carries it,
+/// `t=<timestamp>,v1=<signature>`. The signature is the hex encoded
+/// HMAC-SHA256 of the timestamp, a dot and the body, keyed with the secret of
+/// the webhook. Receivers should recompute it and reject old timestamps.
+#[must_use]
+pub fn sign(secret: &str, timestamp: u64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+
+    format!(
+        "t={timestamp},v1={}",
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+/// Whether [`ALLOW_LOOPBACK_VARIABLE`] is set.
+#[must_use]
+pub fn loopback_allowed() -> bool {
+    std::env::var(ALLOW_LOOPBACK_VARIABLE).is_ok_and(|value| value == "true")
+}
+
+/// Whether events may be sent to an address. Only public addresses qualify,
+/// so that webhooks cannot reach the private network or the metadata service,
+/// and the loopback interface only when allowed.
+#[must_use]
+pub fn reachable(address: IpAddr, allow_loopback: bool) -> bool {
+    match address {
+        IpAddr::V4(address) if address.is_loopback() => allow_loopback,
+        IpAddr::V4(address) => {
+            let [first, second, ..] = address.octets();
+            let shared = first == 100 && (second & 0b1100_0000) == 64;
+            !(first == 0
+                || shared
+                || address.is_private()
+                || address.is_link_local()
+                || address.is_broadcast()
+                || address.is_documentation()
+                || address.is_multicast())
+        },
+        IpAddr::V6(address) => match address.to_ipv4_mapped() {
+            Some(mapped) => reachable(IpAddr::V4(mapped), allow_loopback),
+            None if address.is_loopback() => allow_loopback,
+            None => {
+                !(address.is_unspecified()
+                    || address.is_unique_local()
+                    || address.is_unicast_link_local()
+                    || address.is_multicast())
+            },
+        },
+    }
+}
+
+/// The address a URL points to without looking up its host, for hosts that
+/// are addresses themselves or name the loopback interface.
+#[must_use]
+pub fn literal_address(url: &Url) -> Option<IpAddr> {
+    let host = url.host_str()?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if let Ok(address) = host.parse() {
+        return Some(address);
+    }
+
+    (host == "localhost" || host.ends_with(".localhost")).then_some(Ipv4Addr::LOCALHOST.into())
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    fn get_webhooks(
+        &self,
+        household_id: &str,
+    ) -> impl Future<Output = Result<Vec<Webhook>, StatusCode>> + Send;
+    fn find_webhook(
+        &self,
+        household_id: &str,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Webhook, StatusCode>> + Send;
+    fn save_webhook(
+        &self,
+        webhook: &Webhook,
+    ) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    fn delete_webhook(
+        &self,
+        household_id: &str,
+        id: Uuid,
+    ) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    fn record_attempt(
+        &self,
+        attempt: &DeliveryAttempt,
+    ) -> impl Future<Output = Result<(), StatusCode>> + Send;
+    /// The latest attempts to deliver to a webhook, newest first.
+    fn get_attempts(
+        &self,
+        webhook_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<DeliveryAttempt>, StatusCode>> + Send;
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use super::{reachable, sign, EventType, Webhook, SECRET_PREFIX};
+
+    #[test]
+    fn generate_drops_repeated_events() {
+        let webhook = Webhook::generate(
+            "household",
+            "grandma",
+            "https://example.com/hook",
+            &[EventType::RecipeCreated, EventType::RecipeCreated],
+            0,
+        );
+
+        assert_eq!(webhook.events, vec![EventType::RecipeCreated]);
+        assert!(webhook.secret.starts_with(SECRET_PREFIX));
+        assert!(webhook.subscribes_to(EventType::RecipeCreated));
+        assert!(!webhook.subscribes_to(EventType::PlanUpdated));
+    }
+
+    #[test]
+    fn sign_known_value() {
+        // echo -n '1700000000.{}' | openssl dgst -sha256 -hmac whsec_test
+        assert_eq!(
+            sign("whsec_test", 1_700_000_000, "{}"),
+            "t=1700000000,v1=35495024f4ef3f94e5a93e22221544c4b75e9a42300cd965ab81cb85cd994e91"
+        );
+    }
+
+    #[test]
+    fn reachable_addresses() {
+        let reachable_by_default =
+            |address: &str| reachable(address.parse::<IpAddr>().unwrap(), false);
+
+        for public in ["93.184.215.14", "2606:2800:21f:cb07:6820:80da:af6b:8b2c"] {
+            assert!(reachable_by_default(public), "{public}");
+        }
+        for private in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00:ec2::254",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(!reachable_by_default(private), "{private}");
+        }
+        assert!(reachable("127.0.0.1".parse().unwrap(), true));
+        assert!(reachable("::1".parse().unwrap(), true));
+        assert!(!reachable("169.254.169.254".parse().unwrap(), true));
+    }
+}
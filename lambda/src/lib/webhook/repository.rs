@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aws_config::SdkConfig;
+use aws_sdk_dynamodb::operation::delete_item::DeleteItemError;
+use aws_sdk_dynamodb::operation::get_item::GetItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use axum::http::StatusCode;
+use serde_dynamo::aws_sdk_dynamodb_1::{from_item, from_items, to_item};
+use uuid::Uuid;
+
+use super::{DeliveryAttempt, Webhook, WebhookRepository};
+use crate::aws_client::{query_all, DynamoDbClient, DynamoDbClientImpl, QueryExpression};
+
+/// How many delivery attempts of a webhook are listed.
+pub const ATTEMPT_PAGE_SIZE: i32 = 100;
+
+#[derive(Clone)]
+pub struct DynamoDbWebhook {
+    client: Arc<dyn DynamoDbClient>,
+    table_name: String,
+    delivery_table_name: String,
+}
+
+impl DynamoDbWebhook {
+    #[must_use]
+    pub fn new(sdk_config: &SdkConfig, table_name: &str, delivery_table_name: &str) -> Self {
+        Self {
+            client: Arc::new(DynamoDbClientImpl::new(sdk_config)),
+            table_name: table_name.to_owned(),
+            delivery_table_name: delivery_table_name.to_owned(),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn mock(client: Arc<dyn DynamoDbClient>) -> Self {
+        Self {
+            client,
+            table_name: "webhooks".to_owned(),
+            delivery_table_name: "webhook-deliveries".to_owned(),
+        }
+    }
+}
+
+impl WebhookRepository for DynamoDbWebhook {
+    async fn get_webhooks(&self, household_id: &str) -> Result<Vec<Webhook>, StatusCode> {
+        let query = QueryExpression {
+            key_condition: "household_id = :household_id".to_owned(),
+            values: HashMap::from([(
+                ":household_id".to_owned(),
+                AttributeValue::S(household_id.to_owned()),
+            )]),
+            scan_forward: true,
+            ..QueryExpression::default()
+        };
+        let items = query_all(self.client.as_ref(), &self.table_name, query)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(items.into_iter().flat_map(from_item).collect())
+    }
+
+    async fn find_webhook(&self, household_id: &str, id: Uuid) -> Result<Webhook, StatusCode> {
+        let output = self
+            .client
+            .get_item(&self.table_name, DynamoDbWebhook::get_key(household_id, id))
+            .await
+            .map_err(|err| match err {
+                GetItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+        let item = output.item().ok_or(StatusCode::NOT_FOUND)?.to_owned();
+
+        from_item(item).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    async fn save_webhook(&self, webhook: &Webhook) -> Result<(), StatusCode> {
+        let item = to_item(webhook).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.client
+            .put_new_item(&self.table_name, item, "id")
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(())
+    }
+
+    async fn delete_webhook(&self, household_id: &str, id: Uuid) -> Result<(), StatusCode> {
+        self.client
+            .delete_item(
+                &self.table_name,
+                DynamoDbWebhook::get_key(household_id, id),
+                "id",
+            )
+            .await
+            .map_err(|err| match err {
+                DeleteItemError::ConditionalCheckFailedException(_)
+                | DeleteItemError::ResourceNotFoundException(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+
+        Ok(())
+    }
+
+    async fn record_attempt(&self, attempt: &DeliveryAttempt) -> Result<(), StatusCode> {
+        let item = to_item(attempt).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        self.client
+            .put_item(&self.delivery_table_name, item)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok(())
+    }
+
+    async fn get_attempts(&self, webhook_id: Uuid) -> Result<Vec<DeliveryAttempt>, StatusCode> {
+        let query = QueryExpression {
+            key_condition: "webhook_id = :webhook_id".to_owned(),
+            values: HashMap::from([(
+                ":webhook_id".to_owned(),
+                AttributeValue::S(webhook_id.as_hyphenated().to_string()),
+            )]),
+            scan_forward: false,
+            limit: Some(ATTEMPT_PAGE_SIZE),
+            ..QueryExpression::default()
+        };
+        let output = self
+            .client
+            .query(&self.delivery_table_name, query)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        from_items(output.items().to_vec()).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl DynamoDbWebhook {
+    fn get_key(household_id: &str, id: Uuid) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            (
+                "household_id".to_owned(),
+                AttributeValue::S(household_id.to_owned()),
+            ),
+            (
+                "id".to_owned(),
+                AttributeValue::S(id.as_hyphenated().to_string()),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::result_large_err)]
+mod test {
+    use aws_sdk_dynamodb::operation::get_item::GetItemOutput;
+    use aws_sdk_dynamodb::operation::query::QueryOutput;
+    use mockall::predicate::{eq, function};
+
+    use super::*;
+    use crate::aws_client::MockDynamoDbClient as DynamoDbClient;
+    use crate::webhook::EventType;
+
+    #[tokio::test]
+    async fn test_find_webhook() {
+        let webhook = Webhook {
+            household_id: "grandma".to_owned(),
+            id: Uuid::nil(),
+            url: "https://example.com/hook".to_owned(),
+            events: vec![EventType::RecipeCreated, EventType::PlanUpdated],
+            secret: "whsec_test".to_owned(),
+            ..Webhook::default()
+        };
+        let item = to_item(&webhook).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_get_item()
+            .with(
+                eq("webhooks"),
+                eq(DynamoDbWebhook::get_key("grandma", Uuid::nil())),
+            )
+            .return_once(|_, _| Ok(GetItemOutput::builder().set_item(Some(item)).build()));
+
+        let repo = DynamoDbWebhook::mock(Arc::new(mock));
+
+        let result = repo.find_webhook("grandma", Uuid::nil()).await;
+        assert_eq!(result, Ok(webhook));
+    }
+
+    #[tokio::test]
+    async fn test_get_attempts_newest_first() {
+        let attempt = DeliveryAttempt {
+            webhook_id: Uuid::nil(),
+            attempt_id: "00000000100#evt_1#1".to_owned(),
+            status: Some(200),
+            succeeded: true,
+            ..DeliveryAttempt::default()
+        };
+        let item = to_item(&attempt).unwrap();
+        let mut mock = DynamoDbClient::default();
+        mock.expect_query()
+            .with(
+                eq("webhook-deliveries"),
+                function(|query: &QueryExpression| {
+                    !query.scan_forward && query.limit == Some(ATTEMPT_PAGE_SIZE)
+                }),
+            )
+            .return_once(|_, _| Ok(QueryOutput::builder().items(item).build()));
+
+        let repo = DynamoDbWebhook::mock(Arc::new(mock));
+
+        let result = repo.get_attempts(Uuid::nil()).await;
+        assert_eq!(result, Ok(vec![attempt]));
+    }
+}
@@ -0,0 +1,111 @@
+use reqwest::Url;
+use serde::Deserialize;
+
+use super::{literal_address, loopback_allowed, reachable, EventType};
+use crate::validation::{max_length, not_empty, Validate, Violations};
+
+pub const MAX_URL_LENGTH: usize = 2048;
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct PostWebhook {
+    /// Where events are sent to, over HTTPS.
+    pub(crate) url: String,
+    pub(crate) events: Vec<EventType>,
+}
+
+impl Validate for PostWebhook {
+    fn validate_at(&self, pointer: &str, violations: &mut Violations) {
+        let url_pointer = format!("{pointer}/url");
+        violations.check(&url_pointer, max_length(&self.url, MAX_URL_LENGTH));
+        violations.check(&url_pointer, secure_url(&self.url, loopback_allowed()));
+        violations.check(&format!("{pointer}/events"), not_empty(&self.events));
+    }
+}
+
+/// Events carry the recipes of a household, so they are only sent over
+/// HTTPS, and never to private addresses. Plain HTTP is allowed for receivers
+/// on the loopback interface, where that is allowed at all.
+fn secure_url(value: &str, allow_loopback: bool) -> Result<(), String> {
+    let url = Url::parse(value).map_err(|_| "must be an absolute URL".to_owned())?;
+    let address = literal_address(&url);
+    if address.is_some_and(|address| !reachable(address, allow_loopback)) {
+        return Err("must not point to a private address".to_owned());
+    }
+
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" if address.is_some_and(|address| address.is_loopback()) => Ok(()),
+        _ => Err("must use https".to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{secure_url, PostWebhook};
+    use crate::validation::Validate;
+    use crate::webhook::EventType;
+
+    fn pointers(webhook: &PostWebhook) -> Vec<String> {
+        webhook.validate().map_or_else(
+            |violations| {
+                violations
+                    .errors()
+                    .iter()
+                    .map(|violation| violation.pointer.clone())
+                    .collect()
+            },
+            |()| Vec::new(),
+        )
+    }
+
+    #[test]
+    fn validate_url_and_events() {
+        let valid = PostWebhook {
+            url: "https://example.com/hook".to_owned(),
+            events: vec![EventType::RecipeCreated],
+        };
+
+        assert!(pointers(&valid).is_empty());
+        assert_eq!(
+            pointers(&PostWebhook {
+                url: "http://example.com/hook".to_owned(),
+                events: Vec::new(),
+            }),
+            vec!["/url", "/events"]
+        );
+        assert_eq!(
+            pointers(&PostWebhook {
+                url: "example.com".to_owned(),
+                events: vec![EventType::PlanUpdated],
+            }),
+            vec!["/url"]
+        );
+    }
+
+    #[test]
+    fn secure_url_rejects_private_addresses() {
+        let private = Err("must not point to a private address".to_owned());
+
+        assert_eq!(secure_url("https://example.com/hook", false), Ok(()));
+        assert_eq!(secure_url("https://93.184.215.14/hook", false), Ok(()));
+        assert_eq!(secure_url("http://localhost:8080/hook", false), private);
+        assert_eq!(
+            secure_url("http://127.0.0.1:9001/2018-06-01/runtime", false),
+            private
+        );
+        assert_eq!(secure_url("https://[::1]/hook", false), private);
+        assert_eq!(secure_url("https://169.254.169.254/latest", true), private);
+        assert_eq!(secure_url("https://10.0.0.1/hook", true), private);
+        assert_eq!(secure_url("https://[fe80::1]/hook", true), private);
+    }
+
+    #[test]
+    fn secure_url_allows_loopback_when_asked() {
+        assert_eq!(secure_url("http://localhost:8080/hook", true), Ok(()));
+        assert_eq!(secure_url("http://[::1]:8080/hook", true), Ok(()));
+        assert_eq!(
+            secure_url("http://example.com/hook", true),
+            Err("must use https".to_owned())
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{DeliveryAttempt, EventType, Webhook};
+
+/// A webhook as listed to the owners of its household, without its secret.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct WebhookResponse {
+    pub(crate) id: Uuid,
+    pub(crate) url: String,
+    pub(crate) events: Vec<EventType>,
+    pub(crate) created_by: String,
+    pub(crate) created_at: u64,
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            id: webhook.id,
+            url: webhook.url,
+            events: webhook.events,
+            created_by: webhook.created_by,
+            created_at: webhook.created_at,
+        }
+    }
+}
+
+/// A newly created webhook along with its signing secret, which cannot be
+/// read again later.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    pub(crate) details: WebhookResponse,
+    pub(crate) secret: String,
+}
+
+/// An attempt to deliver an event, for finding out why a receiver missed it.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DeliveryAttemptResponse {
+    pub(crate) event_id: String,
+    pub(crate) event_type: String,
+    pub(crate) attempt: u32,
+    pub(crate) attempted_at: u64,
+    pub(crate) status: Option<u16>,
+    pub(crate) error: Option<String>,
+    pub(crate) succeeded: bool,
+}
+
+impl From<DeliveryAttempt> for DeliveryAttemptResponse {
+    fn from(attempt: DeliveryAttempt) -> Self {
+        Self {
+            event_id: attempt.event_id,
+            event_type: attempt.event_type,
+            attempt: attempt.attempt,
+            attempted_at: attempt.attempted_at,
+            status: attempt.status,
+            error: attempt.error,
+            succeeded: attempt.succeeded,
+        }
+    }
+}